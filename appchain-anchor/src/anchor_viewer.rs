@@ -43,10 +43,55 @@ impl AnchorViewer for AppchainAnchor {
         self.wrapped_appchain_nfts.get().unwrap().to_vec()
     }
     //
+    fn get_near_nft_collections(&self) -> Vec<NearNFTCollection> {
+        self.near_nft_collections.get().unwrap().to_vec()
+    }
+    //
+    fn get_wrapped_appchain_assets(&self) -> Vec<WrappedAppchainAsset> {
+        self.wrapped_appchain_assets.get().unwrap().to_vec()
+    }
+    //
     fn get_appchain_state(&self) -> AppchainState {
         self.appchain_state.clone()
     }
     //
+    fn get_appchain_settlement_status(&self) -> Option<AppchainSettlementStatus> {
+        self.appchain_settlement_status.clone()
+    }
+    //
+    fn get_net_locked_balance_of(
+        &self,
+        contract_account: AccountId,
+        account_id: AccountId,
+    ) -> U128 {
+        U128::from(
+            self.net_locked_balances
+                .get()
+                .unwrap()
+                .get(&contract_account, &account_id),
+        )
+    }
+    //
+    fn get_unreturned_locked_balance_of(
+        &self,
+        contract_account: AccountId,
+        account_id: AccountId,
+    ) -> U128 {
+        U128::from(
+            self.unreturned_locked_balances
+                .get(&(contract_account, account_id))
+                .unwrap_or(0),
+        )
+    }
+    //
+    fn get_appchain_liveness(&self) -> AppchainLiveness {
+        AppchainLiveness {
+            status: self.appchain_liveness_status.clone(),
+            latest_era_start_timestamp: U64::from(self.get_latest_era_start_timestamp()),
+            latest_message_staged_timestamp: U64::from(self.latest_message_staged_timestamp),
+        }
+    }
+    //
     fn get_anchor_status(&self) -> AnchorStatus {
         let next_validator_set = self.next_validator_set.get().unwrap();
         let appchain_messages = self.appchain_messages.get().unwrap();
@@ -84,7 +129,9 @@ impl AnchorViewer for AppchainAnchor {
         let validator_set_histories = self.validator_set_histories.get().unwrap();
         if validator_set_histories.contains(&era_number.0) {
             let validator_set = validator_set_histories.get(&era_number.0).unwrap();
-            Some(validator_set.to_validator_set_info())
+            Some(validator_set.to_validator_set_info(|validator_id| {
+                self.get_validator_commission_percent_in_validator_set(&validator_set, validator_id)
+            }))
         } else {
             None
         }
@@ -171,12 +218,23 @@ impl AnchorViewer for AppchainAnchor {
                 .unwrap()
                 .get(&era_number.0)
             {
-                validator_set_of_era.get_validator_list()
+                validator_set_of_era.get_validator_list(|validator_id| {
+                    self.get_validator_commission_percent_in_validator_set(
+                        &validator_set_of_era,
+                        validator_id,
+                    )
+                })
             } else {
                 Vec::new()
             }
         } else {
-            self.next_validator_set.get().unwrap().get_validator_list()
+            let next_validator_set = self.next_validator_set.get().unwrap();
+            next_validator_set.get_validator_list(|validator_id| {
+                self.get_validator_commission_percent_of_era(
+                    validator_id,
+                    next_validator_set.era_number(),
+                )
+            })
         }
     }
     //
@@ -228,6 +286,81 @@ impl AnchorViewer for AppchainAnchor {
         result
     }
     //
+    fn get_pending_transfers_of(&self, account_id: AccountId) -> Vec<PendingTransfer> {
+        self.pending_transfers.get(&account_id).unwrap_or_default()
+    }
+    //
+    fn get_failed_appchain_messages(&self) -> Vec<FailedAppchainMessage> {
+        self.failed_appchain_message_nonces
+            .to_vec()
+            .iter()
+            .filter_map(|nonce| self.failed_appchain_messages.get(nonce))
+            .collect()
+    }
+    //
+    fn get_failed_appchain_message(&self, nonce: u32) -> Option<FailedAppchainMessage> {
+        self.failed_appchain_messages.get(&nonce)
+    }
+    //
+    fn get_pending_message_batch_attestations(&self) -> Vec<MessageBatchAttestation> {
+        self.pending_message_batch_hashes
+            .to_vec()
+            .iter()
+            .filter_map(|batch_hash| self.message_batch_attestations.get(batch_hash))
+            .collect()
+    }
+    //
+    fn get_challengeable_message_batches(&self) -> Vec<WitnessedMessageBatch> {
+        self.challengeable_message_batch_hashes
+            .iter()
+            .filter_map(|batch_hash| self.get_challengeable_message_batch(&batch_hash))
+            .collect()
+    }
+    //
+    fn get_witness_bond_of(&self, account_id: AccountId) -> U128 {
+        U128::from(self.witness_bonds.get(&account_id).unwrap_or(0))
+    }
+    //
+    fn get_bridge_fee_of_token(&self, contract_account: AccountId) -> Option<BridgeFeeOfToken> {
+        self.bridge_fees_of_tokens.get(&contract_account)
+    }
+    //
+    fn get_bridge_fee_of_nft(&self, contract_account: AccountId) -> Option<NftBridgeFee> {
+        self.bridge_fees_of_nfts.get(&contract_account)
+    }
+    //
+    fn get_nft_bridge_fee_deposit_of(&self, account_id: AccountId) -> NftBridgeFeeBalance {
+        self.nft_bridge_fee_deposits
+            .get(&account_id)
+            .unwrap_or_default()
+    }
+    //
+    fn get_nft_bridge_fees(&self) -> NftBridgeFees {
+        self.nft_bridge_fees.clone()
+    }
+    //
+    fn get_native_near_token(&self) -> NativeNearToken {
+        self.native_near_token.clone()
+    }
+    //
+    fn get_bridge_rate_limit_of_asset(
+        &self,
+        contract_account: AccountId,
+    ) -> Option<BridgeRateLimit> {
+        self.bridge_rate_limits.get(&contract_account)
+    }
+    //
+    fn get_bridge_storage_fund(&self) -> BridgeStorageFund {
+        self.bridge_storage_fund.clone()
+    }
+    //
+    fn get_storage_registration_of_token(
+        &self,
+        contract_account: AccountId,
+    ) -> Option<StorageRegistrationOfToken> {
+        self.storage_registration_of_tokens.get(&contract_account)
+    }
+    //
     fn get_unbonded_stakes_of(&self, account_id: AccountId) -> Vec<UnbondedStake> {
         let protocol_settings = self.protocol_settings.get().unwrap();
         let mut results = Vec::<UnbondedStake>::new();
@@ -245,6 +378,8 @@ impl AnchorViewer for AppchainAnchor {
                     .unwrap()
                     .get(&reference.staking_history_index)
                     .unwrap();
+                let reduced_amount = self.get_rebonded_amount_of(reference.staking_history_index)
+                    + self.get_slashed_amount_of_unbonded_stake(reference.staking_history_index);
                 match staking_history.staking_fact {
                     StakingFact::StakeDecreased {
                         validator_id,
@@ -260,7 +395,8 @@ impl AnchorViewer for AppchainAnchor {
                     } => results.push(UnbondedStake {
                         era_number: U64::from(reference.era_number),
                         account_id: validator_id,
-                        amount,
+                        amount: U128::from(amount.0 - reduced_amount),
+                        staking_history_index: U64::from(reference.staking_history_index),
                         unlock_time: U64::from(
                             validator_set.start_timestamp()
                                 + protocol_settings.unlock_period_of_validator_deposit.0
@@ -285,7 +421,8 @@ impl AnchorViewer for AppchainAnchor {
                     } => results.push(UnbondedStake {
                         era_number: U64::from(reference.era_number),
                         account_id: delegator_id,
-                        amount,
+                        amount: U128::from(amount.0 - reduced_amount),
+                        staking_history_index: U64::from(reference.staking_history_index),
                         unlock_time: U64::from(
                             validator_set.start_timestamp()
                                 + protocol_settings.unlock_period_of_delegator_deposit.0
@@ -337,10 +474,34 @@ impl AnchorViewer for AppchainAnchor {
         validator_id: AccountId,
     ) -> Vec<RewardHistory> {
         let validator_set_histories = self.validator_set_histories.get().unwrap();
+        let claimed_index = self
+            .delegator_reward_snapshots
+            .get(&(delegator_id.clone(), validator_id.clone()))
+            .map(|snapshot| snapshot.withdrawn_index)
+            .unwrap_or(0);
         let mut reward_histories = Vec::<RewardHistory>::new();
         for era_number in start_era.0..end_era.0 + 1 {
             if let Some(validator_set) = validator_set_histories.get(&era_number) {
-                if let Some(reward) =
+                if let Some(reward) = self.get_indexed_delegator_reward_of(
+                    &validator_set,
+                    &delegator_id,
+                    &validator_id,
+                    0,
+                ) {
+                    let unwithdrawn_reward = self
+                        .get_indexed_delegator_reward_of(
+                            &validator_set,
+                            &delegator_id,
+                            &validator_id,
+                            claimed_index,
+                        )
+                        .unwrap_or(0);
+                    reward_histories.push(RewardHistory {
+                        era_number: U64::from(era_number),
+                        total_reward: U128::from(reward),
+                        unwithdrawn_reward: U128::from(unwithdrawn_reward),
+                    });
+                } else if let Some(reward) =
                     validator_set.get_delegator_rewards_of(&delegator_id, &validator_id)
                 {
                     let unwithdrawn_reward = match self.unwithdrawn_delegator_rewards.get(&(
@@ -511,6 +672,27 @@ impl AnchorViewer for AppchainAnchor {
         None
     }
     //
+    fn get_retained_commitments_of_appchain(&self) -> Vec<RetainedAppchainCommitment> {
+        self.retained_beefy_commitments
+            .get()
+            .unwrap()
+            .to_vec()
+            .iter()
+            .map(|commitment| commitment.to_view())
+            .collect()
+    }
+    //
+    fn get_retained_commitment_of_appchain(
+        &self,
+        block_number: u32,
+    ) -> Option<RetainedAppchainCommitment> {
+        self.retained_beefy_commitments
+            .get()
+            .unwrap()
+            .get_by_block_number(block_number)
+            .map(|commitment| commitment.to_view())
+    }
+    //
     fn get_beefy_light_client_status(&self) -> BeefyLightClientStatus {
         if let Some(light_client) = self.beefy_light_client_state.get() {
             if light_client.is_updating_state() {
@@ -523,6 +705,23 @@ impl AnchorViewer for AppchainAnchor {
         }
     }
     //
+    fn get_light_client_status(&self) -> LightClientStatus {
+        LightClientStatus {
+            light_client_type: self.anchor_settings.get().unwrap().light_client_type,
+            beefy_light_client_status: self.get_beefy_light_client_status(),
+            grandpa_light_client_status: self
+                .grandpa_light_client_state
+                .get()
+                .map(|light_client| light_client.get_status()),
+        }
+    }
+    //
+    fn get_era_number_of_grandpa_set(&self, set_id: U64) -> Option<U64> {
+        self.era_numbers_of_grandpa_sets
+            .get(&set_id.0)
+            .map(|era_number| U64::from(era_number))
+    }
+    //
     fn get_user_staking_histories_of(&self, account_id: AccountId) -> Vec<UserStakingHistory> {
         let staking_histories = self.staking_histories.get().unwrap();
         let user_staking_histories = self.user_staking_histories.get().unwrap();
@@ -602,4 +801,45 @@ impl AnchorViewer for AppchainAnchor {
             .unwrap()
             .get_slice_of(&start_index.0, quantity.map(|q| q.0))
     }
+    //
+    fn get_appchain_challenge_state(&self, index: U64) -> Option<AppchainChallengeState> {
+        self.appchain_challenge_states.get(&index.0)
+    }
+    //
+    fn get_appchain_challenge_states(
+        &self,
+        start_index: U64,
+        quantity: Option<U64>,
+        status: Option<AppchainChallengeStatus>,
+        submitter_account: Option<AccountId>,
+    ) -> Vec<AppchainChallengeState> {
+        let index_range = self.appchain_challenges.get().unwrap().index_range();
+        let start_index = match start_index.0 < index_range.start_index.0 {
+            true => index_range.start_index.0,
+            false => start_index.0,
+        };
+        let end_index = start_index
+            + match quantity {
+                Some(quantity) => match quantity.0 > 50 {
+                    true => 49,
+                    false => quantity.0.saturating_sub(1),
+                },
+                None => 49,
+            };
+        let mut results = Vec::<AppchainChallengeState>::new();
+        for index in start_index..end_index.min(index_range.end_index.0) + 1 {
+            if let Some(challenge_state) = self.appchain_challenge_states.get(&index) {
+                if status
+                    .as_ref()
+                    .map_or(true, |status| challenge_state.status.eq(status))
+                    && submitter_account.as_ref().map_or(true, |account_id| {
+                        challenge_state.submitter_account.eq(account_id)
+                    })
+                {
+                    results.push(challenge_state);
+                }
+            }
+        }
+        results
+    }
 }
@@ -76,6 +76,45 @@ pub enum GrandpaMessage {
     Precommit(GrandpaPrecommit),
 }
 
+/// Check the ed25519 signature of a GRANDPA message, which is signed with
+/// the round number and the authority set id.
+pub fn check_grandpa_message_signature(
+    message: &GrandpaMessage,
+    round: u64,
+    set_id: u64,
+    signature: &SignatureData,
+    pubkey: &PublicKey,
+) -> bool {
+    let mut buffer = Vec::<u8>::new();
+    (message, round, set_id).encode_to(&mut buffer);
+    if signature.0.len() != 64 {
+        log!("Invalid signature data length.");
+        return false;
+    }
+    let mut sig_data: [u8; 64] = [0; 64];
+    for i in 0..64 {
+        sig_data[i] = signature.0.get(i).unwrap_or(&0).clone();
+    }
+    if let Ok(signature) = ed25519_dalek::Signature::try_from(sig_data) {
+        match ed25519_dalek::PublicKey::from_bytes(&pubkey.0) {
+            Ok(pubkey) => match pubkey.verify(&buffer, &signature) {
+                Ok(()) => true,
+                Err(err) => {
+                    log!("Signature verification failed: {}", err);
+                    false
+                }
+            },
+            Err(err) => {
+                log!("Invalid ed25519 pubkey: {}", err);
+                false
+            }
+        }
+    } else {
+        log!("Invalid ed25519 signature data.");
+        false
+    }
+}
+
 impl EquivocationProof {
     ///
     pub fn is_valid(&self) -> bool {
@@ -127,36 +166,39 @@ impl EquivocationProof {
         signature: &SignatureData,
         pubkey: &PublicKey,
     ) -> bool {
-        let mut buffer = Vec::<u8>::new();
         // Notice:
         // Need to convert `round` and `set_id` to u64 to match original
         // signing data in appchain side
-        (message, u64::from(*round), u64::from(self.set_id)).encode_to(&mut buffer);
-        if signature.0.len() != 64 {
-            log!("Invalid signature data length.");
-            return false;
-        }
-        let mut sig_data: [u8; 64] = [0; 64];
-        for i in 0..64 {
-            sig_data[i] = signature.0.get(i).unwrap_or(&0).clone();
+        check_grandpa_message_signature(
+            message,
+            u64::from(*round),
+            u64::from(self.set_id),
+            signature,
+            pubkey,
+        )
+    }
+    ///
+    pub fn round_number(&self) -> RoundNumber {
+        match &self.equivocation {
+            Equivocation::Prevote(equivocation) => equivocation.round_number,
+            Equivocation::Precommit(equivocation) => equivocation.round_number,
         }
-        if let Ok(signature) = ed25519_dalek::Signature::try_from(sig_data) {
-            match ed25519_dalek::PublicKey::from_bytes(&pubkey.0) {
-                Ok(pubkey) => match pubkey.verify(&buffer, &signature) {
-                    Ok(()) => true,
-                    Err(err) => {
-                        log!("Signature verification failed: {}", err);
-                        false
-                    }
-                },
-                Err(err) => {
-                    log!("Invalid ed25519 pubkey: {}", err);
-                    false
-                }
-            }
-        } else {
-            log!("Invalid ed25519 signature data.");
-            false
+    }
+    ///
+    pub fn identity(&self) -> &PublicKey {
+        match &self.equivocation {
+            Equivocation::Prevote(equivocation) => &equivocation.identity,
+            Equivocation::Precommit(equivocation) => &equivocation.identity,
         }
     }
+    /// The key for checking duplicated proofs, which is composed of
+    /// `set_id`, `round_number` and `identity`.
+    pub fn equivocation_key(&self) -> String {
+        format!(
+            "{}:{}:{}",
+            self.set_id,
+            self.round_number(),
+            hex::encode(self.identity().0)
+        )
+    }
 }
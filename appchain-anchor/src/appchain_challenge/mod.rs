@@ -1,9 +1,16 @@
-mod equivocation_challenge;
+pub mod equivocation_challenge;
 
+use crate::appchain_messages::RawMessage;
+use crate::retained_beefy_commitments::RetainedBeefyCommitment;
 use crate::*;
+use codec::{Decode, Encode};
+use near_contract_standards::fungible_token::core::ext_ft_core;
 
 use self::equivocation_challenge::EquivocationProof;
 
+/// The offence kind of equivocation, for looking up slashing percent in protocol settings.
+pub const EQUIVOCATION_OFFENCE_KIND: &str = "grandpa_equivocation";
+
 #[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
 #[serde(crate = "near_sdk::serde")]
 pub enum AppchainChallenge {
@@ -11,8 +18,538 @@ pub enum AppchainChallenge {
         submitter_account: AccountId,
         proof: EquivocationProof,
     },
+    /// A signed commitment which conflicts with the latest commitment
+    /// accepted by the beefy light client, at the same block number.
     ConspiracyMmr {
         submitter_account: AccountId,
         block_number: u32,
+        signed_commitment: Vec<u8>,
+        validator_proofs: Vec<ValidatorMerkleProof>,
+        mmr_leaf: Vec<u8>,
+        mmr_proof: Vec<u8>,
+    },
+    /// A batch of appchain messages committed by the appchain, which conflicts with
+    /// a batch staged by witnesses in its challenge period.
+    FraudulentMessageBatch {
+        submitter_account: AccountId,
+        batch_hash: String,
+        /// The encoded messages of the witnessed batch
+        witnessed_messages: Vec<u8>,
+        encoded_messages: Vec<u8>,
+        header: Vec<u8>,
+        mmr_leaf: Vec<u8>,
+        mmr_proof: Vec<u8>,
+        /// The justification of `header`, which is needed by GRANDPA light client
+        justification: Option<Vec<u8>>,
     },
 }
+
+impl AppchainChallenge {
+    ///
+    pub fn submitter_account(&self) -> &AccountId {
+        match self {
+            AppchainChallenge::EquivocationChallenge {
+                submitter_account, ..
+            } => submitter_account,
+            AppchainChallenge::ConspiracyMmr {
+                submitter_account, ..
+            } => submitter_account,
+            AppchainChallenge::FraudulentMessageBatch {
+                submitter_account, ..
+            } => submitter_account,
+        }
+    }
+}
+
+/// The status of an appchain challenge.
+///
+/// The order of status is: `Submitted` -> `Accepted` / `Rejected` / `Expired`
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub enum AppchainChallengeStatus {
+    /// The challenge is recorded but not adjudicated yet.
+    Submitted,
+    /// The challenge is accepted, the bond is refunded to the submitter with reward.
+    Accepted,
+    /// The challenge is rejected, the bond is forfeited.
+    Rejected,
+    /// The challenge is not adjudicated in time, the bond is refunded to the submitter.
+    Expired,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct AppchainChallengeState {
+    /// The index of the challenge
+    pub index: U64,
+    ///
+    pub submitter_account: AccountId,
+    /// The amount of OCT token bonded by the submitter
+    pub bond_amount: U128,
+    /// The timestamp when the challenge is committed
+    pub submitted_at: U64,
+    ///
+    pub status: AppchainChallengeStatus,
+    /// The validator who is punished by the challenge
+    pub punished_validator_id: Option<AccountId>,
+    /// The amount of OCT token slashed from the punished validator
+    pub slashed_amount: U128,
+    /// The amount of OCT token rewarded to the submitter
+    pub reward_amount: U128,
+    /// The reason why the challenge is rejected
+    pub rejected_reason: Option<String>,
+}
+
+impl AppchainAnchor {
+    /// Commit an appchain challenge with the OCT token bonded by the submitter.
+    ///
+    /// An equivocation challenge is adjudicated immediately, and the deposit is returned
+    /// if the equivocation has already been challenged. A conspiracy MMR challenge
+    /// is also adjudicated immediately if the commitment it conflicts with is retained,
+    /// otherwise it is kept as `Submitted` until it is adjudicated by the owner.
+    pub fn internal_commit_appchain_challenge(
+        &mut self,
+        sender_id: AccountId,
+        bond_amount: U128,
+        appchain_challenge: AppchainChallenge,
+    ) -> PromiseOrValue<U128> {
+        let protocol_settings = self.protocol_settings.get().unwrap();
+        assert!(
+            bond_amount.0 >= protocol_settings.challenge_bond_amount.0,
+            "The bond for committing a challenge should be at least {}.",
+            protocol_settings.challenge_bond_amount.0
+        );
+        assert!(
+            appchain_challenge.submitter_account().eq(&sender_id),
+            "The submitter account of challenge should be '{}'.",
+            sender_id
+        );
+        if let AppchainChallenge::EquivocationChallenge { proof, .. } = &appchain_challenge {
+            assert!(
+                !self
+                    .equivocation_challenge_indexes
+                    .contains_key(&proof.equivocation_key()),
+                "The equivocation has already been challenged. Return deposit."
+            );
+        }
+        let mut appchain_challenges = self.appchain_challenges.get().unwrap();
+        appchain_challenges.append(&mut appchain_challenge.clone());
+        self.appchain_challenges.set(&appchain_challenges);
+        let challenge_index = appchain_challenges.index_range().end_index;
+        let mut challenge_state = AppchainChallengeState {
+            index: challenge_index,
+            submitter_account: sender_id,
+            bond_amount,
+            submitted_at: U64::from(env::block_timestamp()),
+            status: AppchainChallengeStatus::Submitted,
+            punished_validator_id: None,
+            slashed_amount: U128::from(0),
+            reward_amount: U128::from(0),
+            rejected_reason: None,
+        };
+        match &appchain_challenge {
+            AppchainChallenge::EquivocationChallenge {
+                submitter_account: _,
+                proof,
+            } => match self.adjudicate_equivocation_challenge(proof, challenge_index.0) {
+                Ok((validator_id, slashed_amount)) => self.accept_appchain_challenge_with(
+                    &mut challenge_state,
+                    Some(validator_id),
+                    slashed_amount,
+                ),
+                Err(reason) => self.reject_appchain_challenge_with(&mut challenge_state, reason),
+            },
+            AppchainChallenge::ConspiracyMmr {
+                submitter_account: _,
+                block_number,
+                signed_commitment,
+                validator_proofs,
+                mmr_leaf,
+                mmr_proof,
+            } => {
+                if self.conspiracy_mmr_challenge_can_be_verified(*block_number) {
+                    match self.adjudicate_conspiracy_mmr_challenge(
+                        *block_number,
+                        signed_commitment,
+                        validator_proofs,
+                        mmr_leaf,
+                        mmr_proof,
+                    ) {
+                        Ok(()) => {
+                            self.accept_appchain_challenge_with(&mut challenge_state, None, 0);
+                            self.internal_freeze_appchain();
+                        }
+                        Err(reason) => {
+                            self.reject_appchain_challenge_with(&mut challenge_state, reason)
+                        }
+                    }
+                }
+            }
+            AppchainChallenge::FraudulentMessageBatch {
+                submitter_account,
+                batch_hash,
+                witnessed_messages,
+                encoded_messages,
+                header,
+                mmr_leaf,
+                mmr_proof,
+                justification,
+            } => match self.adjudicate_fraudulent_message_batch_challenge(
+                batch_hash,
+                witnessed_messages,
+                encoded_messages,
+                header,
+                mmr_leaf,
+                mmr_proof,
+                justification,
+            ) {
+                Ok(batch) => {
+                    let slashed_amount = self.internal_revert_witnessed_message_batch(&batch);
+                    self.accept_appchain_challenge_with(&mut challenge_state, None, 0);
+                    // The bonds of the witnesses are fully rewarded to the submitter.
+                    self.transfer_oct_token_to_challenge_submitter(
+                        submitter_account,
+                        slashed_amount,
+                    );
+                    challenge_state.slashed_amount = U128::from(slashed_amount);
+                    challenge_state.reward_amount = U128::from(slashed_amount);
+                    AnchorEvent::WitnessedMessageBatchReverted {
+                        batch_hash: batch_hash.clone(),
+                        slashed_witnesses: batch.witnesses,
+                        slashed_amount: U128::from(slashed_amount),
+                        challenger_id: submitter_account.clone(),
+                    }
+                    .emit();
+                }
+                Err(reason) => self.reject_appchain_challenge_with(&mut challenge_state, reason),
+            },
+        }
+        self.appchain_challenge_states
+            .insert(&challenge_index.0, &challenge_state);
+        PromiseOrValue::Value(U128::from(0))
+    }
+    //
+    pub fn get_submitted_appchain_challenge_state(&self, index: &U64) -> AppchainChallengeState {
+        let challenge_state = match self.appchain_challenge_states.get(&index.0) {
+            Some(challenge_state) => challenge_state,
+            None => panic!("Appchain challenge '{}' does not exist.", index.0),
+        };
+        assert!(
+            challenge_state.status == AppchainChallengeStatus::Submitted,
+            "Appchain challenge '{}' is already adjudicated.",
+            index.0
+        );
+        challenge_state
+    }
+    /// Mark the challenge as `Accepted`, refund the bond with reward to the submitter and
+    /// transfer the rest of slashed OCT token to the receiver of slashed OCT token.
+    pub fn accept_appchain_challenge_with(
+        &mut self,
+        challenge_state: &mut AppchainChallengeState,
+        punished_validator_id: Option<AccountId>,
+        slashed_amount: u128,
+    ) {
+        let protocol_settings = self.protocol_settings.get().unwrap();
+        let reward_amount =
+            slashed_amount * u128::from(protocol_settings.challenge_reward_percent) / 100;
+        if slashed_amount > reward_amount {
+            self.transfer_slashed_oct_token(slashed_amount - reward_amount);
+        }
+        self.transfer_oct_token_to_challenge_submitter(
+            &challenge_state.submitter_account,
+            challenge_state.bond_amount.0 + reward_amount,
+        );
+        challenge_state.status = AppchainChallengeStatus::Accepted;
+        challenge_state.punished_validator_id = punished_validator_id;
+        challenge_state.slashed_amount = U128::from(slashed_amount);
+        challenge_state.reward_amount = U128::from(reward_amount);
+    }
+    /// Mark the challenge as `Rejected`, and the bond is forfeited to
+    /// the receiver of slashed OCT token.
+    pub fn reject_appchain_challenge_with(
+        &mut self,
+        challenge_state: &mut AppchainChallengeState,
+        reason: String,
+    ) {
+        log!(
+            "Appchain challenge '{}' is rejected: {}",
+            challenge_state.index.0,
+            reason
+        );
+        if challenge_state.bond_amount.0 > 0 {
+            self.transfer_slashed_oct_token(challenge_state.bond_amount.0);
+        }
+        challenge_state.status = AppchainChallengeStatus::Rejected;
+        challenge_state.rejected_reason = Some(reason);
+    }
+    /// Mark the challenge as `Expired`, and refund the bond to the submitter.
+    pub fn expire_appchain_challenge_with(&mut self, challenge_state: &mut AppchainChallengeState) {
+        self.transfer_oct_token_to_challenge_submitter(
+            &challenge_state.submitter_account,
+            challenge_state.bond_amount.0,
+        );
+        challenge_state.status = AppchainChallengeStatus::Expired;
+    }
+    //
+    fn transfer_oct_token_to_challenge_submitter(
+        &self,
+        submitter_account: &AccountId,
+        amount: u128,
+    ) {
+        if amount > 0 {
+            ext_ft_core::ext(self.oct_token.get().unwrap().contract_account)
+                .with_attached_deposit(1)
+                .with_static_gas(Gas::ONE_TERA.mul(T_GAS_FOR_FT_TRANSFER))
+                .with_unused_gas_weight(0)
+                .ft_transfer(submitter_account.clone(), amount.into(), None);
+        }
+    }
+    /// Adjudicate an equivocation proof, punish the validator who signed both votes
+    /// and force to unbond the validator in next era.
+    ///
+    /// The `set_id` of the proof is mapped to the era of validator set by the authority sets
+    /// tracked by GRANDPA light client, and the identity is resolved to a validator
+    /// by the GRANDPA keys recorded in the validator set of the era.
+    ///
+    /// Return the id of punished validator and the slashed amount, or the reason
+    /// why the challenge is rejected.
+    pub fn adjudicate_equivocation_challenge(
+        &mut self,
+        proof: &EquivocationProof,
+        challenge_index: u64,
+    ) -> Result<(AccountId, u128), String> {
+        if !proof.is_valid() {
+            return Err("Invalid equivocation challenge data.".to_string());
+        }
+        let equivocation_key = proof.equivocation_key();
+        let era_number = match self
+            .era_numbers_of_grandpa_sets
+            .get(&u64::from(proof.set_id))
+        {
+            Some(era_number) => era_number,
+            None => {
+                return Err(format!(
+                    "Unknown GRANDPA authority set id '{}'.",
+                    proof.set_id
+                ))
+            }
+        };
+        let validator_set = match self.validator_set_histories.get().unwrap().get(&era_number) {
+            Some(validator_set) => validator_set,
+            None => return Err(format!("Missing validator set of era '{}'.", era_number)),
+        };
+        let validator_id = match validator_set
+            .get_validator_id_by_grandpa_key(&format!("0x{}", hex::encode(proof.identity().0)))
+        {
+            Some(validator_id) => validator_id,
+            None => {
+                return Err(format!(
+                    "The identity of equivocation is not a validator of era '{}'.",
+                    era_number
+                ))
+            }
+        };
+        //
+        let protocol_settings = self.protocol_settings.get().unwrap();
+        let mut next_validator_set = self.next_validator_set.get().unwrap();
+        let mut slashed_amount: u128 = 0;
+        if let Some(validator) = next_validator_set
+            .validator_set()
+            .get_validator(&validator_id)
+        {
+            let slashing_percent = match protocol_settings
+                .slashing_percent_of_offence_kinds
+                .get(EQUIVOCATION_OFFENCE_KIND)
+            {
+                Some(percent) => u128::from(*percent),
+                None => 0,
+            };
+            slashed_amount = validator.deposit_amount * slashing_percent / 100;
+            if slashed_amount > 0 {
+                let staking_history = self.record_staking_fact(StakingFact::ValidatorSlashed {
+                    validator_id: validator_id.clone(),
+                    amount: U128::from(slashed_amount),
+                });
+                next_validator_set.apply_staking_fact(&staking_history.staking_fact);
+            }
+            // The validators which are already unbonding will be removed in next era anyway.
+            if next_validator_set.contains_validator(&validator_id) {
+                next_validator_set.add_auto_unbonding_validator(&validator_id);
+            }
+        }
+        self.next_validator_set.set(&next_validator_set);
+        self.equivocation_challenge_indexes
+            .insert(&equivocation_key, &challenge_index);
+        self.sync_state_to_registry();
+        log!(
+            "Validator '{}' is punished for equivocation in era '{}'.",
+            validator_id,
+            era_number
+        );
+        Ok((validator_id, slashed_amount))
+    }
+    /// Verify the given messages with the state of beefy light client. If they are
+    /// committed by the appchain, cover all the nonces of a witnessed batch in its
+    /// challenge period, and conflict with the witnessed messages (a message with
+    /// the same nonce is different or is missing in the committed messages),
+    /// the batch is fraudulent.
+    ///
+    /// Return the fraudulent batch, or the reason why the challenge is rejected.
+    pub fn adjudicate_fraudulent_message_batch_challenge(
+        &mut self,
+        batch_hash: &String,
+        witnessed_messages: &Vec<u8>,
+        encoded_messages: &Vec<u8>,
+        header: &Vec<u8>,
+        mmr_leaf: &Vec<u8>,
+        mmr_proof: &Vec<u8>,
+        justification: &Option<Vec<u8>>,
+    ) -> Result<WitnessedMessageBatch, String> {
+        let batch = match self.get_challengeable_message_batch(batch_hash) {
+            Some(batch) => batch,
+            None => {
+                return Err(format!(
+                    "Message batch '{}' is not in challenge period.",
+                    batch_hash
+                ))
+            }
+        };
+        if hex::encode(env::sha256(witnessed_messages)).ne(batch_hash) {
+            return Err("The witnessed messages do not match the batch hash.".to_string());
+        }
+        // The committed messages must be verified by the light client,
+        // even if the witness mode is on.
+        self.light_client_message_verifier().verify(
+            self,
+            encoded_messages,
+            &AppchainMessagesProof {
+                header: header.clone(),
+                mmr_leaf: mmr_leaf.clone(),
+                mmr_proof: mmr_proof.clone(),
+                justification: justification.clone(),
+                commitment_block_number: None,
+            },
+        )?;
+        let witnessed_messages: Vec<RawMessage> = match Decode::decode(&mut &witnessed_messages[..])
+        {
+            Ok(messages) => messages,
+            Err(err) => return Err(format!("Failed to decode witnessed messages: {}", err)),
+        };
+        let committed_messages: Vec<RawMessage> = match Decode::decode(&mut &encoded_messages[..]) {
+            Ok(messages) => messages,
+            Err(err) => return Err(format!("Failed to decode messages: {}", err)),
+        };
+        let min_nonce = committed_messages
+            .iter()
+            .map(|message| message.nonce())
+            .min();
+        let max_nonce = committed_messages
+            .iter()
+            .map(|message| message.nonce())
+            .max();
+        let covers_batch = match (min_nonce, max_nonce) {
+            (Some(min_nonce), Some(max_nonce)) => batch
+                .nonces
+                .iter()
+                .all(|nonce| *nonce >= min_nonce && *nonce <= max_nonce),
+            _ => false,
+        };
+        if !covers_batch {
+            return Err(
+                "The committed messages do not cover the nonces of the witnessed batch."
+                    .to_string(),
+            );
+        }
+        let is_fraudulent = batch.nonces.iter().any(|nonce| {
+            let witnessed_message = witnessed_messages
+                .iter()
+                .find(|message| message.nonce() == *nonce)
+                .map(|message| message.encode());
+            let committed_message = committed_messages
+                .iter()
+                .find(|message| message.nonce() == *nonce)
+                .map(|message| message.encode());
+            witnessed_message != committed_message
+        });
+        match is_fraudulent {
+            true => Ok(batch),
+            false => Err("No conflict with the witnessed messages is found.".to_string()),
+        }
+    }
+    /// A conspiracy MMR challenge can be verified if the commitment at the block number
+    /// is retained with the state of beefy light client before it. This does not depend on
+    /// the witness mode, as the retained commitments are verified by the light client.
+    fn conspiracy_mmr_challenge_can_be_verified(&self, block_number: u32) -> bool {
+        self.retained_beefy_commitments
+            .get()
+            .unwrap()
+            .get_by_block_number(block_number)
+            .map_or(false, |commitment| {
+                commitment.previous_light_client_state.is_some()
+            })
+    }
+    /// Verify the given signed commitment with the state of beefy light client before
+    /// the retained commitment at the same block number is accepted. If the signed commitment
+    /// is signed by the validators of appchain at that time and its MMR root is different from
+    /// the retained commitment, the validators have conspired to forge the MMR.
+    pub fn adjudicate_conspiracy_mmr_challenge(
+        &self,
+        block_number: u32,
+        signed_commitment: &Vec<u8>,
+        validator_proofs: &Vec<ValidatorMerkleProof>,
+        mmr_leaf: &Vec<u8>,
+        mmr_proof: &Vec<u8>,
+    ) -> Result<(), String> {
+        let retained_commitment = match self
+            .retained_beefy_commitments
+            .get()
+            .unwrap()
+            .get_by_block_number(block_number)
+        {
+            Some(commitment) => commitment,
+            None => {
+                return Err(format!(
+                    "There is no retained commitment at block number '{}'.",
+                    block_number
+                ))
+            }
+        };
+        let mut light_client = match retained_commitment.previous_light_client_state {
+            Some(light_client) => light_client,
+            None => {
+                return Err(format!(
+                    "The state of beefy light client before commitment '{}' is not retained.",
+                    block_number
+                ))
+            }
+        };
+        if let Err(err) = light_client.update_state(
+            signed_commitment,
+            &validator_proofs
+                .iter()
+                .map(|proof| beefy_light_client::ValidatorMerkleProof {
+                    proof: proof.proof.clone(),
+                    number_of_leaves: proof.number_of_leaves.try_into().unwrap_or_default(),
+                    leaf_index: proof.leaf_index.try_into().unwrap_or_default(),
+                    leaf: proof.leaf.clone(),
+                })
+                .collect::<Vec<beefy_light_client::ValidatorMerkleProof>>(),
+            mmr_leaf,
+            mmr_proof,
+        ) {
+            return Err(format!("Invalid signed commitment: {:?}", err));
+        }
+        match light_client.get_latest_commitment() {
+            Some(commitment)
+                if commitment.block_number == block_number
+                    && RetainedBeefyCommitment::mmr_root_of(&commitment)
+                        != retained_commitment.mmr_root =>
+            {
+                Ok(())
+            }
+            _ => Err(
+                "The signed commitment does not conflict with the retained commitment.".to_string(),
+            ),
+        }
+    }
+}
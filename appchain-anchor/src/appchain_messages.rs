@@ -9,6 +9,10 @@ pub enum PayloadType {
     PlanNewEra,
     EraPayout,
     LockNft,
+    BurnNativeNear,
+    BurnNearNft,
+    LockAsset,
+    AuthoritySetChanged,
 }
 
 #[derive(Clone, Serialize, Deserialize, BorshDeserialize, BorshSerialize)]
@@ -34,6 +38,13 @@ pub struct PlanNewEraPayload {
     pub new_era: u32,
 }
 
+#[derive(Clone, Serialize, Deserialize, BorshDeserialize, BorshSerialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct AuthoritySetChangedPayload {
+    pub set_id: u64,
+    pub era: u32,
+}
+
 #[derive(Clone, Serialize, Deserialize, BorshDeserialize, BorshSerialize)]
 #[serde(crate = "near_sdk::serde")]
 pub struct Offender {
@@ -60,6 +71,32 @@ pub struct LockNftPayload {
     pub metadata: TokenMetadata,
 }
 
+#[derive(Clone, Serialize, Deserialize, BorshDeserialize, BorshSerialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct BurnNativeNearPayload {
+    pub sender: String,
+    pub receiver_id: AccountId,
+    pub amount: u128,
+}
+
+#[derive(Clone, Serialize, Deserialize, BorshDeserialize, BorshSerialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct BurnNearNftPayload {
+    pub sender: String,
+    pub receiver_id: AccountId,
+    pub collection: String,
+    pub token_id: String,
+}
+
+#[derive(Clone, Serialize, Deserialize, BorshDeserialize, BorshSerialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct LockAssetPayload {
+    pub asset_id: String,
+    pub sender: String,
+    pub receiver_id: AccountId,
+    pub amount: u128,
+}
+
 #[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
 #[serde(crate = "near_sdk::serde")]
 pub struct AppchainMessage {
@@ -77,6 +114,10 @@ pub enum MessagePayload {
     PlanNewEra(PlanNewEraPayload),
     EraPayout(EraPayoutPayload),
     LockNft(LockNftPayload),
+    BurnNativeNear(BurnNativeNearPayload),
+    BurnNearNft(BurnNearNftPayload),
+    LockAsset(LockAssetPayload),
+    AuthoritySetChanged(AuthoritySetChangedPayload),
 }
 
 #[derive(Encode, Decode, Clone)]
@@ -248,24 +289,51 @@ impl AppchainMessages {
         }
         self.min_nonce = *nonce;
     }
+    /// Remove the staged messages (and the processing results generated in staging)
+    /// with the given nonces.
+    pub fn remove_messages_of(&mut self, nonces: &Vec<u32>) {
+        for nonce in nonces {
+            self.message_map.remove(nonce);
+            self.processing_result_map.remove(nonce);
+        }
+        while self.max_nonce > self.min_nonce
+            && !self.message_map.contains_key(&self.max_nonce)
+            && !self.processing_result_map.contains_key(&self.max_nonce)
+        {
+            self.max_nonce -= 1;
+        }
+    }
 }
 
 impl AppchainAnchor {
-    ///
-    pub fn internal_stage_appchain_messages(&mut self, messages: &Vec<RawMessage>) {
+    /// Stage the given raw messages, and return the nonces of the messages
+    /// which are newly staged.
+    pub fn internal_stage_appchain_messages(&mut self, messages: &Vec<RawMessage>) -> Vec<u32> {
         let mut processing_status = self.permissionless_actions_status.get().unwrap();
         let mut appchain_messages = self.appchain_messages.get().unwrap();
+        let mut staged_nonces = Vec::<u32>::new();
         messages
             .iter()
             .filter(|message| {
                 message.nonce as u32 > processing_status.latest_applied_appchain_message_nonce
             })
             .for_each(|raw_message| {
-                self.internal_stage_raw_message(&mut appchain_messages, raw_message)
+                if appchain_messages
+                    .get_message(&raw_message.nonce())
+                    .is_none()
+                    && appchain_messages
+                        .get_processing_result(&raw_message.nonce())
+                        .is_none()
+                {
+                    staged_nonces.push(raw_message.nonce());
+                }
+                self.internal_stage_raw_message(&mut appchain_messages, raw_message);
+                self.latest_message_staged_timestamp = env::block_timestamp();
             });
         self.appchain_messages.set(&appchain_messages);
         processing_status.max_nonce_of_staged_appchain_messages = appchain_messages.max_nonce();
         self.permissionless_actions_status.set(&processing_status);
+        staged_nonces
     }
     //
     fn internal_stage_raw_message(
@@ -381,6 +449,10 @@ impl AppchainAnchor {
                             );
                             return;
                         }
+                        self.record_unslashed_offences(
+                            raw_message.nonce as u32,
+                            &payload.offenders,
+                        );
                         appchain_messages.insert_message(&AppchainMessage {
                             nonce: raw_message.nonce as u32,
                             appchain_event: AppchainEvent::EraRewardConcluded {
@@ -428,6 +500,115 @@ impl AppchainAnchor {
                     ),
                 }
             }
+            PayloadType::BurnNativeNear => {
+                let payload_result: Result<BurnNativeNearPayload, std::io::Error> =
+                    BorshDeserialize::deserialize(&mut &raw_message.payload[..]);
+                match payload_result {
+                    Ok(payload) => {
+                        log!(
+                            "Origin appchain message: '{}'",
+                            serde_json::to_string(&payload).unwrap()
+                        );
+                        appchain_messages.insert_message(&AppchainMessage {
+                            nonce: raw_message.nonce as u32,
+                            appchain_event: AppchainEvent::NativeNearBurnt {
+                                owner_id_in_appchain: payload.sender,
+                                receiver_id_in_near: payload.receiver_id,
+                                amount: payload.amount.into(),
+                            },
+                        });
+                    }
+                    Err(err) => appchain_messages.insert_processing_result(
+                        raw_message.nonce as u32,
+                        &AppchainMessageProcessingResult::Error {
+                            nonce: raw_message.nonce as u32,
+                            message: format!("Failed to deserialize raw message payload: {}", err),
+                        },
+                    ),
+                }
+            }
+            PayloadType::BurnNearNft => {
+                let payload_result: Result<BurnNearNftPayload, std::io::Error> =
+                    BorshDeserialize::deserialize(&mut &raw_message.payload[..]);
+                match payload_result {
+                    Ok(payload) => {
+                        log!(
+                            "Origin appchain message: '{}'",
+                            serde_json::to_string(&payload).unwrap()
+                        );
+                        appchain_messages.insert_message(&AppchainMessage {
+                            nonce: raw_message.nonce as u32,
+                            appchain_event: AppchainEvent::NearNFTBurnt {
+                                owner_id_in_appchain: payload.sender,
+                                receiver_id_in_near: payload.receiver_id,
+                                contract_account: payload.collection,
+                                token_id: payload.token_id,
+                            },
+                        });
+                    }
+                    Err(err) => appchain_messages.insert_processing_result(
+                        raw_message.nonce as u32,
+                        &AppchainMessageProcessingResult::Error {
+                            nonce: raw_message.nonce as u32,
+                            message: format!("Failed to deserialize raw message payload: {}", err),
+                        },
+                    ),
+                }
+            }
+            PayloadType::LockAsset => {
+                let payload_result: Result<LockAssetPayload, std::io::Error> =
+                    BorshDeserialize::deserialize(&mut &raw_message.payload[..]);
+                match payload_result {
+                    Ok(payload) => {
+                        log!(
+                            "Origin appchain message: '{}'",
+                            serde_json::to_string(&payload).unwrap()
+                        );
+                        appchain_messages.insert_message(&AppchainMessage {
+                            nonce: raw_message.nonce as u32,
+                            appchain_event: AppchainEvent::AppchainAssetLocked {
+                                asset_id: payload.asset_id,
+                                owner_id_in_appchain: payload.sender,
+                                receiver_id_in_near: payload.receiver_id,
+                                amount: payload.amount.into(),
+                            },
+                        });
+                    }
+                    Err(err) => appchain_messages.insert_processing_result(
+                        raw_message.nonce as u32,
+                        &AppchainMessageProcessingResult::Error {
+                            nonce: raw_message.nonce as u32,
+                            message: format!("Failed to deserialize raw message payload: {}", err),
+                        },
+                    ),
+                }
+            }
+            PayloadType::AuthoritySetChanged => {
+                let payload_result: Result<AuthoritySetChangedPayload, std::io::Error> =
+                    BorshDeserialize::deserialize(&mut &raw_message.payload[..]);
+                match payload_result {
+                    Ok(payload) => {
+                        log!(
+                            "Origin appchain message: '{}'",
+                            serde_json::to_string(&payload).unwrap()
+                        );
+                        appchain_messages.insert_message(&AppchainMessage {
+                            nonce: raw_message.nonce as u32,
+                            appchain_event: AppchainEvent::GrandpaAuthoritySetChanged {
+                                set_id: U64::from(payload.set_id),
+                                era_number: payload.era,
+                            },
+                        });
+                    }
+                    Err(err) => appchain_messages.insert_processing_result(
+                        raw_message.nonce as u32,
+                        &AppchainMessageProcessingResult::Error {
+                            nonce: raw_message.nonce as u32,
+                            message: format!("Failed to deserialize raw message payload: {}", err),
+                        },
+                    ),
+                }
+            }
         }
     }
     //
@@ -0,0 +1,578 @@
+use crate::{interfaces::BridgeFeeManager, *};
+use near_contract_standards::fungible_token::core::ext_ft_core;
+use near_sdk::json_types::I128;
+
+/// The denominator of percent in basis points.
+const BASIS_POINTS_DENOMINATOR: u128 = 10_000;
+
+pub trait BridgeFeeWithdrawalResolver {
+    /// Resolver for withdrawing collected bridge fee of a fungible token
+    fn resolve_bridge_fee_withdrawal(
+        &mut self,
+        contract_account: AccountId,
+        receiver_id: AccountId,
+        amount: U128,
+    );
+    /// Resolver for withdrawing collected bridge fee of NFTs
+    fn resolve_nft_bridge_fee_withdrawal(
+        &mut self,
+        fee_asset: NftBridgeFeeAsset,
+        receiver_id: AccountId,
+        amount: U128,
+    );
+    /// Resolver for withdrawing prepaid bridge fee deposit of NFT transfers
+    fn resolve_nft_bridge_fee_deposit_withdrawal(
+        &mut self,
+        fee_asset: NftBridgeFeeAsset,
+        account_id: AccountId,
+        amount: U128,
+    );
+}
+
+/// The bridge fee charged for an NFT transfer to NEAR protocol,
+/// which is refunded to the payer if the transfer fails.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct NftBridgeFeeCharge {
+    pub payer_id: AccountId,
+    pub fee_asset: NftBridgeFeeAsset,
+    pub amount: U128,
+}
+
+/// The direction of a bridge transfer.
+pub enum BridgingDirection {
+    /// From NEAR protocol to the appchain.
+    ToAppchain,
+    /// From the appchain to NEAR protocol.
+    ToNear,
+}
+
+impl BridgeFee {
+    /// Calculate the fee of a transferred amount, which is no more than the amount.
+    pub fn calculate(&self, amount: u128) -> u128 {
+        let percent_fee = amount / BASIS_POINTS_DENOMINATOR
+            * u128::from(self.percent_in_basis_points)
+            + amount % BASIS_POINTS_DENOMINATOR * u128::from(self.percent_in_basis_points)
+                / BASIS_POINTS_DENOMINATOR;
+        std::cmp::min(amount, self.fixed_amount.0.saturating_add(percent_fee))
+    }
+    //
+    fn assert_valid(&self) {
+        assert!(
+            u128::from(self.percent_in_basis_points) <= BASIS_POINTS_DENOMINATOR,
+            "The percent of bridge fee should not be greater than {} basis points.",
+            BASIS_POINTS_DENOMINATOR
+        );
+    }
+}
+
+impl Default for NftBridgeFeeBalance {
+    fn default() -> Self {
+        Self {
+            native_near: U128::from(0),
+            oct_token: U128::from(0),
+        }
+    }
+}
+
+impl NftBridgeFeeBalance {
+    //
+    pub fn get(&self, fee_asset: &NftBridgeFeeAsset) -> u128 {
+        match fee_asset {
+            NftBridgeFeeAsset::NativeNear => self.native_near.0,
+            NftBridgeFeeAsset::OctToken => self.oct_token.0,
+        }
+    }
+    //
+    pub fn add(&mut self, fee_asset: &NftBridgeFeeAsset, amount: u128) {
+        match fee_asset {
+            NftBridgeFeeAsset::NativeNear => {
+                self.native_near = U128::from(self.native_near.0 + amount)
+            }
+            NftBridgeFeeAsset::OctToken => self.oct_token = U128::from(self.oct_token.0 + amount),
+        }
+    }
+    //
+    pub fn sub(&mut self, fee_asset: &NftBridgeFeeAsset, amount: u128) {
+        match fee_asset {
+            NftBridgeFeeAsset::NativeNear => {
+                self.native_near = U128::from(self.native_near.0 - amount)
+            }
+            NftBridgeFeeAsset::OctToken => self.oct_token = U128::from(self.oct_token.0 - amount),
+        }
+    }
+}
+
+#[near_bindgen]
+impl BridgeFeeManager for AppchainAnchor {
+    //
+    fn set_bridge_fee_of_token(
+        &mut self,
+        contract_account: AccountId,
+        fee_to_appchain: BridgeFee,
+        fee_to_near: BridgeFee,
+    ) {
+        self.assert_owner();
+        assert!(
+            self.near_fungible_tokens
+                .get()
+                .unwrap()
+                .get_by_contract_account(&contract_account)
+                .is_some()
+                || self.is_contract_of_wrapped_appchain_token(&contract_account),
+            "Invalid contract account of fungible token: '{}'.",
+            contract_account
+        );
+        fee_to_appchain.assert_valid();
+        fee_to_near.assert_valid();
+        let bridge_fee = match self.bridge_fees_of_tokens.get(&contract_account) {
+            Some(mut bridge_fee) => {
+                bridge_fee.fee_to_appchain = fee_to_appchain;
+                bridge_fee.fee_to_near = fee_to_near;
+                bridge_fee
+            }
+            None => BridgeFeeOfToken {
+                fee_to_appchain,
+                fee_to_near,
+                collected_amount: U128::from(0),
+                withdrawn_amount: U128::from(0),
+            },
+        };
+        self.bridge_fees_of_tokens
+            .insert(&contract_account, &bridge_fee);
+    }
+    //
+    fn withdraw_bridge_fee_of_token(&mut self, contract_account: AccountId) {
+        let receiver_id = self.assert_bridge_fee_collector();
+        let mut bridge_fee = self
+            .bridge_fees_of_tokens
+            .get(&contract_account)
+            .expect("No bridge fee of the token.");
+        let amount = bridge_fee.collected_amount.0;
+        assert!(amount > 0, "No collected bridge fee to withdraw.");
+        bridge_fee.collected_amount = U128::from(0);
+        self.bridge_fees_of_tokens
+            .insert(&contract_account, &bridge_fee);
+        let promise = match self.is_contract_of_wrapped_appchain_token(&contract_account) {
+            // The bridge fee of wrapped appchain token is burnt (or not minted) when
+            // it is charged, so it is minted to the receiver when withdrawing.
+            true => {
+                #[derive(near_sdk::serde::Serialize)]
+                #[serde(crate = "near_sdk::serde")]
+                struct Args {
+                    account_id: AccountId,
+                    amount: U128,
+                }
+                let args = near_sdk::serde_json::to_vec(&Args {
+                    account_id: receiver_id.clone(),
+                    amount: U128::from(amount),
+                })
+                .expect("Failed to serialize the cross contract args using JSON.");
+                Promise::new(contract_account.clone()).function_call(
+                    "mint".to_string(),
+                    args,
+                    STORAGE_DEPOSIT_FOR_NEP141_TOEKN,
+                    Gas::ONE_TERA.mul(T_GAS_FOR_MINT_FUNGIBLE_TOKEN),
+                )
+            }
+            false => ext_ft_core::ext(contract_account.clone())
+                .with_attached_deposit(1)
+                .with_static_gas(Gas::ONE_TERA.mul(T_GAS_FOR_FT_TRANSFER))
+                .with_unused_gas_weight(0)
+                .ft_transfer(receiver_id.clone(), U128::from(amount), None),
+        };
+        promise.then(
+            ext_self::ext(env::current_account_id())
+                .with_attached_deposit(0)
+                .with_static_gas(Gas::ONE_TERA.mul(T_GAS_FOR_RESOLVER_FUNCTION))
+                .with_unused_gas_weight(0)
+                .resolve_bridge_fee_withdrawal(contract_account, receiver_id, U128::from(amount)),
+        );
+    }
+    //
+    fn set_bridge_fee_of_nft(&mut self, contract_account: AccountId, bridge_fee: NftBridgeFee) {
+        self.assert_owner();
+        assert!(
+            self.near_nft_collections
+                .get()
+                .unwrap()
+                .contains(&contract_account)
+                || self
+                    .wrapped_appchain_nfts
+                    .get()
+                    .unwrap()
+                    .get_by_contract_account(&contract_account)
+                    .is_some(),
+            "Invalid contract account of NFT: '{}'.",
+            contract_account
+        );
+        self.bridge_fees_of_nfts
+            .insert(&contract_account, &bridge_fee);
+    }
+    //
+    #[payable]
+    fn deposit_nft_bridge_fee(&mut self) {
+        let amount = env::attached_deposit();
+        assert!(amount > 0, "Attached deposit should not be zero.");
+        self.internal_deposit_nft_bridge_fee(
+            &env::predecessor_account_id(),
+            &NftBridgeFeeAsset::NativeNear,
+            amount,
+        );
+    }
+    //
+    fn withdraw_nft_bridge_fee_deposit(&mut self, fee_asset: NftBridgeFeeAsset, amount: U128) {
+        let account_id = env::predecessor_account_id();
+        let mut deposit = self
+            .nft_bridge_fee_deposits
+            .get(&account_id)
+            .unwrap_or_default();
+        assert!(
+            amount.0 > 0 && deposit.get(&fee_asset) >= amount.0,
+            "Not enough bridge fee deposit to withdraw."
+        );
+        deposit.sub(&fee_asset, amount.0);
+        self.nft_bridge_fee_deposits.insert(&account_id, &deposit);
+        self.nft_bridge_fees.deposited.sub(&fee_asset, amount.0);
+        self.transfer_nft_bridge_fee(&fee_asset, &account_id, amount.0)
+            .then(
+                ext_self::ext(env::current_account_id())
+                    .with_attached_deposit(0)
+                    .with_static_gas(Gas::ONE_TERA.mul(T_GAS_FOR_RESOLVER_FUNCTION))
+                    .with_unused_gas_weight(0)
+                    .resolve_nft_bridge_fee_deposit_withdrawal(fee_asset, account_id, amount),
+            );
+    }
+    //
+    fn withdraw_nft_bridge_fee(&mut self, fee_asset: NftBridgeFeeAsset) {
+        let receiver_id = self.assert_bridge_fee_collector();
+        let amount = self.nft_bridge_fees.collected.get(&fee_asset);
+        assert!(amount > 0, "No collected bridge fee to withdraw.");
+        self.nft_bridge_fees.collected.sub(&fee_asset, amount);
+        self.transfer_nft_bridge_fee(&fee_asset, &receiver_id, amount)
+            .then(
+                ext_self::ext(env::current_account_id())
+                    .with_attached_deposit(0)
+                    .with_static_gas(Gas::ONE_TERA.mul(T_GAS_FOR_RESOLVER_FUNCTION))
+                    .with_unused_gas_weight(0)
+                    .resolve_nft_bridge_fee_withdrawal(fee_asset, receiver_id, U128::from(amount)),
+            );
+    }
+}
+
+#[near_bindgen]
+impl BridgeFeeWithdrawalResolver for AppchainAnchor {
+    //
+    fn resolve_bridge_fee_withdrawal(
+        &mut self,
+        contract_account: AccountId,
+        receiver_id: AccountId,
+        amount: U128,
+    ) {
+        assert_self();
+        let mut bridge_fee = self.bridge_fees_of_tokens.get(&contract_account).unwrap();
+        match env::promise_result(0) {
+            PromiseResult::NotReady => unreachable!(),
+            PromiseResult::Successful(_) => {
+                if self.is_contract_of_wrapped_appchain_token(&contract_account) {
+                    let mut wrapped_appchain_token = self.wrapped_appchain_token.get().unwrap();
+                    wrapped_appchain_token.changed_balance = I128::from(
+                        wrapped_appchain_token.changed_balance.0
+                            + i128::try_from(amount.0).unwrap(),
+                    );
+                    self.wrapped_appchain_token.set(&wrapped_appchain_token);
+                }
+                bridge_fee.withdrawn_amount = U128::from(bridge_fee.withdrawn_amount.0 + amount.0);
+                AnchorEvent::BridgeFeeWithdrawn {
+                    contract_account: contract_account.clone(),
+                    receiver_id,
+                    amount,
+                }
+                .emit();
+            }
+            PromiseResult::Failed => {
+                bridge_fee.collected_amount = U128::from(bridge_fee.collected_amount.0 + amount.0);
+                log!(
+                    "Failed to withdraw bridge fee of token '{}' to '{}'. Amount: '{}'",
+                    &contract_account,
+                    &receiver_id,
+                    &amount.0
+                );
+            }
+        }
+        self.bridge_fees_of_tokens
+            .insert(&contract_account, &bridge_fee);
+    }
+    //
+    fn resolve_nft_bridge_fee_withdrawal(
+        &mut self,
+        fee_asset: NftBridgeFeeAsset,
+        receiver_id: AccountId,
+        amount: U128,
+    ) {
+        assert_self();
+        match env::promise_result(0) {
+            PromiseResult::NotReady => unreachable!(),
+            PromiseResult::Successful(_) => {
+                self.nft_bridge_fees.withdrawn.add(&fee_asset, amount.0);
+                AnchorEvent::NftBridgeFeeWithdrawn {
+                    fee_asset,
+                    receiver_id,
+                    amount,
+                }
+                .emit();
+            }
+            PromiseResult::Failed => {
+                self.nft_bridge_fees.collected.add(&fee_asset, amount.0);
+                log!(
+                    "Failed to withdraw bridge fee of NFTs to '{}'. Amount: '{}'",
+                    &receiver_id,
+                    &amount.0
+                );
+            }
+        }
+    }
+    //
+    fn resolve_nft_bridge_fee_deposit_withdrawal(
+        &mut self,
+        fee_asset: NftBridgeFeeAsset,
+        account_id: AccountId,
+        amount: U128,
+    ) {
+        assert_self();
+        match env::promise_result(0) {
+            PromiseResult::NotReady => unreachable!(),
+            PromiseResult::Successful(_) => {}
+            PromiseResult::Failed => {
+                self.internal_deposit_nft_bridge_fee(&account_id, &fee_asset, amount.0);
+                log!(
+                    "Failed to withdraw bridge fee deposit of NFT transfers to '{}'. Amount: '{}'",
+                    &account_id,
+                    &amount.0
+                );
+            }
+        }
+    }
+}
+
+impl AppchainAnchor {
+    /// Get the bridge fee of a transfer of a fungible token.
+    pub fn get_bridge_fee_of_transfer(
+        &self,
+        contract_account: &AccountId,
+        amount: u128,
+        direction: BridgingDirection,
+    ) -> u128 {
+        match self.bridge_fees_of_tokens.get(contract_account) {
+            Some(bridge_fee) => match direction {
+                BridgingDirection::ToAppchain => bridge_fee.fee_to_appchain.calculate(amount),
+                BridgingDirection::ToNear => bridge_fee.fee_to_near.calculate(amount),
+            },
+            None => 0,
+        }
+    }
+    /// Charge bridge fee from a transfer of a fungible token, and return the net amount.
+    pub fn charge_bridge_fee(
+        &mut self,
+        contract_account: &AccountId,
+        amount: u128,
+        direction: BridgingDirection,
+    ) -> u128 {
+        let fee_amount = self.get_bridge_fee_of_transfer(contract_account, amount, direction);
+        if fee_amount > 0 {
+            let mut bridge_fee = self.bridge_fees_of_tokens.get(contract_account).unwrap();
+            bridge_fee.collected_amount = U128::from(bridge_fee.collected_amount.0 + fee_amount);
+            self.bridge_fees_of_tokens
+                .insert(contract_account, &bridge_fee);
+            AnchorEvent::BridgeFeeCharged {
+                contract_account: contract_account.clone(),
+                amount: U128::from(amount),
+                fee_amount: U128::from(fee_amount),
+            }
+            .emit();
+        }
+        amount - fee_amount
+    }
+    /// Get the fee asset and amount of the bridge fee of an NFT transfer, if any.
+    fn get_nft_bridge_fee_of_transfer(
+        &self,
+        contract_account: &AccountId,
+        direction: BridgingDirection,
+    ) -> Option<(NftBridgeFeeAsset, u128)> {
+        self.bridge_fees_of_nfts
+            .get(contract_account)
+            .map(|bridge_fee| match direction {
+                BridgingDirection::ToAppchain => {
+                    (bridge_fee.fee_asset, bridge_fee.fee_to_appchain.0)
+                }
+                BridgingDirection::ToNear => (bridge_fee.fee_asset, bridge_fee.fee_to_near.0),
+            })
+            .filter(|(_, amount)| *amount > 0)
+    }
+    /// Check that the prepaid deposit of the payer covers the bridge fee of an NFT transfer.
+    pub fn check_nft_bridge_fee_deposit(
+        &self,
+        contract_account: &AccountId,
+        payer_id: &AccountId,
+        direction: BridgingDirection,
+    ) -> Result<(), String> {
+        if let Some((fee_asset, amount)) =
+            self.get_nft_bridge_fee_of_transfer(contract_account, direction)
+        {
+            let deposit = self
+                .nft_bridge_fee_deposits
+                .get(payer_id)
+                .unwrap_or_default();
+            if deposit.get(&fee_asset) < amount {
+                return Err(format!(
+                    "Not enough bridge fee deposit of '{}' for NFT transfer. Required: {:?} {}.",
+                    payer_id, fee_asset, amount
+                ));
+            }
+        }
+        Ok(())
+    }
+    /// Take the bridge fee of an NFT transfer from the prepaid deposit of the payer,
+    /// which should be checked by `check_nft_bridge_fee_deposit` before.
+    fn take_nft_bridge_fee_from_deposit(
+        &mut self,
+        contract_account: &AccountId,
+        payer_id: &AccountId,
+        direction: BridgingDirection,
+    ) -> Option<(NftBridgeFeeAsset, u128)> {
+        let (fee_asset, amount) =
+            self.get_nft_bridge_fee_of_transfer(contract_account, direction)?;
+        let mut deposit = self.nft_bridge_fee_deposits.get(payer_id).unwrap();
+        deposit.sub(&fee_asset, amount);
+        self.nft_bridge_fee_deposits.insert(payer_id, &deposit);
+        self.nft_bridge_fees.deposited.sub(&fee_asset, amount);
+        AnchorEvent::NftBridgeFeeCharged {
+            contract_account: contract_account.clone(),
+            payer_id: payer_id.clone(),
+            fee_asset: fee_asset.clone(),
+            fee_amount: U128::from(amount),
+        }
+        .emit();
+        Some((fee_asset, amount))
+    }
+    /// Charge the bridge fee of an NFT transfer to the appchain from the sender.
+    pub fn charge_nft_bridge_fee_to_appchain(
+        &mut self,
+        contract_account: &AccountId,
+        sender_id: &AccountId,
+    ) {
+        if let Some((fee_asset, amount)) = self.take_nft_bridge_fee_from_deposit(
+            contract_account,
+            sender_id,
+            BridgingDirection::ToAppchain,
+        ) {
+            self.nft_bridge_fees.collected.add(&fee_asset, amount);
+        }
+    }
+    /// Charge the bridge fee of an NFT transfer to NEAR protocol from the receiver.
+    /// The fee is pending until the transfer is resolved.
+    pub fn charge_nft_bridge_fee_to_near(
+        &mut self,
+        contract_account: &AccountId,
+        receiver_id: &AccountId,
+        appchain_message_nonce: u32,
+    ) {
+        if let Some((fee_asset, amount)) = self.take_nft_bridge_fee_from_deposit(
+            contract_account,
+            receiver_id,
+            BridgingDirection::ToNear,
+        ) {
+            self.nft_bridge_fees.pending.add(&fee_asset, amount);
+            self.pending_nft_bridge_fee_charges.insert(
+                &appchain_message_nonce,
+                &NftBridgeFeeCharge {
+                    payer_id: receiver_id.clone(),
+                    fee_asset,
+                    amount: U128::from(amount),
+                },
+            );
+        }
+    }
+    /// Settle the pending bridge fee of an NFT transfer to NEAR protocol. The fee is
+    /// collected if the transfer succeeded, otherwise it is refunded to the payer.
+    pub fn settle_nft_bridge_fee_charge(&mut self, appchain_message_nonce: u32, succeeded: bool) {
+        if let Some(charge) = self
+            .pending_nft_bridge_fee_charges
+            .remove(&appchain_message_nonce)
+        {
+            self.nft_bridge_fees
+                .pending
+                .sub(&charge.fee_asset, charge.amount.0);
+            match succeeded {
+                true => self
+                    .nft_bridge_fees
+                    .collected
+                    .add(&charge.fee_asset, charge.amount.0),
+                false => self.internal_deposit_nft_bridge_fee(
+                    &charge.payer_id,
+                    &charge.fee_asset,
+                    charge.amount.0,
+                ),
+            }
+        }
+    }
+    //
+    pub fn internal_deposit_nft_bridge_fee(
+        &mut self,
+        account_id: &AccountId,
+        fee_asset: &NftBridgeFeeAsset,
+        amount: u128,
+    ) {
+        let mut deposit = self
+            .nft_bridge_fee_deposits
+            .get(account_id)
+            .unwrap_or_default();
+        deposit.add(fee_asset, amount);
+        self.nft_bridge_fee_deposits.insert(account_id, &deposit);
+        self.nft_bridge_fees.deposited.add(fee_asset, amount);
+    }
+    //
+    fn transfer_nft_bridge_fee(
+        &self,
+        fee_asset: &NftBridgeFeeAsset,
+        receiver_id: &AccountId,
+        amount: u128,
+    ) -> Promise {
+        match fee_asset {
+            NftBridgeFeeAsset::NativeNear => Promise::new(receiver_id.clone()).transfer(amount),
+            NftBridgeFeeAsset::OctToken => {
+                ext_ft_core::ext(self.oct_token.get().unwrap().contract_account)
+                    .with_attached_deposit(1)
+                    .with_static_gas(Gas::ONE_TERA.mul(T_GAS_FOR_FT_TRANSFER))
+                    .with_unused_gas_weight(0)
+                    .ft_transfer(receiver_id.clone(), U128::from(amount), None)
+            }
+        }
+    }
+    /// Assert that the caller is the bridge fee collector (or the owner if it is not set),
+    /// and return it.
+    fn assert_bridge_fee_collector(&self) -> AccountId {
+        let collector = match self
+            .anchor_settings
+            .get()
+            .unwrap()
+            .bridge_fee_collector_account
+        {
+            Some(account_id) => account_id,
+            None => self.owner.clone(),
+        };
+        assert_eq!(
+            env::predecessor_account_id(),
+            collector,
+            "Only the bridge fee collector can call this function."
+        );
+        collector
+    }
+    //
+    pub fn is_contract_of_wrapped_appchain_token(&self, contract_account: &AccountId) -> bool {
+        self.wrapped_appchain_token
+            .get()
+            .unwrap()
+            .contract_account
+            .map_or(false, |account_id| account_id.eq(contract_account))
+    }
+}
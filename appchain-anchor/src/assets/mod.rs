@@ -1,3 +1,11 @@
+pub mod bridge_fees;
+mod native_near_token;
 pub mod near_fungible_tokens;
+pub mod near_nft_collections;
+pub mod net_locked_balances;
+mod pending_transfers;
+mod rate_limits;
+mod storage_registration;
+pub mod wrapped_appchain_assets;
 pub mod wrapped_appchain_nfts;
 mod wrapped_appchain_token;
@@ -0,0 +1,316 @@
+use super::bridge_fees::BridgingDirection;
+use crate::{
+    interfaces::NativeNearTokenManager, permissionless_actions::AppchainMessagesProcessingContext,
+    *,
+};
+
+pub trait NativeNearTokenResolver {
+    /// Resolver for transfer native NEAR token
+    fn resolve_native_near_transfer(
+        &mut self,
+        sender_id_in_appchain: String,
+        receiver_id_in_near: AccountId,
+        amount: U128,
+        appchain_message_nonce: u32,
+    );
+}
+
+impl Default for NativeNearToken {
+    fn default() -> Self {
+        Self {
+            price_in_usd: U128::from(0),
+            locked_balance: U128::from(0),
+            bridging_state: BridgingState::Closed,
+        }
+    }
+}
+
+impl NativeNearToken {
+    ///
+    pub fn total_market_value(&self) -> Balance {
+        self.get_market_value_of(self.locked_balance.0)
+    }
+    ///
+    pub fn get_market_value_of(&self, amount: u128) -> Balance {
+        amount / NEAR_DECIMALS_VALUE * self.price_in_usd.0
+    }
+}
+
+#[near_bindgen]
+impl NativeNearTokenManager for AppchainAnchor {
+    //
+    fn set_price_of_native_near_token(&mut self, price: U128) {
+        self.assert_token_price_maintainer();
+        self.native_near_token.price_in_usd = price;
+    }
+    //
+    fn open_bridging_of_native_near_token(&mut self) {
+        self.assert_owner();
+        assert!(
+            self.native_near_token
+                .bridging_state
+                .ne(&BridgingState::Active),
+            "Bridging is already active."
+        );
+        self.native_near_token.bridging_state = BridgingState::Active;
+    }
+    //
+    fn close_bridging_of_native_near_token(&mut self) {
+        self.assert_owner();
+        assert!(
+            self.native_near_token
+                .bridging_state
+                .ne(&BridgingState::Closed),
+            "Bridging is already closed."
+        );
+        self.native_near_token.bridging_state = BridgingState::Closed;
+    }
+    //
+    #[payable]
+    fn lock_near_to_appchain(&mut self, receiver_id_in_appchain: String) {
+        self.assert_asset_transfer_is_not_paused();
+        self.assert_appchain_is_not_frozen();
+        let sender_id = env::predecessor_account_id();
+        let amount = env::attached_deposit();
+        assert!(amount > 0, "Attached deposit should be greater than 0.");
+        assert!(
+            self.native_near_token
+                .bridging_state
+                .eq(&BridgingState::Active),
+            "Bridging for native NEAR token is not active."
+        );
+        match self.appchain_state {
+            AppchainState::Broken | AppchainState::Dead => panic!(
+                "Cannot bridge token while appchain state is '{}'.",
+                serde_json::to_string(&self.appchain_state).unwrap()
+            ),
+            _ => (),
+        };
+        AccountIdInAppchain::new(
+            Some(receiver_id_in_appchain.clone()),
+            &self.appchain_template_type,
+        )
+        .assert_valid();
+        let protocol_settings = self.protocol_settings.get().unwrap();
+        assert!(
+            self.near_fungible_tokens
+                .get()
+                .unwrap()
+                .total_market_value()
+                + self.native_near_token.total_market_value()
+                + self.native_near_token.get_market_value_of(amount)
+                <= self.get_market_value_of_staked_oct_token().0
+                    * u128::from(
+                        protocol_settings.maximum_market_value_percent_of_near_fungible_tokens
+                    )
+                    / 100,
+            "Too much native NEAR token to lock."
+        );
+        // Refund without panic, to keep the paused state of native NEAR token (if any).
+        if let Err(message) = self.check_and_record_bridge_volume(
+            &env::current_account_id(),
+            amount,
+            BridgingDirection::ToAppchain,
+        ) {
+            log!("{} Return deposit.", message);
+            Promise::new(sender_id).transfer(amount);
+            return;
+        }
+        self.native_near_token.locked_balance =
+            match self.native_near_token.locked_balance.0.checked_add(amount) {
+                Some(value) => U128::from(value),
+                None => panic!("Locked balance overflow."),
+            };
+        self.increase_net_locked_balance(&env::current_account_id(), &sender_id, amount);
+        let appchain_notification_history =
+            self.internal_append_appchain_notification(AppchainNotification::NativeNearLocked {
+                sender_id_in_near: sender_id.clone(),
+                receiver_id_in_appchain: receiver_id_in_appchain.clone(),
+                amount: U128::from(amount),
+            });
+        log!(
+            "Received native NEAR token from '{}'. Start transfer to '{}' of appchain. Amount: '{}', Crosschain notification index: '{}'.",
+            &sender_id,
+            &receiver_id_in_appchain,
+            &amount,
+            &appchain_notification_history.index.0
+        );
+        AnchorEvent::NativeNearLocked {
+            sender_id_in_near: sender_id,
+            receiver_id_in_appchain,
+            amount: U128::from(amount),
+        }
+        .emit();
+    }
+}
+
+impl AppchainAnchor {
+    /// Get the NEAR balance of this contract which can not be spent, including the locked
+    /// native NEAR token, the bridge storage fund, the bridge fee of NFTs paid in NEAR
+    /// and the balance for storage staking.
+    pub fn get_reserved_near_balance(&self) -> Balance {
+        self.native_near_token.locked_balance.0
+            + self.bridge_storage_fund.balance.0
+            + self.nft_bridge_fees.deposited.native_near.0
+            + self.nft_bridge_fees.pending.native_near.0
+            + self.nft_bridge_fees.collected.native_near.0
+            + u128::from(env::storage_usage()) * env::storage_byte_cost()
+    }
+    /// Whether the NEAR balance of this contract covers the reserved balance
+    /// and the given amount to spend.
+    pub fn near_balance_is_enough_for(&self, amount: Balance) -> bool {
+        env::account_balance() >= self.get_reserved_near_balance() + amount
+    }
+    //
+    pub fn internal_unlock_native_near(
+        &mut self,
+        sender_id_in_appchain: &String,
+        receiver_id_in_near: &AccountId,
+        amount: &U128,
+        appchain_message_nonce: u32,
+        processing_context: &mut AppchainMessagesProcessingContext,
+    ) -> MultiTxsOperationProcessingResult {
+        let message = if self
+            .native_near_token
+            .bridging_state
+            .ne(&BridgingState::Active)
+        {
+            Some(format!("Bridging for native NEAR token is not active."))
+        } else if self.native_near_token.locked_balance.0 < amount.0 {
+            Some(format!(
+                "Not enough locked balance of native NEAR token. Amount: '{}'",
+                amount.0
+            ))
+        } else {
+            self.check_and_record_bridge_volume(
+                &env::current_account_id(),
+                amount.0,
+                BridgingDirection::ToNear,
+            )
+            .err()
+        };
+        if let Some(message) = message {
+            let result = AppchainMessageProcessingResult::Error {
+                nonce: appchain_message_nonce,
+                message: message.clone(),
+            };
+            self.record_appchain_message_processing_result(&result);
+            return MultiTxsOperationProcessingResult::Error(message);
+        }
+        self.native_near_token.locked_balance =
+            U128::from(self.native_near_token.locked_balance.0 - amount.0);
+        self.decrease_net_locked_balance(&env::current_account_id(), receiver_id_in_near, amount.0);
+        let prepaid_gas = self.internal_pay_out_transfer(
+            &env::current_account_id(),
+            receiver_id_in_near,
+            PendingTransfer::NativeNearUnlocking {
+                sender_id_in_appchain: sender_id_in_appchain.clone(),
+                amount: amount.clone(),
+                appchain_message_nonce,
+            },
+        );
+        processing_context.add_prepaid_gas(prepaid_gas);
+        MultiTxsOperationProcessingResult::Ok
+    }
+}
+
+/// Transfer native NEAR token to the receiver,
+/// and resolve the result by `resolve_native_near_transfer`.
+pub fn transfer_native_near_and_resolve(
+    sender_id_in_appchain: &String,
+    receiver_id_in_near: &AccountId,
+    amount: &U128,
+    appchain_message_nonce: u32,
+) {
+    Promise::new(receiver_id_in_near.clone())
+        .transfer(amount.0)
+        .then(
+            ext_self::ext(env::current_account_id())
+                .with_attached_deposit(0)
+                .with_static_gas(Gas::ONE_TERA.mul(T_GAS_FOR_RESOLVER_FUNCTION))
+                .with_unused_gas_weight(0)
+                .resolve_native_near_transfer(
+                    sender_id_in_appchain.clone(),
+                    receiver_id_in_near.clone(),
+                    amount.clone(),
+                    appchain_message_nonce,
+                ),
+        );
+}
+
+#[near_bindgen]
+impl NativeNearTokenResolver for AppchainAnchor {
+    //
+    fn resolve_native_near_transfer(
+        &mut self,
+        sender_id_in_appchain: String,
+        receiver_id_in_near: AccountId,
+        amount: U128,
+        appchain_message_nonce: u32,
+    ) {
+        assert_self();
+        match env::promise_result(0) {
+            PromiseResult::NotReady => unreachable!(),
+            PromiseResult::Successful(_) => {
+                let message = format!(
+                    "Native NEAR token with amount '{}' for appchain account '{}' is unlocked.",
+                    amount.0, sender_id_in_appchain
+                );
+                self.record_appchain_message_processing_result(
+                    &AppchainMessageProcessingResult::Ok {
+                        nonce: appchain_message_nonce,
+                        message: Some(message),
+                    },
+                );
+                AnchorEvent::NativeNearUnlocked {
+                    sender_id_in_appchain,
+                    receiver_id_in_near,
+                    amount,
+                    appchain_message_nonce,
+                }
+                .emit();
+            }
+            PromiseResult::Failed => {
+                let reason = format!(
+                    "Maybe the receiver account '{}' is not exised.",
+                    &receiver_id_in_near
+                );
+                let message = format!(
+                    "Failed to unlock native NEAR token with amount '{}' for appchain account '{}'. {}",
+                    amount.0, sender_id_in_appchain, reason
+                );
+                self.record_appchain_message_processing_result(
+                    &AppchainMessageProcessingResult::Error {
+                        nonce: appchain_message_nonce,
+                        message,
+                    },
+                );
+                // The token is refunded to this contract, restore the locked balance and
+                // keep the transfer pending for claiming.
+                self.native_near_token.locked_balance =
+                    U128::from(self.native_near_token.locked_balance.0 + amount.0);
+                self.increase_net_locked_balance(
+                    &env::current_account_id(),
+                    &receiver_id_in_near,
+                    amount.0,
+                );
+                self.add_pending_transfer(
+                    &receiver_id_in_near,
+                    PendingTransfer::NativeNearUnlocking {
+                        sender_id_in_appchain: sender_id_in_appchain.clone(),
+                        amount,
+                        appchain_message_nonce,
+                    },
+                );
+                AnchorEvent::FailedToUnlockNativeNear {
+                    sender_id_in_appchain,
+                    receiver_id_in_near,
+                    amount,
+                    appchain_message_nonce,
+                    reason,
+                }
+                .emit();
+            }
+        }
+    }
+}
@@ -1,6 +1,7 @@
 use near_contract_standards::fungible_token::core::ext_ft_core;
 use near_contract_standards::fungible_token::metadata::FungibleTokenMetadata;
 
+use super::bridge_fees::BridgingDirection;
 use crate::{
     interfaces::NearFungibleTokenManager,
     permissionless_actions::AppchainMessagesProcessingContext, *,
@@ -224,7 +225,7 @@ impl AppchainAnchor {
                 near_fungible_token
                     .bridging_state
                     .eq(&BridgingState::Active),
-                "Bridging for '{}({})' is closed.",
+                "Bridging for '{}({})' is not active.",
                 near_fungible_token.metadata.symbol,
                 near_fungible_token.metadata.name
             );
@@ -232,6 +233,13 @@ impl AppchainAnchor {
                 FTDepositMessage::BridgeToAppchain {
                     receiver_id_in_appchain,
                 } => {
+                    match self.appchain_state {
+                        AppchainState::Broken | AppchainState::Dead => panic!(
+                            "Cannot bridge token while appchain state is '{}'. Return deposit.",
+                            serde_json::to_string(&self.appchain_state).unwrap()
+                        ),
+                        _ => (),
+                    };
                     AccountIdInAppchain::new(
                         Some(receiver_id_in_appchain.clone()),
                         &self.appchain_template_type
@@ -240,6 +248,7 @@ impl AppchainAnchor {
                     let protocol_settings = self.protocol_settings.get().unwrap();
                     assert!(
                         near_fungible_tokens.total_market_value()
+                            + self.native_near_token.total_market_value()
                             + near_fungible_tokens.get_market_value_of(
                                 &near_fungible_token.metadata.symbol,
                                 amount.0
@@ -252,12 +261,35 @@ impl AppchainAnchor {
                                 / 100,
                         "Too much NEAR fungible token to lock. Return deposit."
                     );
+                    // Refund without panic, to keep the paused state of the token (if any).
+                    if let Err(message) = self.check_and_record_bridge_volume(
+                        &near_fungible_token.contract_account,
+                        amount.0,
+                        BridgingDirection::ToAppchain,
+                    ) {
+                        log!("{} Return deposit.", message);
+                        return PromiseOrValue::Value(amount);
+                    }
+                    let amount = U128::from(self.charge_bridge_fee(
+                        &near_fungible_token.contract_account,
+                        amount.0,
+                        BridgingDirection::ToAppchain,
+                    ));
+                    assert!(
+                        amount.0 > 0,
+                        "The amount is not enough to pay the bridge fee. Return deposit."
+                    );
                     near_fungible_token.locked_balance =
                         match near_fungible_token.locked_balance.0.checked_add(amount.0) {
                             Some(value) => U128::from(value),
                             None => panic!("Locked balance overflow. Return deposit."),
                         };
                     near_fungible_tokens.insert(&near_fungible_token);
+                    self.increase_net_locked_balance(
+                        &near_fungible_token.contract_account,
+                        &sender_id,
+                        amount.0,
+                    );
                     let appchain_notification_history = self.internal_append_appchain_notification(
                         AppchainNotification::NearFungibleTokenLocked {
                             contract_account: near_fungible_token.contract_account.clone(),
@@ -274,6 +306,13 @@ impl AppchainAnchor {
                         &amount.0,
                         &appchain_notification_history.index.0
                     );
+                    AnchorEvent::NearFungibleTokenLocked {
+                        symbol: near_fungible_token.metadata.symbol,
+                        sender_id_in_near: sender_id,
+                        receiver_id_in_appchain,
+                        amount,
+                    }
+                    .emit();
                     return PromiseOrValue::Value(0.into());
                 }
                 _ => panic!(
@@ -302,10 +341,10 @@ impl AppchainAnchor {
         {
             if near_fungible_token
                 .bridging_state
-                .eq(&BridgingState::Closed)
+                .ne(&BridgingState::Active)
             {
                 let message = format!(
-                    "Bridging for NEAR fungible token in contract '{}' is now closed.",
+                    "Bridging for NEAR fungible token in contract '{}' is not active.",
                     contract_account
                 );
                 let result = AppchainMessageProcessingResult::Error {
@@ -315,32 +354,55 @@ impl AppchainAnchor {
                 self.record_appchain_message_processing_result(&result);
                 return MultiTxsOperationProcessingResult::Error(message);
             }
+            if let Err(message) = self.check_and_record_bridge_volume(
+                contract_account,
+                amount.0,
+                BridgingDirection::ToNear,
+            ) {
+                let result = AppchainMessageProcessingResult::Error {
+                    nonce: appchain_message_nonce,
+                    message: message.clone(),
+                };
+                self.record_appchain_message_processing_result(&result);
+                return MultiTxsOperationProcessingResult::Error(message);
+            }
             near_fungible_token.locked_balance =
                 match near_fungible_token.locked_balance.0.checked_sub(amount.0) {
                     Some(value) => U128::from(value),
                     None => U128::from(0),
                 };
             near_fungible_tokens.insert(&near_fungible_token);
-            ext_ft_core::ext(near_fungible_token.contract_account)
-                .with_attached_deposit(1)
-                .with_static_gas(Gas::ONE_TERA.mul(T_GAS_FOR_FT_TRANSFER))
-                .with_unused_gas_weight(0)
-                .ft_transfer(receiver_id_in_near.clone(), amount.clone(), None)
-                .then(
-                    ext_self::ext(env::current_account_id())
-                        .with_attached_deposit(0)
-                        .with_static_gas(Gas::ONE_TERA.mul(T_GAS_FOR_RESOLVER_FUNCTION))
-                        .with_unused_gas_weight(0)
-                        .resolve_fungible_token_transfer(
-                            near_fungible_token.metadata.symbol,
-                            sender_id_in_appchain.clone(),
-                            receiver_id_in_near.clone(),
-                            amount.clone(),
-                            appchain_message_nonce,
-                        ),
+            self.decrease_net_locked_balance(contract_account, receiver_id_in_near, amount.0);
+            // The bridge fee is kept in this contract, and the net amount is unlocked.
+            let amount = &U128::from(self.charge_bridge_fee(
+                contract_account,
+                amount.0,
+                BridgingDirection::ToNear,
+            ));
+            if amount.0 == 0 {
+                let message = format!(
+                    "The whole amount of NEAR fungible token '{}' is charged as bridge fee.",
+                    near_fungible_token.metadata.symbol
                 );
-            processing_context.add_prepaid_gas(Gas::ONE_TERA.mul(T_GAS_FOR_FT_TRANSFER));
-            processing_context.add_prepaid_gas(Gas::ONE_TERA.mul(T_GAS_FOR_RESOLVER_FUNCTION));
+                self.record_appchain_message_processing_result(
+                    &AppchainMessageProcessingResult::Ok {
+                        nonce: appchain_message_nonce,
+                        message: Some(message),
+                    },
+                );
+                return MultiTxsOperationProcessingResult::Ok;
+            }
+            let prepaid_gas = self.internal_pay_out_transfer(
+                &near_fungible_token.contract_account,
+                receiver_id_in_near,
+                PendingTransfer::NearFungibleTokenUnlocking {
+                    symbol: near_fungible_token.metadata.symbol.clone(),
+                    sender_id_in_appchain: sender_id_in_appchain.clone(),
+                    amount: amount.clone(),
+                    appchain_message_nonce,
+                },
+            );
+            processing_context.add_prepaid_gas(prepaid_gas);
             MultiTxsOperationProcessingResult::Ok
         } else {
             let message = format!(
@@ -357,6 +419,35 @@ impl AppchainAnchor {
     }
 }
 
+/// Transfer NEAR fungible token to the receiver,
+/// and resolve the result by `resolve_fungible_token_transfer`.
+pub fn transfer_near_fungible_token_and_resolve(
+    near_fungible_token: &NearFungibleToken,
+    sender_id_in_appchain: &String,
+    receiver_id_in_near: &AccountId,
+    amount: &U128,
+    appchain_message_nonce: u32,
+) {
+    ext_ft_core::ext(near_fungible_token.contract_account.clone())
+        .with_attached_deposit(1)
+        .with_static_gas(Gas::ONE_TERA.mul(T_GAS_FOR_FT_TRANSFER))
+        .with_unused_gas_weight(0)
+        .ft_transfer(receiver_id_in_near.clone(), amount.clone(), None)
+        .then(
+            ext_self::ext(env::current_account_id())
+                .with_attached_deposit(0)
+                .with_static_gas(Gas::ONE_TERA.mul(T_GAS_FOR_RESOLVER_FUNCTION))
+                .with_unused_gas_weight(0)
+                .resolve_fungible_token_transfer(
+                    near_fungible_token.metadata.symbol.clone(),
+                    sender_id_in_appchain.clone(),
+                    receiver_id_in_near.clone(),
+                    amount.clone(),
+                    appchain_message_nonce,
+                ),
+        );
+}
+
 #[near_bindgen]
 impl FungibleTokenContractResolver for AppchainAnchor {
     //
@@ -382,6 +473,14 @@ impl FungibleTokenContractResolver for AppchainAnchor {
                         message: Some(message),
                     },
                 );
+                AnchorEvent::NearFungibleTokenUnlocked {
+                    symbol,
+                    sender_id_in_appchain,
+                    receiver_id_in_near,
+                    amount,
+                    appchain_message_nonce,
+                }
+                .emit();
             }
             PromiseResult::Failed => {
                 let reason = format!(
@@ -398,6 +497,37 @@ impl FungibleTokenContractResolver for AppchainAnchor {
                         message,
                     },
                 );
+                // The token is not transferred, restore the locked balance and
+                // keep the transfer pending for claiming.
+                let mut near_fungible_tokens = self.near_fungible_tokens.get().unwrap();
+                if let Some(mut near_fungible_token) = near_fungible_tokens.get(&symbol) {
+                    near_fungible_token.locked_balance =
+                        U128::from(near_fungible_token.locked_balance.0 + amount.0);
+                    near_fungible_tokens.insert(&near_fungible_token);
+                    self.increase_net_locked_balance(
+                        &near_fungible_token.contract_account,
+                        &receiver_id_in_near,
+                        amount.0,
+                    );
+                }
+                self.add_pending_transfer(
+                    &receiver_id_in_near,
+                    PendingTransfer::NearFungibleTokenUnlocking {
+                        symbol: symbol.clone(),
+                        sender_id_in_appchain: sender_id_in_appchain.clone(),
+                        amount,
+                        appchain_message_nonce,
+                    },
+                );
+                AnchorEvent::FailedToUnlockNearFungibleToken {
+                    symbol,
+                    sender_id_in_appchain,
+                    receiver_id_in_near,
+                    amount,
+                    appchain_message_nonce,
+                    reason,
+                }
+                .emit();
             }
         }
     }
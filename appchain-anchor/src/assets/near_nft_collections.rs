@@ -0,0 +1,477 @@
+use std::str::FromStr;
+
+use near_contract_standards::non_fungible_token::Token;
+
+use super::bridge_fees::BridgingDirection;
+use crate::interfaces::NearNFTCollectionManager;
+use crate::permissionless_actions::AppchainMessagesProcessingContext;
+use crate::*;
+
+pub trait NearNFTCollectionResolver {
+    /// Resolver for querying the token of a NEAR NFT collection which is transferred
+    /// to this contract
+    fn resolve_near_nft_token_query(
+        &mut self,
+        contract_account: AccountId,
+        token_id: TokenId,
+        sender_id_in_near: AccountId,
+        owner_id_in_near: AccountId,
+        receiver_id_in_appchain: String,
+    ) -> bool;
+    /// Resolver for transfer locked NFT of a NEAR NFT collection
+    fn resolve_near_nft_transfer(
+        &mut self,
+        owner_id_in_appchain: String,
+        receiver_id_in_near: AccountId,
+        contract_account: AccountId,
+        token_id: TokenId,
+        appchain_message_nonce: u32,
+    );
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct InternalNearNFTCollection {
+    pub contract_account: AccountId,
+    pub bridging_state: BridgingState,
+    pub locked_token_id_set: UnorderedSet<String>,
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct NearNFTCollections {
+    /// The set of contract accounts of NEAR NFT collections.
+    contract_account_set: UnorderedSet<AccountId>,
+    /// The NEAR NFT collections data, mapped by the contract account.
+    collections: LookupMap<AccountId, InternalNearNFTCollection>,
+}
+
+impl InternalNearNFTCollection {
+    ///
+    pub fn new(contract_account: AccountId) -> Self {
+        Self {
+            contract_account: contract_account.clone(),
+            bridging_state: BridgingState::Closed,
+            locked_token_id_set: UnorderedSet::new(
+                StorageKey::NearNFTCollectionLockedTokenIdSet(contract_account.to_string())
+                    .into_bytes(),
+            ),
+        }
+    }
+    ///
+    pub fn is_nft_locked(&self, token_id: &TokenId) -> bool {
+        self.locked_token_id_set.contains(token_id)
+    }
+}
+
+impl NearNFTCollections {
+    ///
+    pub fn new() -> Self {
+        Self {
+            contract_account_set: UnorderedSet::new(
+                StorageKey::NearNFTCollectionContractAccounts.into_bytes(),
+            ),
+            collections: LookupMap::new(StorageKey::NearNFTCollectionsMap.into_bytes()),
+        }
+    }
+    ///
+    pub fn insert(&mut self, collection: &InternalNearNFTCollection) {
+        self.contract_account_set
+            .insert(&collection.contract_account);
+        self.collections
+            .insert(&collection.contract_account, collection);
+    }
+    ///
+    pub fn get(&self, contract_account: &AccountId) -> Option<InternalNearNFTCollection> {
+        self.collections.get(contract_account)
+    }
+    ///
+    pub fn contains(&self, contract_account: &AccountId) -> bool {
+        self.contract_account_set.contains(contract_account)
+    }
+    ///
+    pub fn to_vec(&self) -> Vec<NearNFTCollection> {
+        self.contract_account_set
+            .to_vec()
+            .iter()
+            .map(|contract_account| self.collections.get(contract_account).unwrap().into())
+            .collect()
+    }
+}
+
+impl From<InternalNearNFTCollection> for NearNFTCollection {
+    fn from(collection: InternalNearNFTCollection) -> Self {
+        Self {
+            contract_account: collection.contract_account,
+            bridging_state: collection.bridging_state,
+            count_of_locked_tokens: collection.locked_token_id_set.len().into(),
+        }
+    }
+}
+
+#[near_bindgen]
+impl NearNFTCollectionManager for AppchainAnchor {
+    //
+    fn register_near_nft_collection(&mut self, contract_account: AccountId) {
+        self.assert_owner();
+        assert!(
+            self.wrapped_appchain_nfts
+                .get()
+                .unwrap()
+                .get_class_id_by_contract_account(&contract_account)
+                .is_none(),
+            "The contract account is a wrapped appchain NFT contract."
+        );
+        let mut near_nft_collections = self.near_nft_collections.get().unwrap();
+        assert!(
+            !near_nft_collections.contains(&contract_account),
+            "The NFT collection '{}' is already registered.",
+            contract_account
+        );
+        near_nft_collections.insert(&InternalNearNFTCollection::new(contract_account));
+        self.near_nft_collections.set(&near_nft_collections);
+    }
+    //
+    fn open_bridging_of_near_nft_collection(&mut self, contract_account: AccountId) {
+        self.assert_owner();
+        let mut near_nft_collections = self.near_nft_collections.get().unwrap();
+        let mut collection = near_nft_collections
+            .get(&contract_account)
+            .expect("Unregistered NFT collection.");
+        assert!(
+            collection.bridging_state.ne(&BridgingState::Active),
+            "Bridging is already active."
+        );
+        collection.bridging_state = BridgingState::Active;
+        near_nft_collections.insert(&collection);
+    }
+    //
+    fn close_bridging_of_near_nft_collection(&mut self, contract_account: AccountId) {
+        self.assert_owner();
+        let mut near_nft_collections = self.near_nft_collections.get().unwrap();
+        let mut collection = near_nft_collections
+            .get(&contract_account)
+            .expect("Unregistered NFT collection.");
+        assert!(
+            collection.bridging_state.ne(&BridgingState::Closed),
+            "Bridging is already closed."
+        );
+        collection.bridging_state = BridgingState::Closed;
+        near_nft_collections.insert(&collection);
+    }
+}
+
+impl AppchainAnchor {
+    /// Process the NFT of a NEAR NFT collection which is transferred to this contract.
+    /// The metadata of the token is queried from the collection contract before locking it.
+    pub fn internal_process_near_nft_transfer(
+        &mut self,
+        predecessor_account_id: AccountId,
+        sender_id: AccountId,
+        nft_owner_id: AccountId,
+        token_id: TokenId,
+        transfer_message: NFTTransferMessage,
+    ) -> PromiseOrValue<bool> {
+        let collection = self
+            .near_nft_collections
+            .get()
+            .unwrap()
+            .get(&predecessor_account_id)
+            .unwrap();
+        assert!(
+            collection.bridging_state.eq(&BridgingState::Active),
+            "Bridging for NFT collection '{}' is not active.",
+            predecessor_account_id
+        );
+        match transfer_message {
+            NFTTransferMessage::BridgeToAppchain {
+                receiver_id_in_appchain,
+            } => {
+                match self.appchain_state {
+                    AppchainState::Broken | AppchainState::Dead => panic!(
+                        "Cannot bridge NFT while appchain state is '{}'. Return it.",
+                        serde_json::to_string(&self.appchain_state).unwrap()
+                    ),
+                    _ => (),
+                };
+                AccountIdInAppchain::new(
+                    Some(receiver_id_in_appchain.clone()),
+                    &self.appchain_template_type,
+                )
+                .assert_valid();
+                #[derive(near_sdk::serde::Serialize)]
+                #[serde(crate = "near_sdk::serde")]
+                struct Args {
+                    token_id: TokenId,
+                }
+                let args = near_sdk::serde_json::to_vec(&Args {
+                    token_id: token_id.clone(),
+                })
+                .expect("Failed to serialize the cross contract args using JSON.");
+                PromiseOrValue::Promise(
+                    Promise::new(predecessor_account_id.clone())
+                        .function_call(
+                            "nft_token".to_string(),
+                            args,
+                            0,
+                            Gas::ONE_TERA.mul(T_GAS_FOR_NFT_TOKEN_QUERY),
+                        )
+                        .then(
+                            ext_self::ext(env::current_account_id())
+                                .with_attached_deposit(0)
+                                .with_static_gas(Gas::ONE_TERA.mul(T_GAS_FOR_RESOLVER_FUNCTION))
+                                .with_unused_gas_weight(0)
+                                .resolve_near_nft_token_query(
+                                    predecessor_account_id,
+                                    token_id,
+                                    sender_id,
+                                    nft_owner_id,
+                                    receiver_id_in_appchain,
+                                ),
+                        ),
+                )
+            }
+        }
+    }
+    //
+    pub fn internal_unlock_near_nft(
+        &mut self,
+        owner_id_in_appchain: &String,
+        receiver_id_in_near: &AccountId,
+        contract_account: &String,
+        token_id: &TokenId,
+        appchain_message_nonce: u32,
+        processing_context: &mut AppchainMessagesProcessingContext,
+    ) -> MultiTxsOperationProcessingResult {
+        let collection = AccountId::from_str(contract_account)
+            .ok()
+            .and_then(|account_id| self.near_nft_collections.get().unwrap().get(&account_id));
+        let message = match &collection {
+            None => Some(format!(
+                "Unregistered NFT collection: '{}'.",
+                contract_account
+            )),
+            Some(collection) => {
+                if collection.bridging_state.ne(&BridgingState::Active) {
+                    Some(format!(
+                        "Bridging for NFT collection '{}' is not active.",
+                        contract_account
+                    ))
+                } else if !collection.is_nft_locked(token_id) {
+                    Some(format!(
+                        "Token '{}' of NFT collection '{}' is not locked.",
+                        token_id, contract_account
+                    ))
+                } else {
+                    self.check_nft_bridge_fee_deposit(
+                        &collection.contract_account,
+                        receiver_id_in_near,
+                        BridgingDirection::ToNear,
+                    )
+                    .and_then(|_| {
+                        self.check_and_record_bridge_volume(
+                            &collection.contract_account,
+                            1,
+                            BridgingDirection::ToNear,
+                        )
+                    })
+                    .err()
+                }
+            }
+        };
+        if let Some(message) = message {
+            let result = AppchainMessageProcessingResult::Error {
+                nonce: appchain_message_nonce,
+                message: message.clone(),
+            };
+            self.record_appchain_message_processing_result(&result);
+            return MultiTxsOperationProcessingResult::Error(message);
+        }
+        let collection = collection.unwrap();
+        self.charge_nft_bridge_fee_to_near(
+            &collection.contract_account,
+            receiver_id_in_near,
+            appchain_message_nonce,
+        );
+        // The token stays locked until the transfer is resolved, so that
+        // the appchain message can be retried if the transfer fails.
+        #[derive(near_sdk::serde::Serialize)]
+        #[serde(crate = "near_sdk::serde")]
+        struct Args {
+            receiver_id: AccountId,
+            token_id: TokenId,
+            approval_id: Option<u64>,
+            memo: Option<String>,
+        }
+        let args = near_sdk::serde_json::to_vec(&Args {
+            receiver_id: receiver_id_in_near.clone(),
+            token_id: token_id.clone(),
+            approval_id: None,
+            memo: None,
+        })
+        .expect("Failed to serialize the cross contract args using JSON.");
+        Promise::new(collection.contract_account.clone())
+            .function_call(
+                "nft_transfer".to_string(),
+                args,
+                1,
+                Gas::ONE_TERA.mul(T_GAS_FOR_NFT_TRANSFER),
+            )
+            .then(
+                ext_self::ext(env::current_account_id())
+                    .with_attached_deposit(0)
+                    .with_static_gas(Gas::ONE_TERA.mul(T_GAS_FOR_RESOLVER_FUNCTION))
+                    .with_unused_gas_weight(0)
+                    .resolve_near_nft_transfer(
+                        owner_id_in_appchain.clone(),
+                        receiver_id_in_near.clone(),
+                        collection.contract_account,
+                        token_id.clone(),
+                        appchain_message_nonce,
+                    ),
+            );
+        processing_context.add_prepaid_gas(Gas::ONE_TERA.mul(T_GAS_FOR_NFT_TRANSFER));
+        processing_context.add_prepaid_gas(Gas::ONE_TERA.mul(T_GAS_FOR_RESOLVER_FUNCTION));
+        MultiTxsOperationProcessingResult::Ok
+    }
+}
+
+#[near_bindgen]
+impl NearNFTCollectionResolver for AppchainAnchor {
+    //
+    fn resolve_near_nft_token_query(
+        &mut self,
+        contract_account: AccountId,
+        token_id: TokenId,
+        sender_id_in_near: AccountId,
+        owner_id_in_near: AccountId,
+        receiver_id_in_appchain: String,
+    ) -> bool {
+        assert_self();
+        let token = match env::promise_result(0) {
+            PromiseResult::NotReady => unreachable!(),
+            PromiseResult::Successful(value) => {
+                match near_sdk::serde_json::from_slice::<Option<Token>>(&value) {
+                    Ok(token) => token,
+                    Err(_) => None,
+                }
+            }
+            PromiseResult::Failed => None,
+        };
+        // The metadata of token is optional in NEP-177, use empty metadata if it is not set.
+        let token_metadata = match token {
+            Some(token) => token.metadata.unwrap_or(TokenMetadata {
+                title: None,
+                description: None,
+                media: None,
+                media_hash: None,
+                copies: None,
+                issued_at: None,
+                expires_at: None,
+                starts_at: None,
+                updated_at: None,
+                extra: None,
+                reference: None,
+                reference_hash: None,
+            }),
+            None => {
+                log!(
+                    "Failed to query token '{}' in NFT collection '{}'. Return it.",
+                    token_id,
+                    contract_account
+                );
+                return true;
+            }
+        };
+        // Return the NFT without panic, to keep the paused state of the collection (if any).
+        if let Err(message) = self
+            .check_nft_bridge_fee_deposit(
+                &contract_account,
+                &sender_id_in_near,
+                BridgingDirection::ToAppchain,
+            )
+            .and_then(|_| {
+                self.check_and_record_bridge_volume(
+                    &contract_account,
+                    1,
+                    BridgingDirection::ToAppchain,
+                )
+            })
+        {
+            log!("{} Return it.", message);
+            return true;
+        }
+        self.charge_nft_bridge_fee_to_appchain(&contract_account, &sender_id_in_near);
+        let mut near_nft_collections = self.near_nft_collections.get().unwrap();
+        let mut collection = near_nft_collections.get(&contract_account).unwrap();
+        collection.locked_token_id_set.insert(&token_id);
+        near_nft_collections.insert(&collection);
+        self.owners_of_locked_near_nfts.insert(
+            &(contract_account.clone(), token_id.clone()),
+            &owner_id_in_near,
+        );
+        let appchain_notification_history =
+            self.internal_append_appchain_notification(AppchainNotification::NearNFTLocked {
+                contract_account: contract_account.clone(),
+                token_id: token_id.clone(),
+                sender_id_in_near: sender_id_in_near.clone(),
+                owner_id_in_near,
+                receiver_id_in_appchain: receiver_id_in_appchain.clone(),
+                token_metadata,
+            });
+        log!(
+            "Received NFT '{}' in collection '{}' from '{}'. Start transfer to '{}' of appchain. Crosschain notification index: '{}'.",
+            token_id,
+            contract_account,
+            sender_id_in_near,
+            receiver_id_in_appchain,
+            appchain_notification_history.index.0
+        );
+        false
+    }
+    //
+    fn resolve_near_nft_transfer(
+        &mut self,
+        owner_id_in_appchain: String,
+        receiver_id_in_near: AccountId,
+        contract_account: AccountId,
+        token_id: TokenId,
+        appchain_message_nonce: u32,
+    ) {
+        assert_self();
+        match env::promise_result(0) {
+            PromiseResult::NotReady => unreachable!(),
+            PromiseResult::Successful(_) => {
+                let mut near_nft_collections = self.near_nft_collections.get().unwrap();
+                if let Some(mut collection) = near_nft_collections.get(&contract_account) {
+                    collection.locked_token_id_set.remove(&token_id);
+                    near_nft_collections.insert(&collection);
+                }
+                self.owners_of_locked_near_nfts
+                    .remove(&(contract_account.clone(), token_id.clone()));
+                self.settle_nft_bridge_fee_charge(appchain_message_nonce, true);
+                let message = format!(
+                    "Token '{}' of NFT collection '{}' for appchain account '{}' is unlocked.",
+                    token_id, contract_account, owner_id_in_appchain
+                );
+                self.record_appchain_message_processing_result(
+                    &AppchainMessageProcessingResult::Ok {
+                        nonce: appchain_message_nonce,
+                        message: Some(message),
+                    },
+                );
+            }
+            PromiseResult::Failed => {
+                self.settle_nft_bridge_fee_charge(appchain_message_nonce, false);
+                let message = format!(
+                    "Failed to unlock token '{}' of NFT collection '{}' for '{}'.",
+                    token_id, contract_account, receiver_id_in_near
+                );
+                self.record_appchain_message_processing_result(
+                    &AppchainMessageProcessingResult::Error {
+                        nonce: appchain_message_nonce,
+                        message,
+                    },
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,91 @@
+use crate::*;
+
+/// The net balances (locked minus unlocked) of NEAR assets of accounts,
+/// for returning the locked assets in the final settlement of a dead appchain.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct NetLockedBalances {
+    /// The set of keys `(contract_account, account_id)` of the balances.
+    keys: UnorderedSet<(AccountId, AccountId)>,
+    /// The net locked balances, mapped by `(contract_account, account_id)`.
+    balances: LookupMap<(AccountId, AccountId), Balance>,
+}
+
+impl NetLockedBalances {
+    ///
+    pub fn new() -> Self {
+        Self {
+            keys: UnorderedSet::new(StorageKey::NetLockedBalanceKeys.into_bytes()),
+            balances: LookupMap::new(StorageKey::NetLockedBalancesMap.into_bytes()),
+        }
+    }
+    ///
+    pub fn get(&self, contract_account: &AccountId, account_id: &AccountId) -> Balance {
+        self.balances
+            .get(&(contract_account.clone(), account_id.clone()))
+            .unwrap_or(0)
+    }
+    ///
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+    /// Increase the net locked balance of an account, when the account locks
+    /// the asset in this contract.
+    pub fn increase(&mut self, contract_account: &AccountId, account_id: &AccountId, amount: u128) {
+        if amount == 0 {
+            return;
+        }
+        let key = (contract_account.clone(), account_id.clone());
+        let balance = self.balances.get(&key).unwrap_or(0);
+        self.balances
+            .insert(&key, &(balance.saturating_add(amount)));
+        self.keys.insert(&key);
+    }
+    /// Decrease the net locked balance of an account, when the asset is unlocked
+    /// to the account. The balance will not be less than zero.
+    pub fn decrease(&mut self, contract_account: &AccountId, account_id: &AccountId, amount: u128) {
+        let key = (contract_account.clone(), account_id.clone());
+        if let Some(balance) = self.balances.get(&key) {
+            match balance > amount {
+                true => {
+                    self.balances.insert(&key, &(balance - amount));
+                }
+                false => {
+                    self.balances.remove(&key);
+                    self.keys.remove(&key);
+                }
+            }
+        }
+    }
+    /// Remove and return the first net locked balance in storage.
+    pub fn pop_first(&mut self) -> Option<(AccountId, AccountId, Balance)> {
+        let key = self.keys.as_vector().get(0)?;
+        self.keys.remove(&key);
+        let balance = self.balances.remove(&key).unwrap_or(0);
+        Some((key.0, key.1, balance))
+    }
+}
+
+impl AppchainAnchor {
+    //
+    pub fn increase_net_locked_balance(
+        &mut self,
+        contract_account: &AccountId,
+        account_id: &AccountId,
+        amount: u128,
+    ) {
+        let mut net_locked_balances = self.net_locked_balances.get().unwrap();
+        net_locked_balances.increase(contract_account, account_id, amount);
+        self.net_locked_balances.set(&net_locked_balances);
+    }
+    //
+    pub fn decrease_net_locked_balance(
+        &mut self,
+        contract_account: &AccountId,
+        account_id: &AccountId,
+        amount: u128,
+    ) {
+        let mut net_locked_balances = self.net_locked_balances.get().unwrap();
+        net_locked_balances.decrease(contract_account, account_id, amount);
+        self.net_locked_balances.set(&net_locked_balances);
+    }
+}
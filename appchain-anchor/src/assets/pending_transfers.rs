@@ -0,0 +1,162 @@
+use crate::*;
+
+impl AppchainAnchor {
+    /// Record a failed asset transfer for the receiver, which can be claimed later.
+    pub fn add_pending_transfer(
+        &mut self,
+        receiver_id_in_near: &AccountId,
+        pending_transfer: PendingTransfer,
+    ) {
+        let mut pending_transfers = self
+            .pending_transfers
+            .get(receiver_id_in_near)
+            .unwrap_or_default();
+        pending_transfers.push(pending_transfer);
+        self.pending_transfers
+            .insert(receiver_id_in_near, &pending_transfers);
+        log!(
+            "A pending transfer for '{}' is recorded. Call 'claim_pending_transfer' to retry it after the receiver is registered in the token contract.",
+            receiver_id_in_near
+        );
+    }
+    //
+    pub fn internal_claim_pending_transfer(
+        &mut self,
+        receiver_id_in_near: AccountId,
+        appchain_message_nonce: u32,
+    ) {
+        self.assert_asset_transfer_is_not_paused();
+        let pending_transfer = self
+            .remove_pending_transfer(&receiver_id_in_near, appchain_message_nonce)
+            .expect("Pending transfer is not found.");
+        self.start_retrying_failed_appchain_message(appchain_message_nonce);
+        let token_contract = match &pending_transfer {
+            PendingTransfer::NearFungibleTokenUnlocking { symbol, amount, .. } => {
+                let mut near_fungible_tokens = self.near_fungible_tokens.get().unwrap();
+                let mut near_fungible_token = near_fungible_tokens
+                    .get(&symbol)
+                    .expect("Invalid symbol of NEAR fungible token.");
+                assert!(
+                    near_fungible_token
+                        .bridging_state
+                        .eq(&BridgingState::Active),
+                    "Bridging for NEAR fungible token '{}' is not active.",
+                    symbol
+                );
+                assert!(
+                    near_fungible_token.locked_balance.0 >= amount.0,
+                    "Not enough locked balance of NEAR fungible token '{}'.",
+                    symbol
+                );
+                near_fungible_token.locked_balance =
+                    U128::from(near_fungible_token.locked_balance.0 - amount.0);
+                near_fungible_tokens.insert(&near_fungible_token);
+                self.decrease_net_locked_balance(
+                    &near_fungible_token.contract_account,
+                    &receiver_id_in_near,
+                    amount.0,
+                );
+                near_fungible_token.contract_account
+            }
+            PendingTransfer::WrappedAppchainTokenMinting {
+                sender_id_in_appchain,
+                ..
+            } => {
+                self.assert_contract_account_of_wrapped_appchain_token_is_set();
+                let wrapped_appchain_token = self.wrapped_appchain_token.get().unwrap();
+                assert!(
+                    sender_id_in_appchain.is_none()
+                        || wrapped_appchain_token
+                            .bridging_state
+                            .eq(&BridgingState::Active),
+                    "Bridging for wrapped appchain token is not active."
+                );
+                wrapped_appchain_token.contract_account.unwrap()
+            }
+            PendingTransfer::NativeNearUnlocking { amount, .. } => {
+                assert!(
+                    self.native_near_token
+                        .bridging_state
+                        .eq(&BridgingState::Active),
+                    "Bridging for native NEAR token is not active."
+                );
+                assert!(
+                    self.native_near_token.locked_balance.0 >= amount.0,
+                    "Not enough locked balance of native NEAR token."
+                );
+                self.native_near_token.locked_balance =
+                    U128::from(self.native_near_token.locked_balance.0 - amount.0);
+                self.decrease_net_locked_balance(
+                    &env::current_account_id(),
+                    &receiver_id_in_near,
+                    amount.0,
+                );
+                env::current_account_id()
+            }
+            // The amount is still counted in the pending mint amount of the asset.
+            PendingTransfer::WrappedAppchainAssetMinting { asset_id, .. } => {
+                let wrapped_appchain_asset = self
+                    .wrapped_appchain_assets
+                    .get()
+                    .unwrap()
+                    .get(&asset_id)
+                    .expect("Unregistered asset id.");
+                assert!(
+                    wrapped_appchain_asset
+                        .bridging_state
+                        .eq(&BridgingState::Active),
+                    "Bridging for wrapped appchain asset '{}' is not active.",
+                    asset_id
+                );
+                wrapped_appchain_asset.contract_account
+            }
+        };
+        self.internal_pay_out_transfer(&token_contract, &receiver_id_in_near, pending_transfer);
+    }
+    /// Remove the pending transfer of the receiver for a certain appchain message.
+    pub fn remove_pending_transfer(
+        &mut self,
+        receiver_id_in_near: &AccountId,
+        appchain_message_nonce: u32,
+    ) -> Option<PendingTransfer> {
+        let mut pending_transfers = self
+            .pending_transfers
+            .get(receiver_id_in_near)
+            .unwrap_or_default();
+        let index = pending_transfers.iter().position(|pending_transfer| {
+            pending_transfer.appchain_message_nonce() == appchain_message_nonce
+        })?;
+        let pending_transfer = pending_transfers.remove(index);
+        match pending_transfers.is_empty() {
+            true => self.pending_transfers.remove(receiver_id_in_near),
+            false => self
+                .pending_transfers
+                .insert(receiver_id_in_near, &pending_transfers),
+        };
+        Some(pending_transfer)
+    }
+}
+
+impl PendingTransfer {
+    ///
+    pub fn appchain_message_nonce(&self) -> u32 {
+        match self {
+            PendingTransfer::NearFungibleTokenUnlocking {
+                appchain_message_nonce,
+                ..
+            } => *appchain_message_nonce,
+            PendingTransfer::WrappedAppchainTokenMinting {
+                appchain_message_nonce,
+                ..
+            } => *appchain_message_nonce,
+            PendingTransfer::NativeNearUnlocking {
+                appchain_message_nonce,
+                ..
+            } => *appchain_message_nonce,
+            PendingTransfer::WrappedAppchainAssetMinting {
+                appchain_message_nonce,
+                ..
+            } => *appchain_message_nonce,
+        }
+    }
+}
@@ -0,0 +1,226 @@
+use super::bridge_fees::BridgingDirection;
+use crate::{interfaces::BridgeRateLimitManager, *};
+
+const NANO_SECONDS_PER_SECOND: u64 = 1_000_000_000;
+
+impl Default for BridgeVolumeWindow {
+    fn default() -> Self {
+        Self {
+            start_timestamp: U64::from(env::block_timestamp()),
+            current_volume: U128::from(0),
+            previous_volume: U128::from(0),
+        }
+    }
+}
+
+impl BridgeVolumeWindow {
+    /// Move the window forward to the current block timestamp.
+    fn roll(&mut self, window_length_in_seconds: u64) {
+        let window_length = window_length_in_seconds * NANO_SECONDS_PER_SECOND;
+        let elapsed = env::block_timestamp().saturating_sub(self.start_timestamp.0);
+        if elapsed >= window_length * 2 {
+            self.start_timestamp = U64::from(env::block_timestamp());
+            self.previous_volume = U128::from(0);
+            self.current_volume = U128::from(0);
+        } else if elapsed >= window_length {
+            self.start_timestamp = U64::from(self.start_timestamp.0 + window_length);
+            self.previous_volume = self.current_volume;
+            self.current_volume = U128::from(0);
+        }
+    }
+    /// Get the estimated volume in the rolling window which ends at the current
+    /// block timestamp. The volume of previous window is weighted by the part of it
+    /// which is still in the rolling window.
+    fn estimated_volume(&self, window_length_in_seconds: u64) -> u128 {
+        let window_length = u128::from(window_length_in_seconds);
+        let elapsed = u128::from(
+            env::block_timestamp().saturating_sub(self.start_timestamp.0) / NANO_SECONDS_PER_SECOND,
+        );
+        let remained = window_length.saturating_sub(elapsed);
+        let previous_volume = self.previous_volume.0;
+        previous_volume / window_length * remained
+            + previous_volume % window_length * remained / window_length
+            + self.current_volume.0
+    }
+}
+
+#[near_bindgen]
+impl BridgeRateLimitManager for AppchainAnchor {
+    //
+    fn set_bridge_rate_limit_of_asset(
+        &mut self,
+        contract_account: AccountId,
+        maximum_amount_per_transfer: Option<U128>,
+        window_length_in_seconds: U64,
+        maximum_volume_per_window_to_appchain: Option<U128>,
+        maximum_volume_per_window_to_near: Option<U128>,
+    ) {
+        self.assert_owner();
+        assert!(
+            self.near_fungible_tokens
+                .get()
+                .unwrap()
+                .get_by_contract_account(&contract_account)
+                .is_some()
+                || self.is_contract_of_wrapped_appchain_token(&contract_account)
+                || self
+                    .wrapped_appchain_nfts
+                    .get()
+                    .unwrap()
+                    .get_class_id_by_contract_account(&contract_account)
+                    .is_some()
+                || self
+                    .near_nft_collections
+                    .get()
+                    .unwrap()
+                    .contains(&contract_account)
+                || self
+                    .wrapped_appchain_assets
+                    .get()
+                    .unwrap()
+                    .get_by_contract_account(&contract_account)
+                    .is_some()
+                || contract_account.eq(&env::current_account_id()),
+            "Invalid contract account of asset: '{}'.",
+            contract_account
+        );
+        assert!(
+            window_length_in_seconds.0 > 0,
+            "The length of time window should be greater than 0."
+        );
+        let bridge_rate_limit = match self.bridge_rate_limits.get(&contract_account) {
+            Some(mut bridge_rate_limit) => {
+                bridge_rate_limit.maximum_amount_per_transfer = maximum_amount_per_transfer;
+                bridge_rate_limit.maximum_volume_per_window_to_appchain =
+                    maximum_volume_per_window_to_appchain;
+                bridge_rate_limit.maximum_volume_per_window_to_near =
+                    maximum_volume_per_window_to_near;
+                if bridge_rate_limit.window_length_in_seconds.0 != window_length_in_seconds.0 {
+                    bridge_rate_limit.window_length_in_seconds = window_length_in_seconds;
+                    bridge_rate_limit.volume_window_to_appchain = BridgeVolumeWindow::default();
+                    bridge_rate_limit.volume_window_to_near = BridgeVolumeWindow::default();
+                }
+                bridge_rate_limit
+            }
+            None => BridgeRateLimit {
+                maximum_amount_per_transfer,
+                window_length_in_seconds,
+                maximum_volume_per_window_to_appchain,
+                maximum_volume_per_window_to_near,
+                volume_window_to_appchain: BridgeVolumeWindow::default(),
+                volume_window_to_near: BridgeVolumeWindow::default(),
+            },
+        };
+        self.bridge_rate_limits
+            .insert(&contract_account, &bridge_rate_limit);
+    }
+    //
+    fn remove_bridge_rate_limit_of_asset(&mut self, contract_account: AccountId) {
+        self.assert_owner();
+        assert!(
+            self.bridge_rate_limits.contains_key(&contract_account),
+            "No bridge rate limit of asset '{}'.",
+            contract_account
+        );
+        self.bridge_rate_limits.remove(&contract_account);
+    }
+}
+
+impl AppchainAnchor {
+    /// Check the rate limits of an asset for a bridge transfer, and record the volume
+    /// of the transfer if it is allowed.
+    ///
+    /// If the volume limit in rolling time window is crossed, the bridging of the asset
+    /// is paused. The caller should NOT panic on error, to keep the paused state.
+    pub fn check_and_record_bridge_volume(
+        &mut self,
+        contract_account: &AccountId,
+        amount: u128,
+        direction: BridgingDirection,
+    ) -> Result<(), String> {
+        let mut bridge_rate_limit = match self.bridge_rate_limits.get(contract_account) {
+            Some(bridge_rate_limit) => bridge_rate_limit,
+            None => return Ok(()),
+        };
+        if let Some(maximum_amount) = &bridge_rate_limit.maximum_amount_per_transfer {
+            if amount > maximum_amount.0 {
+                return Err(format!(
+                    "The amount '{}' exceeds the maximum amount per transfer '{}' of asset '{}'.",
+                    amount, maximum_amount.0, contract_account
+                ));
+            }
+        }
+        let window_length_in_seconds = bridge_rate_limit.window_length_in_seconds.0;
+        let (maximum_volume, volume_window) = match direction {
+            BridgingDirection::ToAppchain => (
+                bridge_rate_limit.maximum_volume_per_window_to_appchain,
+                &mut bridge_rate_limit.volume_window_to_appchain,
+            ),
+            BridgingDirection::ToNear => (
+                bridge_rate_limit.maximum_volume_per_window_to_near,
+                &mut bridge_rate_limit.volume_window_to_near,
+            ),
+        };
+        volume_window.roll(window_length_in_seconds);
+        if let Some(maximum_volume) = maximum_volume {
+            if volume_window
+                .estimated_volume(window_length_in_seconds)
+                .saturating_add(amount)
+                > maximum_volume.0
+            {
+                self.bridge_rate_limits
+                    .insert(contract_account, &bridge_rate_limit);
+                let reason = format!(
+                    "The volume limit '{}' in time window of asset '{}' is crossed.",
+                    maximum_volume.0, contract_account
+                );
+                self.pause_bridging_of_asset(contract_account, &reason);
+                return Err(format!("{} Bridging of the asset is paused.", reason));
+            }
+        }
+        volume_window.current_volume = U128::from(volume_window.current_volume.0 + amount);
+        self.bridge_rate_limits
+            .insert(contract_account, &bridge_rate_limit);
+        Ok(())
+    }
+    //
+    fn pause_bridging_of_asset(&mut self, contract_account: &AccountId, reason: &String) {
+        let mut near_fungible_tokens = self.near_fungible_tokens.get().unwrap();
+        if contract_account.eq(&env::current_account_id()) {
+            self.native_near_token.bridging_state = BridgingState::Paused;
+        } else if let Some(mut near_fungible_token) =
+            near_fungible_tokens.get_by_contract_account(contract_account)
+        {
+            near_fungible_token.bridging_state = BridgingState::Paused;
+            near_fungible_tokens.insert(&near_fungible_token);
+        } else if self.is_contract_of_wrapped_appchain_token(contract_account) {
+            let mut wrapped_appchain_token = self.wrapped_appchain_token.get().unwrap();
+            wrapped_appchain_token.bridging_state = BridgingState::Paused;
+            self.wrapped_appchain_token.set(&wrapped_appchain_token);
+        } else {
+            let mut wrapped_appchain_nfts = self.wrapped_appchain_nfts.get().unwrap();
+            let mut near_nft_collections = self.near_nft_collections.get().unwrap();
+            let mut wrapped_appchain_assets = self.wrapped_appchain_assets.get().unwrap();
+            if let Some(class_id) =
+                wrapped_appchain_nfts.get_class_id_by_contract_account(contract_account)
+            {
+                let mut wrapped_appchain_nft = wrapped_appchain_nfts.get(&class_id).unwrap();
+                wrapped_appchain_nft.bridging_state = BridgingState::Paused;
+                wrapped_appchain_nfts.insert(&class_id, &wrapped_appchain_nft);
+            } else if let Some(mut collection) = near_nft_collections.get(contract_account) {
+                collection.bridging_state = BridgingState::Paused;
+                near_nft_collections.insert(&collection);
+            } else if let Some(mut wrapped_appchain_asset) =
+                wrapped_appchain_assets.get_by_contract_account(contract_account)
+            {
+                wrapped_appchain_asset.bridging_state = BridgingState::Paused;
+                wrapped_appchain_assets.insert(&wrapped_appchain_asset);
+            }
+        }
+        AnchorEvent::AssetBridgingPaused {
+            contract_account: contract_account.clone(),
+            reason: reason.clone(),
+        }
+        .emit();
+    }
+}
@@ -0,0 +1,314 @@
+use super::native_near_token::transfer_native_near_and_resolve;
+use super::near_fungible_tokens::transfer_near_fungible_token_and_resolve;
+use super::wrapped_appchain_assets::mint_wrapped_appchain_asset_and_resolve;
+use super::wrapped_appchain_token::mint_wrapped_appchain_token_and_resolve;
+use crate::*;
+use near_contract_standards::storage_management::StorageBalance;
+
+pub trait StorageRegistrationResolver {
+    /// Resolver for checking storage balance of the receiver of a bridge payout
+    fn resolve_storage_balance_checking(
+        &mut self,
+        token_contract: AccountId,
+        receiver_id_in_near: AccountId,
+        transfer: PendingTransfer,
+    );
+    /// Resolver for storage deposit for the receiver of a bridge payout
+    fn resolve_storage_deposit(
+        &mut self,
+        token_contract: AccountId,
+        receiver_id_in_near: AccountId,
+        transfer: PendingTransfer,
+        deposit: U128,
+    );
+}
+
+impl Default for BridgeStorageFund {
+    fn default() -> Self {
+        Self {
+            balance: U128::from(0),
+            spent_amount: U128::from(0),
+        }
+    }
+}
+
+impl Default for StorageRegistrationOfToken {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            spent_amount: U128::from(0),
+            registered_account_count: U64::from(0),
+        }
+    }
+}
+
+impl AppchainAnchor {
+    /// Pay out a bridge transfer to the receiver. If automatic storage registration
+    /// is enabled for the token contract, the storage balance of the receiver is checked
+    /// (and registered by bridge storage fund if necessary) before the transfer.
+    ///
+    /// Return the gas prepaid for the scheduled promises.
+    pub fn internal_pay_out_transfer(
+        &mut self,
+        token_contract: &AccountId,
+        receiver_id_in_near: &AccountId,
+        transfer: PendingTransfer,
+    ) -> Gas {
+        let storage_registration_is_enabled = self
+            .storage_registration_of_tokens
+            .get(token_contract)
+            .map_or(false, |registration| registration.enabled);
+        // Native NEAR token needs no storage registration.
+        if !storage_registration_is_enabled
+            || self.bridge_storage_fund.balance.0 < STORAGE_DEPOSIT_FOR_NEP141_TOEKN
+            || token_contract.eq(&env::current_account_id())
+        {
+            return self.execute_transfer(token_contract, receiver_id_in_near, transfer);
+        }
+        #[derive(near_sdk::serde::Serialize)]
+        #[serde(crate = "near_sdk::serde")]
+        struct Args {
+            account_id: AccountId,
+        }
+        let args = near_sdk::serde_json::to_vec(&Args {
+            account_id: receiver_id_in_near.clone(),
+        })
+        .expect("Failed to serialize the cross contract args using JSON.");
+        let gas_for_resolver = gas_for_resolving_storage_balance_checking(&transfer);
+        Promise::new(token_contract.clone())
+            .function_call(
+                "storage_balance_of".to_string(),
+                args,
+                0,
+                Gas::ONE_TERA.mul(T_GAS_FOR_STORAGE_BALANCE_OF),
+            )
+            .then(
+                ext_self::ext(env::current_account_id())
+                    .with_attached_deposit(0)
+                    .with_static_gas(gas_for_resolver)
+                    .with_unused_gas_weight(0)
+                    .resolve_storage_balance_checking(
+                        token_contract.clone(),
+                        receiver_id_in_near.clone(),
+                        transfer,
+                    ),
+            );
+        Gas::ONE_TERA.mul(T_GAS_FOR_STORAGE_BALANCE_OF) + gas_for_resolver
+    }
+    //
+    fn execute_transfer(
+        &self,
+        token_contract: &AccountId,
+        receiver_id_in_near: &AccountId,
+        transfer: PendingTransfer,
+    ) -> Gas {
+        let gas = gas_for_executing_transfer(&transfer);
+        match transfer {
+            PendingTransfer::NearFungibleTokenUnlocking {
+                symbol,
+                sender_id_in_appchain,
+                amount,
+                appchain_message_nonce,
+            } => {
+                let near_fungible_token = self
+                    .near_fungible_tokens
+                    .get()
+                    .unwrap()
+                    .get(&symbol)
+                    .expect("Invalid symbol of NEAR fungible token.");
+                transfer_near_fungible_token_and_resolve(
+                    &near_fungible_token,
+                    &sender_id_in_appchain,
+                    receiver_id_in_near,
+                    &amount,
+                    appchain_message_nonce,
+                );
+            }
+            PendingTransfer::WrappedAppchainTokenMinting {
+                sender_id_in_appchain,
+                amount,
+                appchain_message_nonce,
+            } => {
+                mint_wrapped_appchain_token_and_resolve(
+                    token_contract,
+                    sender_id_in_appchain.as_ref(),
+                    receiver_id_in_near,
+                    &amount,
+                    appchain_message_nonce,
+                );
+            }
+            PendingTransfer::NativeNearUnlocking {
+                sender_id_in_appchain,
+                amount,
+                appchain_message_nonce,
+            } => {
+                transfer_native_near_and_resolve(
+                    &sender_id_in_appchain,
+                    receiver_id_in_near,
+                    &amount,
+                    appchain_message_nonce,
+                );
+            }
+            PendingTransfer::WrappedAppchainAssetMinting {
+                asset_id,
+                sender_id_in_appchain,
+                amount,
+                appchain_message_nonce,
+            } => {
+                mint_wrapped_appchain_asset_and_resolve(
+                    token_contract,
+                    &asset_id,
+                    &sender_id_in_appchain,
+                    receiver_id_in_near,
+                    &amount,
+                    appchain_message_nonce,
+                );
+            }
+        }
+        gas
+    }
+}
+
+#[near_bindgen]
+impl StorageRegistrationResolver for AppchainAnchor {
+    //
+    fn resolve_storage_balance_checking(
+        &mut self,
+        token_contract: AccountId,
+        receiver_id_in_near: AccountId,
+        transfer: PendingTransfer,
+    ) {
+        assert_self();
+        let is_registered = match env::promise_result(0) {
+            PromiseResult::NotReady => unreachable!(),
+            PromiseResult::Successful(value) => {
+                match near_sdk::serde_json::from_slice::<Option<StorageBalance>>(&value) {
+                    Ok(storage_balance) => storage_balance.is_some(),
+                    Err(_) => true,
+                }
+            }
+            // The token contract may not support NEP-145, transfer directly.
+            PromiseResult::Failed => true,
+        };
+        // The bridge storage fund is not spent if the balance of this contract can not cover it,
+        // to keep the locked native NEAR token untouched.
+        if is_registered
+            || self.bridge_storage_fund.balance.0 < STORAGE_DEPOSIT_FOR_NEP141_TOEKN
+            || !self.near_balance_is_enough_for(0)
+        {
+            self.execute_transfer(&token_contract, &receiver_id_in_near, transfer);
+            return;
+        }
+        let deposit = STORAGE_DEPOSIT_FOR_NEP141_TOEKN;
+        self.bridge_storage_fund.balance = U128::from(self.bridge_storage_fund.balance.0 - deposit);
+        #[derive(near_sdk::serde::Serialize)]
+        #[serde(crate = "near_sdk::serde")]
+        struct Args {
+            account_id: AccountId,
+            registration_only: bool,
+        }
+        let args = near_sdk::serde_json::to_vec(&Args {
+            account_id: receiver_id_in_near.clone(),
+            registration_only: true,
+        })
+        .expect("Failed to serialize the cross contract args using JSON.");
+        let gas_for_resolver =
+            Gas::ONE_TERA.mul(T_GAS_FOR_RESOLVER_FUNCTION) + gas_for_executing_transfer(&transfer);
+        Promise::new(token_contract.clone())
+            .function_call(
+                "storage_deposit".to_string(),
+                args,
+                deposit,
+                Gas::ONE_TERA.mul(T_GAS_FOR_STORAGE_DEPOSIT),
+            )
+            .then(
+                ext_self::ext(env::current_account_id())
+                    .with_attached_deposit(0)
+                    .with_static_gas(gas_for_resolver)
+                    .with_unused_gas_weight(0)
+                    .resolve_storage_deposit(
+                        token_contract,
+                        receiver_id_in_near,
+                        transfer,
+                        U128::from(deposit),
+                    ),
+            );
+    }
+    //
+    fn resolve_storage_deposit(
+        &mut self,
+        token_contract: AccountId,
+        receiver_id_in_near: AccountId,
+        transfer: PendingTransfer,
+        deposit: U128,
+    ) {
+        assert_self();
+        match env::promise_result(0) {
+            PromiseResult::NotReady => unreachable!(),
+            PromiseResult::Successful(value) => {
+                // The deposit exceeding the minimum storage balance is refunded
+                // by the token contract.
+                let spent_amount = match near_sdk::serde_json::from_slice::<StorageBalance>(&value)
+                {
+                    Ok(storage_balance) => std::cmp::min(storage_balance.total.0, deposit.0),
+                    Err(_) => deposit.0,
+                };
+                self.bridge_storage_fund.balance =
+                    U128::from(self.bridge_storage_fund.balance.0 + deposit.0 - spent_amount);
+                self.bridge_storage_fund.spent_amount =
+                    U128::from(self.bridge_storage_fund.spent_amount.0 + spent_amount);
+                let mut storage_registration = self
+                    .storage_registration_of_tokens
+                    .get(&token_contract)
+                    .unwrap_or_default();
+                storage_registration.spent_amount =
+                    U128::from(storage_registration.spent_amount.0 + spent_amount);
+                storage_registration.registered_account_count =
+                    U64::from(storage_registration.registered_account_count.0 + 1);
+                self.storage_registration_of_tokens
+                    .insert(&token_contract, &storage_registration);
+                log!(
+                    "Storage of '{}' is registered in token contract '{}'. Spent amount: '{}'",
+                    &receiver_id_in_near,
+                    &token_contract,
+                    spent_amount
+                );
+            }
+            PromiseResult::Failed => {
+                // The deposit is refunded.
+                self.bridge_storage_fund.balance =
+                    U128::from(self.bridge_storage_fund.balance.0 + deposit.0);
+                log!(
+                    "Failed to register storage of '{}' in token contract '{}'.",
+                    &receiver_id_in_near,
+                    &token_contract
+                );
+            }
+        }
+        self.execute_transfer(&token_contract, &receiver_id_in_near, transfer);
+    }
+}
+
+/// The gas needed for executing a transfer and resolving its result.
+fn gas_for_executing_transfer(transfer: &PendingTransfer) -> Gas {
+    Gas::ONE_TERA.mul(T_GAS_FOR_RESOLVER_FUNCTION)
+        + match transfer {
+            PendingTransfer::NearFungibleTokenUnlocking { .. } => {
+                Gas::ONE_TERA.mul(T_GAS_FOR_FT_TRANSFER)
+            }
+            PendingTransfer::WrappedAppchainTokenMinting { .. }
+            | PendingTransfer::WrappedAppchainAssetMinting { .. } => {
+                Gas::ONE_TERA.mul(T_GAS_FOR_MINT_FUNGIBLE_TOKEN)
+            }
+            PendingTransfer::NativeNearUnlocking { .. } => Gas::from(0),
+        }
+}
+
+/// The gas needed for resolving the checking of storage balance,
+/// including the possible storage deposit and the transfer.
+fn gas_for_resolving_storage_balance_checking(transfer: &PendingTransfer) -> Gas {
+    Gas::ONE_TERA.mul(T_GAS_FOR_RESOLVER_FUNCTION)
+        + Gas::ONE_TERA.mul(T_GAS_FOR_STORAGE_DEPOSIT)
+        + Gas::ONE_TERA.mul(T_GAS_FOR_RESOLVER_FUNCTION)
+        + gas_for_executing_transfer(transfer)
+}
@@ -0,0 +1,556 @@
+use std::str::FromStr;
+
+use near_contract_standards::fungible_token::metadata::FungibleTokenMetadata;
+use near_sdk::json_types::Base58CryptoHash;
+
+use super::bridge_fees::BridgingDirection;
+use crate::interfaces::WrappedAppchainAssetManager;
+use crate::permissionless_actions::AppchainMessagesProcessingContext;
+use crate::*;
+
+pub trait WrappedAppchainAssetContractResolver {
+    /// Resolver for burning wrapped appchain asset
+    fn resolve_wrapped_appchain_asset_burning(
+        &mut self,
+        asset_id: String,
+        sender_id_in_near: AccountId,
+        receiver_id_in_appchain: String,
+        amount: U128,
+    );
+    /// Resolver for minting wrapped appchain asset
+    fn resolve_wrapped_appchain_asset_minting(
+        &mut self,
+        asset_id: String,
+        sender_id_in_appchain: String,
+        receiver_id_in_near: AccountId,
+        amount: U128,
+        appchain_message_nonce: u32,
+    );
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct WrappedAppchainAssets {
+    /// The set of ids of wrapped appchain assets.
+    asset_id_set: UnorderedSet<String>,
+    /// The wrapped appchain assets data, mapped by the asset id.
+    assets: LookupMap<String, WrappedAppchainAsset>,
+}
+
+impl WrappedAppchainAssets {
+    ///
+    pub fn new() -> Self {
+        Self {
+            asset_id_set: UnorderedSet::new(StorageKey::WrappedAppchainAssetIds.into_bytes()),
+            assets: LookupMap::new(StorageKey::WrappedAppchainAssetsMap.into_bytes()),
+        }
+    }
+    ///
+    pub fn insert(&mut self, wrapped_appchain_asset: &WrappedAppchainAsset) {
+        self.asset_id_set.insert(&wrapped_appchain_asset.asset_id);
+        self.assets
+            .insert(&wrapped_appchain_asset.asset_id, wrapped_appchain_asset);
+    }
+    ///
+    pub fn get(&self, asset_id: &String) -> Option<WrappedAppchainAsset> {
+        self.assets.get(asset_id)
+    }
+    ///
+    pub fn get_by_contract_account(&self, account_id: &AccountId) -> Option<WrappedAppchainAsset> {
+        self.asset_id_set
+            .iter()
+            .map(|asset_id| self.assets.get(&asset_id).unwrap())
+            .find(|wrapped_appchain_asset| wrapped_appchain_asset.contract_account.eq(account_id))
+    }
+    /// The total market value of all wrapped appchain assets, including the pending mints.
+    pub fn total_market_value(&self) -> Balance {
+        self.asset_id_set
+            .iter()
+            .map(|asset_id| {
+                let wrapped_appchain_asset = self.assets.get(&asset_id).unwrap();
+                wrapped_appchain_asset.get_market_value_of(
+                    wrapped_appchain_asset.total_supply.0
+                        + wrapped_appchain_asset.pending_mint_amount.0,
+                )
+            })
+            .sum()
+    }
+    ///
+    pub fn to_vec(&self) -> Vec<WrappedAppchainAsset> {
+        self.asset_id_set
+            .iter()
+            .map(|asset_id| self.assets.get(&asset_id).unwrap())
+            .collect()
+    }
+}
+
+impl WrappedAppchainAsset {
+    ///
+    pub fn get_market_value_of(&self, amount: u128) -> Balance {
+        amount / u128::pow(10, u32::from(self.metadata.decimals)) * self.price_in_usd.0
+    }
+}
+
+#[near_bindgen]
+impl WrappedAppchainAssetManager for AppchainAnchor {
+    //
+    fn register_wrapped_appchain_asset(
+        &mut self,
+        asset_id: String,
+        metadata: FungibleTokenMetadata,
+        price: U128,
+    ) {
+        self.assert_owner();
+        assert!(
+            env::storage_has_key(&StorageKey::WrappedAppchainAssetContractWasm.into_bytes()),
+            "Wasm file for deployment is not staged yet."
+        );
+        let mut wrapped_appchain_assets = self.wrapped_appchain_assets.get().unwrap();
+        assert!(
+            wrapped_appchain_assets.get(&asset_id).is_none(),
+            "The given asset id has already registered."
+        );
+        let contract_account =
+            AccountId::from_str(format!("{}.{}", asset_id, env::current_account_id()).as_str());
+        assert!(contract_account.is_ok(), "Invalid asset id.");
+        let wrapped_appchain_asset = WrappedAppchainAsset {
+            asset_id,
+            metadata: metadata.clone(),
+            contract_account: contract_account.unwrap(),
+            price_in_usd: price,
+            total_supply: U128::from(0),
+            pending_mint_amount: U128::from(0),
+            bridging_state: BridgingState::Closed,
+        };
+        wrapped_appchain_assets.insert(&wrapped_appchain_asset);
+        self.wrapped_appchain_assets.set(&wrapped_appchain_assets);
+        //
+        #[derive(near_sdk::serde::Serialize)]
+        #[serde(crate = "near_sdk::serde")]
+        struct Input {
+            owner_id: AccountId,
+            premined_beneficiary: AccountId,
+            premined_balance: U128,
+            metadata: FungibleTokenMetadata,
+        }
+        let args = Input {
+            owner_id: env::current_account_id(),
+            premined_beneficiary: env::current_account_id(),
+            premined_balance: U128::from(0),
+            metadata,
+        };
+        let args = near_sdk::serde_json::to_vec(&args)
+            .expect("Failed to serialize the cross contract args using JSON.");
+        assert!(
+            self.near_balance_is_enough_for(WRAPPED_APPCHAIN_ASSET_CONTRACT_INIT_BALANCE),
+            "Not enough NEAR balance for deploying the contract of wrapped appchain asset."
+        );
+        Promise::new(wrapped_appchain_asset.contract_account)
+            .create_account()
+            .transfer(WRAPPED_APPCHAIN_ASSET_CONTRACT_INIT_BALANCE)
+            .add_full_access_key(self.owner_pk.clone())
+            .deploy_contract(
+                env::storage_read(&StorageKey::WrappedAppchainAssetContractWasm.into_bytes())
+                    .unwrap(),
+            )
+            .function_call(
+                "new".to_string(),
+                args,
+                0,
+                Gas::ONE_TERA.mul(T_GAS_FOR_FT_CONTRACT_INITIALIZATION),
+            );
+    }
+    //
+    fn set_price_of_wrapped_appchain_asset(&mut self, asset_id: String, price: U128) {
+        self.assert_token_price_maintainer();
+        let mut wrapped_appchain_assets = self.wrapped_appchain_assets.get().unwrap();
+        let mut wrapped_appchain_asset = wrapped_appchain_assets
+            .get(&asset_id)
+            .expect("Unregistered asset id.");
+        wrapped_appchain_asset.price_in_usd = price;
+        wrapped_appchain_assets.insert(&wrapped_appchain_asset);
+    }
+    //
+    fn open_bridging_of_wrapped_appchain_asset(&mut self, asset_id: String) {
+        self.assert_owner();
+        let mut wrapped_appchain_assets = self.wrapped_appchain_assets.get().unwrap();
+        let mut wrapped_appchain_asset = wrapped_appchain_assets
+            .get(&asset_id)
+            .expect("Unregistered asset id.");
+        assert!(
+            wrapped_appchain_asset
+                .bridging_state
+                .ne(&BridgingState::Active),
+            "Bridging is already active."
+        );
+        wrapped_appchain_asset.bridging_state = BridgingState::Active;
+        wrapped_appchain_assets.insert(&wrapped_appchain_asset);
+    }
+    //
+    fn close_bridging_of_wrapped_appchain_asset(&mut self, asset_id: String) {
+        self.assert_owner();
+        let mut wrapped_appchain_assets = self.wrapped_appchain_assets.get().unwrap();
+        let mut wrapped_appchain_asset = wrapped_appchain_assets
+            .get(&asset_id)
+            .expect("Unregistered asset id.");
+        assert!(
+            wrapped_appchain_asset
+                .bridging_state
+                .ne(&BridgingState::Closed),
+            "Bridging is already closed."
+        );
+        wrapped_appchain_asset.bridging_state = BridgingState::Closed;
+        wrapped_appchain_assets.insert(&wrapped_appchain_asset);
+    }
+    //
+    fn burn_wrapped_appchain_asset(&mut self, asset_id: String, receiver_id: String, amount: U128) {
+        self.assert_asset_transfer_is_not_paused();
+        let wrapped_appchain_asset = self
+            .wrapped_appchain_assets
+            .get()
+            .unwrap()
+            .get(&asset_id)
+            .expect("Unregistered asset id.");
+        assert!(
+            wrapped_appchain_asset
+                .bridging_state
+                .eq(&BridgingState::Active),
+            "Bridging for wrapped appchain asset '{}' is not active.",
+            asset_id
+        );
+        assert!(amount.0 > 0, "The amount should be greater than 0.");
+        let sender_id = env::predecessor_account_id();
+        AccountIdInAppchain::new(Some(receiver_id.clone()), &self.appchain_template_type)
+            .assert_valid();
+        // Return without panic, to keep the paused state of the asset (if any).
+        if let Err(message) = self.check_and_record_bridge_volume(
+            &wrapped_appchain_asset.contract_account,
+            amount.0,
+            BridgingDirection::ToAppchain,
+        ) {
+            log!("{}", message);
+            return;
+        }
+        #[derive(near_sdk::serde::Serialize)]
+        #[serde(crate = "near_sdk::serde")]
+        struct Args {
+            account_id: AccountId,
+            amount: U128,
+        }
+        let args = near_sdk::serde_json::to_vec(&Args {
+            account_id: sender_id.clone(),
+            amount,
+        })
+        .expect("Failed to serialize the cross contract args using JSON.");
+        Promise::new(wrapped_appchain_asset.contract_account)
+            .function_call(
+                "burn".to_string(),
+                args,
+                1,
+                Gas::ONE_TERA.mul(T_GAS_FOR_BURN_FUNGIBLE_TOKEN),
+            )
+            .then(
+                ext_self::ext(env::current_account_id())
+                    .with_attached_deposit(0)
+                    .with_static_gas(Gas::ONE_TERA.mul(T_GAS_FOR_RESOLVER_FUNCTION))
+                    .with_unused_gas_weight(0)
+                    .resolve_wrapped_appchain_asset_burning(
+                        asset_id,
+                        sender_id,
+                        receiver_id,
+                        amount,
+                    ),
+            );
+    }
+}
+
+impl AppchainAnchor {
+    //
+    pub fn internal_mint_wrapped_appchain_asset(
+        &mut self,
+        asset_id: &String,
+        sender_id_in_appchain: &String,
+        receiver_id_in_near: &AccountId,
+        amount: &U128,
+        appchain_message_nonce: u32,
+        processing_context: &mut AppchainMessagesProcessingContext,
+    ) -> MultiTxsOperationProcessingResult {
+        let mut wrapped_appchain_assets = self.wrapped_appchain_assets.get().unwrap();
+        let wrapped_appchain_asset = wrapped_appchain_assets.get(asset_id);
+        let message = match &wrapped_appchain_asset {
+            None => Some(format!(
+                "Unregistered wrapped appchain asset: '{}'.",
+                asset_id
+            )),
+            Some(wrapped_appchain_asset) => {
+                let protocol_settings = self.protocol_settings.get().unwrap();
+                if wrapped_appchain_asset
+                    .bridging_state
+                    .ne(&BridgingState::Active)
+                {
+                    Some(format!(
+                        "Bridging for wrapped appchain asset '{}' is not active.",
+                        asset_id
+                    ))
+                } else if self
+                    .wrapped_appchain_token
+                    .get()
+                    .unwrap()
+                    .total_market_value()
+                    + wrapped_appchain_assets.total_market_value()
+                    + wrapped_appchain_asset.get_market_value_of(amount.0)
+                    > self.get_market_value_of_staked_oct_token().0
+                        * u128::from(
+                            protocol_settings
+                                .maximum_market_value_percent_of_wrapped_appchain_token,
+                        )
+                        / 100
+                {
+                    Some(format!(
+                        "Too much wrapped appchain asset '{}' to mint.",
+                        asset_id
+                    ))
+                } else if !self.near_balance_is_enough_for(STORAGE_DEPOSIT_FOR_NEP141_TOEKN) {
+                    Some(format!(
+                        "Not enough NEAR balance for the storage deposit of minting wrapped appchain asset '{}'.",
+                        asset_id
+                    ))
+                } else {
+                    self.check_and_record_bridge_volume(
+                        &wrapped_appchain_asset.contract_account,
+                        amount.0,
+                        BridgingDirection::ToNear,
+                    )
+                    .err()
+                }
+            }
+        };
+        if let Some(message) = message {
+            let result = AppchainMessageProcessingResult::Error {
+                nonce: appchain_message_nonce,
+                message: message.clone(),
+            };
+            self.record_appchain_message_processing_result(&result);
+            return MultiTxsOperationProcessingResult::Error(message);
+        }
+        // The amount is counted in the market value of the asset until it is minted,
+        // or until the pending transfer is claimed if the minting fails.
+        let mut wrapped_appchain_asset = wrapped_appchain_asset.unwrap();
+        wrapped_appchain_asset.pending_mint_amount =
+            U128::from(wrapped_appchain_asset.pending_mint_amount.0 + amount.0);
+        wrapped_appchain_assets.insert(&wrapped_appchain_asset);
+        let prepaid_gas = self.internal_pay_out_transfer(
+            &wrapped_appchain_asset.contract_account,
+            receiver_id_in_near,
+            PendingTransfer::WrappedAppchainAssetMinting {
+                asset_id: asset_id.clone(),
+                sender_id_in_appchain: sender_id_in_appchain.clone(),
+                amount: amount.clone(),
+                appchain_message_nonce,
+            },
+        );
+        processing_context.add_prepaid_gas(prepaid_gas);
+        MultiTxsOperationProcessingResult::Ok
+    }
+}
+
+/// Mint wrapped appchain asset in its contract for the receiver,
+/// and resolve the result by `resolve_wrapped_appchain_asset_minting`.
+pub fn mint_wrapped_appchain_asset_and_resolve(
+    contract_account: &AccountId,
+    asset_id: &String,
+    sender_id_in_appchain: &String,
+    receiver_id_in_near: &AccountId,
+    amount: &U128,
+    appchain_message_nonce: u32,
+) {
+    #[derive(near_sdk::serde::Serialize)]
+    #[serde(crate = "near_sdk::serde")]
+    struct Args {
+        account_id: AccountId,
+        amount: U128,
+    }
+    let args = near_sdk::serde_json::to_vec(&Args {
+        account_id: receiver_id_in_near.clone(),
+        amount: amount.clone(),
+    })
+    .expect("Failed to serialize the cross contract args using JSON.");
+    Promise::new(contract_account.clone())
+        .function_call(
+            "mint".to_string(),
+            args,
+            STORAGE_DEPOSIT_FOR_NEP141_TOEKN,
+            Gas::ONE_TERA.mul(T_GAS_FOR_MINT_FUNGIBLE_TOKEN),
+        )
+        .then(
+            ext_self::ext(env::current_account_id())
+                .with_attached_deposit(0)
+                .with_static_gas(Gas::ONE_TERA.mul(T_GAS_FOR_RESOLVER_FUNCTION))
+                .with_unused_gas_weight(0)
+                .resolve_wrapped_appchain_asset_minting(
+                    asset_id.clone(),
+                    sender_id_in_appchain.clone(),
+                    receiver_id_in_near.clone(),
+                    amount.clone(),
+                    appchain_message_nonce,
+                ),
+        );
+}
+
+#[near_bindgen]
+impl WrappedAppchainAssetContractResolver for AppchainAnchor {
+    //
+    fn resolve_wrapped_appchain_asset_burning(
+        &mut self,
+        asset_id: String,
+        sender_id_in_near: AccountId,
+        receiver_id_in_appchain: String,
+        amount: U128,
+    ) {
+        assert_self();
+        match env::promise_result(0) {
+            PromiseResult::NotReady => unreachable!(),
+            PromiseResult::Successful(_) => {
+                let mut wrapped_appchain_assets = self.wrapped_appchain_assets.get().unwrap();
+                if let Some(mut wrapped_appchain_asset) = wrapped_appchain_assets.get(&asset_id) {
+                    wrapped_appchain_asset.total_supply = U128::from(
+                        wrapped_appchain_asset
+                            .total_supply
+                            .0
+                            .saturating_sub(amount.0),
+                    );
+                    wrapped_appchain_assets.insert(&wrapped_appchain_asset);
+                }
+                let appchain_notification_history = self.internal_append_appchain_notification(
+                    AppchainNotification::WrappedAppchainAssetBurnt {
+                        asset_id: asset_id.clone(),
+                        sender_id_in_near: sender_id_in_near.clone(),
+                        receiver_id_in_appchain: receiver_id_in_appchain.clone(),
+                        amount,
+                    },
+                );
+                log!(
+                    "Wrapped appchain asset '{}' burnt by '{}' for '{}' of appchain. Amount: '{}', Crosschain notification index: '{}'.",
+                    &asset_id,
+                    &sender_id_in_near,
+                    &receiver_id_in_appchain,
+                    &amount.0,
+                    &appchain_notification_history.index.0
+                );
+                AnchorEvent::WrappedAppchainAssetBurnt {
+                    asset_id,
+                    sender_id_in_near,
+                    receiver_id_in_appchain,
+                    amount,
+                }
+                .emit();
+            }
+            PromiseResult::Failed => {
+                log!(
+                    "Failed to burn wrapped appchain asset '{}' by '{}' for '{}' in appchain. Amount: '{}'",
+                    &asset_id,
+                    &sender_id_in_near,
+                    &receiver_id_in_appchain,
+                    &amount.0
+                );
+            }
+        }
+    }
+    //
+    fn resolve_wrapped_appchain_asset_minting(
+        &mut self,
+        asset_id: String,
+        sender_id_in_appchain: String,
+        receiver_id_in_near: AccountId,
+        amount: U128,
+        appchain_message_nonce: u32,
+    ) {
+        assert_self();
+        match env::promise_result(0) {
+            PromiseResult::NotReady => unreachable!(),
+            PromiseResult::Successful(_) => {
+                let mut wrapped_appchain_assets = self.wrapped_appchain_assets.get().unwrap();
+                if let Some(mut wrapped_appchain_asset) = wrapped_appchain_assets.get(&asset_id) {
+                    wrapped_appchain_asset.total_supply =
+                        U128::from(wrapped_appchain_asset.total_supply.0 + amount.0);
+                    wrapped_appchain_asset.pending_mint_amount = U128::from(
+                        wrapped_appchain_asset
+                            .pending_mint_amount
+                            .0
+                            .saturating_sub(amount.0),
+                    );
+                    wrapped_appchain_assets.insert(&wrapped_appchain_asset);
+                }
+                let message = format!(
+                    "Wrapped appchain asset '{}' is minted by '{}' of appchain for '{}' with amount '{}'.",
+                    &asset_id, &sender_id_in_appchain, &receiver_id_in_near, &amount.0
+                );
+                self.record_appchain_message_processing_result(
+                    &AppchainMessageProcessingResult::Ok {
+                        nonce: appchain_message_nonce,
+                        message: Some(message),
+                    },
+                );
+                AnchorEvent::WrappedAppchainAssetMinted {
+                    asset_id,
+                    sender_id_in_appchain,
+                    receiver_id_in_near,
+                    amount,
+                    appchain_message_nonce,
+                }
+                .emit();
+            }
+            PromiseResult::Failed => {
+                let message = format!(
+                    "Failed to mint wrapped appchain asset '{}' for '{}' with amount '{}'.",
+                    &asset_id, &receiver_id_in_near, &amount.0
+                );
+                self.record_appchain_message_processing_result(
+                    &AppchainMessageProcessingResult::Error {
+                        nonce: appchain_message_nonce,
+                        message,
+                    },
+                );
+                self.add_pending_transfer(
+                    &receiver_id_in_near,
+                    PendingTransfer::WrappedAppchainAssetMinting {
+                        asset_id,
+                        sender_id_in_appchain,
+                        amount,
+                        appchain_message_nonce,
+                    },
+                );
+            }
+        }
+    }
+}
+
+/// Stores attached data into blob store and returns hash of it.
+/// Implemented to avoid loading the data into WASM for optimal gas usage.
+#[no_mangle]
+pub extern "C" fn store_wasm_of_wrapped_appchain_asset_contract() {
+    env::setup_panic_hook();
+    let contract: AppchainAnchor = env::state_read().expect("ERR_CONTRACT_IS_NOT_INITIALIZED");
+    contract.assert_owner();
+    let input = env::input().expect("ERR_NO_INPUT");
+    let sha256_hash = env::sha256(&input);
+
+    let blob_len = input.len();
+    let storage_cost = ((blob_len + 32) as u128) * env::storage_byte_cost();
+    assert!(
+        env::attached_deposit() >= storage_cost,
+        "ERR_NOT_ENOUGH_DEPOSIT:{}",
+        storage_cost
+    );
+
+    env::storage_write(
+        &StorageKey::WrappedAppchainAssetContractWasm.into_bytes(),
+        &input,
+    );
+    let mut blob_hash = [0u8; 32];
+    blob_hash.copy_from_slice(&sha256_hash);
+    let blob_hash_str = near_sdk::serde_json::to_string(&Base58CryptoHash::from(blob_hash))
+        .unwrap()
+        .into_bytes();
+
+    env::value_return(&blob_hash_str);
+}
@@ -4,6 +4,7 @@ use near_contract_standards::non_fungible_token::metadata::NFTContractMetadata;
 use near_sdk::json_types::Base58CryptoHash;
 use near_sdk::Promise;
 
+use super::bridge_fees::BridgingDirection;
 use crate::interfaces::WrappedAppchainNFTManager;
 use crate::permissionless_actions::AppchainMessagesProcessingContext;
 use crate::types::WrappedAppchainNFT;
@@ -181,6 +182,10 @@ impl WrappedAppchainNFTManager for AppchainAnchor {
         };
         let args = near_sdk::serde_json::to_vec(&args)
             .expect("Failed to serialize the cross contract args using JSON.");
+        assert!(
+            self.near_balance_is_enough_for(WRAPPED_APPCHAIN_NFT_CONTRACT_INIT_BALANCE),
+            "Not enough NEAR balance for deploying the contract of wrapped appchain NFT."
+        );
         Promise::new(internal_wrapped_appchain_nft.contract_account)
             .create_account()
             .transfer(WRAPPED_APPCHAIN_NFT_CONTRACT_INIT_BALANCE)
@@ -263,10 +268,10 @@ impl AppchainAnchor {
         if let Some(wrapped_appchain_nft) = wrapped_appchain_nfts.get(&class_id) {
             if wrapped_appchain_nft
                 .bridging_state
-                .eq(&BridgingState::Closed)
+                .ne(&BridgingState::Active)
             {
                 let message = format!(
-                    "Bridging for wrapped appchain NFT in contract '{}' is now closed.",
+                    "Bridging for wrapped appchain NFT in contract '{}' is not active.",
                     wrapped_appchain_nft.contract_account
                 );
                 let result = AppchainMessageProcessingResult::Error {
@@ -276,6 +281,44 @@ impl AppchainAnchor {
                 self.record_appchain_message_processing_result(&result);
                 return MultiTxsOperationProcessingResult::Error(message);
             }
+            if !wrapped_appchain_nft.is_nft_locked(&instance_id)
+                && !self.near_balance_is_enough_for(STORAGE_DEPOSIT_FOR_MINT_NFT)
+            {
+                let message =
+                    "Not enough NEAR balance for the storage deposit of minting NFT.".to_string();
+                let result = AppchainMessageProcessingResult::Error {
+                    nonce: appchain_message_nonce,
+                    message: message.clone(),
+                };
+                self.record_appchain_message_processing_result(&result);
+                return MultiTxsOperationProcessingResult::Error(message);
+            }
+            if let Err(message) = self
+                .check_nft_bridge_fee_deposit(
+                    &wrapped_appchain_nft.contract_account,
+                    receiver_id_in_near,
+                    BridgingDirection::ToNear,
+                )
+                .and_then(|_| {
+                    self.check_and_record_bridge_volume(
+                        &wrapped_appchain_nft.contract_account,
+                        1,
+                        BridgingDirection::ToNear,
+                    )
+                })
+            {
+                let result = AppchainMessageProcessingResult::Error {
+                    nonce: appchain_message_nonce,
+                    message: message.clone(),
+                };
+                self.record_appchain_message_processing_result(&result);
+                return MultiTxsOperationProcessingResult::Error(message);
+            }
+            self.charge_nft_bridge_fee_to_near(
+                &wrapped_appchain_nft.contract_account,
+                receiver_id_in_near,
+                appchain_message_nonce,
+            );
             if wrapped_appchain_nft.is_nft_locked(&instance_id) {
                 #[derive(near_sdk::serde::Serialize)]
                 #[serde(crate = "near_sdk::serde")]
@@ -387,7 +430,7 @@ impl AppchainAnchor {
                 wrapped_appchain_nft
                     .bridging_state
                     .eq(&BridgingState::Active),
-                "Bridging for '{}({})' is closed.",
+                "Bridging for '{}({})' is not active.",
                 wrapped_appchain_nft.metadata.symbol,
                 wrapped_appchain_nft.metadata.name
             );
@@ -400,6 +443,25 @@ impl AppchainAnchor {
                         &self.appchain_template_type,
                     )
                     .assert_valid();
+                    // Return the NFT without panic, to keep the paused state of the class (if any).
+                    if let Err(message) = self
+                        .check_nft_bridge_fee_deposit(
+                            &predecessor_account_id,
+                            &sender_id,
+                            BridgingDirection::ToAppchain,
+                        )
+                        .and_then(|_| {
+                            self.check_and_record_bridge_volume(
+                                &predecessor_account_id,
+                                1,
+                                BridgingDirection::ToAppchain,
+                            )
+                        })
+                    {
+                        log!("{} Return it.", message);
+                        return PromiseOrValue::Value(true);
+                    }
+                    self.charge_nft_bridge_fee_to_appchain(&predecessor_account_id, &sender_id);
                     wrapped_appchain_nft.add_locked_nft(&token_id);
                     let class_id = wrapped_appchain_nfts
                         .get_class_id_by_contract_account(&predecessor_account_id)
@@ -453,6 +515,7 @@ impl WrappedAppchainNFTContractResolver for AppchainAnchor {
                     wrapped_appchain_nft.remove_locked_nft(&instance_id);
                     wrapped_appchain_nfts.insert(&class_id, &wrapped_appchain_nft);
                 };
+                self.settle_nft_bridge_fee_charge(appchain_message_nonce, true);
                 self.record_appchain_message_processing_result(
                     &AppchainMessageProcessingResult::Ok {
                         nonce: appchain_message_nonce,
@@ -461,6 +524,7 @@ impl WrappedAppchainNFTContractResolver for AppchainAnchor {
                 );
             }
             PromiseResult::Failed => {
+                self.settle_nft_bridge_fee_charge(appchain_message_nonce, false);
                 let wrapped_appchain_nfts = self.wrapped_appchain_nfts.get().unwrap();
                 let reason = format!(
                     "Maybe the receiver account '{}' is not registered in contract '{}'.",
@@ -499,6 +563,7 @@ impl WrappedAppchainNFTContractResolver for AppchainAnchor {
         match env::promise_result(0) {
             PromiseResult::NotReady => unreachable!(),
             PromiseResult::Successful(_) => {
+                self.settle_nft_bridge_fee_charge(appchain_message_nonce, true);
                 let message = format!(
                     "NFT '{}' from appchain account '{}' with metadata '{}' is minted.",
                     instance_id,
@@ -513,6 +578,7 @@ impl WrappedAppchainNFTContractResolver for AppchainAnchor {
                 );
             }
             PromiseResult::Failed => {
+                self.settle_nft_bridge_fee_charge(appchain_message_nonce, false);
                 let wrapped_appchain_nfts = self.wrapped_appchain_nfts.get().unwrap();
                 let reason = format!(
                     "Maybe the receiver account '{}' is not registered in contract '{}'.",
@@ -2,6 +2,7 @@ use core::convert::TryFrom;
 use near_contract_standards::fungible_token::metadata::FungibleTokenMetadata;
 use near_sdk::json_types::I128;
 
+use super::bridge_fees::BridgingDirection;
 use crate::{
     interfaces::WrappedAppchainTokenManager,
     permissionless_actions::AppchainMessagesProcessingContext, *,
@@ -43,6 +44,7 @@ impl Default for WrappedAppchainToken {
             changed_balance: I128::from(0),
             price_in_usd: U128::from(0),
             total_supply: U128::from(0),
+            bridging_state: BridgingState::Active,
         }
     }
 }
@@ -107,14 +109,64 @@ impl WrappedAppchainTokenManager for AppchainAnchor {
         self.wrapped_appchain_token.set(&wrapped_appchain_token);
     }
     //
-    fn burn_wrapped_appchain_token(&self, receiver_id: String, amount: U128) {
+    fn open_bridging_of_wrapped_appchain_token(&mut self) {
+        self.assert_owner();
+        let mut wrapped_appchain_token = self.wrapped_appchain_token.get().unwrap();
+        assert!(
+            wrapped_appchain_token
+                .bridging_state
+                .ne(&BridgingState::Active),
+            "Bridging is already active."
+        );
+        wrapped_appchain_token.bridging_state = BridgingState::Active;
+        self.wrapped_appchain_token.set(&wrapped_appchain_token);
+    }
+    //
+    fn close_bridging_of_wrapped_appchain_token(&mut self) {
+        self.assert_owner();
+        let mut wrapped_appchain_token = self.wrapped_appchain_token.get().unwrap();
+        assert!(
+            wrapped_appchain_token
+                .bridging_state
+                .ne(&BridgingState::Closed),
+            "Bridging is already closed."
+        );
+        wrapped_appchain_token.bridging_state = BridgingState::Closed;
+        self.wrapped_appchain_token.set(&wrapped_appchain_token);
+    }
+    //
+    fn burn_wrapped_appchain_token(&mut self, receiver_id: String, amount: U128) {
         self.assert_asset_transfer_is_not_paused();
         self.assert_contract_account_of_wrapped_appchain_token_is_set();
         let wrapped_appchain_token = self.wrapped_appchain_token.get().unwrap();
+        assert!(
+            wrapped_appchain_token
+                .bridging_state
+                .eq(&BridgingState::Active),
+            "Bridging for wrapped appchain token is not active."
+        );
         let sender_id = env::predecessor_account_id();
         let account_id_in_appchain =
             AccountIdInAppchain::new(Some(receiver_id.clone()), &self.appchain_template_type);
         account_id_in_appchain.assert_valid();
+        assert!(
+            amount.0
+                > self.get_bridge_fee_of_transfer(
+                    wrapped_appchain_token.contract_account.as_ref().unwrap(),
+                    amount.0,
+                    BridgingDirection::ToAppchain,
+                ),
+            "The amount is not enough to pay the bridge fee."
+        );
+        // Return without panic, to keep the paused state of the token (if any).
+        if let Err(message) = self.check_and_record_bridge_volume(
+            wrapped_appchain_token.contract_account.as_ref().unwrap(),
+            amount.0,
+            BridgingDirection::ToAppchain,
+        ) {
+            log!("{}", message);
+            return;
+        }
         // burn token in wrapped appchain token contract
         #[derive(near_sdk::serde::Serialize)]
         #[serde(crate = "near_sdk::serde")]
@@ -182,44 +234,108 @@ impl AppchainAnchor {
                 return MultiTxsOperationProcessingResult::Error(message);
             }
         }
-        // mint token in wrapped appchain token contract
-        #[derive(near_sdk::serde::Serialize)]
-        #[serde(crate = "near_sdk::serde")]
-        struct Args {
-            account_id: AccountId,
-            amount: U128,
+        let contract_account = wrapped_appchain_token.contract_account.unwrap();
+        // The bridging state and rate limits only apply to cross-chain transfers.
+        if sender_id.is_some() {
+            let message = match wrapped_appchain_token
+                .bridging_state
+                .eq(&BridgingState::Active)
+            {
+                true => self
+                    .check_and_record_bridge_volume(
+                        &contract_account,
+                        amount.0,
+                        BridgingDirection::ToNear,
+                    )
+                    .err(),
+                false => Some(format!(
+                    "Bridging for wrapped appchain token is not active."
+                )),
+            };
+            if let Some(message) = message {
+                let result = AppchainMessageProcessingResult::Error {
+                    nonce: appchain_message_nonce,
+                    message: message.clone(),
+                };
+                self.record_appchain_message_processing_result(&result);
+                return MultiTxsOperationProcessingResult::Error(message);
+            }
         }
-        let args = Args {
-            account_id: receiver_id.clone(),
-            amount: amount.clone(),
+        // Only charge bridge fee in cross-chain transfer case,
+        // the bridge fee is not minted until it is withdrawn.
+        let amount = match sender_id {
+            Some(_) => &U128::from(self.charge_bridge_fee(
+                &contract_account,
+                amount.0,
+                BridgingDirection::ToNear,
+            )),
+            None => amount,
         };
-        let args = near_sdk::serde_json::to_vec(&args)
-            .expect("Failed to serialize the cross contract args using JSON.");
-        Promise::new(wrapped_appchain_token.contract_account.unwrap())
-            .function_call(
-                "mint".to_string(),
-                args,
-                STORAGE_DEPOSIT_FOR_NEP141_TOEKN,
-                Gas::ONE_TERA.mul(T_GAS_FOR_MINT_FUNGIBLE_TOKEN),
-            )
-            .then(
-                ext_self::ext(env::current_account_id())
-                    .with_attached_deposit(0)
-                    .with_static_gas(Gas::ONE_TERA.mul(T_GAS_FOR_RESOLVER_FUNCTION))
-                    .with_unused_gas_weight(0)
-                    .resolve_wrapped_appchain_token_minting(
-                        sender_id.map_or(None, |s| Some(s.clone())),
-                        receiver_id.clone(),
-                        amount.clone(),
-                        appchain_message_nonce,
-                    ),
-            );
-        processing_context.add_prepaid_gas(Gas::ONE_TERA.mul(T_GAS_FOR_MINT_FUNGIBLE_TOKEN));
-        processing_context.add_prepaid_gas(Gas::ONE_TERA.mul(T_GAS_FOR_RESOLVER_FUNCTION));
+        if amount.0 == 0 {
+            let message =
+                format!("The whole amount of wrapped appchain token is charged as bridge fee.");
+            self.record_appchain_message_processing_result(&AppchainMessageProcessingResult::Ok {
+                nonce: appchain_message_nonce,
+                message: Some(message),
+            });
+            return MultiTxsOperationProcessingResult::Ok;
+        }
+        let prepaid_gas = self.internal_pay_out_transfer(
+            &contract_account,
+            receiver_id,
+            PendingTransfer::WrappedAppchainTokenMinting {
+                sender_id_in_appchain: sender_id.map(|s| s.clone()),
+                amount: amount.clone(),
+                appchain_message_nonce,
+            },
+        );
+        processing_context.add_prepaid_gas(prepaid_gas);
         MultiTxsOperationProcessingResult::Ok
     }
 }
 
+/// Mint wrapped appchain token in its contract for the receiver,
+/// and resolve the result by `resolve_wrapped_appchain_token_minting`.
+pub fn mint_wrapped_appchain_token_and_resolve(
+    contract_account: &AccountId,
+    sender_id: Option<&String>,
+    receiver_id: &AccountId,
+    amount: &U128,
+    appchain_message_nonce: u32,
+) {
+    #[derive(near_sdk::serde::Serialize)]
+    #[serde(crate = "near_sdk::serde")]
+    struct Args {
+        account_id: AccountId,
+        amount: U128,
+    }
+    let args = Args {
+        account_id: receiver_id.clone(),
+        amount: amount.clone(),
+    };
+    let args = near_sdk::serde_json::to_vec(&args)
+        .expect("Failed to serialize the cross contract args using JSON.");
+    Promise::new(contract_account.clone())
+        .function_call(
+            "mint".to_string(),
+            args,
+            STORAGE_DEPOSIT_FOR_NEP141_TOEKN,
+            Gas::ONE_TERA.mul(T_GAS_FOR_MINT_FUNGIBLE_TOKEN),
+        )
+        .then(
+            ext_self::ext(env::current_account_id())
+                .with_attached_deposit(0)
+                .with_static_gas(Gas::ONE_TERA.mul(T_GAS_FOR_RESOLVER_FUNCTION))
+                .with_unused_gas_weight(0)
+                .resolve_wrapped_appchain_token_minting(
+                    sender_id.map_or(None, |s| Some(s.clone())),
+                    receiver_id.clone(),
+                    amount.clone(),
+                    appchain_message_nonce,
+                ),
+        );
+}
+
 #[near_bindgen]
 impl WrappedAppchainTokenContractResolver for AppchainAnchor {
     //
@@ -238,6 +354,13 @@ impl WrappedAppchainTokenContractResolver for AppchainAnchor {
                     wrapped_appchain_token.changed_balance.0 - i128::try_from(amount.0).unwrap(),
                 );
                 self.wrapped_appchain_token.set(&wrapped_appchain_token);
+                // The bridge fee is burnt together, and will be minted to
+                // the bridge fee collector when it is withdrawn.
+                let amount = U128::from(self.charge_bridge_fee(
+                    wrapped_appchain_token.contract_account.as_ref().unwrap(),
+                    amount.0,
+                    BridgingDirection::ToAppchain,
+                ));
                 let appchain_notification_history = self.internal_append_appchain_notification(
                     AppchainNotification::WrappedAppchainTokenBurnt {
                         sender_id_in_near: sender_id_in_near.clone(),
@@ -253,6 +376,12 @@ impl WrappedAppchainTokenContractResolver for AppchainAnchor {
                     &amount.0,
                     &appchain_notification_history.index.0
                 );
+                AnchorEvent::WrappedAppchainTokenBurnt {
+                    sender_id_in_near,
+                    receiver_id_in_appchain,
+                    amount,
+                }
+                .emit();
             }
             PromiseResult::Failed => {
                 log!(
@@ -262,6 +391,13 @@ impl WrappedAppchainTokenContractResolver for AppchainAnchor {
                     &receiver_id_in_appchain,
                     &amount.0
                 );
+                AnchorEvent::FailedToBurnWrappedAppchainToken {
+                    sender_id_in_near,
+                    receiver_id_in_appchain,
+                    amount,
+                    reason: format!("Failed to burn wrapped appchain token."),
+                }
+                .emit();
             }
         }
     }
@@ -282,7 +418,7 @@ impl WrappedAppchainTokenContractResolver for AppchainAnchor {
                     wrapped_appchain_token.changed_balance.0 + i128::try_from(amount.0).unwrap(),
                 );
                 self.wrapped_appchain_token.set(&wrapped_appchain_token);
-                if let Some(sender_id) = sender_id_in_appchain {
+                if let Some(sender_id) = sender_id_in_appchain.clone() {
                     // Only generate appchain message processing result in cross-chain transfer case
                     let message = format!(
                         "Wrapped appchain token is minted by '{}' of appchain for '{}' with amount '{}'.",
@@ -295,6 +431,13 @@ impl WrappedAppchainTokenContractResolver for AppchainAnchor {
                         },
                     );
                 };
+                AnchorEvent::WrappedAppchainTokenMinted {
+                    sender_id_in_appchain,
+                    receiver_id_in_near,
+                    amount,
+                    appchain_message_nonce,
+                }
+                .emit();
             }
             PromiseResult::Failed => {
                 let reason = format!("Maybe the total supply will overflow.");
@@ -308,6 +451,22 @@ impl WrappedAppchainTokenContractResolver for AppchainAnchor {
                         message,
                     },
                 );
+                self.add_pending_transfer(
+                    &receiver_id_in_near,
+                    PendingTransfer::WrappedAppchainTokenMinting {
+                        sender_id_in_appchain: sender_id_in_appchain.clone(),
+                        amount,
+                        appchain_message_nonce,
+                    },
+                );
+                AnchorEvent::FailedToMintWrappedAppchainToken {
+                    sender_id_in_appchain,
+                    receiver_id_in_near,
+                    amount,
+                    appchain_message_nonce,
+                    reason,
+                }
+                .emit();
             }
         }
     }
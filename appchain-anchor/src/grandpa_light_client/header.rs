@@ -0,0 +1,101 @@
+use crate::appchain_challenge::equivocation_challenge::{BlockNumber, Hash};
+use codec::{Decode, Encode};
+
+/// The engine id of GRANDPA in the digest of appchain header.
+pub const GRANDPA_ENGINE_ID: [u8; 4] = *b"FRNK";
+
+/// The header of appchain block, in SCALE codec.
+#[derive(Clone, Debug, Decode, Encode)]
+pub struct Header {
+    pub parent_hash: Hash,
+    #[codec(compact)]
+    pub number: BlockNumber,
+    pub state_root: Hash,
+    pub extrinsics_root: Hash,
+    pub digest: Digest,
+}
+
+#[derive(Clone, Debug, Decode, Encode)]
+pub struct Digest {
+    pub logs: Vec<DigestItem>,
+}
+
+#[derive(Clone, Debug, Decode, Encode)]
+pub enum DigestItem {
+    #[codec(index = 0)]
+    Other(Vec<u8>),
+    #[codec(index = 4)]
+    Consensus([u8; 4], Vec<u8>),
+    #[codec(index = 5)]
+    Seal([u8; 4], Vec<u8>),
+    #[codec(index = 6)]
+    PreRuntime([u8; 4], Vec<u8>),
+    #[codec(index = 8)]
+    RuntimeEnvironmentUpdated,
+}
+
+/// The consensus log of GRANDPA, which is deposited in the digest of appchain header.
+#[derive(Clone, Debug, Decode, Encode)]
+pub enum ConsensusLog {
+    #[codec(index = 1)]
+    ScheduledChange(ScheduledChange),
+    #[codec(index = 2)]
+    ForcedChange(BlockNumber, ScheduledChange),
+    #[codec(index = 3)]
+    OnDisabled(u64),
+    #[codec(index = 4)]
+    Pause(BlockNumber),
+    #[codec(index = 5)]
+    Resume(BlockNumber),
+}
+
+#[derive(Clone, Debug, Decode, Encode)]
+pub struct ScheduledChange {
+    pub next_authorities: Vec<([u8; 32], u64)>,
+    pub delay: BlockNumber,
+}
+
+/// Calculate the blake2-256 hash of the given data.
+pub fn blake2_256(data: &[u8]) -> [u8; 32] {
+    let mut hash = [0u8; 32];
+    hash.copy_from_slice(blake2_rfc::blake2b::blake2b(32, &[], data).as_bytes());
+    hash
+}
+
+impl Header {
+    ///
+    pub fn hash(&self) -> [u8; 32] {
+        blake2_256(&self.encode())
+    }
+    /// Get the scheduled change of GRANDPA authority set in the digest.
+    pub fn scheduled_change(&self) -> Option<ScheduledChange> {
+        self.digest.logs.iter().find_map(|item| match item {
+            DigestItem::Consensus(engine_id, data) if engine_id.eq(&GRANDPA_ENGINE_ID) => {
+                match ConsensusLog::decode(&mut &data[..]) {
+                    Ok(ConsensusLog::ScheduledChange(change)) => Some(change),
+                    _ => None,
+                }
+            }
+            _ => None,
+        })
+    }
+    /// Whether there is a forced change of GRANDPA authority set in the digest.
+    pub fn has_forced_change(&self) -> bool {
+        self.digest.logs.iter().any(|item| match item {
+            DigestItem::Consensus(engine_id, data) if engine_id.eq(&GRANDPA_ENGINE_ID) => {
+                matches!(
+                    ConsensusLog::decode(&mut &data[..]),
+                    Ok(ConsensusLog::ForcedChange(..))
+                )
+            }
+            _ => false,
+        })
+    }
+    /// Whether the given commitment is in the `Other` items of the digest.
+    pub fn contains_commitment(&self, commitment: &[u8]) -> bool {
+        self.digest.logs.iter().any(|item| match item {
+            DigestItem::Other(data) => data.as_slice().eq(commitment),
+            _ => false,
+        })
+    }
+}
@@ -0,0 +1,111 @@
+use super::header::Header;
+use super::{GrandpaAuthority, GrandpaLightClientError};
+use crate::appchain_challenge::equivocation_challenge::{
+    check_grandpa_message_signature, BlockNumber, GrandpaMessage, GrandpaPrecommit, Hash,
+    PublicKey, SignatureData,
+};
+use codec::{Decode, Encode};
+use std::collections::HashMap;
+
+#[derive(Clone, Debug, Decode, Encode)]
+pub struct SignedPrecommit {
+    pub precommit: GrandpaPrecommit,
+    pub signature: [u8; 64],
+    pub id: [u8; 32],
+}
+
+#[derive(Clone, Debug, Decode, Encode)]
+pub struct Commit {
+    pub target_hash: Hash,
+    pub target_number: BlockNumber,
+    pub precommits: Vec<SignedPrecommit>,
+}
+
+/// The GRANDPA justification of a finalized appchain header, in SCALE codec.
+#[derive(Clone, Debug, Decode, Encode)]
+pub struct GrandpaJustification {
+    pub round: u64,
+    pub commit: Commit,
+    pub votes_ancestries: Vec<Header>,
+}
+
+impl GrandpaJustification {
+    /// Verify that the justification finalizes the given block with the given authority set.
+    ///
+    /// The precommits must be signed by distinct authorities of the set, and the total weight
+    /// of them must reach the supermajority (more than 2/3) of the set.
+    pub fn verify(
+        &self,
+        target_hash: &[u8; 32],
+        target_number: BlockNumber,
+        set_id: u64,
+        authorities: &Vec<GrandpaAuthority>,
+    ) -> Result<(), GrandpaLightClientError> {
+        if self.commit.target_hash.0.ne(target_hash) || self.commit.target_number != target_number {
+            return Err(GrandpaLightClientError::InvalidJustificationTarget);
+        }
+        let ancestries: HashMap<[u8; 32], &Header> = self
+            .votes_ancestries
+            .iter()
+            .map(|header| (header.hash(), header))
+            .collect();
+        let mut signers: Vec<[u8; 32]> = Vec::new();
+        let mut signed_weight: u64 = 0;
+        for signed_precommit in &self.commit.precommits {
+            let authority = match authorities
+                .iter()
+                .find(|authority| authority.public_key.eq(&signed_precommit.id))
+            {
+                Some(authority) => authority,
+                None => return Err(GrandpaLightClientError::UnknownAuthority),
+            };
+            if signers.contains(&signed_precommit.id) {
+                continue;
+            }
+            if !self.is_descendant_of_target(&signed_precommit.precommit, &ancestries) {
+                return Err(GrandpaLightClientError::InvalidVotesAncestries);
+            }
+            if !check_grandpa_message_signature(
+                &GrandpaMessage::Precommit(signed_precommit.precommit.clone()),
+                self.round,
+                set_id,
+                &SignatureData(signed_precommit.signature.to_vec()),
+                &PublicKey(signed_precommit.id),
+            ) {
+                return Err(GrandpaLightClientError::InvalidSignature);
+            }
+            signers.push(signed_precommit.id);
+            signed_weight += authority.weight;
+        }
+        let total_weight: u64 = authorities.iter().map(|authority| authority.weight).sum();
+        if total_weight == 0 || signed_weight < total_weight - total_weight.saturating_sub(1) / 3 {
+            return Err(GrandpaLightClientError::NotEnoughSignatures);
+        }
+        Ok(())
+    }
+    // Whether the target of the precommit is the target of commit or a descendant of it,
+    // by walking back the votes ancestries.
+    fn is_descendant_of_target(
+        &self,
+        precommit: &GrandpaPrecommit,
+        ancestries: &HashMap<[u8; 32], &Header>,
+    ) -> bool {
+        let mut current_hash = precommit.target_hash.0;
+        let mut current_number = precommit.target_number;
+        loop {
+            if current_hash.eq(&self.commit.target_hash.0) {
+                return true;
+            }
+            if current_number <= self.commit.target_number {
+                return false;
+            }
+            match ancestries.get(&current_hash) {
+                Some(header) => {
+                    current_hash = header.parent_hash.0;
+                    current_number = header.number.saturating_sub(1);
+                }
+                None => return false,
+            }
+        }
+    }
+}
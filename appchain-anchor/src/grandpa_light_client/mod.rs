@@ -0,0 +1,204 @@
+pub mod header;
+pub mod justification;
+
+use crate::*;
+use codec::Decode;
+use header::Header;
+use justification::GrandpaJustification;
+
+#[derive(Debug, PartialEq)]
+pub enum GrandpaLightClientError {
+    InvalidHeader,
+    InvalidJustification,
+    InvalidJustificationTarget,
+    UnknownAuthority,
+    InvalidVotesAncestries,
+    InvalidSignature,
+    NotEnoughSignatures,
+    HeaderIsNotNewer,
+    ForcedChangeIsNotSupported,
+    ScheduledChangeIsSkipped,
+    MessagesCommitmentNotFound,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Clone, Debug)]
+pub struct GrandpaAuthority {
+    pub public_key: [u8; 32],
+    pub weight: u64,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Clone, Debug)]
+pub struct PendingAuthoritySetChange {
+    pub next_authorities: Vec<GrandpaAuthority>,
+    /// The block number at which the change will be enacted.
+    pub enacted_at: u32,
+}
+
+/// The authority set before the latest enacted change.
+#[derive(BorshDeserialize, BorshSerialize, Clone, Debug)]
+pub struct PreviousAuthoritySet {
+    pub set_id: u64,
+    pub authorities: Vec<GrandpaAuthority>,
+    /// The number of the last block finalized by the set.
+    pub last_block_number: u32,
+}
+
+/// The light client which tracks the GRANDPA authority sets of the appchain
+/// by verifying the justifications of finalized headers.
+#[derive(BorshDeserialize, BorshSerialize, Clone, Debug)]
+pub struct GrandpaLightClient {
+    set_id: u64,
+    authorities: Vec<GrandpaAuthority>,
+    latest_finalized_block_number: Option<u32>,
+    latest_finalized_block_hash: Option<[u8; 32]>,
+    pending_authority_set_change: Option<PendingAuthoritySetChange>,
+    previous_authority_set: Option<PreviousAuthoritySet>,
+}
+
+impl GrandpaLightClient {
+    ///
+    pub fn new(initial_public_keys: Vec<String>, set_id: u64) -> Self {
+        assert!(
+            !initial_public_keys.is_empty(),
+            "The initial authorities can not be empty."
+        );
+        Self {
+            set_id,
+            authorities: initial_public_keys
+                .iter()
+                .map(|public_key| {
+                    let bytes = hex::decode(public_key.trim_start_matches("0x")).expect(
+                        format!("Invalid hex string of public key '{}'.", public_key).as_str(),
+                    );
+                    GrandpaAuthority {
+                        public_key: bytes.try_into().expect(
+                            format!("Invalid length of public key '{}'.", public_key).as_str(),
+                        ),
+                        weight: 1,
+                    }
+                })
+                .collect(),
+            latest_finalized_block_number: None,
+            latest_finalized_block_hash: None,
+            pending_authority_set_change: None,
+            previous_authority_set: None,
+        }
+    }
+    //
+    fn decode_and_verify(
+        encoded_header: &[u8],
+        encoded_justification: &[u8],
+        set_id: u64,
+        authorities: &Vec<GrandpaAuthority>,
+    ) -> Result<Header, GrandpaLightClientError> {
+        let header: Header = Decode::decode(&mut &encoded_header[..])
+            .map_err(|_| GrandpaLightClientError::InvalidHeader)?;
+        let justification: GrandpaJustification =
+            Decode::decode(&mut &encoded_justification[..])
+                .map_err(|_| GrandpaLightClientError::InvalidJustification)?;
+        justification.verify(
+            &header::blake2_256(encoded_header),
+            header.number,
+            set_id,
+            authorities,
+        )?;
+        Ok(header)
+    }
+    /// Update the state with a header finalized by the current authority set.
+    ///
+    /// The header which schedules a change of authority set, and the header at which
+    /// the scheduled change is enacted, must be submitted in order. A header after
+    /// the pending change is rejected, as it is finalized by the next authority set.
+    pub fn update_state(
+        &mut self,
+        encoded_header: &[u8],
+        encoded_justification: &[u8],
+    ) -> Result<(), GrandpaLightClientError> {
+        let header = Self::decode_and_verify(
+            encoded_header,
+            encoded_justification,
+            self.set_id,
+            &self.authorities,
+        )?;
+        if let Some(number) = self.latest_finalized_block_number {
+            if header.number <= number {
+                return Err(GrandpaLightClientError::HeaderIsNotNewer);
+            }
+        }
+        if header.has_forced_change() {
+            return Err(GrandpaLightClientError::ForcedChangeIsNotSupported);
+        }
+        if let Some(change) = &self.pending_authority_set_change {
+            if header.number > change.enacted_at {
+                return Err(GrandpaLightClientError::ScheduledChangeIsSkipped);
+            }
+        }
+        self.latest_finalized_block_number = Some(header.number);
+        self.latest_finalized_block_hash = Some(header::blake2_256(encoded_header));
+        if let Some(change) = header.scheduled_change() {
+            self.pending_authority_set_change = Some(PendingAuthoritySetChange {
+                next_authorities: change
+                    .next_authorities
+                    .iter()
+                    .map(|(public_key, weight)| GrandpaAuthority {
+                        public_key: public_key.clone(),
+                        weight: *weight,
+                    })
+                    .collect(),
+                enacted_at: header.number + change.delay,
+            });
+        }
+        if let Some(change) = self.pending_authority_set_change.clone() {
+            if header.number == change.enacted_at {
+                self.previous_authority_set = Some(PreviousAuthoritySet {
+                    set_id: self.set_id,
+                    authorities: self.authorities.clone(),
+                    last_block_number: header.number,
+                });
+                self.set_id += 1;
+                self.authorities = change.next_authorities;
+                self.pending_authority_set_change = None;
+            }
+        }
+        Ok(())
+    }
+    /// Verify that the given messages are committed in the digest of a header
+    /// finalized by the current authority set, or by the previous authority set
+    /// if the header is not after the last block finalized by it.
+    ///
+    /// The commitment is the keccak256 hash of the encoded messages.
+    pub fn verify_solochain_messages(
+        &self,
+        encoded_messages: &[u8],
+        encoded_header: &[u8],
+        encoded_justification: &[u8],
+    ) -> Result<(), GrandpaLightClientError> {
+        let header: Header = Decode::decode(&mut &encoded_header[..])
+            .map_err(|_| GrandpaLightClientError::InvalidHeader)?;
+        let (set_id, authorities) = match &self.previous_authority_set {
+            Some(previous) if header.number <= previous.last_block_number => {
+                (previous.set_id, &previous.authorities)
+            }
+            _ => (self.set_id, &self.authorities),
+        };
+        let header =
+            Self::decode_and_verify(encoded_header, encoded_justification, set_id, authorities)?;
+        match header.contains_commitment(&env::keccak256(encoded_messages)) {
+            true => Ok(()),
+            false => Err(GrandpaLightClientError::MessagesCommitmentNotFound),
+        }
+    }
+    ///
+    pub fn get_status(&self) -> GrandpaLightClientStatus {
+        GrandpaLightClientStatus {
+            set_id: U64::from(self.set_id),
+            authority_count: self.authorities.len().try_into().unwrap_or_default(),
+            latest_finalized_block_number: self.latest_finalized_block_number,
+            latest_finalized_block_hash: self.latest_finalized_block_hash.map(hex::encode),
+            pending_authority_set_change_enacted_at: self
+                .pending_authority_set_change
+                .as_ref()
+                .map(|change| change.enacted_at),
+        }
+    }
+}
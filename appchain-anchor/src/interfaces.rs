@@ -28,8 +28,27 @@ pub trait AnchorViewer {
     fn get_near_fungible_tokens(&self) -> Vec<NearFungibleToken>;
     /// Get info of wrapped appchain NFT contracts which has registered in this contract.
     fn get_wrapped_appchain_nfts(&self) -> Vec<WrappedAppchainNFT>;
+    /// Get the NFT collections native to NEAR protocol which are registered for bridging.
+    fn get_near_nft_collections(&self) -> Vec<NearNFTCollection>;
+    /// Get the fungible assets of the appchain which are wrapped in NEAR protocol.
+    fn get_wrapped_appchain_assets(&self) -> Vec<WrappedAppchainAsset>;
     /// Get state of corresponding appchain.
     fn get_appchain_state(&self) -> AppchainState;
+    /// Get the status of the final settlement of corresponding appchain.
+    fn get_appchain_settlement_status(&self) -> Option<AppchainSettlementStatus>;
+    /// Get the net locked balance (locked minus unlocked) of a NEAR asset of an account,
+    /// which will be returned to the account in the final settlement of appchain.
+    fn get_net_locked_balance_of(&self, contract_account: AccountId, account_id: AccountId)
+        -> U128;
+    /// Get the locked balance of a NEAR asset of an account which is failed to be returned
+    /// in the final settlement of appchain, and can be claimed by the account.
+    fn get_unreturned_locked_balance_of(
+        &self,
+        contract_account: AccountId,
+        account_id: AccountId,
+    ) -> U128;
+    /// Get the liveness of corresponding appchain.
+    fn get_appchain_liveness(&self) -> AppchainLiveness;
     /// Get current status of anchor.
     fn get_anchor_status(&self) -> AnchorStatus;
     /// Get validator set history info.
@@ -75,6 +94,41 @@ pub trait AnchorViewer {
     ) -> Vec<AppchainDelegator>;
     /// Get unbonded stakes of an account.
     fn get_unbonded_stakes_of(&self, account_id: AccountId) -> Vec<UnbondedStake>;
+    /// Get the failed asset transfers of an account, which can be claimed again.
+    fn get_pending_transfers_of(&self, account_id: AccountId) -> Vec<PendingTransfer>;
+    /// Get the failed appchain messages which are not resolved or abandoned yet.
+    fn get_failed_appchain_messages(&self) -> Vec<FailedAppchainMessage>;
+    /// Get the record of a failed appchain message by nonce.
+    fn get_failed_appchain_message(&self, nonce: u32) -> Option<FailedAppchainMessage>;
+    /// Get the attestations of witnesses to message batches which are not staged yet.
+    fn get_pending_message_batch_attestations(&self) -> Vec<MessageBatchAttestation>;
+    /// Get the batches of appchain messages staged by witnesses which are still
+    /// in challenge period.
+    fn get_challengeable_message_batches(&self) -> Vec<WitnessedMessageBatch>;
+    /// Get the amount of OCT token bonded by a witness.
+    fn get_witness_bond_of(&self, account_id: AccountId) -> U128;
+    /// Get the bridge fee settings and the collected bridge fee of a fungible token.
+    fn get_bridge_fee_of_token(&self, contract_account: AccountId) -> Option<BridgeFeeOfToken>;
+    /// Get the bridge fee settings of an NFT contract.
+    fn get_bridge_fee_of_nft(&self, contract_account: AccountId) -> Option<NftBridgeFee>;
+    /// Get the prepaid bridge fee deposit of NFT transfers of an account.
+    fn get_nft_bridge_fee_deposit_of(&self, account_id: AccountId) -> NftBridgeFeeBalance;
+    /// Get the status of bridge fee of NFTs.
+    fn get_nft_bridge_fees(&self) -> NftBridgeFees;
+    /// Get the status of native NEAR token bridged to the appchain.
+    fn get_native_near_token(&self) -> NativeNearToken;
+    /// Get the rate limits and the volume in rolling time windows of an asset.
+    fn get_bridge_rate_limit_of_asset(
+        &self,
+        contract_account: AccountId,
+    ) -> Option<BridgeRateLimit>;
+    /// Get the status of bridge storage fund.
+    fn get_bridge_storage_fund(&self) -> BridgeStorageFund;
+    /// Get the setting and accounting of automatic storage registration of a token contract.
+    fn get_storage_registration_of_token(
+        &self,
+        contract_account: AccountId,
+    ) -> Option<StorageRegistrationOfToken>;
     /// Get validator rewards of a certain era range.
     fn get_validator_rewards_of(
         &self,
@@ -118,8 +172,19 @@ pub trait AnchorViewer {
     ) -> Option<ValidatorProfile>;
     /// Get the latest commitment data of appchain state.
     fn get_latest_commitment_of_appchain(&self) -> Option<AppchainCommitment>;
+    /// Get the recent commitments retained in this contract, from the oldest to the latest.
+    fn get_retained_commitments_of_appchain(&self) -> Vec<RetainedAppchainCommitment>;
+    /// Get the retained commitment at the given block number.
+    fn get_retained_commitment_of_appchain(
+        &self,
+        block_number: u32,
+    ) -> Option<RetainedAppchainCommitment>;
     /// Get status of the beefy light client.
     fn get_beefy_light_client_status(&self) -> BeefyLightClientStatus;
+    /// Get the type of light client in use and the status of all light clients.
+    fn get_light_client_status(&self) -> LightClientStatus;
+    /// Get the era number of validator set which a GRANDPA authority set of appchain belongs to.
+    fn get_era_number_of_grandpa_set(&self, set_id: U64) -> Option<U64>;
     /// Get staking histories related to the given account id.
     fn get_user_staking_histories_of(&self, account_id: AccountId) -> Vec<UserStakingHistory>;
     /// Get a certain appchain message.
@@ -154,6 +219,18 @@ pub trait AnchorViewer {
         start_index: U64,
         quantity: Option<U64>,
     ) -> Vec<AppchainChallenge>;
+    /// Get the state of appchain challenge by index.
+    fn get_appchain_challenge_state(&self, index: U64) -> Option<AppchainChallengeState>;
+    /// Get the state of appchain challenges by start index and quantity,
+    /// filtered by status and submitter account (if they are provided).
+    /// If the param `quantity` is omitted, up to 50 challenges will be checked.
+    fn get_appchain_challenge_states(
+        &self,
+        start_index: U64,
+        quantity: Option<U64>,
+        status: Option<AppchainChallengeStatus>,
+        submitter_account: Option<AccountId>,
+    ) -> Vec<AppchainChallengeState>;
 }
 
 pub trait AppchainLifecycleManager {
@@ -163,6 +240,15 @@ pub trait AppchainLifecycleManager {
     fn go_live(&mut self);
     /// Initialize the beefy light client
     fn initialize_beefy_light_client(&mut self, initial_public_keys: Vec<String>);
+    /// Initialize the GRANDPA light client with the authority set of the given id
+    fn initialize_grandpa_light_client(&mut self, initial_public_keys: Vec<String>, set_id: U64);
+    /// Change the state of corresponding appchain from `frozen` back to `active`.
+    fn unfreeze_appchain(&mut self);
+    /// Change the state of corresponding appchain from `active` or `frozen` to `broken`.
+    fn go_broken(&mut self);
+    /// Change the state of corresponding appchain from `frozen` or `broken` to `dead`,
+    /// and start the final settlement of the appchain.
+    fn go_dead(&mut self);
 }
 
 pub trait NearFungibleTokenManager {
@@ -191,6 +277,47 @@ pub trait NearFungibleTokenManager {
     fn close_bridging_of_near_fungible_token(&mut self, symbol: String);
 }
 
+pub trait NativeNearTokenManager {
+    ///
+    fn set_price_of_native_near_token(&mut self, price: U128);
+    ///
+    fn open_bridging_of_native_near_token(&mut self);
+    ///
+    fn close_bridging_of_native_near_token(&mut self);
+    /// Lock the attached native NEAR token in this contract, for transferring
+    /// to a receiver in the appchain.
+    fn lock_near_to_appchain(&mut self, receiver_id_in_appchain: String);
+}
+
+pub trait NearNFTCollectionManager {
+    /// Register a NFT collection native to NEAR protocol, by its contract account.
+    fn register_near_nft_collection(&mut self, contract_account: AccountId);
+    ///
+    fn open_bridging_of_near_nft_collection(&mut self, contract_account: AccountId);
+    ///
+    fn close_bridging_of_near_nft_collection(&mut self, contract_account: AccountId);
+}
+
+pub trait WrappedAppchainAssetManager {
+    /// Register a fungible asset of the appchain, and deploy the wrapped asset contract
+    /// in sub-account `<asset_id>.<anchor>` of this contract.
+    fn register_wrapped_appchain_asset(
+        &mut self,
+        asset_id: String,
+        metadata: FungibleTokenMetadata,
+        price: U128,
+    );
+    ///
+    fn set_price_of_wrapped_appchain_asset(&mut self, asset_id: String, price: U128);
+    ///
+    fn open_bridging_of_wrapped_appchain_asset(&mut self, asset_id: String);
+    ///
+    fn close_bridging_of_wrapped_appchain_asset(&mut self, asset_id: String);
+    /// Burn a certain amount of a wrapped appchain asset of the caller, for transferring
+    /// to a receiver in the appchain.
+    fn burn_wrapped_appchain_asset(&mut self, asset_id: String, receiver_id: String, amount: U128);
+}
+
 pub trait OwnerActions {
     ///
     fn remove_validator_set_before(&mut self, era_number: U64);
@@ -198,6 +325,24 @@ pub trait OwnerActions {
     fn remove_staking_history_before(&mut self, index: U64);
     ///
     fn remove_appchain_notification_history_before(&mut self, index: U64);
+    /// Accept a submitted appchain challenge, the bond of the challenge will be
+    /// refunded to the submitter.
+    fn accept_appchain_challenge(&mut self, index: U64);
+    /// Reject a submitted appchain challenge, the bond of the challenge will be forfeited.
+    fn reject_appchain_challenge(&mut self, index: U64, reason: String);
+    /// Deposit the attached NEAR to the fund for registering storage of receivers
+    /// of bridge payouts in token contracts.
+    fn deposit_bridge_storage_fund(&mut self);
+    /// Withdraw a certain amount of NEAR from bridge storage fund to the owner.
+    fn withdraw_bridge_storage_fund(&mut self, amount: U128);
+    /// Enable automatic storage registration of receivers of bridge payouts
+    /// in a token contract.
+    fn enable_storage_registration_of_token(&mut self, contract_account: AccountId);
+    /// Disable automatic storage registration of receivers of bridge payouts
+    /// in a token contract.
+    fn disable_storage_registration_of_token(&mut self, contract_account: AccountId);
+    /// Abandon a failed appchain message, it will not be retried any more.
+    fn mark_appchain_message_abandoned(&mut self, nonce: u32, reason: String);
 }
 
 pub trait PermissionlessActions {
@@ -214,18 +359,64 @@ pub trait PermissionlessActions {
         &mut self,
     ) -> MultiTxsOperationProcessingResult;
     ///
+    fn update_state_of_grandpa_light_client(&mut self, header: Vec<u8>, justification: Vec<u8>);
+    /// Verify and stage appchain messages.
+    ///
+    /// The `mmr_leaf` and `mmr_proof` are used by beefy light client, and the `justification`
+    /// of `header` is used by GRANDPA light client. The `commitment_block_number` specifies
+    /// the retained beefy commitment which the MMR proof is made against.
     fn verify_and_stage_appchain_messages(
         &mut self,
         encoded_messages: Vec<u8>,
         header: Vec<u8>,
         mmr_leaf: Vec<u8>,
         mmr_proof: Vec<u8>,
+        justification: Option<Vec<u8>>,
+        commitment_block_number: Option<u32>,
     );
+    /// Attest to a batch of appchain messages by the hex string of the sha256 hash of
+    /// the encoded messages. Only witness account can call this function.
+    fn attest_appchain_message_batch(&mut self, batch_hash: String);
     ///
     fn process_appchain_messages(&mut self) -> MultiTxsOperationProcessingResult;
-    ///
-    fn commit_appchain_challenge(&mut self, appchain_challenge: AppchainChallenge);
-    //
+    /// Expire an appchain challenge which is not adjudicated in the expiring period,
+    /// the bond of the challenge will be refunded to the submitter.
+    fn expire_appchain_challenge(&mut self, index: U64);
+    /// Settle a dead appchain: force unbond all validators and delegators, return
+    /// the locked NEAR fungible tokens and native NEAR token to accounts by their
+    /// net locked balances (locked minus unlocked), and return the locked NFTs of
+    /// NEAR NFT collections to their owners. Call it until it returns `Ok`.
+    fn settle_dead_appchain(&mut self) -> MultiTxsOperationProcessingResult;
+    /// Retry returning the locked asset of an account, which is failed to be returned
+    /// in the final settlement of appchain.
+    fn claim_locked_asset_of_dead_appchain(
+        &mut self,
+        account_id: AccountId,
+        contract_account: AccountId,
+    );
+    /// Retry returning a locked NFT to its owner, which is failed to be returned
+    /// in the final settlement of appchain.
+    fn claim_locked_nft_of_dead_appchain(&mut self, contract_account: AccountId, token_id: TokenId);
+    /// Check the liveness of an active appchain, by the start time of the latest era
+    /// and the time of the latest staged appchain message. If the appchain is stalled,
+    /// it will be changed to `broken` and all stakes will be unbonded for emergency withdrawal,
+    /// in which case this function should be called until it returns `Ok`.
+    fn check_appchain_liveness(&mut self) -> MultiTxsOperationProcessingResult;
+    /// Retry a failed asset transfer (unlocking NEAR fungible token or minting wrapped
+    /// appchain token) for the receiver, after the receiver is registered in the token contract.
+    fn claim_pending_transfer(
+        &mut self,
+        receiver_id_in_near: AccountId,
+        appchain_message_nonce: u32,
+    );
+    /// Retry a failed appchain message for transferring asset to NEAR protocol
+    /// (unlocking NEAR fungible token, minting wrapped appchain token or NFT).
+    fn retry_appchain_message(&mut self, nonce: u32);
+    /// Update the state of beefy light client with the signed commitment (if it is not empty),
+    /// then verify and stage the appchain messages, and process the staged messages.
+    ///
+    /// The `commitment_block_number` specifies the retained beefy commitment which
+    /// the MMR proof of header is made against.
     fn process_appchain_messages_with_all_proofs(
         &mut self,
         signed_commitment: Vec<u8>,
@@ -236,7 +427,8 @@ pub trait PermissionlessActions {
         header: Vec<u8>,
         mmr_leaf_for_header: Vec<u8>,
         mmr_proof_for_header: Vec<u8>,
-    );
+        commitment_block_number: Option<u32>,
+    ) -> MultiTxsOperationProcessingResult;
 }
 
 pub trait ProtocolSettingsManager {
@@ -274,6 +466,34 @@ pub trait ProtocolSettingsManager {
     fn change_validator_commission_percent(&mut self, value: u16);
     ///
     fn change_maximum_allowed_unprofitable_era_count(&mut self, value: u16);
+    /// Set the percent of stake to be slashed for a certain kind of offence.
+    /// The value `0` means the offenders of the kind will not be slashed.
+    fn change_slashing_percent_of_offence_kind(&mut self, kind: String, value: u16);
+    ///
+    fn change_challenge_bond_amount(&mut self, value: U128);
+    ///
+    fn change_challenge_reward_percent(&mut self, value: u16);
+    ///
+    fn change_challenge_expiring_period(&mut self, value: U64);
+    ///
+    fn change_liveness_degraded_threshold(&mut self, value: U64);
+    ///
+    fn change_liveness_broken_threshold(&mut self, value: U64);
+    ///
+    fn change_minimum_validator_commission_percent(&mut self, value: u16);
+    ///
+    fn change_maximum_validator_commission_percent(&mut self, value: u16);
+    ///
+    fn change_maximum_validator_commission_change_per_era(&mut self, value: u16);
+    ///
+    fn change_validator_commission_change_notice_period(&mut self, value: U64);
+    ///
+    fn change_validator_set_checkpoint_interval(&mut self, value: U64);
+    ///
+    fn change_witness_bond_amount(&mut self, value: U128);
+    /// Change the period (in seconds) in which a batch of appchain messages staged by
+    /// witnesses can be challenged.
+    fn change_challenge_period_of_witnessed_messages(&mut self, value: U64);
 }
 
 pub trait AppchainSettingsManager {
@@ -287,6 +507,13 @@ pub trait AppchainSettingsManager {
     fn set_bonus_for_new_validator(&mut self, bonus_amount: U128);
 }
 
+pub trait WitnessActions {
+    /// Withdraw the OCT token bonded by the caller, after the caller is removed from
+    /// the witness committee and all batches attested by the caller are out of
+    /// challenge period.
+    fn withdraw_witness_bond(&mut self);
+}
+
 pub trait AnchorSettingsManager {
     ///
     fn set_token_price_maintainer_account(&mut self, account_id: AccountId);
@@ -296,6 +523,59 @@ pub trait AnchorSettingsManager {
     fn turn_on_beefy_light_client_witness_mode(&mut self);
     ///
     fn turn_off_beefy_light_client_witness_mode(&mut self);
+    /// Set the light client which is used to verify appchain messages.
+    fn set_light_client_type(&mut self, light_client_type: LightClientType);
+    ///
+    fn set_slashed_oct_receiver_account(&mut self, account_id: AccountId);
+    ///
+    fn set_bridge_fee_collector_account(&mut self, account_id: AccountId);
+    /// Add an account to the witness committee.
+    fn add_witness_account(&mut self, account_id: AccountId);
+    /// Remove an account from the witness committee.
+    fn remove_witness_account(&mut self, account_id: AccountId);
+    /// Set the count of distinct witnesses which must attest to a batch of
+    /// appchain messages before it can be staged.
+    fn set_witness_threshold(&mut self, value: u16);
+    /// Remove the pending attestations of a message batch which will never be staged.
+    fn remove_pending_message_batch_attestation(&mut self, batch_hash: String);
+}
+
+pub trait BridgeFeeManager {
+    /// Set the bridge fees of a fungible token (a NEAR fungible token or
+    /// the wrapped appchain token), by its contract account.
+    fn set_bridge_fee_of_token(
+        &mut self,
+        contract_account: AccountId,
+        fee_to_appchain: BridgeFee,
+        fee_to_near: BridgeFee,
+    );
+    /// Withdraw the collected bridge fee of a fungible token to the bridge fee collector.
+    fn withdraw_bridge_fee_of_token(&mut self, contract_account: AccountId);
+    /// Set the fixed bridge fee of an NFT contract (a NEAR NFT collection or
+    /// a wrapped appchain NFT contract), by its contract account.
+    fn set_bridge_fee_of_nft(&mut self, contract_account: AccountId, bridge_fee: NftBridgeFee);
+    /// Deposit the attached NEAR as prepaid bridge fee of NFT transfers.
+    /// (The OCT token can be deposited by `ft_transfer_call` with `DepositNftBridgeFee`.)
+    fn deposit_nft_bridge_fee(&mut self);
+    /// Withdraw the prepaid bridge fee deposit of NFT transfers of the caller.
+    fn withdraw_nft_bridge_fee_deposit(&mut self, fee_asset: NftBridgeFeeAsset, amount: U128);
+    /// Withdraw the collected bridge fee of NFTs in an asset to the bridge fee collector.
+    fn withdraw_nft_bridge_fee(&mut self, fee_asset: NftBridgeFeeAsset);
+}
+
+pub trait BridgeRateLimitManager {
+    /// Set the rate limits of bridge transfers of an asset, by its contract account.
+    /// Bridging of the asset is paused automatically when a volume limit is crossed.
+    fn set_bridge_rate_limit_of_asset(
+        &mut self,
+        contract_account: AccountId,
+        maximum_amount_per_transfer: Option<U128>,
+        window_length_in_seconds: U64,
+        maximum_volume_per_window_to_appchain: Option<U128>,
+        maximum_volume_per_window_to_near: Option<U128>,
+    );
+    ///
+    fn remove_bridge_rate_limit_of_asset(&mut self, contract_account: AccountId);
 }
 
 pub trait StakingManager {
@@ -317,6 +597,21 @@ pub trait StakingManager {
     /// Unbond delegation of an account (delegator) to a validator.
     /// This function can only be called by a delegator.
     fn unbond_delegation(&mut self, validator_id: AccountId);
+    /// Move a certain amount of delegation of an account (delegator) from a validator
+    /// to another, without unbonding. The delegation to a validator can only be
+    /// redelegated once in an era.
+    /// This function can only be called by a delegator.
+    fn redelegate(
+        &mut self,
+        from_validator_id: AccountId,
+        to_validator_id: AccountId,
+        amount: U128,
+    );
+    /// Bond a certain amount (or all, if `amount` is omitted) of an unbonded stake again,
+    /// before its unlock period ends. The unbonded stake is specified by the index of
+    /// its staking history, and it will be added back to the validator of the staking history.
+    /// This function can only be called by the owner of the unbonded stake.
+    fn rebond(&mut self, staking_history_index: U64, amount: Option<U128>);
     /// Withdraw unbonded stake(s) of a certain account.
     /// This function can be called by any account.
     fn withdraw_stake(&mut self, account_id: AccountId);
@@ -344,6 +639,8 @@ pub trait SudoActions {
     ///
     fn reset_beefy_light_client(&mut self, initial_public_keys: Vec<String>);
     ///
+    fn reset_grandpa_light_client(&mut self, initial_public_keys: Vec<String>, set_id: U64);
+    ///
     fn pause_asset_transfer(&mut self);
     ///
     fn resume_asset_transfer(&mut self);
@@ -373,6 +670,13 @@ pub trait ValidatorActions {
     fn set_validator_id_in_appchain(&mut self, account_id_in_appchain: String);
     ///
     fn set_validator_profile(&mut self, profile: HashMap<String, String>);
+    /// Change the commission percent of the validator (the caller).
+    /// A decrease takes effect from the next era, and an increase takes effect
+    /// after the notice period (in eras) in protocol settings.
+    fn change_validator_commission(&mut self, commission_percent: u16);
+    /// Set the GRANDPA key (in hex format) of the validator (the caller) in appchain,
+    /// which takes effect from the next era.
+    fn set_grandpa_key_in_appchain(&mut self, grandpa_key: String);
 }
 
 pub trait WrappedAppchainTokenManager {
@@ -390,7 +694,11 @@ pub trait WrappedAppchainTokenManager {
     ///
     fn set_price_of_wrapped_appchain_token(&mut self, price: U128);
     ///
-    fn burn_wrapped_appchain_token(&self, receiver_id: String, amount: U128);
+    fn burn_wrapped_appchain_token(&mut self, receiver_id: String, amount: U128);
+    ///
+    fn open_bridging_of_wrapped_appchain_token(&mut self);
+    ///
+    fn close_bridging_of_wrapped_appchain_token(&mut self);
 }
 
 pub trait WrappedAppchainNFTManager {
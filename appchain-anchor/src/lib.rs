@@ -2,9 +2,12 @@ mod anchor_viewer;
 pub mod appchain_challenge;
 pub mod appchain_messages;
 mod assets;
+pub mod grandpa_light_client;
 pub mod interfaces;
 mod lookup_array;
+mod message_verifiers;
 mod permissionless_actions;
+mod retained_beefy_commitments;
 mod reward_distribution_records;
 mod storage_key;
 pub mod storage_migration;
@@ -21,7 +24,7 @@ use near_contract_standards::non_fungible_token::metadata::TokenMetadata;
 use near_contract_standards::non_fungible_token::TokenId;
 use near_contract_standards::upgrade::Ownable;
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
-use near_sdk::collections::{LazyOption, LookupMap, UnorderedSet};
+use near_sdk::collections::{LazyOption, LookupMap, UnorderedMap, UnorderedSet};
 use near_sdk::json_types::{U128, U64};
 use near_sdk::serde::{Deserialize, Serialize};
 use near_sdk::{
@@ -33,13 +36,20 @@ use std::ops::Mul;
 pub use appchain_messages::AppchainMessage;
 pub use permissionless_actions::AppchainEvent;
 
-use appchain_challenge::AppchainChallenge;
+use appchain_challenge::{AppchainChallenge, AppchainChallengeState, AppchainChallengeStatus};
 use appchain_messages::AppchainMessages;
+use assets::bridge_fees::NftBridgeFeeCharge;
 use assets::near_fungible_tokens::NearFungibleTokens;
+use assets::near_nft_collections::NearNFTCollections;
+use assets::net_locked_balances::NetLockedBalances;
+use assets::wrapped_appchain_assets::WrappedAppchainAssets;
 use assets::wrapped_appchain_nfts::WrappedAppchainNFTs;
 use beefy_light_client::Hash;
 use beefy_light_client::LightClient;
+use grandpa_light_client::GrandpaLightClient;
 use lookup_array::{IndexedAndClearable, LookupArray};
+use message_verifiers::AppchainMessagesProof;
+use retained_beefy_commitments::RetainedBeefyCommitments;
 use reward_distribution_records::RewardDistributionRecords;
 use storage_key::StorageKey;
 use types::*;
@@ -53,7 +63,11 @@ use validator_set::ValidatorSetViewer;
 register_custom_getrandom!(get_random_in_near);
 
 /// Version of this contract (the same as in Cargo.toml)
-const ANCHOR_VERSION: &str = "v2.2.0";
+const ANCHOR_VERSION: &str = "v2.3.0";
+/// The standard name of events emitted by this contract, following NEP-297.
+const ANCHOR_EVENT_STANDARD: &str = "appchain_anchor";
+/// The version of the standard of events emitted by this contract.
+const ANCHOR_EVENT_VERSION: &str = "1.0.0";
 /// Constants for gas.
 const T_GAS_FOR_FT_TRANSFER: u64 = 10;
 const T_GAS_FOR_BURN_FUNGIBLE_TOKEN: u64 = 10;
@@ -65,12 +79,20 @@ const T_GAS_FOR_SYNC_STATE_TO_REGISTRY: u64 = 10;
 const T_GAS_CAP_FOR_MULTI_TXS_PROCESSING: u64 = 150;
 const T_GAS_CAP_FOR_PROCESSING_APPCHAIN_MESSAGES: u64 = 240;
 const T_GAS_FOR_NFT_CONTRACT_INITIALIZATION: u64 = 50;
+const T_GAS_FOR_FT_CONTRACT_INITIALIZATION: u64 = 50;
 const T_GAS_FOR_REGISTER_VALIDATOR: u64 = 100;
 const T_GAS_FOR_BURN_WRAPPED_APPCHAIN_TOKEN: u64 = 50;
+const T_GAS_FOR_STORAGE_BALANCE_OF: u64 = 5;
+const T_GAS_FOR_STORAGE_DEPOSIT: u64 = 10;
+const T_GAS_FOR_NFT_TOKEN_QUERY: u64 = 5;
 /// The value of decimals value of USD.
 const USD_DECIMALS_VALUE: Balance = 1_000_000;
 /// The value of decimals value of OCT token.
 const OCT_DECIMALS_VALUE: Balance = 1_000_000_000_000_000_000;
+/// The value of decimals value of native NEAR token.
+const NEAR_DECIMALS_VALUE: Balance = 1_000_000_000_000_000_000_000_000;
+/// The precision of the cumulative reward per stake of delegators.
+const REWARD_PER_STAKE_PRECISION: Balance = 1_000_000_000_000;
 /// The seconds of a day.
 const SECONDS_OF_A_DAY: u64 = 86400;
 /// Multiple of nano seconds for a second.
@@ -81,6 +103,10 @@ const STORAGE_DEPOSIT_FOR_NEP141_TOEKN: Balance = 12_500_000_000_000_000_000_000
 const STORAGE_DEPOSIT_FOR_MINT_NFT: Balance = 100_000_000_000_000_000_000_000;
 /// Storage deposit for wrapped appchain NFT contract (in yocto)
 const WRAPPED_APPCHAIN_NFT_CONTRACT_INIT_BALANCE: Balance = 3_200_000_000_000_000_000_000_000;
+/// Storage deposit for wrapped appchain asset contract (in yocto)
+const WRAPPED_APPCHAIN_ASSET_CONTRACT_INIT_BALANCE: Balance = 3_000_000_000_000_000_000_000_000;
+/// The maximum count of recent commitments of beefy light client retained in this contract.
+const MAX_COUNT_OF_RETAINED_BEEFY_COMMITMENTS: u32 = 16;
 
 #[ext_contract(ext_self)]
 trait ResolverForSelfCallback {
@@ -108,6 +134,104 @@ trait ResolverForSelfCallback {
         amount: U128,
         appchain_message_nonce: u32,
     );
+    /// Resolver for checking storage balance of the receiver of a bridge payout
+    fn resolve_storage_balance_checking(
+        &mut self,
+        token_contract: AccountId,
+        receiver_id_in_near: AccountId,
+        transfer: PendingTransfer,
+    );
+    /// Resolver for storage deposit for the receiver of a bridge payout
+    fn resolve_storage_deposit(
+        &mut self,
+        token_contract: AccountId,
+        receiver_id_in_near: AccountId,
+        transfer: PendingTransfer,
+        deposit: U128,
+    );
+    /// Resolver for returning locked asset in the final settlement of appchain
+    fn resolve_locked_asset_returning(
+        &mut self,
+        contract_account: AccountId,
+        account_id: AccountId,
+        amount: U128,
+    );
+    /// Resolver for returning locked NFT in the final settlement of appchain
+    fn resolve_locked_nft_returning(
+        &mut self,
+        contract_account: AccountId,
+        token_id: TokenId,
+        owner_id: AccountId,
+    );
+    /// Resolver for withdrawing collected bridge fee of a fungible token
+    fn resolve_bridge_fee_withdrawal(
+        &mut self,
+        contract_account: AccountId,
+        receiver_id: AccountId,
+        amount: U128,
+    );
+    /// Resolver for withdrawing collected bridge fee of NFTs
+    fn resolve_nft_bridge_fee_withdrawal(
+        &mut self,
+        fee_asset: NftBridgeFeeAsset,
+        receiver_id: AccountId,
+        amount: U128,
+    );
+    /// Resolver for withdrawing prepaid bridge fee deposit of NFT transfers
+    fn resolve_nft_bridge_fee_deposit_withdrawal(
+        &mut self,
+        fee_asset: NftBridgeFeeAsset,
+        account_id: AccountId,
+        amount: U128,
+    );
+    /// Resolver for transferring slashed OCT token to the receiver of slashed OCT token
+    fn resolve_slashed_oct_transfer(&mut self, receiver_id: AccountId, amount: U128);
+    /// Resolver for withdrawing the bond of a witness
+    fn resolve_witness_bond_withdrawal(&mut self, witness_id: AccountId, amount: U128);
+    /// Resolver for transfer native NEAR token
+    fn resolve_native_near_transfer(
+        &mut self,
+        sender_id_in_appchain: String,
+        receiver_id_in_near: AccountId,
+        amount: U128,
+        appchain_message_nonce: u32,
+    );
+    /// Resolver for querying the token of a NEAR NFT collection which is transferred
+    /// to this contract
+    fn resolve_near_nft_token_query(
+        &mut self,
+        contract_account: AccountId,
+        token_id: TokenId,
+        sender_id_in_near: AccountId,
+        owner_id_in_near: AccountId,
+        receiver_id_in_appchain: String,
+    ) -> bool;
+    /// Resolver for transfer locked NFT of a NEAR NFT collection
+    fn resolve_near_nft_transfer(
+        &mut self,
+        owner_id_in_appchain: String,
+        receiver_id_in_near: AccountId,
+        contract_account: AccountId,
+        token_id: TokenId,
+        appchain_message_nonce: u32,
+    );
+    /// Resolver for burning wrapped appchain asset
+    fn resolve_wrapped_appchain_asset_burning(
+        &mut self,
+        asset_id: String,
+        sender_id_in_near: AccountId,
+        receiver_id_in_appchain: String,
+        amount: U128,
+    );
+    /// Resolver for minting wrapped appchain asset
+    fn resolve_wrapped_appchain_asset_minting(
+        &mut self,
+        asset_id: String,
+        sender_id_in_appchain: String,
+        receiver_id_in_near: AccountId,
+        amount: U128,
+        appchain_message_nonce: u32,
+    );
     /// Resolver for transfer wrapped appchain NFT
     fn resolve_wrapped_appchain_nft_transfer(
         &mut self,
@@ -194,6 +318,106 @@ pub struct AppchainAnchor {
     appchain_challenges: LazyOption<LookupArray<AppchainChallenge>>,
     /// The wrapped appchain NFT data
     wrapped_appchain_nfts: LazyOption<WrappedAppchainNFTs>,
+    /// The state of appchain challenges, the key in map is the index of challenge
+    appchain_challenge_states: LookupMap<u64, AppchainChallengeState>,
+    /// The indexes of accepted equivocation challenges.
+    /// The key in map is `set_id:round_number:identity` of equivocation
+    equivocation_challenge_indexes: LookupMap<String, u64>,
+    /// The state of beefy light client before the latest commitment is accepted,
+    /// for verifying conspiracy MMR challenges.
+    previous_beefy_light_client_state: LazyOption<LightClient>,
+    /// The status of the final settlement, which is only set after the appchain goes `dead`.
+    appchain_settlement_status: Option<AppchainSettlementStatus>,
+    /// The liveness status of the appchain.
+    appchain_liveness_status: AppchainLivenessStatus,
+    /// The timestamp of the latest staged appchain message.
+    latest_message_staged_timestamp: u64,
+    /// The commission settings of validators, which have set their own commission
+    validator_commissions: LookupMap<AccountId, ValidatorCommission>,
+    /// The era number of the latest redelegation of delegators, mapped by delegator id.
+    redelegation_eras: LookupMap<AccountId, u64>,
+    /// The amount of unbonded stakes which are bonded again.
+    /// The key in map is the index of staking history of the unbonded stake
+    rebonded_stake_amounts: LookupMap<u64, u128>,
+    /// The latest cumulative reward per stake of delegators of validators.
+    delegator_reward_indexes: LookupMap<AccountId, u128>,
+    /// The cumulative reward per stake of delegators of validators in eras.
+    /// The key in map is `(validator_id, era_number)`
+    delegator_reward_indexes_of_era: LookupMap<(AccountId, u64), RewardIndexOfEra>,
+    /// The snapshots of stakes and settled rewards of delegators of validators.
+    /// The key in map is `(delegator_id, validator_id)`
+    delegator_reward_snapshots: LookupMap<(AccountId, AccountId), DelegatorRewardSnapshot>,
+    /// The era from which the stakes of delegators are tracked by reward snapshots.
+    delegator_reward_snapshot_start_era: u64,
+    /// The failed asset transfers which can be claimed again, mapped by receiver account.
+    pending_transfers: LookupMap<AccountId, Vec<PendingTransfer>>,
+    /// The fund for registering storage of receivers of bridge payouts.
+    bridge_storage_fund: BridgeStorageFund,
+    /// The settings of automatic storage registration, mapped by token contract account.
+    storage_registration_of_tokens: LookupMap<AccountId, StorageRegistrationOfToken>,
+    /// The records of failed appchain messages, mapped by nonce.
+    failed_appchain_messages: LookupMap<u32, FailedAppchainMessage>,
+    /// The nonces of failed appchain messages which are not resolved or abandoned yet.
+    failed_appchain_message_nonces: UnorderedSet<u32>,
+    /// The bridge fees of fungible tokens, mapped by token contract account.
+    bridge_fees_of_tokens: LookupMap<AccountId, BridgeFeeOfToken>,
+    /// The rate limits of bridge transfers, mapped by contract account of asset.
+    bridge_rate_limits: LookupMap<AccountId, BridgeRateLimit>,
+    /// The status of native NEAR token bridged to the appchain.
+    native_near_token: NativeNearToken,
+    /// The NFT collections native to NEAR protocol, which can be bridged to the appchain.
+    near_nft_collections: LazyOption<NearNFTCollections>,
+    /// The fungible assets of the appchain, which are wrapped by sub-account contracts.
+    wrapped_appchain_assets: LazyOption<WrappedAppchainAssets>,
+    /// The attestations of witnesses to batches of appchain messages, mapped by batch hash.
+    message_batch_attestations: LookupMap<String, MessageBatchAttestation>,
+    /// The hashes of message batches which are attested but not staged yet.
+    pending_message_batch_hashes: UnorderedSet<String>,
+    /// The amount of OCT token bonded by witnesses, mapped by witness account.
+    witness_bonds: LookupMap<AccountId, Balance>,
+    /// The batches of appchain messages staged by witnesses, mapped by batch hash.
+    witnessed_message_batches: LookupMap<String, WitnessedMessageBatch>,
+    /// The hashes of witnessed message batches which are still in challenge period.
+    challengeable_message_batch_hashes: UnorderedSet<String>,
+    /// The state of GRANDPA light client
+    grandpa_light_client_state: LazyOption<GrandpaLightClient>,
+    /// The recent commitments accepted by beefy light client.
+    retained_beefy_commitments: LazyOption<RetainedBeefyCommitments>,
+    /// The net locked balances of NEAR assets of accounts, for the final settlement.
+    net_locked_balances: LazyOption<NetLockedBalances>,
+    /// The locked balances of NEAR assets which are failed to be returned in the final
+    /// settlement, mapped by `(contract_account, account_id)`. They can be claimed later.
+    unreturned_locked_balances: LookupMap<(AccountId, AccountId), Balance>,
+    /// The owners (before locking) of the locked NFTs of NEAR NFT collections,
+    /// mapped by `(contract_account, token_id)`, for the final settlement.
+    owners_of_locked_near_nfts: UnorderedMap<(AccountId, TokenId), AccountId>,
+    /// The locked NFTs which are failed to be returned in the final settlement,
+    /// mapped by `(contract_account, token_id)` to their owners. They can be claimed later.
+    unreturned_locked_near_nfts: LookupMap<(AccountId, TokenId), AccountId>,
+    /// The bridge fee settings of NFT contracts, mapped by the contract account.
+    bridge_fees_of_nfts: LookupMap<AccountId, NftBridgeFee>,
+    /// The prepaid bridge fee deposits of NFT transfers, mapped by the payer.
+    nft_bridge_fee_deposits: LookupMap<AccountId, NftBridgeFeeBalance>,
+    /// The bridge fee charged for the NFT transfers to NEAR protocol which are not
+    /// resolved yet, mapped by the nonce of appchain message.
+    pending_nft_bridge_fee_charges: LookupMap<u32, NftBridgeFeeCharge>,
+    /// The status of bridge fee of NFTs.
+    nft_bridge_fees: NftBridgeFees,
+    /// The GRANDPA keys (in hex format) of validators, which are used to resolve
+    /// the offenders of equivocations.
+    grandpa_keys_of_validators: LookupMap<AccountId, String>,
+    /// The era number of the validator set which each GRANDPA authority set of appchain
+    /// belongs to, recorded when the authority set is tracked by GRANDPA light client.
+    era_numbers_of_grandpa_sets: LookupMap<u64, u64>,
+    /// The nonce of the appchain message which reports the offences of a validator
+    /// that are not slashed yet, mapped by validator id.
+    unslashed_offences: LookupMap<AccountId, u32>,
+    /// The slashed amount of unbonded stakes, mapped by the index of staking history
+    /// of the unbonded stake.
+    slashed_unbonded_stake_amounts: LookupMap<u64, u128>,
+    /// The amount of slashed OCT token which failed to be transferred to
+    /// the receiver of slashed OCT token, it will be transferred with the next slashing.
+    untransferred_slashed_oct_amount: Balance,
 }
 
 #[near_bindgen]
@@ -305,6 +529,105 @@ impl AppchainAnchor {
                 StorageKey::WrappedAppchainNFTs.into_bytes(),
                 Some(&WrappedAppchainNFTs::new()),
             ),
+            appchain_challenge_states: LookupMap::new(
+                StorageKey::AppchainChallengeStates.into_bytes(),
+            ),
+            equivocation_challenge_indexes: LookupMap::new(
+                StorageKey::EquivocationChallengeIndexes.into_bytes(),
+            ),
+            previous_beefy_light_client_state: LazyOption::new(
+                StorageKey::PreviousBeefyLightClientState.into_bytes(),
+                None,
+            ),
+            appchain_settlement_status: None,
+            appchain_liveness_status: AppchainLivenessStatus::Normal,
+            latest_message_staged_timestamp: 0,
+            validator_commissions: LookupMap::new(StorageKey::ValidatorCommissions.into_bytes()),
+            redelegation_eras: LookupMap::new(StorageKey::RedelegationEras.into_bytes()),
+            rebonded_stake_amounts: LookupMap::new(StorageKey::RebondedStakeAmounts.into_bytes()),
+            delegator_reward_indexes: LookupMap::new(
+                StorageKey::DelegatorRewardIndexes.into_bytes(),
+            ),
+            delegator_reward_indexes_of_era: LookupMap::new(
+                StorageKey::DelegatorRewardIndexesOfEra.into_bytes(),
+            ),
+            delegator_reward_snapshots: LookupMap::new(
+                StorageKey::DelegatorRewardSnapshots.into_bytes(),
+            ),
+            delegator_reward_snapshot_start_era: 0,
+            pending_transfers: LookupMap::new(StorageKey::PendingTransfers.into_bytes()),
+            bridge_storage_fund: BridgeStorageFund::default(),
+            storage_registration_of_tokens: LookupMap::new(
+                StorageKey::StorageRegistrationOfTokens.into_bytes(),
+            ),
+            failed_appchain_messages: LookupMap::new(
+                StorageKey::FailedAppchainMessages.into_bytes(),
+            ),
+            failed_appchain_message_nonces: UnorderedSet::new(
+                StorageKey::FailedAppchainMessageNonces.into_bytes(),
+            ),
+            bridge_fees_of_tokens: LookupMap::new(StorageKey::BridgeFeesOfTokens.into_bytes()),
+            bridge_rate_limits: LookupMap::new(StorageKey::BridgeRateLimits.into_bytes()),
+            native_near_token: NativeNearToken::default(),
+            near_nft_collections: LazyOption::new(
+                StorageKey::NearNFTCollections.into_bytes(),
+                Some(&NearNFTCollections::new()),
+            ),
+            wrapped_appchain_assets: LazyOption::new(
+                StorageKey::WrappedAppchainAssets.into_bytes(),
+                Some(&WrappedAppchainAssets::new()),
+            ),
+            message_batch_attestations: LookupMap::new(
+                StorageKey::MessageBatchAttestations.into_bytes(),
+            ),
+            pending_message_batch_hashes: UnorderedSet::new(
+                StorageKey::PendingMessageBatchHashes.into_bytes(),
+            ),
+            witness_bonds: LookupMap::new(StorageKey::WitnessBonds.into_bytes()),
+            witnessed_message_batches: LookupMap::new(
+                StorageKey::WitnessedMessageBatches.into_bytes(),
+            ),
+            challengeable_message_batch_hashes: UnorderedSet::new(
+                StorageKey::ChallengeableMessageBatchHashes.into_bytes(),
+            ),
+            grandpa_light_client_state: LazyOption::new(
+                StorageKey::GrandpaLightClientState.into_bytes(),
+                None,
+            ),
+            retained_beefy_commitments: LazyOption::new(
+                StorageKey::RetainedBeefyCommitments.into_bytes(),
+                Some(&RetainedBeefyCommitments::new()),
+            ),
+            net_locked_balances: LazyOption::new(
+                StorageKey::NetLockedBalances.into_bytes(),
+                Some(&NetLockedBalances::new()),
+            ),
+            unreturned_locked_balances: LookupMap::new(
+                StorageKey::UnreturnedLockedBalances.into_bytes(),
+            ),
+            owners_of_locked_near_nfts: UnorderedMap::new(
+                StorageKey::OwnersOfLockedNearNfts.into_bytes(),
+            ),
+            unreturned_locked_near_nfts: LookupMap::new(
+                StorageKey::UnreturnedLockedNearNfts.into_bytes(),
+            ),
+            bridge_fees_of_nfts: LookupMap::new(StorageKey::BridgeFeesOfNfts.into_bytes()),
+            nft_bridge_fee_deposits: LookupMap::new(StorageKey::NftBridgeFeeDeposits.into_bytes()),
+            pending_nft_bridge_fee_charges: LookupMap::new(
+                StorageKey::PendingNftBridgeFeeCharges.into_bytes(),
+            ),
+            nft_bridge_fees: NftBridgeFees::default(),
+            grandpa_keys_of_validators: LookupMap::new(
+                StorageKey::GrandpaKeysOfValidators.into_bytes(),
+            ),
+            era_numbers_of_grandpa_sets: LookupMap::new(
+                StorageKey::EraNumbersOfGrandpaSets.into_bytes(),
+            ),
+            unslashed_offences: LookupMap::new(StorageKey::UnslashedOffences.into_bytes()),
+            slashed_unbonded_stake_amounts: LookupMap::new(
+                StorageKey::SlashedUnbondedStakeAmounts.into_bytes(),
+            ),
+            untransferred_slashed_oct_amount: 0,
         }
     }
     // Assert that the contract called by the owner.
@@ -328,19 +651,6 @@ impl AppchainAnchor {
             token_price_maintainer_account
         );
     }
-    //
-    fn assert_relayer(&self) {
-        let anchor_settings = self.anchor_settings.get().unwrap();
-        let relayer_account = anchor_settings
-            .relayer_account
-            .expect("Relayer account is not set.");
-        assert_eq!(
-            env::predecessor_account_id(),
-            relayer_account,
-            "Only '{}' can call this function.",
-            relayer_account
-        );
-    }
     // Assert the given validator is existed in the given validator set.
     fn assert_validator_id<V: ValidatorSetViewer>(
         &self,
@@ -376,6 +686,16 @@ impl AppchainAnchor {
         );
     }
     //
+    fn assert_light_client_of_type_initialized(&self, light_client_type: &LightClientType) {
+        match light_client_type {
+            LightClientType::Beefy => self.assert_light_client_initialized(),
+            LightClientType::Grandpa => assert!(
+                self.grandpa_light_client_state.is_some(),
+                "GRANDPA light client is not initialized."
+            ),
+        }
+    }
+    //
     fn assert_light_client_is_ready(&self) {
         self.assert_light_client_initialized();
         assert!(
@@ -395,6 +715,20 @@ impl AppchainAnchor {
         );
     }
     //
+    fn assert_appchain_is_not_frozen(&self) {
+        assert!(
+            !self.appchain_state.eq(&AppchainState::Frozen),
+            "The appchain is frozen."
+        );
+    }
+    //
+    fn assert_appchain_is_not_dead(&self) {
+        assert!(
+            !self.appchain_state.eq(&AppchainState::Dead),
+            "The appchain is dead."
+        );
+    }
+    //
     fn assert_rewards_withdrawal_is_not_paused(&self) {
         assert!(
             !self.rewards_withdrawal_is_paused,
@@ -472,6 +806,7 @@ impl AppchainAnchor {
         msg: String,
     ) -> PromiseOrValue<U128> {
         self.assert_asset_transfer_is_not_paused();
+        self.assert_appchain_is_not_frozen();
         log!(
             "Deposit {} from '@{}' received. msg: '{}'",
             amount.0,
@@ -510,6 +845,41 @@ impl AppchainAnchor {
                     amount,
                     deposit_message,
                 ),
+            FTDepositMessage::CommitChallenge { appchain_challenge } => {
+                assert!(
+                    predecessor_account_id.eq(&self.oct_token.get().unwrap().contract_account),
+                    "Received invalid deposit '{}' in contract '{}' from '{}'. Return deposit.",
+                    &amount.0,
+                    &predecessor_account_id,
+                    &sender_id,
+                );
+                self.internal_commit_appchain_challenge(sender_id, amount, appchain_challenge)
+            }
+            FTDepositMessage::BondAsWitness => {
+                assert!(
+                    predecessor_account_id.eq(&self.oct_token.get().unwrap().contract_account),
+                    "Received invalid deposit '{}' in contract '{}' from '{}'. Return deposit.",
+                    &amount.0,
+                    &predecessor_account_id,
+                    &sender_id,
+                );
+                self.internal_bond_as_witness(sender_id, amount)
+            }
+            FTDepositMessage::DepositNftBridgeFee => {
+                assert!(
+                    predecessor_account_id.eq(&self.oct_token.get().unwrap().contract_account),
+                    "Received invalid deposit '{}' in contract '{}' from '{}'. Return deposit.",
+                    &amount.0,
+                    &predecessor_account_id,
+                    &sender_id,
+                );
+                self.internal_deposit_nft_bridge_fee(
+                    &sender_id,
+                    &NftBridgeFeeAsset::OctToken,
+                    amount.0,
+                );
+                PromiseOrValue::Value(0.into())
+            }
         }
     }
 }
@@ -525,6 +895,7 @@ impl AppchainAnchor {
         msg: String,
     ) -> PromiseOrValue<bool> {
         self.assert_asset_transfer_is_not_paused();
+        self.assert_appchain_is_not_frozen();
         log!(
             "NFT transfer from '@{}' received. msg: '{}'",
             sender_id,
@@ -541,6 +912,20 @@ impl AppchainAnchor {
             }
         };
         let predecessor_account_id = env::predecessor_account_id();
+        if self
+            .near_nft_collections
+            .get()
+            .unwrap()
+            .contains(&predecessor_account_id)
+        {
+            return self.internal_process_near_nft_transfer(
+                predecessor_account_id,
+                sender_id,
+                previous_owner_id,
+                token_id,
+                transfer_message,
+            );
+        }
         match transfer_message {
             NFTTransferMessage::BridgeToAppchain { .. } => self.internal_process_nft_transfer(
                 predecessor_account_id,
@@ -570,9 +955,33 @@ impl AppchainAnchor {
             });
         self.appchain_notification_histories
             .set(&appchain_notification_histories);
+        AnchorEvent::AppchainNotificationRecorded {
+            appchain_notification_history: appchain_notification_history.clone(),
+        }
+        .emit();
         appchain_notification_history
     }
     ///
+    pub fn internal_change_appchain_state(&mut self, appchain_state: AppchainState) {
+        let previous_state = self.appchain_state.clone();
+        self.appchain_state = appchain_state.clone();
+        AnchorEvent::AppchainStateChanged {
+            previous_state,
+            state: appchain_state,
+        }
+        .emit();
+    }
+    ///
+    /// Freeze the appchain, all deposit and withdraw actions and the processing of
+    /// appchain messages are halted until the appchain is unfrozen or dead.
+    pub fn internal_freeze_appchain(&mut self) {
+        if self.appchain_state.eq(&AppchainState::Active) {
+            self.internal_change_appchain_state(AppchainState::Frozen);
+            self.sync_state_to_registry();
+            log!("Appchain '{}' is frozen.", self.appchain_id);
+        }
+    }
+    ///
     pub fn sync_state_to_registry(&self) {
         let next_validator_set = self.next_validator_set.get().unwrap();
         // sync state to appchain registry contract
@@ -41,6 +41,10 @@ where
     pub fn get(&self, index: &u64) -> Option<T> {
         self.lookup_map.get(index)
     }
+    /// Get a record of the array by the storage key of it, without loading the array.
+    pub fn get_by_storage_key(storage_key: StorageKey, index: &u64) -> Option<T> {
+        LookupMap::new(storage_key.into_bytes()).get(index)
+    }
     ///
     pub fn get_slice_of(&self, start_index: &u64, quantity: Option<u64>) -> Vec<T> {
         let mut results = Vec::<T>::new();
@@ -0,0 +1,55 @@
+use super::{AppchainMessageVerifier, AppchainMessagesProof, MessagesVerification};
+use crate::*;
+
+/// Verify appchain messages by the MMR proof of header against the latest commitment
+/// accepted by beefy light client, or a retained recent commitment.
+pub struct BeefyLightClientVerifier;
+
+impl AppchainMessageVerifier for BeefyLightClientVerifier {
+    //
+    fn verify(
+        &self,
+        anchor: &mut AppchainAnchor,
+        encoded_messages: &Vec<u8>,
+        proof: &AppchainMessagesProof,
+    ) -> Result<MessagesVerification, String> {
+        let light_client = match anchor.beefy_light_client_state.get() {
+            Some(light_client) => light_client,
+            None => return Err("Beefy light client is not initialized.".to_string()),
+        };
+        if let Some(block_number) = proof.commitment_block_number {
+            return match anchor
+                .retained_beefy_commitments
+                .get()
+                .unwrap()
+                .get_by_block_number(block_number)
+            {
+                Some(commitment) => commitment
+                    .verify_solochain_messages(
+                        encoded_messages,
+                        &proof.header,
+                        &proof.mmr_leaf,
+                        &proof.mmr_proof,
+                    )
+                    .map(|_| MessagesVerification::Verified)
+                    .map_err(|err| format!("Failed in verifying appchain messages: {}", err)),
+                None => Err(format!(
+                    "The commitment at block '{}' is not retained.",
+                    block_number
+                )),
+            };
+        }
+        if light_client.is_updating_state() {
+            return Err("Beefy light client is updating state.".to_string());
+        }
+        light_client
+            .verify_solochain_messages(
+                encoded_messages,
+                &proof.header,
+                &proof.mmr_leaf,
+                &proof.mmr_proof,
+            )
+            .map(|_| MessagesVerification::Verified)
+            .map_err(|err| format!("Failed in verifying appchain messages: {:?}", err))
+    }
+}
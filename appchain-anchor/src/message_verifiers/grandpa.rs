@@ -0,0 +1,29 @@
+use super::{AppchainMessageVerifier, AppchainMessagesProof, MessagesVerification};
+use crate::*;
+
+/// Verify appchain messages by the justification of header which commits the messages,
+/// with the authority set tracked by GRANDPA light client.
+pub struct GrandpaLightClientVerifier;
+
+impl AppchainMessageVerifier for GrandpaLightClientVerifier {
+    //
+    fn verify(
+        &self,
+        anchor: &mut AppchainAnchor,
+        encoded_messages: &Vec<u8>,
+        proof: &AppchainMessagesProof,
+    ) -> Result<MessagesVerification, String> {
+        let light_client = match anchor.grandpa_light_client_state.get() {
+            Some(light_client) => light_client,
+            None => return Err("GRANDPA light client is not initialized.".to_string()),
+        };
+        let justification = match &proof.justification {
+            Some(justification) => justification,
+            None => return Err("Missing justification of the header.".to_string()),
+        };
+        light_client
+            .verify_solochain_messages(encoded_messages, &proof.header, justification)
+            .map(|_| MessagesVerification::Verified)
+            .map_err(|err| format!("Failed in verifying appchain messages: {:?}", err))
+    }
+}
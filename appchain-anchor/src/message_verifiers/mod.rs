@@ -0,0 +1,94 @@
+mod beefy;
+mod grandpa;
+mod witness_committee;
+
+use crate::appchain_messages::RawMessage;
+use crate::*;
+use beefy::BeefyLightClientVerifier;
+use codec::Decode;
+use grandpa::GrandpaLightClientVerifier;
+use witness_committee::WitnessCommitteeVerifier;
+
+/// The proof data of a batch of appchain messages.
+///
+/// A verifier only uses the parts of the proof which it needs.
+pub struct AppchainMessagesProof {
+    pub header: Vec<u8>,
+    pub mmr_leaf: Vec<u8>,
+    pub mmr_proof: Vec<u8>,
+    pub justification: Option<Vec<u8>>,
+    /// The block number of the retained beefy commitment which the MMR proof is made against.
+    /// If it is not specified, the latest commitment of beefy light client is used.
+    pub commitment_block_number: Option<u32>,
+}
+
+pub enum MessagesVerification {
+    /// The messages are verified and can be staged.
+    Verified,
+    /// The messages can not be staged until they are confirmed by others,
+    /// e.g. the attestations of other witnesses.
+    Pending,
+}
+
+/// The verifier of batches of appchain messages.
+pub trait AppchainMessageVerifier {
+    /// Verify the encoded messages with the proof.
+    fn verify(
+        &self,
+        anchor: &mut AppchainAnchor,
+        encoded_messages: &Vec<u8>,
+        proof: &AppchainMessagesProof,
+    ) -> Result<MessagesVerification, String>;
+    /// Called after the verified messages are staged.
+    fn on_messages_staged(
+        &self,
+        _anchor: &mut AppchainAnchor,
+        _encoded_messages: &Vec<u8>,
+        _staged_nonces: Vec<u32>,
+    ) {
+    }
+}
+
+impl AppchainAnchor {
+    /// Get the verifier of the light client selected in anchor settings.
+    pub fn light_client_message_verifier(&self) -> Box<dyn AppchainMessageVerifier> {
+        match self.anchor_settings.get().unwrap().light_client_type {
+            LightClientType::Beefy => Box::new(BeefyLightClientVerifier),
+            LightClientType::Grandpa => Box::new(GrandpaLightClientVerifier),
+        }
+    }
+    /// Get the verifier in use for staging appchain messages.
+    pub fn message_verifier(&self) -> Box<dyn AppchainMessageVerifier> {
+        match self
+            .anchor_settings
+            .get()
+            .unwrap()
+            .beefy_light_client_witness_mode
+        {
+            true => Box::new(WitnessCommitteeVerifier),
+            false => self.light_client_message_verifier(),
+        }
+    }
+    /// Verify a batch of appchain messages by the verifier in use and stage them.
+    ///
+    /// Return the decoded messages if they are staged.
+    pub fn internal_verify_and_stage_appchain_messages(
+        &mut self,
+        encoded_messages: &Vec<u8>,
+        proof: &AppchainMessagesProof,
+    ) -> Option<Vec<RawMessage>> {
+        let verifier = self.message_verifier();
+        match verifier.verify(self, encoded_messages, proof) {
+            Ok(MessagesVerification::Verified) => (),
+            Ok(MessagesVerification::Pending) => return None,
+            Err(err) => panic!("{}", err),
+        }
+        let messages: Vec<RawMessage> = match Decode::decode(&mut &encoded_messages[..]) {
+            Ok(messages) => messages,
+            Err(err) => panic!("Failed to decode messages: {}", err),
+        };
+        let staged_nonces = self.internal_stage_appchain_messages(&messages);
+        verifier.on_messages_staged(self, encoded_messages, staged_nonces);
+        Some(messages)
+    }
+}
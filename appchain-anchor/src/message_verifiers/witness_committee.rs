@@ -0,0 +1,41 @@
+use super::{AppchainMessageVerifier, AppchainMessagesProof, MessagesVerification};
+use crate::*;
+
+/// Verify appchain messages by the attestations of the witness committee.
+///
+/// The staged messages can be challenged in the challenge period.
+pub struct WitnessCommitteeVerifier;
+
+impl WitnessCommitteeVerifier {
+    //
+    fn batch_hash_of(encoded_messages: &Vec<u8>) -> String {
+        hex::encode(env::sha256(encoded_messages))
+    }
+}
+
+impl AppchainMessageVerifier for WitnessCommitteeVerifier {
+    //
+    fn verify(
+        &self,
+        anchor: &mut AppchainAnchor,
+        encoded_messages: &Vec<u8>,
+        _proof: &AppchainMessagesProof,
+    ) -> Result<MessagesVerification, String> {
+        match anchor.internal_attest_message_batch(&Self::batch_hash_of(encoded_messages)) {
+            true => Ok(MessagesVerification::Verified),
+            false => Ok(MessagesVerification::Pending),
+        }
+    }
+    //
+    fn on_messages_staged(
+        &self,
+        anchor: &mut AppchainAnchor,
+        encoded_messages: &Vec<u8>,
+        staged_nonces: Vec<u32>,
+    ) {
+        anchor.internal_record_witnessed_message_batch(
+            &Self::batch_hash_of(encoded_messages),
+            staged_nonces,
+        );
+    }
+}
@@ -0,0 +1,249 @@
+use crate::interfaces::WitnessActions;
+use crate::*;
+use near_contract_standards::fungible_token::core::ext_ft_core;
+
+pub trait WitnessBondWithdrawalResolver {
+    /// Resolver for withdrawing the bond of a witness
+    fn resolve_witness_bond_withdrawal(&mut self, witness_id: AccountId, amount: U128);
+}
+
+impl AppchainAnchor {
+    /// Record the attestation of the caller (a witness) to a batch of appchain messages.
+    ///
+    /// Return `true` if the count of distinct witnesses (in current committee) which have
+    /// attested to the batch reaches the threshold.
+    pub fn internal_attest_message_batch(&mut self, batch_hash: &String) -> bool {
+        let anchor_settings = self.anchor_settings.get().unwrap();
+        assert!(
+            anchor_settings.beefy_light_client_witness_mode,
+            "Beefy light client is not in witness mode."
+        );
+        let witness_id = env::predecessor_account_id();
+        assert!(
+            anchor_settings.witness_accounts.contains(&witness_id),
+            "Only witness account can call this function."
+        );
+        let protocol_settings = self.protocol_settings.get().unwrap();
+        assert!(
+            self.witness_bonds.get(&witness_id).unwrap_or(0)
+                >= protocol_settings.witness_bond_amount.0,
+            "The bond of witness should be at least {}.",
+            protocol_settings.witness_bond_amount.0
+        );
+        let mut attestation = match self.message_batch_attestations.get(batch_hash) {
+            Some(attestation) => attestation,
+            None => MessageBatchAttestation {
+                batch_hash: batch_hash.clone(),
+                witnesses: Vec::new(),
+                first_attested_at: U64::from(env::block_timestamp()),
+                updated_at: U64::from(env::block_timestamp()),
+            },
+        };
+        if !attestation.witnesses.contains(&witness_id) {
+            attestation.witnesses.push(witness_id.clone());
+            attestation.updated_at = U64::from(env::block_timestamp());
+            self.message_batch_attestations
+                .insert(batch_hash, &attestation);
+            self.pending_message_batch_hashes.insert(batch_hash);
+        }
+        let count_of_attestations = attestation
+            .witnesses
+            .iter()
+            .filter(|witness_id| anchor_settings.witness_accounts.contains(witness_id))
+            .count();
+        log!(
+            "Message batch '{}' is attested by '{}'. Attestations: {}/{}",
+            batch_hash,
+            witness_id,
+            count_of_attestations,
+            anchor_settings.witness_threshold
+        );
+        count_of_attestations >= usize::from(anchor_settings.witness_threshold)
+    }
+    //
+    pub fn internal_remove_message_batch_attestation(&mut self, batch_hash: &String) {
+        self.message_batch_attestations.remove(batch_hash);
+        self.pending_message_batch_hashes.remove(batch_hash);
+    }
+    /// Move the attestations of a staged message batch to the record of witnessed
+    /// message batch, which can be challenged in the challenge period.
+    pub fn internal_record_witnessed_message_batch(
+        &mut self,
+        batch_hash: &String,
+        staged_nonces: Vec<u32>,
+    ) {
+        let anchor_settings = self.anchor_settings.get().unwrap();
+        let attestation = self.message_batch_attestations.get(batch_hash).unwrap();
+        self.internal_remove_message_batch_attestation(batch_hash);
+        if staged_nonces.is_empty() {
+            return;
+        }
+        self.witnessed_message_batches.insert(
+            batch_hash,
+            &WitnessedMessageBatch {
+                batch_hash: batch_hash.clone(),
+                witnesses: attestation
+                    .witnesses
+                    .into_iter()
+                    .filter(|witness_id| anchor_settings.witness_accounts.contains(witness_id))
+                    .collect(),
+                nonces: staged_nonces,
+                staged_at: U64::from(env::block_timestamp()),
+            },
+        );
+        self.challengeable_message_batch_hashes.insert(batch_hash);
+    }
+    //
+    fn message_batch_is_in_challenge_period(&self, batch: &WitnessedMessageBatch) -> bool {
+        let protocol_settings = self.protocol_settings.get().unwrap();
+        env::block_timestamp()
+            < batch.staged_at.0
+                + protocol_settings.challenge_period_of_witnessed_messages.0 * NANO_SECONDS_MULTIPLE
+    }
+    /// Get the witnessed message batch with the given hash which is still
+    /// in challenge period.
+    pub fn get_challengeable_message_batch(
+        &self,
+        batch_hash: &String,
+    ) -> Option<WitnessedMessageBatch> {
+        if !self.challengeable_message_batch_hashes.contains(batch_hash) {
+            return None;
+        }
+        self.witnessed_message_batches
+            .get(batch_hash)
+            .filter(|batch| self.message_batch_is_in_challenge_period(batch))
+    }
+    //
+    pub fn appchain_message_is_in_challenge_period(&self, nonce: u32) -> bool {
+        self.challengeable_message_batch_hashes
+            .iter()
+            .filter_map(|batch_hash| self.witnessed_message_batches.get(&batch_hash))
+            .any(|batch| {
+                batch.nonces.contains(&nonce) && self.message_batch_is_in_challenge_period(&batch)
+            })
+    }
+    /// Remove the witnessed message batches whose challenge period has passed.
+    pub fn internal_clear_unchallengeable_message_batches(&mut self) {
+        let batch_hashes: Vec<String> = self
+            .challengeable_message_batch_hashes
+            .iter()
+            .filter(|batch_hash| self.get_challengeable_message_batch(batch_hash).is_none())
+            .collect();
+        for batch_hash in batch_hashes {
+            self.witnessed_message_batches.remove(&batch_hash);
+            self.challengeable_message_batch_hashes.remove(&batch_hash);
+        }
+    }
+    /// Revert a fraudulent message batch, slash the bonds of the witnesses which have
+    /// attested to it and remove them from the witness committee.
+    ///
+    /// Return the total amount of slashed OCT token.
+    pub fn internal_revert_witnessed_message_batch(
+        &mut self,
+        batch: &WitnessedMessageBatch,
+    ) -> Balance {
+        let mut appchain_messages = self.appchain_messages.get().unwrap();
+        appchain_messages.remove_messages_of(&batch.nonces);
+        self.appchain_messages.set(&appchain_messages);
+        let mut processing_status = self.permissionless_actions_status.get().unwrap();
+        processing_status.max_nonce_of_staged_appchain_messages = appchain_messages.max_nonce();
+        self.permissionless_actions_status.set(&processing_status);
+        self.witnessed_message_batches.remove(&batch.batch_hash);
+        self.challengeable_message_batch_hashes
+            .remove(&batch.batch_hash);
+        //
+        let mut anchor_settings = self.anchor_settings.get().unwrap();
+        let mut slashed_amount: Balance = 0;
+        for witness_id in &batch.witnesses {
+            slashed_amount += self.witness_bonds.remove(witness_id).unwrap_or(0);
+            anchor_settings
+                .witness_accounts
+                .retain(|account_id| !account_id.eq(witness_id));
+        }
+        self.internal_set_anchor_settings(&anchor_settings);
+        slashed_amount
+    }
+    //
+    pub fn internal_bond_as_witness(
+        &mut self,
+        witness_id: AccountId,
+        amount: U128,
+    ) -> PromiseOrValue<U128> {
+        assert!(
+            self.anchor_settings
+                .get()
+                .unwrap()
+                .witness_accounts
+                .contains(&witness_id),
+            "Account '{}' is not a witness. Return deposit.",
+            witness_id
+        );
+        let bond_amount = self.witness_bonds.get(&witness_id).unwrap_or(0) + amount.0;
+        self.witness_bonds.insert(&witness_id, &bond_amount);
+        log!(
+            "Witness '{}' bonded '{}' OCT token. Total bond: '{}'",
+            witness_id,
+            amount.0,
+            bond_amount
+        );
+        PromiseOrValue::Value(U128::from(0))
+    }
+}
+
+#[near_bindgen]
+impl WitnessActions for AppchainAnchor {
+    //
+    fn withdraw_witness_bond(&mut self) {
+        let witness_id = env::predecessor_account_id();
+        let anchor_settings = self.anchor_settings.get().unwrap();
+        assert!(
+            !anchor_settings.witness_accounts.contains(&witness_id),
+            "The bond can not be withdrawn while the account is a witness."
+        );
+        assert!(
+            !self
+                .challengeable_message_batch_hashes
+                .iter()
+                .filter_map(|batch_hash| self.get_challengeable_message_batch(&batch_hash))
+                .any(|batch| batch.witnesses.contains(&witness_id)),
+            "The bond can not be withdrawn while a message batch attested by the account is in challenge period."
+        );
+        let bond_amount = self
+            .witness_bonds
+            .remove(&witness_id)
+            .expect("No bond of the account.");
+        ext_ft_core::ext(self.oct_token.get().unwrap().contract_account)
+            .with_attached_deposit(1)
+            .with_static_gas(Gas::ONE_TERA.mul(T_GAS_FOR_FT_TRANSFER))
+            .with_unused_gas_weight(0)
+            .ft_transfer(witness_id.clone(), bond_amount.into(), None)
+            .then(
+                ext_self::ext(env::current_account_id())
+                    .with_attached_deposit(0)
+                    .with_static_gas(Gas::ONE_TERA.mul(T_GAS_FOR_RESOLVER_FUNCTION))
+                    .with_unused_gas_weight(0)
+                    .resolve_witness_bond_withdrawal(witness_id, U128::from(bond_amount)),
+            );
+    }
+}
+
+#[near_bindgen]
+impl WitnessBondWithdrawalResolver for AppchainAnchor {
+    //
+    fn resolve_witness_bond_withdrawal(&mut self, witness_id: AccountId, amount: U128) {
+        assert_self();
+        match env::promise_result(0) {
+            PromiseResult::NotReady => unreachable!(),
+            PromiseResult::Successful(_) => (),
+            PromiseResult::Failed => {
+                let bond_amount = self.witness_bonds.get(&witness_id).unwrap_or(0) + amount.0;
+                self.witness_bonds.insert(&witness_id, &bond_amount);
+                log!(
+                    "Failed to withdraw the bond of witness '{}'. Amount: '{}'",
+                    &witness_id,
+                    &amount.0
+                );
+            }
+        }
+    }
+}
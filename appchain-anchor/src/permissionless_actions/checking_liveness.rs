@@ -0,0 +1,74 @@
+use crate::*;
+
+impl AppchainAnchor {
+    //
+    pub fn internal_check_appchain_liveness(&mut self) -> MultiTxsOperationProcessingResult {
+        if self
+            .appchain_liveness_status
+            .eq(&AppchainLivenessStatus::Stalled)
+        {
+            return self.unbond_stakes_of_stalled_appchain();
+        }
+        assert_eq!(
+            self.appchain_state,
+            AppchainState::Active,
+            "Appchain state must be 'active'."
+        );
+        let protocol_settings = self.protocol_settings.get().unwrap();
+        let latest_activity_timestamp = std::cmp::max(
+            self.get_latest_era_start_timestamp(),
+            self.latest_message_staged_timestamp,
+        );
+        let inactive_period = env::block_timestamp().saturating_sub(latest_activity_timestamp);
+        let status = if inactive_period
+            >= protocol_settings.liveness_broken_threshold.0
+                * SECONDS_OF_A_DAY
+                * NANO_SECONDS_MULTIPLE
+        {
+            AppchainLivenessStatus::Stalled
+        } else if inactive_period
+            >= protocol_settings.liveness_degraded_threshold.0
+                * SECONDS_OF_A_DAY
+                * NANO_SECONDS_MULTIPLE
+        {
+            AppchainLivenessStatus::Degraded
+        } else {
+            AppchainLivenessStatus::Normal
+        };
+        if status.eq(&self.appchain_liveness_status) {
+            return MultiTxsOperationProcessingResult::Ok;
+        }
+        self.appchain_liveness_status = status.clone();
+        AnchorEvent::AppchainLivenessStatusChanged {
+            status: status.clone(),
+            latest_activity_timestamp: U64::from(latest_activity_timestamp),
+        }
+        .emit();
+        if status.eq(&AppchainLivenessStatus::Stalled) {
+            self.internal_change_appchain_state(AppchainState::Broken);
+            self.sync_state_to_registry();
+            log!(
+                "Appchain '{}' is stalled and changed to 'broken'. Emergency withdrawal of stake is enabled.",
+                self.appchain_id
+            );
+            return self.unbond_stakes_of_stalled_appchain();
+        }
+        MultiTxsOperationProcessingResult::Ok
+    }
+    //
+    pub fn get_latest_era_start_timestamp(&self) -> u64 {
+        let validator_set_histories = self.validator_set_histories.get().unwrap();
+        match validator_set_histories.get(&validator_set_histories.index_range().end_index.0) {
+            Some(validator_set) => validator_set.start_timestamp(),
+            None => 0,
+        }
+    }
+    //
+    fn unbond_stakes_of_stalled_appchain(&mut self) -> MultiTxsOperationProcessingResult {
+        let result = self.force_unbond_stakes_in_next_validator_set();
+        if result.is_ok() {
+            self.sync_state_to_registry();
+        }
+        result
+    }
+}
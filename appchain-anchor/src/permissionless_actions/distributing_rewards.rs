@@ -1,4 +1,4 @@
-use super::{AppchainMessagesProcessingContext, ResultOfLoopingValidatorSet};
+use super::AppchainMessagesProcessingContext;
 use crate::*;
 use core::convert::{TryFrom, TryInto};
 
@@ -11,6 +11,15 @@ impl ValidatorSetProcessingStatus {
             _ => false,
         }
     }
+    ///
+    pub fn reward_is_distributed(&self) -> bool {
+        match self {
+            ValidatorSetProcessingStatus::CheckingForAutoUnbondingValidator { .. }
+            | ValidatorSetProcessingStatus::SlashingOffender { .. }
+            | ValidatorSetProcessingStatus::Completed => true,
+            _ => false,
+        }
+    }
 }
 
 impl AppchainAnchor {
@@ -110,49 +119,34 @@ impl AppchainAnchor {
             ValidatorSetProcessingStatus::DistributingReward {
                 appchain_message_nonce,
                 distributing_validator_index,
-                distributing_delegator_index,
+                distributing_delegator_index: _,
             } => {
-                let validator_commission_percent = u128::from(
-                    self.protocol_settings
-                        .get()
-                        .unwrap()
-                        .validator_commission_percent,
-                );
                 let mut validator_index = distributing_validator_index.0;
-                let mut delegator_index = distributing_delegator_index.0;
                 let era_reward = self.appchain_settings.get().unwrap().era_reward;
                 while processing_context.used_gas_of_current_function_call()
                     < Gas::ONE_TERA.mul(T_GAS_CAP_FOR_MULTI_TXS_PROCESSING)
                 {
-                    match self.distribute_reward_in_validator_set(
+                    if !self.distribute_reward_to_validator(
                         appchain_message_nonce,
                         &mut validator_set,
                         validator_index,
-                        delegator_index,
                         era_reward.0,
-                        validator_commission_percent,
                     ) {
-                        ResultOfLoopingValidatorSet::NoMoreDelegator => {
-                            validator_index += 1;
-                            delegator_index = 0;
-                        }
-                        ResultOfLoopingValidatorSet::NoMoreValidator => {
-                            validator_set.set_processing_status(
-                                ValidatorSetProcessingStatus::CheckingForAutoUnbondingValidator {
-                                    unprofitable_validator_index: U64::from(0),
-                                },
-                            );
-                            validator_set_histories.insert(&era_number, &validator_set);
-                            return MultiTxsOperationProcessingResult::NeedMoreGas;
-                        }
-                        ResultOfLoopingValidatorSet::NeedToContinue => delegator_index += 1,
+                        validator_set.set_processing_status(
+                            ValidatorSetProcessingStatus::CheckingForAutoUnbondingValidator {
+                                unprofitable_validator_index: U64::from(0),
+                            },
+                        );
+                        validator_set_histories.insert(&era_number, &validator_set);
+                        return MultiTxsOperationProcessingResult::NeedMoreGas;
                     }
+                    validator_index += 1;
                 }
                 validator_set.set_processing_status(
                     ValidatorSetProcessingStatus::DistributingReward {
                         appchain_message_nonce,
                         distributing_validator_index: U64::from(validator_index),
-                        distributing_delegator_index: U64::from(delegator_index),
+                        distributing_delegator_index: U64::from(0),
                     },
                 );
                 validator_set_histories.insert(&era_number, &validator_set);
@@ -170,8 +164,14 @@ impl AppchainAnchor {
                     if unprofitable_validator_index.0
                         >= unprofitable_validators.len().try_into().unwrap()
                     {
-                        validator_set
-                            .set_processing_status(ValidatorSetProcessingStatus::Completed);
+                        validator_set.set_processing_status(
+                            ValidatorSetProcessingStatus::SlashingOffender {
+                                offender_index: U64::from(0),
+                                slashing_staking_history_index: U64::from(0),
+                                slashing_delegator_index: U64::from(0),
+                                slashed_amount: U128::from(0),
+                            },
+                        );
                         validator_set_histories.insert(&era_number, &validator_set);
                         return MultiTxsOperationProcessingResult::NeedMoreGas;
                     }
@@ -225,7 +225,30 @@ impl AppchainAnchor {
                 validator_set_histories.insert(&era_number, &validator_set);
                 MultiTxsOperationProcessingResult::NeedMoreGas
             }
+            ValidatorSetProcessingStatus::SlashingOffender {
+                offender_index,
+                slashing_staking_history_index,
+                slashing_delegator_index,
+                slashed_amount,
+            } => {
+                let result = self.slash_offenders_in_next_validator_set(
+                    processing_context,
+                    &mut validator_set,
+                    offender_index,
+                    slashing_staking_history_index,
+                    slashing_delegator_index,
+                    slashed_amount,
+                );
+                validator_set_histories.insert(&era_number, &validator_set);
+                result
+            }
             ValidatorSetProcessingStatus::Completed => {
+                AnchorEvent::EraRewardDistributed {
+                    era_number: U64::from(era_number),
+                    era_reward: self.appchain_settings.get().unwrap().era_reward,
+                    unprofitable_validator_ids: validator_set.unprofitable_validator_ids(),
+                }
+                .emit();
                 self.record_appchain_message_processing_result(
                     &AppchainMessageProcessingResult::Ok {
                         nonce: processing_context.processing_nonce().unwrap_or(0),
@@ -240,18 +263,18 @@ impl AppchainAnchor {
             }
         }
     }
-    //
-    fn distribute_reward_in_validator_set(
+    // Distribute the reward of era to a validator, and accumulate the reward per stake
+    // for its delegators. The rewards of delegators are calculated when they withdraw.
+    // Return `false` if there is no more validator in the validator set.
+    fn distribute_reward_to_validator(
         &mut self,
         appchain_message_nonce: u32,
         validator_set: &mut ValidatorSetOfEra,
         validator_index: u64,
-        delegator_index: u64,
         era_reward: Balance,
-        validator_commission_percent: u128,
-    ) -> ResultOfLoopingValidatorSet {
+    ) -> bool {
         if validator_index >= validator_set.validator_count() {
-            return ResultOfLoopingValidatorSet::NoMoreValidator;
+            return false;
         }
         let validator = validator_set
             .get_validator_by_index(&validator_index)
@@ -260,57 +283,55 @@ impl AppchainAnchor {
             .unprofitable_validator_ids()
             .contains(&validator.validator_id)
         {
-            return ResultOfLoopingValidatorSet::NoMoreDelegator;
-        }
-        let total_reward_of_validator = era_reward * (validator.total_stake / OCT_DECIMALS_VALUE)
-            / (validator_set.valid_total_stake() / OCT_DECIMALS_VALUE);
-        let validator_commission_reward =
-            total_reward_of_validator * validator_commission_percent / 100;
-        let mut reward_distribution_records = self.reward_distribution_records.get().unwrap();
-        if delegator_index >= validator_set.get_delegator_count_of(&validator.validator_id) {
-            let validator_reward = validator_commission_reward
-                + (total_reward_of_validator - validator_commission_reward)
-                    * (validator.deposit_amount / OCT_DECIMALS_VALUE)
-                    / (validator.total_stake / OCT_DECIMALS_VALUE);
-            self.add_reward_for_validator(validator_set, &validator.validator_id, validator_reward);
-            reward_distribution_records.insert(
-                appchain_message_nonce,
-                validator_set.era_number(),
-                &None,
+            // The cumulative reward per stake is still recorded for the era,
+            // for settling the rewards of delegators.
+            self.add_reward_per_stake_for_delegators(
                 &validator.validator_id,
+                validator_set.era_number(),
+                0,
             );
-            self.reward_distribution_records
-                .set(&reward_distribution_records);
-            return ResultOfLoopingValidatorSet::NoMoreDelegator;
+            return true;
         }
-        let delegator = validator_set
-            .get_delegator_by_index(&delegator_index, &validator.validator_id)
-            .unwrap();
-        if !reward_distribution_records.contains_record(
+        let mut reward_distribution_records = self.reward_distribution_records.get().unwrap();
+        if reward_distribution_records.contains_record(
             appchain_message_nonce,
             validator_set.era_number(),
-            &Some(delegator.delegator_id.clone()),
-            &delegator.validator_id,
+            &None,
+            &validator.validator_id,
         ) {
-            let delegator_reward = (total_reward_of_validator - validator_commission_reward)
-                * (delegator.deposit_amount / OCT_DECIMALS_VALUE)
-                / (validator.total_stake / OCT_DECIMALS_VALUE);
-            self.add_reward_for_delegator(
-                validator_set,
-                &delegator.delegator_id,
-                &delegator.validator_id,
-                delegator_reward,
-            );
-            reward_distribution_records.insert(
-                appchain_message_nonce,
-                validator_set.era_number(),
-                &Some(delegator.delegator_id),
-                &delegator.validator_id,
-            );
-            self.reward_distribution_records
-                .set(&reward_distribution_records);
+            return true;
         }
-        return ResultOfLoopingValidatorSet::NeedToContinue;
+        let total_reward_of_validator = era_reward * (validator.total_stake / OCT_DECIMALS_VALUE)
+            / (validator_set.valid_total_stake() / OCT_DECIMALS_VALUE);
+        let validator_commission_percent =
+            u128::from(self.get_validator_commission_percent_in_validator_set(
+                validator_set,
+                &validator.validator_id,
+            ));
+        let validator_commission_reward =
+            total_reward_of_validator * validator_commission_percent / 100;
+        let reward_of_stakers = total_reward_of_validator - validator_commission_reward;
+        let validator_reward = validator_commission_reward
+            + reward_of_stakers * (validator.deposit_amount / OCT_DECIMALS_VALUE)
+                / (validator.total_stake / OCT_DECIMALS_VALUE);
+        self.add_reward_for_validator(validator_set, &validator.validator_id, validator_reward);
+        self.add_reward_per_stake_for_delegators(
+            &validator.validator_id,
+            validator_set.era_number(),
+            match validator.total_stake > validator.deposit_amount {
+                true => get_reward_per_stake(reward_of_stakers, validator.total_stake),
+                false => 0,
+            },
+        );
+        reward_distribution_records.insert(
+            appchain_message_nonce,
+            validator_set.era_number(),
+            &None,
+            &validator.validator_id,
+        );
+        self.reward_distribution_records
+            .set(&reward_distribution_records);
+        true
     }
     //
     fn add_reward_for_validator(
@@ -337,34 +358,302 @@ impl AppchainAnchor {
         );
     }
     //
-    fn add_reward_for_delegator(
+    fn add_reward_per_stake_for_delegators(
         &mut self,
-        validator_set: &mut ValidatorSetOfEra,
-        delegator_id: &AccountId,
         validator_id: &AccountId,
-        amount: u128,
+        era_number: u64,
+        reward_per_stake: u128,
     ) {
-        let delegator_reward =
-            match validator_set.get_delegator_rewards_of(&delegator_id, &validator_id) {
-                Some(reward) => reward + amount,
-                None => amount,
-            };
-        validator_set.set_delegator_reward(&delegator_id, &validator_id, delegator_reward);
-        let unwithdrawn_delegator_reward = match self.unwithdrawn_delegator_rewards.get(&(
-            validator_set.era_number(),
-            delegator_id.clone(),
-            validator_id.clone(),
-        )) {
-            Some(reward) => reward + amount,
-            None => amount,
+        let latest_index = self.delegator_reward_indexes.get(validator_id).unwrap_or(0);
+        let index_after = latest_index
+            .checked_add(reward_per_stake)
+            .expect("Cumulative reward per stake overflows.");
+        let index_before = match self
+            .delegator_reward_indexes_of_era
+            .get(&(validator_id.clone(), era_number))
+        {
+            Some(reward_index) => reward_index.index_before,
+            None => latest_index,
         };
-        self.unwithdrawn_delegator_rewards.insert(
-            &(
-                validator_set.era_number(),
-                delegator_id.clone(),
-                validator_id.clone(),
+        self.delegator_reward_indexes
+            .insert(validator_id, &index_after);
+        self.delegator_reward_indexes_of_era.insert(
+            &(validator_id.clone(), era_number),
+            &RewardIndexOfEra {
+                index_before,
+                index_after,
+            },
+        );
+    }
+    /// Get the reward of a delegator in an era, which is calculated by the
+    /// cumulative reward per stake of the validator.
+    /// Only the part of reward after the given cumulative reward per stake is counted.
+    /// Return `None` if the reward is not recorded by the cumulative reward per stake.
+    pub fn get_indexed_delegator_reward_of(
+        &self,
+        validator_set: &ValidatorSetOfEra,
+        delegator_id: &AccountId,
+        validator_id: &AccountId,
+        claimed_index: u128,
+    ) -> Option<u128> {
+        let reward_index = self
+            .delegator_reward_indexes_of_era
+            .get(&(validator_id.clone(), validator_set.era_number()))?;
+        let delegator = validator_set.get_delegator(delegator_id, validator_id)?;
+        let index_before = std::cmp::max(reward_index.index_before, claimed_index);
+        Some(match reward_index.index_after > index_before {
+            true => get_reward_of_stake(
+                delegator.deposit_amount,
+                reward_index.index_after - index_before,
             ),
-            &unwithdrawn_delegator_reward,
+            false => 0,
+        })
+    }
+    /// Get the cumulative reward per stake of a validator before an era.
+    /// Return `None` if it is not determined yet.
+    fn get_delegator_reward_index_before_era(
+        &self,
+        validator_id: &AccountId,
+        era_number: u64,
+    ) -> Option<u128> {
+        if let Some(reward_index) = self
+            .delegator_reward_indexes_of_era
+            .get(&(validator_id.clone(), era_number))
+        {
+            return Some(reward_index.index_before);
+        }
+        if era_number > 0 {
+            if let Some(reward_index) = self
+                .delegator_reward_indexes_of_era
+                .get(&(validator_id.clone(), era_number - 1))
+            {
+                return Some(reward_index.index_after);
+            }
+        }
+        // The validator is not in the validator set of the era.
+        match self.validator_set_histories.get().unwrap().get(&era_number) {
+            Some(validator_set) if validator_set.processing_status().reward_is_distributed() => {
+                Some(self.delegator_reward_indexes.get(validator_id).unwrap_or(0))
+            }
+            _ => None,
+        }
+    }
+    /// Get the reward snapshot of a delegator of a validator, in which the rewards
+    /// of all distributed eras are settled.
+    pub fn get_settled_delegator_reward_snapshot(
+        &mut self,
+        delegator_id: &AccountId,
+        validator_id: &AccountId,
+    ) -> DelegatorRewardSnapshot {
+        let mut snapshot = match self
+            .delegator_reward_snapshots
+            .get(&(delegator_id.clone(), validator_id.clone()))
+        {
+            Some(snapshot) => snapshot,
+            None => self.new_delegator_reward_snapshot(delegator_id, validator_id),
+        };
+        while let Some((era_number, stake)) = snapshot.pending_stakes.first().cloned() {
+            match self.get_delegator_reward_index_before_era(validator_id, era_number) {
+                Some(index) => {
+                    settle_delegator_reward_snapshot(&mut snapshot, index);
+                    snapshot.stake = stake;
+                    snapshot.pending_stakes.remove(0);
+                }
+                None => break,
+            }
+        }
+        // The eras from which the pending stakes are counted are not distributed yet,
+        // so the latest cumulative reward per stake is counted by the current stake.
+        settle_delegator_reward_snapshot(
+            &mut snapshot,
+            self.delegator_reward_indexes.get(validator_id).unwrap_or(0),
         );
+        snapshot
+    }
+    // Create the reward snapshot of a delegator of a validator, whose stake is not
+    // changed since the start era of reward snapshots.
+    fn new_delegator_reward_snapshot(
+        &mut self,
+        delegator_id: &AccountId,
+        validator_id: &AccountId,
+    ) -> DelegatorRewardSnapshot {
+        let mut snapshot = DelegatorRewardSnapshot::default();
+        let start_era = self.delegator_reward_snapshot_start_era;
+        let maximum_era_count = self
+            .protocol_settings
+            .get()
+            .unwrap()
+            .maximum_era_count_of_unwithdrawn_reward
+            .0;
+        let validator_set_histories = self.validator_set_histories.get().unwrap();
+        for era_number in start_era.saturating_sub(maximum_era_count)..start_era {
+            let key = (era_number, delegator_id.clone(), validator_id.clone());
+            if let Some(reward) = self.unwithdrawn_delegator_rewards.get(&key) {
+                snapshot.unwithdrawn_reward += reward;
+                self.unwithdrawn_delegator_rewards.remove(&key);
+            }
+            if let Some(validator_set) = validator_set_histories.get(&era_number) {
+                let stake = validator_set
+                    .get_delegator(delegator_id, validator_id)
+                    .map(|delegator| delegator.deposit_amount)
+                    .unwrap_or(0);
+                if let Some(reward_index) = self
+                    .delegator_reward_indexes_of_era
+                    .get(&(validator_id.clone(), era_number))
+                {
+                    snapshot.unwithdrawn_reward += get_reward_of_stake(
+                        stake,
+                        reward_index.index_after - reward_index.index_before,
+                    );
+                    snapshot.settled_index = reward_index.index_after;
+                } else if !validator_set.processing_status().reward_is_distributed() {
+                    push_pending_stake(&mut snapshot, era_number, stake);
+                }
+            }
+        }
+        let stake = self
+            .next_validator_set
+            .get()
+            .unwrap()
+            .get_delegator(delegator_id, validator_id)
+            .map(|delegator| delegator.deposit_amount)
+            .unwrap_or(0);
+        push_pending_stake(&mut snapshot, start_era, stake);
+        snapshot
+    }
+    /// Update the reward snapshots of delegators whose stakes are changed by a staking fact.
+    pub fn update_delegator_reward_snapshots(&mut self, staking_fact: &StakingFact) {
+        match staking_fact {
+            StakingFact::DelegatorRegistered {
+                delegator_id,
+                validator_id,
+                amount,
+            }
+            | StakingFact::DelegationIncreased {
+                delegator_id,
+                validator_id,
+                amount,
+            }
+            | StakingFact::DelegationRebonded {
+                delegator_id,
+                validator_id,
+                amount,
+                ..
+            } => self.change_stake_in_delegator_reward_snapshot(
+                delegator_id,
+                validator_id,
+                amount.0,
+                true,
+            ),
+            StakingFact::DelegationDecreased {
+                delegator_id,
+                validator_id,
+                amount,
+            }
+            | StakingFact::DelegatorUnbonded {
+                delegator_id,
+                validator_id,
+                amount,
+            }
+            | StakingFact::DelegatorAutoUnbonded {
+                delegator_id,
+                validator_id,
+                amount,
+            }
+            | StakingFact::DelegatorSlashed {
+                delegator_id,
+                validator_id,
+                amount,
+            } => self.change_stake_in_delegator_reward_snapshot(
+                delegator_id,
+                validator_id,
+                amount.0,
+                false,
+            ),
+            StakingFact::DelegationRedelegated {
+                delegator_id,
+                from_validator_id,
+                to_validator_id,
+                amount,
+            } => {
+                self.change_stake_in_delegator_reward_snapshot(
+                    delegator_id,
+                    from_validator_id,
+                    amount.0,
+                    false,
+                );
+                self.change_stake_in_delegator_reward_snapshot(
+                    delegator_id,
+                    to_validator_id,
+                    amount.0,
+                    true,
+                );
+            }
+            _ => (),
+        }
+    }
+    //
+    fn change_stake_in_delegator_reward_snapshot(
+        &mut self,
+        delegator_id: &AccountId,
+        validator_id: &AccountId,
+        amount: Balance,
+        is_increasing: bool,
+    ) {
+        let mut snapshot = self.get_settled_delegator_reward_snapshot(delegator_id, validator_id);
+        let latest_stake = snapshot
+            .pending_stakes
+            .last()
+            .map(|(_, stake)| *stake)
+            .unwrap_or(snapshot.stake);
+        push_pending_stake(
+            &mut snapshot,
+            self.get_effective_era_number_of_staking_facts(),
+            match is_increasing {
+                true => latest_stake + amount,
+                false => latest_stake.saturating_sub(amount),
+            },
+        );
+        self.delegator_reward_snapshots
+            .insert(&(delegator_id.clone(), validator_id.clone()), &snapshot);
+    }
+}
+
+/// Get the reward per stake, in `REWARD_PER_STAKE_PRECISION`.
+/// The multiplication is split to avoid overflow.
+fn get_reward_per_stake(reward: Balance, total_stake: Balance) -> u128 {
+    reward / total_stake * REWARD_PER_STAKE_PRECISION
+        + reward % total_stake * REWARD_PER_STAKE_PRECISION / total_stake
+}
+
+/// Get the reward of a stake by the increment of cumulative reward per stake.
+/// The multiplication is split to avoid overflow.
+fn get_reward_of_stake(stake: Balance, reward_per_stake: u128) -> Balance {
+    stake / REWARD_PER_STAKE_PRECISION * reward_per_stake
+        + stake % REWARD_PER_STAKE_PRECISION * reward_per_stake / REWARD_PER_STAKE_PRECISION
+}
+
+// Count the reward of the current stake in snapshot, up to the given cumulative reward per stake.
+fn settle_delegator_reward_snapshot(snapshot: &mut DelegatorRewardSnapshot, index: u128) {
+    if index > snapshot.settled_index {
+        snapshot.unwithdrawn_reward +=
+            get_reward_of_stake(snapshot.stake, index - snapshot.settled_index);
+        snapshot.settled_index = index;
+    }
+}
+
+// Record the stake which is counted from the start of an era.
+fn push_pending_stake(snapshot: &mut DelegatorRewardSnapshot, era_number: u64, stake: Balance) {
+    let latest_stake = snapshot
+        .pending_stakes
+        .last()
+        .map(|(_, stake)| *stake)
+        .unwrap_or(snapshot.stake);
+    if stake == latest_stake {
+        return;
+    }
+    match snapshot.pending_stakes.last_mut() {
+        Some(pending_stake) if pending_stake.0 == era_number => pending_stake.1 = stake,
+        _ => snapshot.pending_stakes.push((era_number, stake)),
     }
 }
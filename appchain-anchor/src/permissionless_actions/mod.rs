@@ -1,10 +1,14 @@
+mod attesting_message_batches;
+mod checking_liveness;
 mod distributing_rewards;
+mod retrying_failed_messages;
+mod settling_dead_appchain;
+mod slashing_offenders;
 mod switching_era;
 
 use crate::appchain_messages::Offender;
 use crate::interfaces::PermissionlessActions;
 use crate::*;
-use codec::Decode;
 use core::convert::{TryFrom, TryInto};
 use near_contract_standards::non_fungible_token::metadata::TokenMetadata;
 use std::ops::Add;
@@ -43,6 +47,30 @@ pub enum AppchainEvent {
         instance_id: String,
         token_metadata: TokenMetadata,
     },
+    /// The fact that a certain amount of wrapped NEAR token has been burnt in the appchain.
+    NativeNearBurnt {
+        owner_id_in_appchain: String,
+        receiver_id_in_near: AccountId,
+        amount: U128,
+    },
+    /// The fact that the appchain representation of a NFT of a NEAR NFT collection
+    /// has been burnt in the appchain.
+    NearNFTBurnt {
+        owner_id_in_appchain: String,
+        receiver_id_in_near: AccountId,
+        contract_account: String,
+        token_id: String,
+    },
+    /// The fact that a certain amount of an appchain asset has been locked in the appchain.
+    AppchainAssetLocked {
+        asset_id: String,
+        owner_id_in_appchain: String,
+        receiver_id_in_near: AccountId,
+        amount: U128,
+    },
+    /// The fact that the GRANDPA authority set of the appchain is changed to
+    /// the validators of a certain era.
+    GrandpaAuthoritySetChanged { set_id: U64, era_number: u32 },
 }
 
 pub struct AppchainMessagesProcessingContext {
@@ -138,13 +166,9 @@ impl PermissionlessActions for AppchainAnchor {
         mmr_leaf: Vec<u8>,
         mmr_proof: Vec<u8>,
     ) {
-        let anchor_settings = self.anchor_settings.get().unwrap();
-        assert!(
-            !anchor_settings.beefy_light_client_witness_mode,
-            "Beefy light client is in witness mode."
-        );
         self.assert_light_client_is_ready();
         let mut light_client = self.beefy_light_client_state.get().unwrap();
+        let previous_light_client = light_client.clone();
         if let Err(err) = light_client.start_updating_state(
             &signed_commitment,
             &validator_proofs
@@ -165,16 +189,13 @@ impl PermissionlessActions for AppchainAnchor {
             );
         }
         self.beefy_light_client_state.set(&light_client);
+        self.previous_beefy_light_client_state
+            .set(&previous_light_client);
     }
     //
     fn try_complete_updating_state_of_beefy_light_client(
         &mut self,
     ) -> MultiTxsOperationProcessingResult {
-        let anchor_settings = self.anchor_settings.get().unwrap();
-        assert!(
-            !anchor_settings.beefy_light_client_witness_mode,
-            "Beefy light client is in witness mode."
-        );
         self.assert_light_client_initialized();
         let mut light_client = self.beefy_light_client_state.get().unwrap();
         if !light_client.is_updating_state() {
@@ -185,6 +206,7 @@ impl PermissionlessActions for AppchainAnchor {
                 Ok(flag) => match flag {
                     true => {
                         self.beefy_light_client_state.set(&light_client);
+                        self.internal_retain_beefy_commitment(&light_client);
                         return MultiTxsOperationProcessingResult::Ok;
                     }
                     false => (),
@@ -202,35 +224,187 @@ impl PermissionlessActions for AppchainAnchor {
         MultiTxsOperationProcessingResult::NeedMoreGas
     }
     //
+    fn update_state_of_grandpa_light_client(&mut self, header: Vec<u8>, justification: Vec<u8>) {
+        let mut light_client = self
+            .grandpa_light_client_state
+            .get()
+            .expect("GRANDPA light client is not initialized.");
+        if let Err(err) = light_client.update_state(&header, &justification) {
+            panic!("Failed to update state of GRANDPA light client: {:?}", err);
+        }
+        self.grandpa_light_client_state.set(&light_client);
+    }
+    //
     fn verify_and_stage_appchain_messages(
         &mut self,
         encoded_messages: Vec<u8>,
         header: Vec<u8>,
         mmr_leaf: Vec<u8>,
         mmr_proof: Vec<u8>,
+        justification: Option<Vec<u8>>,
+        commitment_block_number: Option<u32>,
     ) {
-        let anchor_settings = self.anchor_settings.get().unwrap();
-        if anchor_settings.beefy_light_client_witness_mode {
-            self.assert_relayer();
-        } else {
-            self.assert_light_client_is_ready();
-            let light_client = self.beefy_light_client_state.get().unwrap();
-            if let Err(err) = light_client.verify_solochain_messages(
-                &encoded_messages,
-                &header,
-                &mmr_leaf,
-                &mmr_proof,
-            ) {
-                panic!("Failed in verifying appchain messages: {:?}", err);
-            }
-        }
-        match Decode::decode(&mut &encoded_messages[..]) {
-            Ok(messages) => self.internal_stage_appchain_messages(&messages),
-            Err(err) => panic!("Failed to decode messages: {}", err),
+        self.internal_verify_and_stage_appchain_messages(
+            &encoded_messages,
+            &AppchainMessagesProof {
+                header,
+                mmr_leaf,
+                mmr_proof,
+                justification,
+                commitment_block_number,
+            },
+        );
+    }
+    //
+    fn attest_appchain_message_batch(&mut self, batch_hash: String) {
+        if self.internal_attest_message_batch(&batch_hash) {
+            log!(
+                "Message batch '{}' is ready to be staged by a witness.",
+                batch_hash
+            );
         }
     }
     //
     fn process_appchain_messages(&mut self) -> MultiTxsOperationProcessingResult {
+        self.assert_appchain_is_not_frozen();
+        self.assert_appchain_is_not_dead();
+        self.internal_process_appchain_messages()
+    }
+    //
+    fn expire_appchain_challenge(&mut self, index: U64) {
+        let mut challenge_state = self.get_submitted_appchain_challenge_state(&index);
+        let protocol_settings = self.protocol_settings.get().unwrap();
+        assert!(
+            env::block_timestamp()
+                >= challenge_state.submitted_at.0
+                    + protocol_settings.challenge_expiring_period.0
+                        * SECONDS_OF_A_DAY
+                        * NANO_SECONDS_MULTIPLE,
+            "Appchain challenge '{}' is not expired yet.",
+            index.0
+        );
+        self.expire_appchain_challenge_with(&mut challenge_state);
+        self.appchain_challenge_states
+            .insert(&index.0, &challenge_state);
+    }
+    //
+    fn settle_dead_appchain(&mut self) -> MultiTxsOperationProcessingResult {
+        assert_eq!(
+            self.appchain_state,
+            AppchainState::Dead,
+            "Appchain state must be 'dead'."
+        );
+        self.internal_settle_dead_appchain()
+    }
+    //
+    fn claim_locked_asset_of_dead_appchain(
+        &mut self,
+        account_id: AccountId,
+        contract_account: AccountId,
+    ) {
+        self.internal_claim_locked_asset_of_dead_appchain(&account_id, &contract_account);
+    }
+    //
+    fn claim_locked_nft_of_dead_appchain(
+        &mut self,
+        contract_account: AccountId,
+        token_id: TokenId,
+    ) {
+        self.internal_claim_locked_nft_of_dead_appchain(&contract_account, &token_id);
+    }
+    //
+    fn check_appchain_liveness(&mut self) -> MultiTxsOperationProcessingResult {
+        self.internal_check_appchain_liveness()
+    }
+    //
+    fn claim_pending_transfer(
+        &mut self,
+        receiver_id_in_near: AccountId,
+        appchain_message_nonce: u32,
+    ) {
+        self.internal_claim_pending_transfer(receiver_id_in_near, appchain_message_nonce);
+    }
+    //
+    fn retry_appchain_message(&mut self, nonce: u32) {
+        self.internal_retry_appchain_message(nonce);
+    }
+    //
+    fn process_appchain_messages_with_all_proofs(
+        &mut self,
+        signed_commitment: Vec<u8>,
+        validator_proofs: Vec<ValidatorMerkleProof>,
+        mmr_leaf_for_mmr_root: Vec<u8>,
+        mmr_proof_for_mmr_root: Vec<u8>,
+        encoded_messages: Vec<u8>,
+        header: Vec<u8>,
+        mmr_leaf_for_header: Vec<u8>,
+        mmr_proof_for_header: Vec<u8>,
+        commitment_block_number: Option<u32>,
+    ) -> MultiTxsOperationProcessingResult {
+        self.assert_appchain_is_not_frozen();
+        self.assert_appchain_is_not_dead();
+        if !signed_commitment.is_empty() {
+            self.internal_update_state_of_beefy_light_client(
+                &signed_commitment,
+                &validator_proofs,
+                &mmr_leaf_for_mmr_root,
+                &mmr_proof_for_mmr_root,
+            );
+        }
+        self.internal_verify_and_stage_appchain_messages(
+            &encoded_messages,
+            &AppchainMessagesProof {
+                header,
+                mmr_leaf: mmr_leaf_for_header,
+                mmr_proof: mmr_proof_for_header,
+                justification: None,
+                commitment_block_number,
+            },
+        );
+        self.internal_process_appchain_messages()
+    }
+}
+
+impl AppchainAnchor {
+    /// Update the state of beefy light client with the given signed commitment,
+    /// a commitment which is already accepted is ignored.
+    fn internal_update_state_of_beefy_light_client(
+        &mut self,
+        signed_commitment: &Vec<u8>,
+        validator_proofs: &Vec<ValidatorMerkleProof>,
+        mmr_leaf: &Vec<u8>,
+        mmr_proof: &Vec<u8>,
+    ) {
+        self.assert_light_client_is_ready();
+        let mut light_client = self.beefy_light_client_state.get().unwrap();
+        let previous_light_client = light_client.clone();
+        match light_client.update_state(
+            &signed_commitment,
+            &validator_proofs
+                .iter()
+                .map(|proof| beefy_light_client::ValidatorMerkleProof {
+                    proof: proof.proof.clone(),
+                    number_of_leaves: proof.number_of_leaves.try_into().unwrap_or_default(),
+                    leaf_index: proof.leaf_index.try_into().unwrap_or_default(),
+                    leaf: proof.leaf.clone(),
+                })
+                .collect::<Vec<beefy_light_client::ValidatorMerkleProof>>(),
+            mmr_leaf,
+            mmr_proof,
+        ) {
+            Ok(()) => {
+                self.beefy_light_client_state.set(&light_client);
+                self.previous_beefy_light_client_state
+                    .set(&previous_light_client);
+                self.internal_retain_beefy_commitment(&light_client);
+            }
+            Err(beefy_light_client::Error::CommitmentAlreadyUpdated) => {}
+            Err(err) => panic!("Failed to update state of beefy light client: {:?}", err),
+        }
+    }
+    /// Apply the staged appchain messages in order of nonce, until the gas cap is reached.
+    pub fn internal_process_appchain_messages(&mut self) -> MultiTxsOperationProcessingResult {
+        self.internal_clear_unchallengeable_message_batches();
         let processing_status = self.permissionless_actions_status.get().unwrap();
         let appchain_messages = self.appchain_messages.get().unwrap();
         let mut processing_context = AppchainMessagesProcessingContext::new(processing_status);
@@ -278,8 +452,15 @@ impl PermissionlessActions for AppchainAnchor {
                 }
             } else {
                 if processing_context.latest_applied_nonce() < processing_context.max_nonce() {
-                    processing_context
-                        .set_processing_nonce(processing_context.latest_applied_nonce() + 1);
+                    let next_nonce = processing_context.latest_applied_nonce() + 1;
+                    if self.appchain_message_is_in_challenge_period(next_nonce) {
+                        result = MultiTxsOperationProcessingResult::Error(format!(
+                            "Appchain message with nonce '{}' is in challenge period.",
+                            next_nonce
+                        ));
+                        break;
+                    }
+                    processing_context.set_processing_nonce(next_nonce);
                 } else {
                     break;
                 }
@@ -295,85 +476,6 @@ impl PermissionlessActions for AppchainAnchor {
         }
         result
     }
-    //
-    fn commit_appchain_challenge(&mut self, appchain_challenge: AppchainChallenge) {
-        match &appchain_challenge {
-            AppchainChallenge::EquivocationChallenge {
-                submitter_account: _,
-                proof,
-            } => {
-                assert!(proof.is_valid(), "Invalid equivocation challenge data.");
-            }
-            AppchainChallenge::ConspiracyMmr { .. } => (),
-        }
-        let mut appchain_challenges = self.appchain_challenges.get().unwrap();
-        appchain_challenges.append(&mut appchain_challenge.clone());
-        self.appchain_challenges.set(&appchain_challenges);
-    }
-    //
-    fn process_appchain_messages_with_all_proofs(
-        &mut self,
-        signed_commitment: Vec<u8>,
-        validator_proofs: Vec<ValidatorMerkleProof>,
-        mmr_leaf_for_mmr_root: Vec<u8>,
-        mmr_proof_for_mmr_root: Vec<u8>,
-        encoded_messages: Vec<u8>,
-        header: Vec<u8>,
-        mmr_leaf_for_header: Vec<u8>,
-        mmr_proof_for_header: Vec<u8>,
-    ) {
-        self.assert_light_client_is_ready();
-        let mut light_client = self.beefy_light_client_state.get().unwrap();
-        match light_client.update_state(
-            &signed_commitment,
-            &validator_proofs
-                .iter()
-                .map(|proof| beefy_light_client::ValidatorMerkleProof {
-                    proof: proof.proof.clone(),
-                    number_of_leaves: proof.number_of_leaves.try_into().unwrap_or_default(),
-                    leaf_index: proof.leaf_index.try_into().unwrap_or_default(),
-                    leaf: proof.leaf.clone(),
-                })
-                .collect::<Vec<beefy_light_client::ValidatorMerkleProof>>(),
-            &mmr_leaf_for_mmr_root,
-            &mmr_proof_for_mmr_root,
-        ) {
-            Ok(()) => {
-                self.beefy_light_client_state.set(&light_client);
-            }
-            Err(beefy_light_client::Error::CommitmentAlreadyUpdated) => {}
-            Err(err) => panic!("Failed to update state of beefy light client: {:?}", err),
-        }
-        if let Err(err) = light_client.verify_solochain_messages(
-            &encoded_messages,
-            &header,
-            &mmr_leaf_for_header,
-            &mmr_proof_for_header,
-        ) {
-            panic!("Failed in verifying appchain messages: {:?}", err);
-        }
-        let messages = Decode::decode(&mut &encoded_messages[..]).unwrap();
-        self.internal_stage_appchain_messages(&messages);
-        let processing_status = self.permissionless_actions_status.get().unwrap();
-        let mut processing_context = AppchainMessagesProcessingContext::new(processing_status);
-        let mut validator_set_histories = self.validator_set_histories.get().unwrap();
-        messages.iter().for_each(|raw_message| {
-            let appchain_messages = self.appchain_messages.get().unwrap();
-            if appchain_messages
-                .get_processing_result(&raw_message.nonce())
-                .is_none()
-            {
-                self.internal_apply_appchain_message(
-                    &mut processing_context,
-                    &mut validator_set_histories,
-                    &appchain_messages.get_message(&raw_message.nonce()).unwrap(),
-                );
-            }
-        });
-    }
-}
-
-impl AppchainAnchor {
     /// Apply a certain `AppchainMessage`
     pub fn internal_apply_appchain_message(
         &mut self,
@@ -524,6 +626,87 @@ impl AppchainAnchor {
                     token_metadata,
                 )
             }
+            AppchainEvent::NativeNearBurnt {
+                owner_id_in_appchain,
+                receiver_id_in_near,
+                amount,
+            } => {
+                if self.asset_transfer_is_paused {
+                    let message = format!("Asset transfer is now paused.");
+                    let result = AppchainMessageProcessingResult::Error {
+                        nonce: appchain_message.nonce,
+                        message: message.clone(),
+                    };
+                    self.record_appchain_message_processing_result(&result);
+                    return MultiTxsOperationProcessingResult::Error(message);
+                }
+                self.internal_unlock_native_near(
+                    owner_id_in_appchain,
+                    receiver_id_in_near,
+                    amount,
+                    appchain_message.nonce,
+                    processing_context,
+                )
+            }
+            AppchainEvent::NearNFTBurnt {
+                owner_id_in_appchain,
+                receiver_id_in_near,
+                contract_account,
+                token_id,
+            } => {
+                if self.asset_transfer_is_paused {
+                    let message = format!("Asset transfer is now paused.");
+                    let result = AppchainMessageProcessingResult::Error {
+                        nonce: appchain_message.nonce,
+                        message: message.clone(),
+                    };
+                    self.record_appchain_message_processing_result(&result);
+                    return MultiTxsOperationProcessingResult::Error(message);
+                }
+                self.internal_unlock_near_nft(
+                    owner_id_in_appchain,
+                    receiver_id_in_near,
+                    contract_account,
+                    token_id,
+                    appchain_message.nonce,
+                    processing_context,
+                )
+            }
+            AppchainEvent::AppchainAssetLocked {
+                asset_id,
+                owner_id_in_appchain,
+                receiver_id_in_near,
+                amount,
+            } => {
+                if self.asset_transfer_is_paused {
+                    let message = format!("Asset transfer is now paused.");
+                    let result = AppchainMessageProcessingResult::Error {
+                        nonce: appchain_message.nonce,
+                        message: message.clone(),
+                    };
+                    self.record_appchain_message_processing_result(&result);
+                    return MultiTxsOperationProcessingResult::Error(message);
+                }
+                self.internal_mint_wrapped_appchain_asset(
+                    asset_id,
+                    owner_id_in_appchain,
+                    receiver_id_in_near,
+                    amount,
+                    appchain_message.nonce,
+                    processing_context,
+                )
+            }
+            AppchainEvent::GrandpaAuthoritySetChanged { set_id, era_number } => {
+                self.era_numbers_of_grandpa_sets
+                    .insert(&set_id.0, &u64::from(*era_number));
+                self.record_appchain_message_processing_result(
+                    &AppchainMessageProcessingResult::Ok {
+                        nonce: appchain_message.nonce,
+                        message: None,
+                    },
+                );
+                MultiTxsOperationProcessingResult::Ok
+            }
         }
     }
     ///
@@ -534,6 +717,11 @@ impl AppchainAnchor {
         let mut appchain_messages = self.appchain_messages.get().unwrap();
         appchain_messages.insert_processing_result(processing_result.nonce(), processing_result);
         self.appchain_messages.set(&appchain_messages);
+        self.update_failed_appchain_message(processing_result);
+        AnchorEvent::AppchainMessageProcessed {
+            processing_result: processing_result.clone(),
+        }
+        .emit();
         log!(
             "Processing result of appchain message '{}': '{}'",
             serde_json::to_string::<AppchainMessage>(
@@ -0,0 +1,180 @@
+use super::{AppchainEvent, AppchainMessagesProcessingContext};
+use crate::*;
+
+impl AppchainAnchor {
+    /// Update the record of failed appchain message by the processing result.
+    pub fn update_failed_appchain_message(
+        &mut self,
+        processing_result: &AppchainMessageProcessingResult,
+    ) {
+        let nonce = processing_result.nonce();
+        if nonce == 0 {
+            return;
+        }
+        match processing_result {
+            AppchainMessageProcessingResult::Ok { .. } => {
+                if let Some(mut failed_message) = self.failed_appchain_messages.get(&nonce) {
+                    if failed_message
+                        .status
+                        .eq(&FailedAppchainMessageStatus::Abandoned)
+                    {
+                        return;
+                    }
+                    failed_message.status = FailedAppchainMessageStatus::Resolved;
+                    failed_message.updated_at = U64::from(env::block_timestamp());
+                    self.failed_appchain_messages
+                        .insert(&nonce, &failed_message);
+                    self.failed_appchain_message_nonces.remove(&nonce);
+                }
+            }
+            AppchainMessageProcessingResult::Error { .. } => {
+                let failed_message = match self.failed_appchain_messages.get(&nonce) {
+                    Some(mut failed_message) => {
+                        if failed_message
+                            .status
+                            .eq(&FailedAppchainMessageStatus::Abandoned)
+                        {
+                            return;
+                        }
+                        failed_message.processing_result = processing_result.clone();
+                        failed_message.status = FailedAppchainMessageStatus::Failed;
+                        failed_message.updated_at = U64::from(env::block_timestamp());
+                        failed_message
+                    }
+                    None => FailedAppchainMessage {
+                        nonce,
+                        processing_result: processing_result.clone(),
+                        status: FailedAppchainMessageStatus::Failed,
+                        retry_count: 0,
+                        failed_at: U64::from(env::block_timestamp()),
+                        updated_at: U64::from(env::block_timestamp()),
+                        abandon_reason: None,
+                    },
+                };
+                self.failed_appchain_messages
+                    .insert(&nonce, &failed_message);
+                self.failed_appchain_message_nonces.insert(&nonce);
+            }
+        }
+    }
+    /// Change the status of a failed appchain message to `Retrying`, if it is recorded.
+    pub fn start_retrying_failed_appchain_message(&mut self, nonce: u32) {
+        if let Some(mut failed_message) = self.failed_appchain_messages.get(&nonce) {
+            assert_eq!(
+                failed_message.status,
+                FailedAppchainMessageStatus::Failed,
+                "Appchain message '{}' can not be retried in its current status.",
+                nonce
+            );
+            failed_message.status = FailedAppchainMessageStatus::Retrying;
+            failed_message.retry_count += 1;
+            failed_message.updated_at = U64::from(env::block_timestamp());
+            self.failed_appchain_messages
+                .insert(&nonce, &failed_message);
+            AnchorEvent::AppchainMessageRetried {
+                nonce,
+                retry_count: failed_message.retry_count,
+            }
+            .emit();
+        }
+    }
+    //
+    pub fn internal_retry_appchain_message(&mut self, nonce: u32) {
+        self.assert_appchain_is_not_frozen();
+        self.assert_appchain_is_not_dead();
+        assert!(
+            self.failed_appchain_messages.contains_key(&nonce),
+            "Appchain message '{}' is not failed.",
+            nonce
+        );
+        let appchain_message = self
+            .appchain_messages
+            .get()
+            .unwrap()
+            .get_message(&nonce)
+            .expect("Missing appchain message.");
+        let receiver_id_in_near = get_receiver_of_retry_safe_message(&appchain_message)
+            .unwrap_or_else(|| panic!("Appchain message '{}' can not be retried.", nonce));
+        // The asset transfer of the message is failed in cross contract call,
+        // retry the transfer only.
+        if self
+            .pending_transfers
+            .get(&receiver_id_in_near)
+            .unwrap_or_default()
+            .iter()
+            .any(|pending_transfer| pending_transfer.appchain_message_nonce() == nonce)
+        {
+            self.internal_claim_pending_transfer(receiver_id_in_near, nonce);
+            return;
+        }
+        self.start_retrying_failed_appchain_message(nonce);
+        let mut processing_context = AppchainMessagesProcessingContext::new(
+            self.permissionless_actions_status.get().unwrap(),
+        );
+        let mut validator_set_histories = self.validator_set_histories.get().unwrap();
+        self.internal_apply_appchain_message(
+            &mut processing_context,
+            &mut validator_set_histories,
+            &appchain_message,
+        );
+    }
+    //
+    pub fn internal_abandon_appchain_message(&mut self, nonce: u32, reason: String) {
+        let mut failed_message = self
+            .failed_appchain_messages
+            .get(&nonce)
+            .unwrap_or_else(|| panic!("Appchain message '{}' is not failed.", nonce));
+        assert_eq!(
+            failed_message.status,
+            FailedAppchainMessageStatus::Failed,
+            "Appchain message '{}' can not be abandoned in its current status.",
+            nonce
+        );
+        // The failed asset transfer of the message can not be claimed any more.
+        if let Some(appchain_message) = self.appchain_messages.get().unwrap().get_message(&nonce) {
+            if let Some(receiver_id_in_near) = get_receiver_of_retry_safe_message(&appchain_message)
+            {
+                self.remove_pending_transfer(&receiver_id_in_near, nonce);
+            }
+        }
+        failed_message.status = FailedAppchainMessageStatus::Abandoned;
+        failed_message.abandon_reason = Some(reason.clone());
+        failed_message.updated_at = U64::from(env::block_timestamp());
+        self.failed_appchain_messages
+            .insert(&nonce, &failed_message);
+        self.failed_appchain_message_nonces.remove(&nonce);
+        AnchorEvent::AppchainMessageAbandoned { nonce, reason }.emit();
+    }
+}
+
+/// Get the receiver in NEAR of an appchain message which can be retried safely,
+/// that is, a message for transferring asset to NEAR protocol.
+fn get_receiver_of_retry_safe_message(appchain_message: &AppchainMessage) -> Option<AccountId> {
+    match &appchain_message.appchain_event {
+        AppchainEvent::NearFungibleTokenBurnt {
+            receiver_id_in_near,
+            ..
+        }
+        | AppchainEvent::NativeTokenLocked {
+            receiver_id_in_near,
+            ..
+        }
+        | AppchainEvent::NonFungibleTokenLocked {
+            receiver_id_in_near,
+            ..
+        }
+        | AppchainEvent::NativeNearBurnt {
+            receiver_id_in_near,
+            ..
+        }
+        | AppchainEvent::NearNFTBurnt {
+            receiver_id_in_near,
+            ..
+        }
+        | AppchainEvent::AppchainAssetLocked {
+            receiver_id_in_near,
+            ..
+        } => Some(receiver_id_in_near.clone()),
+        _ => None,
+    }
+}
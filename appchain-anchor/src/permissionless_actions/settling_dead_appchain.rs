@@ -0,0 +1,399 @@
+use super::ResultOfLoopingValidatorSet;
+use crate::*;
+use near_contract_standards::fungible_token::core::ext_ft_core;
+use near_sdk::{assert_self, PromiseResult};
+use std::ops::Add;
+
+pub trait LockedAssetReturningResolver {
+    /// Resolver for returning locked asset in the final settlement of appchain
+    fn resolve_locked_asset_returning(
+        &mut self,
+        contract_account: AccountId,
+        account_id: AccountId,
+        amount: U128,
+    );
+    /// Resolver for returning locked NFT in the final settlement of appchain
+    fn resolve_locked_nft_returning(
+        &mut self,
+        contract_account: AccountId,
+        token_id: TokenId,
+        owner_id: AccountId,
+    );
+}
+
+impl AppchainAnchor {
+    //
+    pub fn internal_settle_dead_appchain(&mut self) -> MultiTxsOperationProcessingResult {
+        let mut prepaid_gas_for_extra_actions = Gas::from(0);
+        while env::used_gas() - prepaid_gas_for_extra_actions
+            < Gas::ONE_TERA.mul(T_GAS_CAP_FOR_MULTI_TXS_PROCESSING)
+        {
+            match self.appchain_settlement_status.clone() {
+                Some(AppchainSettlementStatus::UnbondingStakes) => {
+                    match self.force_unbond_stakes_in_next_validator_set() {
+                        MultiTxsOperationProcessingResult::Ok => {
+                            self.appchain_settlement_status =
+                                Some(AppchainSettlementStatus::ReturningLockedTokens);
+                        }
+                        result => return result,
+                    }
+                }
+                Some(AppchainSettlementStatus::ReturningLockedTokens) => {
+                    let mut net_locked_balances = self.net_locked_balances.get().unwrap();
+                    match net_locked_balances.pop_first() {
+                        Some((contract_account, account_id, balance)) => {
+                            self.net_locked_balances.set(&net_locked_balances);
+                            prepaid_gas_for_extra_actions = prepaid_gas_for_extra_actions.add(
+                                self.return_locked_asset(&contract_account, &account_id, balance),
+                            );
+                        }
+                        None => {
+                            self.appchain_settlement_status =
+                                Some(AppchainSettlementStatus::ReturningLockedNfts);
+                        }
+                    }
+                }
+                Some(AppchainSettlementStatus::ReturningLockedNfts) => {
+                    match self.owners_of_locked_near_nfts.keys_as_vector().get(0) {
+                        Some((contract_account, token_id)) => {
+                            let owner_id = self
+                                .owners_of_locked_near_nfts
+                                .remove(&(contract_account.clone(), token_id.clone()))
+                                .unwrap();
+                            prepaid_gas_for_extra_actions = prepaid_gas_for_extra_actions.add(
+                                self.return_locked_nft(&contract_account, &token_id, &owner_id),
+                            );
+                        }
+                        None => {
+                            self.appchain_settlement_status =
+                                Some(AppchainSettlementStatus::Completed);
+                        }
+                    }
+                }
+                Some(AppchainSettlementStatus::Completed) => {
+                    self.sync_state_to_registry();
+                    log!(
+                        "The final settlement of appchain '{}' is completed.",
+                        self.appchain_id
+                    );
+                    return MultiTxsOperationProcessingResult::Ok;
+                }
+                None => {
+                    return MultiTxsOperationProcessingResult::Error(
+                        "The final settlement of appchain is not started.".to_string(),
+                    )
+                }
+            }
+        }
+        MultiTxsOperationProcessingResult::NeedMoreGas
+    }
+    /// Force unbond all validators and delegators in next validator set.
+    pub fn force_unbond_stakes_in_next_validator_set(
+        &mut self,
+    ) -> MultiTxsOperationProcessingResult {
+        while env::used_gas() < Gas::ONE_TERA.mul(T_GAS_CAP_FOR_MULTI_TXS_PROCESSING) {
+            if let ResultOfLoopingValidatorSet::NoMoreValidator =
+                self.force_unbond_first_stake_in_next_validator_set()
+            {
+                let mut next_validator_set = self.next_validator_set.get().unwrap();
+                next_validator_set.clear_unbonding_validator_ids();
+                next_validator_set.clear_auto_unbonding_validator_ids();
+                self.next_validator_set.set(&next_validator_set);
+                return MultiTxsOperationProcessingResult::Ok;
+            }
+        }
+        MultiTxsOperationProcessingResult::NeedMoreGas
+    }
+    // Unbond the first delegator of the first validator in next validator set,
+    // or the validator itself if it has no delegator.
+    fn force_unbond_first_stake_in_next_validator_set(&mut self) -> ResultOfLoopingValidatorSet {
+        let mut next_validator_set = self.next_validator_set.get().unwrap();
+        let validator = match next_validator_set
+            .validator_set()
+            .get_validator_by_index(&0)
+        {
+            Some(validator) => validator,
+            None => return ResultOfLoopingValidatorSet::NoMoreValidator,
+        };
+        let era_number = self
+            .validator_set_histories
+            .get()
+            .unwrap()
+            .index_range()
+            .end_index
+            .0;
+        let result = match next_validator_set
+            .validator_set()
+            .get_delegator_by_index(&0, &validator.validator_id)
+        {
+            Some(delegator) => {
+                let staking_history =
+                    self.record_staking_fact(StakingFact::DelegatorAutoUnbonded {
+                        delegator_id: delegator.delegator_id.clone(),
+                        validator_id: delegator.validator_id,
+                        amount: U128::from(delegator.deposit_amount),
+                    });
+                next_validator_set.apply_staking_fact(&staking_history.staking_fact);
+                self.add_unbonded_stake_of(
+                    &delegator.delegator_id,
+                    UnbondedStakeReference {
+                        era_number,
+                        staking_history_index: staking_history.index.0,
+                    },
+                );
+                ResultOfLoopingValidatorSet::NeedToContinue
+            }
+            None => {
+                let staking_history =
+                    self.record_staking_fact(StakingFact::ValidatorAutoUnbonded {
+                        validator_id: validator.validator_id.clone(),
+                        amount: U128::from(validator.deposit_amount),
+                    });
+                next_validator_set.apply_staking_fact(&staking_history.staking_fact);
+                self.add_unbonded_stake_of(
+                    &validator.validator_id,
+                    UnbondedStakeReference {
+                        era_number,
+                        staking_history_index: staking_history.index.0,
+                    },
+                );
+                ResultOfLoopingValidatorSet::NoMoreDelegator
+            }
+        };
+        self.next_validator_set.set(&next_validator_set);
+        result
+    }
+    /// Return the locked asset to an account, limited by the net locked balance of
+    /// the account and the remaining locked balance of the asset.
+    ///
+    /// Return the gas prepaid for the transfer and its resolver.
+    pub fn return_locked_asset(
+        &mut self,
+        contract_account: &AccountId,
+        account_id: &AccountId,
+        net_locked_balance: Balance,
+    ) -> Gas {
+        if contract_account.eq(&env::current_account_id()) {
+            let amount = std::cmp::min(net_locked_balance, self.native_near_token.locked_balance.0);
+            if amount > 0 {
+                self.native_near_token.locked_balance =
+                    U128::from(self.native_near_token.locked_balance.0 - amount);
+                Promise::new(account_id.clone()).transfer(amount).then(
+                    ext_self::ext(env::current_account_id())
+                        .with_attached_deposit(0)
+                        .with_static_gas(Gas::ONE_TERA.mul(T_GAS_FOR_RESOLVER_FUNCTION))
+                        .with_unused_gas_weight(0)
+                        .resolve_locked_asset_returning(
+                            contract_account.clone(),
+                            account_id.clone(),
+                            amount.into(),
+                        ),
+                );
+                log!(
+                    "Returning locked native NEAR token to '{}'. Amount: '{}'.",
+                    account_id,
+                    amount
+                );
+                return Gas::ONE_TERA.mul(T_GAS_FOR_RESOLVER_FUNCTION);
+            }
+            return Gas::from(0);
+        }
+        let mut near_fungible_tokens = self.near_fungible_tokens.get().unwrap();
+        if let Some(mut near_fungible_token) =
+            near_fungible_tokens.get_by_contract_account(contract_account)
+        {
+            let amount = std::cmp::min(net_locked_balance, near_fungible_token.locked_balance.0);
+            if amount > 0 {
+                near_fungible_token.locked_balance =
+                    U128::from(near_fungible_token.locked_balance.0 - amount);
+                near_fungible_tokens.insert(&near_fungible_token);
+                ext_ft_core::ext(contract_account.clone())
+                    .with_attached_deposit(1)
+                    .with_static_gas(Gas::ONE_TERA.mul(T_GAS_FOR_FT_TRANSFER))
+                    .with_unused_gas_weight(0)
+                    .ft_transfer(account_id.clone(), amount.into(), None)
+                    .then(
+                        ext_self::ext(env::current_account_id())
+                            .with_attached_deposit(0)
+                            .with_static_gas(Gas::ONE_TERA.mul(T_GAS_FOR_RESOLVER_FUNCTION))
+                            .with_unused_gas_weight(0)
+                            .resolve_locked_asset_returning(
+                                contract_account.clone(),
+                                account_id.clone(),
+                                amount.into(),
+                            ),
+                    );
+                log!(
+                    "Returning locked NEAR fungible token in contract '{}' to '{}'. Amount: '{}'.",
+                    contract_account,
+                    account_id,
+                    amount
+                );
+                return Gas::ONE_TERA.mul(T_GAS_FOR_FT_TRANSFER)
+                    + Gas::ONE_TERA.mul(T_GAS_FOR_RESOLVER_FUNCTION);
+            }
+        }
+        Gas::from(0)
+    }
+    //
+    pub fn internal_claim_locked_asset_of_dead_appchain(
+        &mut self,
+        account_id: &AccountId,
+        contract_account: &AccountId,
+    ) {
+        let balance = self
+            .unreturned_locked_balances
+            .remove(&(contract_account.clone(), account_id.clone()))
+            .unwrap_or(0);
+        assert!(balance > 0, "No unreturned locked asset of the account.");
+        self.return_locked_asset(contract_account, account_id, balance);
+    }
+    /// Return a locked NFT of a NEAR NFT collection to its owner.
+    ///
+    /// Return the gas prepaid for the transfer and its resolver.
+    pub fn return_locked_nft(
+        &mut self,
+        contract_account: &AccountId,
+        token_id: &TokenId,
+        owner_id: &AccountId,
+    ) -> Gas {
+        #[derive(near_sdk::serde::Serialize)]
+        #[serde(crate = "near_sdk::serde")]
+        struct Args {
+            receiver_id: AccountId,
+            token_id: TokenId,
+            approval_id: Option<u64>,
+            memo: Option<String>,
+        }
+        let args = near_sdk::serde_json::to_vec(&Args {
+            receiver_id: owner_id.clone(),
+            token_id: token_id.clone(),
+            approval_id: None,
+            memo: None,
+        })
+        .expect("Failed to serialize the cross contract args using JSON.");
+        Promise::new(contract_account.clone())
+            .function_call(
+                "nft_transfer".to_string(),
+                args,
+                1,
+                Gas::ONE_TERA.mul(T_GAS_FOR_NFT_TRANSFER),
+            )
+            .then(
+                ext_self::ext(env::current_account_id())
+                    .with_attached_deposit(0)
+                    .with_static_gas(Gas::ONE_TERA.mul(T_GAS_FOR_RESOLVER_FUNCTION))
+                    .with_unused_gas_weight(0)
+                    .resolve_locked_nft_returning(
+                        contract_account.clone(),
+                        token_id.clone(),
+                        owner_id.clone(),
+                    ),
+            );
+        log!(
+            "Returning locked token '{}' of NFT collection '{}' to '{}'.",
+            token_id,
+            contract_account,
+            owner_id
+        );
+        Gas::ONE_TERA.mul(T_GAS_FOR_NFT_TRANSFER) + Gas::ONE_TERA.mul(T_GAS_FOR_RESOLVER_FUNCTION)
+    }
+    //
+    pub fn internal_claim_locked_nft_of_dead_appchain(
+        &mut self,
+        contract_account: &AccountId,
+        token_id: &TokenId,
+    ) {
+        let owner_id = self
+            .unreturned_locked_near_nfts
+            .remove(&(contract_account.clone(), token_id.clone()))
+            .expect("The NFT is not an unreturned locked NFT.");
+        self.return_locked_nft(contract_account, token_id, &owner_id);
+    }
+}
+
+#[near_bindgen]
+impl LockedAssetReturningResolver for AppchainAnchor {
+    //
+    fn resolve_locked_asset_returning(
+        &mut self,
+        contract_account: AccountId,
+        account_id: AccountId,
+        amount: U128,
+    ) {
+        assert_self();
+        match env::promise_result(0) {
+            PromiseResult::NotReady => unreachable!(),
+            PromiseResult::Successful(_) => {
+                log!(
+                    "Returned locked asset '{}' to '{}'. Amount: '{}'.",
+                    contract_account,
+                    account_id,
+                    amount.0
+                );
+            }
+            PromiseResult::Failed => {
+                // The asset is not transferred, restore the locked balance and keep the amount
+                // out of the settlement, for claiming by the account.
+                if contract_account.eq(&env::current_account_id()) {
+                    self.native_near_token.locked_balance =
+                        U128::from(self.native_near_token.locked_balance.0 + amount.0);
+                } else {
+                    let mut near_fungible_tokens = self.near_fungible_tokens.get().unwrap();
+                    if let Some(mut near_fungible_token) =
+                        near_fungible_tokens.get_by_contract_account(&contract_account)
+                    {
+                        near_fungible_token.locked_balance =
+                            U128::from(near_fungible_token.locked_balance.0 + amount.0);
+                        near_fungible_tokens.insert(&near_fungible_token);
+                    }
+                }
+                let key = (contract_account.clone(), account_id.clone());
+                let unreturned_balance = self.unreturned_locked_balances.get(&key).unwrap_or(0);
+                self.unreturned_locked_balances
+                    .insert(&key, &(unreturned_balance + amount.0));
+                log!(
+                    "Failed to return locked asset '{}' to '{}'. Amount: '{}'. Call 'claim_locked_asset_of_dead_appchain' to retry it.",
+                    contract_account,
+                    account_id,
+                    amount.0
+                );
+            }
+        }
+    }
+    //
+    fn resolve_locked_nft_returning(
+        &mut self,
+        contract_account: AccountId,
+        token_id: TokenId,
+        owner_id: AccountId,
+    ) {
+        assert_self();
+        match env::promise_result(0) {
+            PromiseResult::NotReady => unreachable!(),
+            PromiseResult::Successful(_) => {
+                let mut near_nft_collections = self.near_nft_collections.get().unwrap();
+                if let Some(mut collection) = near_nft_collections.get(&contract_account) {
+                    collection.locked_token_id_set.remove(&token_id);
+                    near_nft_collections.insert(&collection);
+                }
+                log!(
+                    "Returned locked token '{}' of NFT collection '{}' to '{}'.",
+                    token_id,
+                    contract_account,
+                    owner_id
+                );
+            }
+            PromiseResult::Failed => {
+                self.unreturned_locked_near_nfts
+                    .insert(&(contract_account.clone(), token_id.clone()), &owner_id);
+                log!(
+                    "Failed to return locked token '{}' of NFT collection '{}' to '{}'. Call 'claim_locked_nft_of_dead_appchain' to retry it.",
+                    token_id,
+                    contract_account,
+                    owner_id
+                );
+            }
+        }
+    }
+}
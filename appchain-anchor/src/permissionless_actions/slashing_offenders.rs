@@ -0,0 +1,433 @@
+use super::{AppchainMessagesProcessingContext, ResultOfLoopingValidatorSet};
+use crate::appchain_messages::Offender;
+use crate::*;
+use core::convert::TryFrom;
+use near_contract_standards::fungible_token::core::ext_ft_core;
+
+pub trait SlashedOctTransferResolver {
+    /// Resolver for transferring slashed OCT token to the receiver of slashed OCT token
+    fn resolve_slashed_oct_transfer(&mut self, receiver_id: AccountId, amount: U128);
+}
+
+impl AppchainAnchor {
+    //
+    pub fn slash_offenders_in_next_validator_set(
+        &mut self,
+        processing_context: &mut AppchainMessagesProcessingContext,
+        validator_set: &mut ValidatorSetOfEra,
+        offender_index: U64,
+        slashing_staking_history_index: U64,
+        slashing_delegator_index: U64,
+        slashed_amount: U128,
+    ) -> MultiTxsOperationProcessingResult {
+        let offenders = self
+            .get_offenders_in_appchain_message(processing_context.processing_nonce().unwrap_or(0));
+        let protocol_settings = self.protocol_settings.get().unwrap();
+        let mut next_validator_set = self.next_validator_set.get().unwrap();
+        let mut offender_index = offender_index.0;
+        // The stakes unbonded or redelegated since the start of the era of offences
+        // are slashed first, then the stakes in next validator set.
+        let mut staking_history_index = std::cmp::max(
+            slashing_staking_history_index.0,
+            validator_set.staking_history_index() + 1,
+        );
+        let mut delegator_index = slashing_delegator_index.0;
+        let mut slashed_amount = slashed_amount.0;
+        while processing_context.used_gas_of_current_function_call()
+            < Gas::ONE_TERA.mul(T_GAS_CAP_FOR_MULTI_TXS_PROCESSING)
+        {
+            if delegator_index == 0
+                && offender_index < offenders.len().try_into().unwrap()
+                && staking_history_index
+                    <= self
+                        .staking_histories
+                        .get()
+                        .unwrap()
+                        .index_range()
+                        .end_index
+                        .0
+            {
+                self.slash_moved_stake_of_offender(
+                    &protocol_settings,
+                    &mut next_validator_set,
+                    offenders
+                        .get(usize::try_from(offender_index).unwrap())
+                        .unwrap(),
+                    staking_history_index,
+                    &mut slashed_amount,
+                );
+                staking_history_index += 1;
+                continue;
+            }
+            match self.slash_offender_in_next_validator_set(
+                &protocol_settings,
+                &mut next_validator_set,
+                &offenders,
+                offender_index,
+                delegator_index,
+                &mut slashed_amount,
+            ) {
+                ResultOfLoopingValidatorSet::NoMoreDelegator => {
+                    offender_index += 1;
+                    staking_history_index = validator_set.staking_history_index() + 1;
+                    delegator_index = 0;
+                }
+                ResultOfLoopingValidatorSet::NoMoreValidator => {
+                    self.next_validator_set.set(&next_validator_set);
+                    self.clear_unslashed_offences(
+                        processing_context.processing_nonce().unwrap_or(0),
+                        &offenders,
+                    );
+                    if slashed_amount > 0 {
+                        self.transfer_slashed_oct_token(slashed_amount);
+                        processing_context
+                            .add_prepaid_gas(Gas::ONE_TERA.mul(T_GAS_FOR_FT_TRANSFER));
+                        processing_context
+                            .add_prepaid_gas(Gas::ONE_TERA.mul(T_GAS_FOR_RESOLVER_FUNCTION));
+                        self.sync_state_to_registry();
+                    }
+                    validator_set.set_processing_status(ValidatorSetProcessingStatus::Completed);
+                    return MultiTxsOperationProcessingResult::NeedMoreGas;
+                }
+                ResultOfLoopingValidatorSet::NeedToContinue => delegator_index += 1,
+            }
+        }
+        self.next_validator_set.set(&next_validator_set);
+        validator_set.set_processing_status(ValidatorSetProcessingStatus::SlashingOffender {
+            offender_index: U64::from(offender_index),
+            slashing_staking_history_index: U64::from(staking_history_index),
+            slashing_delegator_index: U64::from(delegator_index),
+            slashed_amount: U128::from(slashed_amount),
+        });
+        MultiTxsOperationProcessingResult::NeedMoreGas
+    }
+    //
+    fn get_offenders_in_appchain_message(&self, appchain_message_nonce: u32) -> Vec<Offender> {
+        let appchain_messages = self.appchain_messages.get().unwrap();
+        match appchain_messages.get_message(&appchain_message_nonce) {
+            Some(AppchainMessage {
+                appchain_event: AppchainEvent::EraRewardConcluded { offenders, .. },
+                ..
+            }) => offenders,
+            _ => Vec::new(),
+        }
+    }
+    //
+    fn slash_offender_in_next_validator_set(
+        &mut self,
+        protocol_settings: &ProtocolSettings,
+        next_validator_set: &mut NextValidatorSet,
+        offenders: &Vec<Offender>,
+        offender_index: u64,
+        delegator_index: u64,
+        slashed_amount: &mut u128,
+    ) -> ResultOfLoopingValidatorSet {
+        if offender_index >= offenders.len().try_into().unwrap() {
+            return ResultOfLoopingValidatorSet::NoMoreValidator;
+        }
+        let offender = offenders
+            .get(usize::try_from(offender_index).unwrap())
+            .unwrap();
+        let slashing_percent = match protocol_settings
+            .slashing_percent_of_offence_kinds
+            .get(&offender.kind)
+        {
+            Some(percent) => u128::from(*percent),
+            None => return ResultOfLoopingValidatorSet::NoMoreDelegator,
+        };
+        let validator_id = match self.get_validator_id_of_offender(offender) {
+            Some(validator_id) => validator_id,
+            None => {
+                log!(
+                    "Offender '{}' is not a registered validator. Skip slashing.",
+                    offender.who
+                );
+                return ResultOfLoopingValidatorSet::NoMoreDelegator;
+            }
+        };
+        let validator = match next_validator_set
+            .validator_set()
+            .get_validator(&validator_id)
+        {
+            Some(validator) => validator,
+            None => {
+                log!(
+                    "Validator '{}' is not in next validator set. Skip slashing.",
+                    validator_id
+                );
+                return ResultOfLoopingValidatorSet::NoMoreDelegator;
+            }
+        };
+        if delegator_index
+            >= next_validator_set
+                .validator_set()
+                .get_delegator_count_of(&validator_id)
+        {
+            let amount = validator.deposit_amount * slashing_percent / 100;
+            if amount > 0 {
+                let staking_history = self.record_staking_fact(StakingFact::ValidatorSlashed {
+                    validator_id: validator_id.clone(),
+                    amount: U128::from(amount),
+                });
+                next_validator_set.apply_staking_fact(&staking_history.staking_fact);
+                *slashed_amount += amount;
+                log!(
+                    "Validator '{}' is slashed {} OCT for offence '{}'.",
+                    validator_id,
+                    amount,
+                    offender.kind
+                );
+            }
+            return ResultOfLoopingValidatorSet::NoMoreDelegator;
+        }
+        let delegator = next_validator_set
+            .validator_set()
+            .get_delegator_by_index(&delegator_index, &validator_id)
+            .unwrap();
+        let amount = delegator.deposit_amount * slashing_percent / 100;
+        if amount > 0 {
+            let staking_history = self.record_staking_fact(StakingFact::DelegatorSlashed {
+                delegator_id: delegator.delegator_id,
+                validator_id: delegator.validator_id,
+                amount: U128::from(amount),
+            });
+            next_validator_set.apply_staking_fact(&staking_history.staking_fact);
+            *slashed_amount += amount;
+        }
+        ResultOfLoopingValidatorSet::NeedToContinue
+    }
+    /// Slash the stake moved from the offender in the given staking history.
+    ///
+    /// The unbonded stake is slashed if it is not withdrawn yet, and the redelegated stake
+    /// is slashed from the delegation to the new validator in next validator set.
+    fn slash_moved_stake_of_offender(
+        &mut self,
+        protocol_settings: &ProtocolSettings,
+        next_validator_set: &mut NextValidatorSet,
+        offender: &Offender,
+        staking_history_index: u64,
+        slashed_amount: &mut u128,
+    ) {
+        let slashing_percent = match protocol_settings
+            .slashing_percent_of_offence_kinds
+            .get(&offender.kind)
+        {
+            Some(percent) => u128::from(*percent),
+            None => return,
+        };
+        let offender_validator_id = match self.get_validator_id_of_offender(offender) {
+            Some(validator_id) => validator_id,
+            None => return,
+        };
+        let staking_history = match self
+            .staking_histories
+            .get()
+            .unwrap()
+            .get(&staking_history_index)
+        {
+            Some(staking_history) => staking_history,
+            None => return,
+        };
+        let (account_id, unbonded_amount) = match staking_history.staking_fact {
+            StakingFact::StakeDecreased {
+                validator_id,
+                amount,
+            }
+            | StakingFact::ValidatorUnbonded {
+                validator_id,
+                amount,
+            }
+            | StakingFact::ValidatorAutoUnbonded {
+                validator_id,
+                amount,
+            } if validator_id.eq(&offender_validator_id) => (validator_id, amount.0),
+            StakingFact::DelegationDecreased {
+                delegator_id,
+                validator_id,
+                amount,
+            }
+            | StakingFact::DelegatorUnbonded {
+                delegator_id,
+                validator_id,
+                amount,
+            }
+            | StakingFact::DelegatorAutoUnbonded {
+                delegator_id,
+                validator_id,
+                amount,
+            } if validator_id.eq(&offender_validator_id) => (delegator_id, amount.0),
+            StakingFact::DelegationRedelegated {
+                delegator_id,
+                from_validator_id,
+                to_validator_id,
+                amount,
+            } if from_validator_id.eq(&offender_validator_id) => {
+                self.slash_redelegated_stake_of_offender(
+                    next_validator_set,
+                    offender,
+                    delegator_id,
+                    to_validator_id,
+                    amount.0 * slashing_percent / 100,
+                    slashed_amount,
+                );
+                return;
+            }
+            _ => return,
+        };
+        if self.unbonded_stake_is_withdrawn(&account_id, staking_history_index) {
+            return;
+        }
+        let slashed_amount_before =
+            self.get_slashed_amount_of_unbonded_stake(staking_history_index);
+        let amount = (unbonded_amount
+            - self.get_rebonded_amount_of(staking_history_index)
+            - slashed_amount_before)
+            * slashing_percent
+            / 100;
+        if amount > 0 {
+            self.slashed_unbonded_stake_amounts
+                .insert(&staking_history_index, &(slashed_amount_before + amount));
+            *slashed_amount += amount;
+            log!(
+                "Unbonded stake of '{}' in staking history '{}' is slashed {} OCT for offence '{}'.",
+                account_id,
+                staking_history_index,
+                amount,
+                offender.kind
+            );
+        }
+    }
+    /// Slash the delegation to the new validator in next validator set, for the stake
+    /// redelegated from the offender. The slashed amount is limited by the delegation.
+    fn slash_redelegated_stake_of_offender(
+        &mut self,
+        next_validator_set: &mut NextValidatorSet,
+        offender: &Offender,
+        delegator_id: AccountId,
+        validator_id: AccountId,
+        amount: u128,
+        slashed_amount: &mut u128,
+    ) {
+        let amount = match next_validator_set.get_delegator(&delegator_id, &validator_id) {
+            Some(delegator) => std::cmp::min(amount, delegator.deposit_amount),
+            None => return,
+        };
+        if amount > 0 {
+            let staking_history = self.record_staking_fact(StakingFact::DelegatorSlashed {
+                delegator_id: delegator_id.clone(),
+                validator_id: validator_id.clone(),
+                amount: U128::from(amount),
+            });
+            next_validator_set.apply_staking_fact(&staking_history.staking_fact);
+            *slashed_amount += amount;
+            log!(
+                "Delegation of '{}' to '{}' is slashed {} OCT for offence '{}'.",
+                delegator_id,
+                validator_id,
+                amount,
+                offender.kind
+            );
+        }
+    }
+    /// The unbonded stake is withdrawn if it is neither in the unbonded stakes of
+    /// the account, nor recorded after the start of the latest era.
+    fn unbonded_stake_is_withdrawn(
+        &self,
+        account_id: &AccountId,
+        staking_history_index: u64,
+    ) -> bool {
+        if let Some(references) = self.unbonded_stakes.get(account_id) {
+            if references
+                .iter()
+                .any(|reference| reference.staking_history_index == staking_history_index)
+            {
+                return false;
+            }
+        }
+        let validator_set_histories = self.validator_set_histories.get().unwrap();
+        match validator_set_histories.get(&validator_set_histories.index_range().end_index.0) {
+            Some(validator_set) => staking_history_index <= validator_set.staking_history_index(),
+            None => false,
+        }
+    }
+    //
+    fn get_validator_id_of_offender(&self, offender: &Offender) -> Option<AccountId> {
+        let account_id_in_appchain =
+            AccountIdInAppchain::new(Some(offender.who.clone()), &self.appchain_template_type);
+        self.validator_profiles
+            .get()
+            .unwrap()
+            .get_by_id_in_appchain(&account_id_in_appchain.to_string())
+            .map(|validator_profile| validator_profile.validator_id)
+    }
+    /// Record the offenders reported by an appchain message, which will be slashed
+    /// when the message is processed.
+    pub fn record_unslashed_offences(
+        &mut self,
+        appchain_message_nonce: u32,
+        offenders: &Vec<Offender>,
+    ) {
+        offenders.iter().for_each(|offender| {
+            if let Some(validator_id) = self.get_validator_id_of_offender(offender) {
+                self.unslashed_offences
+                    .insert(&validator_id, &appchain_message_nonce);
+            }
+        });
+    }
+    //
+    fn clear_unslashed_offences(&mut self, appchain_message_nonce: u32, offenders: &Vec<Offender>) {
+        offenders.iter().for_each(|offender| {
+            if let Some(validator_id) = self.get_validator_id_of_offender(offender) {
+                if self.unslashed_offences.get(&validator_id) == Some(appchain_message_nonce) {
+                    self.unslashed_offences.remove(&validator_id);
+                }
+            }
+        });
+    }
+    //
+    pub fn transfer_slashed_oct_token(&mut self, amount: u128) {
+        let amount = amount + self.untransferred_slashed_oct_amount;
+        self.untransferred_slashed_oct_amount = 0;
+        let receiver_id = match self
+            .anchor_settings
+            .get()
+            .unwrap()
+            .slashed_oct_receiver_account
+        {
+            Some(account_id) => account_id,
+            None => self.owner.clone(),
+        };
+        ext_ft_core::ext(self.oct_token.get().unwrap().contract_account)
+            .with_attached_deposit(1)
+            .with_static_gas(Gas::ONE_TERA.mul(T_GAS_FOR_FT_TRANSFER))
+            .with_unused_gas_weight(0)
+            .ft_transfer(receiver_id.clone(), amount.into(), None)
+            .then(
+                ext_self::ext(env::current_account_id())
+                    .with_attached_deposit(0)
+                    .with_static_gas(Gas::ONE_TERA.mul(T_GAS_FOR_RESOLVER_FUNCTION))
+                    .with_unused_gas_weight(0)
+                    .resolve_slashed_oct_transfer(receiver_id, U128::from(amount)),
+            );
+    }
+}
+
+#[near_bindgen]
+impl SlashedOctTransferResolver for AppchainAnchor {
+    //
+    fn resolve_slashed_oct_transfer(&mut self, receiver_id: AccountId, amount: U128) {
+        assert_self();
+        match env::promise_result(0) {
+            PromiseResult::NotReady => unreachable!(),
+            PromiseResult::Successful(_) => (),
+            PromiseResult::Failed => {
+                self.untransferred_slashed_oct_amount += amount.0;
+                log!(
+                    "Failed to transfer slashed OCT token to '{}'. Amount: '{}'",
+                    &receiver_id,
+                    &amount.0
+                );
+            }
+        }
+    }
+}
@@ -12,6 +12,24 @@ impl AppchainAnchor {
         era_number: u64,
     ) -> MultiTxsOperationProcessingResult {
         if !validator_set_histories.contains(&era_number) {
+            let checkpoint_interval = self
+                .protocol_settings
+                .get()
+                .unwrap()
+                .validator_set_checkpoint_interval
+                .0;
+            let checkpoint_era_number = match era_number > 0 {
+                true => match validator_set_histories.get(&(era_number - 1)) {
+                    Some(last_validator_set)
+                        if era_number - last_validator_set.checkpoint_era_number()
+                            < checkpoint_interval =>
+                    {
+                        last_validator_set.checkpoint_era_number()
+                    }
+                    _ => era_number,
+                },
+                false => era_number,
+            };
             validator_set_histories.insert(
                 &era_number,
                 &ValidatorSetOfEra::new(
@@ -22,6 +40,7 @@ impl AppchainAnchor {
                         .index_range()
                         .end_index
                         .0,
+                    checkpoint_era_number,
                 ),
             );
         }
@@ -46,15 +65,19 @@ impl AppchainAnchor {
                         validator_set_histories.contains(&(era_number - 1)),
                         "Missing validator set of last era"
                     );
-                    let last_validator_set =
-                        validator_set_histories.get(&(era_number - 1)).unwrap();
+                    let source_validator_set = validator_set_histories
+                        .get(&get_source_era_number_of(
+                            validator_set_histories,
+                            &validator_set,
+                        ))
+                        .unwrap();
                     let mut validator_index = copying_validator_index.0;
                     let mut delegator_index = copying_delegator_index.0;
                     while processing_context.used_gas_of_current_function_call()
                         < Gas::ONE_TERA.mul(T_GAS_CAP_FOR_MULTI_TXS_PROCESSING)
                     {
                         match self.copy_delegator_to_validator_set(
-                            &last_validator_set,
+                            &source_validator_set,
                             &mut validator_set,
                             validator_index,
                             delegator_index,
@@ -64,7 +87,7 @@ impl AppchainAnchor {
                                 delegator_index = 0;
                             }
                             ResultOfLoopingValidatorSet::NoMoreValidator => {
-                                validator_set.set_total_stake(last_validator_set.total_stake());
+                                validator_set.set_total_stake(source_validator_set.total_stake());
                                 validator_set.set_processing_status(
                                     ValidatorSetProcessingStatus::UnbondingValidator {
                                         unbonding_validator_index: U64::from(0),
@@ -155,12 +178,16 @@ impl AppchainAnchor {
                             let mut next_validator_set = self.next_validator_set.get().unwrap();
                             next_validator_set.clear_auto_unbonding_validator_ids();
                             self.next_validator_set.set(&next_validator_set);
-                            let last_validator_set =
-                                validator_set_histories.get(&(era_number - 1)).unwrap();
+                            let source_validator_set = validator_set_histories
+                                .get(&get_source_era_number_of(
+                                    validator_set_histories,
+                                    &validator_set,
+                                ))
+                                .unwrap();
                             validator_set.set_processing_status(
                                 ValidatorSetProcessingStatus::ApplyingStakingHistory {
                                     applying_index: U64::from(
-                                        last_validator_set.staking_history_index() + 1,
+                                        source_validator_set.staking_history_index() + 1,
                                     ),
                                 },
                             );
@@ -188,6 +215,14 @@ impl AppchainAnchor {
                 MultiTxsOperationProcessingResult::NeedMoreGas
             }
             ValidatorSetProcessingStatus::ApplyingStakingHistory { mut applying_index } => {
+                // The staking histories before the last era are only replayed for
+                // building a checkpoint, they have been applied in previous eras.
+                let last_staking_history_index = match era_number > 0 {
+                    true => validator_set_histories
+                        .get(&(era_number - 1))
+                        .map(|last_validator_set| last_validator_set.staking_history_index()),
+                    false => None,
+                };
                 while processing_context.used_gas_of_current_function_call()
                     < Gas::ONE_TERA.mul(T_GAS_CAP_FOR_MULTI_TXS_PROCESSING)
                     && applying_index.0 <= validator_set.staking_history_index()
@@ -198,6 +233,10 @@ impl AppchainAnchor {
                         self.apply_staking_history_to_validator_set_of_era(
                             &mut validator_set,
                             &staking_history,
+                            match last_staking_history_index {
+                                Some(index) => applying_index.0 > index,
+                                None => true,
+                            },
                         );
                     }
                     applying_index.0 += 1;
@@ -207,6 +246,12 @@ impl AppchainAnchor {
                     validator_set.set_processing_status(
                         ValidatorSetProcessingStatus::ReadyForDistributingReward,
                     );
+                    AnchorEvent::EraSwitched {
+                        era_number: U64::from(era_number),
+                        total_stake: U128::from(validator_set.total_stake()),
+                        validator_count: U64::from(validator_set.validator_count()),
+                    }
+                    .emit();
                     self.record_appchain_message_processing_result(
                         &AppchainMessageProcessingResult::Ok {
                             nonce: processing_context.processing_nonce().unwrap_or(0),
@@ -247,8 +292,12 @@ impl AppchainAnchor {
         let validator = source_validator_set
             .get_validator_by_index(&validator_index)
             .unwrap();
-        if delegator_index >= source_validator_set.get_delegator_count_of(&validator.validator_id) {
+        if !target_validator_set.is_checkpoint()
+            || delegator_index
+                >= source_validator_set.get_delegator_count_of(&validator.validator_id)
+        {
             target_validator_set.insert_validator(&validator);
+            self.record_settings_of_validator_in_era(target_validator_set, &validator.validator_id);
             return ResultOfLoopingValidatorSet::NoMoreDelegator;
         }
         let delegator = source_validator_set
@@ -337,13 +386,43 @@ impl AppchainAnchor {
         });
         return ResultOfLoopingValidatorSet::NeedToContinue;
     }
+    // Record the commission percent and GRANDPA key of a validator in effect in the era.
+    fn record_settings_of_validator_in_era(
+        &self,
+        validator_set: &mut ValidatorSetOfEra,
+        validator_id: &AccountId,
+    ) {
+        validator_set.set_validator_commission_percent(
+            validator_id,
+            self.get_validator_commission_percent_of_era(validator_id, validator_set.era_number()),
+        );
+        if let Some(grandpa_key) = self.get_grandpa_key_of_validator(validator_id) {
+            validator_set.set_grandpa_key_of_validator(validator_id, &grandpa_key);
+        }
+    }
     //
     fn apply_staking_history_to_validator_set_of_era(
         &mut self,
         validator_set: &mut ValidatorSetOfEra,
         staking_history: &StakingHistory,
+        is_new_in_era: bool,
     ) {
         validator_set.apply_staking_fact(&staking_history.staking_fact);
+        match &staking_history.staking_fact {
+            StakingFact::ValidatorRegistered { validator_id, .. }
+            | StakingFact::ValidatorCommissionChanged { validator_id, .. } => {
+                self.record_settings_of_validator_in_era(validator_set, validator_id)
+            }
+            StakingFact::ValidatorUnbonded { validator_id, .. }
+            | StakingFact::ValidatorAutoUnbonded { validator_id, .. } => {
+                validator_set.remove_validator_commission_percent(validator_id);
+                validator_set.remove_grandpa_key_of_validator(validator_id);
+            }
+            _ => (),
+        }
+        if !is_new_in_era {
+            return;
+        }
         match &staking_history.staking_fact {
             StakingFact::StakeDecreased {
                 validator_id,
@@ -403,7 +482,7 @@ impl AppchainAnchor {
         }
     }
     //
-    fn add_unbonded_stake_of(
+    pub fn add_unbonded_stake_of(
         &mut self,
         account_id: &AccountId,
         unbonded_stake_reference: UnbondedStakeReference,
@@ -416,3 +495,20 @@ impl AppchainAnchor {
         self.unbonded_stakes.insert(account_id, &stakes);
     }
 }
+
+// Get the era number of the validator set which the validator set of the given era is
+// copied from. A checkpoint is copied from the last checkpoint with all delegators,
+// while other sets are copied from the set of last era without delegators.
+fn get_source_era_number_of(
+    validator_set_histories: &LookupArray<ValidatorSetOfEra>,
+    validator_set: &ValidatorSetOfEra,
+) -> u64 {
+    let last_era_number = validator_set.era_number() - 1;
+    match validator_set.is_checkpoint() {
+        true => validator_set_histories
+            .get(&last_era_number)
+            .unwrap()
+            .checkpoint_era_number(),
+        false => last_era_number,
+    }
+}
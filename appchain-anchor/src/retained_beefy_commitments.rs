@@ -0,0 +1,166 @@
+use crate::grandpa_light_client::header::Header;
+use crate::*;
+use beefy_light_client::commitment::Commitment;
+use beefy_light_client::mmr::{MmrLeaf, MmrLeafProof};
+use codec::Decode;
+
+/// The payload id of MMR root in the commitment of beefy light client.
+const MMR_ROOT_PAYLOAD_ID: [u8; 2] = *b"mh";
+
+#[derive(BorshDeserialize, BorshSerialize, Clone)]
+pub struct RetainedBeefyCommitment {
+    pub block_number: u32,
+    pub mmr_root: Vec<u8>,
+    pub validator_set_id: u64,
+    /// The state of beefy light client before the commitment is accepted,
+    /// for verifying the commitments which conflict with it.
+    pub previous_light_client_state: Option<LightClient>,
+}
+
+/// A ring buffer of the commitments recently accepted by beefy light client.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct RetainedBeefyCommitments {
+    /// The map from slot index in ring buffer to the retained commitment.
+    commitments: LookupMap<u32, RetainedBeefyCommitment>,
+    /// The total count of commitments which have been retained.
+    total_count: u64,
+}
+
+impl RetainedBeefyCommitment {
+    ///
+    pub fn mmr_root_of(commitment: &Commitment) -> Vec<u8> {
+        commitment
+            .payload
+            .get_raw(&MMR_ROOT_PAYLOAD_ID)
+            .cloned()
+            .unwrap_or_default()
+    }
+    ///
+    pub fn to_view(&self) -> RetainedAppchainCommitment {
+        RetainedAppchainCommitment {
+            block_number: self.block_number,
+            mmr_root: hex::encode(&self.mmr_root),
+            validator_set_id: U64::from(self.validator_set_id),
+        }
+    }
+    /// Verify the messages committed in the given header, by the MMR proof of the header
+    /// against the MMR root of this commitment.
+    pub fn verify_solochain_messages(
+        &self,
+        encoded_messages: &[u8],
+        encoded_header: &[u8],
+        encoded_mmr_leaf: &[u8],
+        encoded_mmr_proof: &[u8],
+    ) -> Result<(), String> {
+        let header = Header::decode(&mut &encoded_header[..])
+            .map_err(|err| format!("Failed to decode header: {}", err))?;
+        if !header.contains_commitment(&env::keccak256(encoded_messages)) {
+            return Err("The messages are not committed in the header.".to_string());
+        }
+        let leaf: Vec<u8> = Decode::decode(&mut &encoded_mmr_leaf[..])
+            .map_err(|err| format!("Failed to decode MMR leaf: {}", err))?;
+        let mmr_leaf = MmrLeaf::decode(&mut &leaf[..])
+            .map_err(|err| format!("Failed to decode MMR leaf: {}", err))?;
+        if mmr_leaf.parent_number_and_hash.1 != header.hash() {
+            return Err("The MMR leaf does not match the header.".to_string());
+        }
+        let mmr_proof = MmrLeafProof::decode(&mut &encoded_mmr_proof[..])
+            .map_err(|err| format!("Failed to decode MMR proof: {}", err))?;
+        let mmr_root: Hash = match self.mmr_root.clone().try_into() {
+            Ok(mmr_root) => mmr_root,
+            Err(_) => return Err("Invalid MMR root in the commitment.".to_string()),
+        };
+        let leaf_hash: Hash = env::keccak256(&leaf).try_into().unwrap();
+        match beefy_light_client::mmr::verify_leaf_proof(mmr_root, leaf_hash, mmr_proof) {
+            Ok(true) => Ok(()),
+            Ok(false) => Err(format!(
+                "Invalid MMR proof against the commitment at block '{}'.",
+                self.block_number
+            )),
+            Err(err) => Err(format!("Failed in verifying MMR proof: {:?}", err)),
+        }
+    }
+}
+
+impl RetainedBeefyCommitments {
+    ///
+    pub fn new() -> Self {
+        Self {
+            commitments: LookupMap::new(StorageKey::RetainedBeefyCommitmentsMap.into_bytes()),
+            total_count: 0,
+        }
+    }
+    //
+    fn slot_of(index: u64) -> u32 {
+        (index % u64::from(MAX_COUNT_OF_RETAINED_BEEFY_COMMITMENTS)) as u32
+    }
+    /// Retain the latest commitment of the given light client state,
+    /// the oldest one is overwritten if the buffer is full.
+    pub fn retain_latest_commitment_of(
+        &mut self,
+        light_client: &LightClient,
+        previous_light_client: Option<LightClient>,
+    ) {
+        let commitment = match light_client.get_latest_commitment() {
+            Some(commitment) => commitment,
+            None => return,
+        };
+        if self.get_latest().map_or(false, |latest| {
+            latest.block_number >= commitment.block_number
+        }) {
+            return;
+        }
+        self.commitments.insert(
+            &Self::slot_of(self.total_count),
+            &RetainedBeefyCommitment {
+                block_number: commitment.block_number,
+                mmr_root: RetainedBeefyCommitment::mmr_root_of(&commitment),
+                validator_set_id: commitment.validator_set_id,
+                previous_light_client_state: previous_light_client,
+            },
+        );
+        self.total_count += 1;
+    }
+    ///
+    pub fn get_latest(&self) -> Option<RetainedBeefyCommitment> {
+        match self.total_count {
+            0 => None,
+            count => self.commitments.get(&Self::slot_of(count - 1)),
+        }
+    }
+    ///
+    pub fn get_by_block_number(&self, block_number: u32) -> Option<RetainedBeefyCommitment> {
+        self.to_vec()
+            .into_iter()
+            .find(|commitment| commitment.block_number == block_number)
+    }
+    /// Get all retained commitments, from the oldest to the latest.
+    pub fn to_vec(&self) -> Vec<RetainedBeefyCommitment> {
+        let start_index = self
+            .total_count
+            .saturating_sub(u64::from(MAX_COUNT_OF_RETAINED_BEEFY_COMMITMENTS));
+        (start_index..self.total_count)
+            .filter_map(|index| self.commitments.get(&Self::slot_of(index)))
+            .collect()
+    }
+    ///
+    pub fn clear(&mut self) {
+        for slot in 0..MAX_COUNT_OF_RETAINED_BEEFY_COMMITMENTS {
+            self.commitments.remove(&slot);
+        }
+        self.total_count = 0;
+    }
+}
+
+impl AppchainAnchor {
+    /// Retain the latest commitment of the given state of beefy light client.
+    pub fn internal_retain_beefy_commitment(&mut self, light_client: &LightClient) {
+        let mut retained_beefy_commitments = self.retained_beefy_commitments.get().unwrap();
+        retained_beefy_commitments.retain_latest_commitment_of(
+            light_client,
+            self.previous_beefy_light_client_state.get(),
+        );
+        self.retained_beefy_commitments
+            .set(&retained_beefy_commitments);
+    }
+}
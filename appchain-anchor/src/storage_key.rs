@@ -45,6 +45,49 @@ pub enum StorageKey {
     AppchainMessageProcessingResultsMap,
     AppchainChallenges,
     AppchainChallengesMap,
+    AppchainChallengeStates,
+    EquivocationChallengeIndexes,
+    PreviousBeefyLightClientState,
+    ValidatorCommissions,
+    RedelegationEras,
+    RebondedStakeAmounts,
+    DelegatorRewardIndexes,
+    DelegatorRewardIndexesOfEra,
+    DelegatorRewardSnapshots,
+    PendingTransfers,
+    StorageRegistrationOfTokens,
+    FailedAppchainMessages,
+    FailedAppchainMessageNonces,
+    BridgeFeesOfTokens,
+    BridgeRateLimits,
+    NearNFTCollections,
+    NearNFTCollectionContractAccounts,
+    NearNFTCollectionsMap,
+    WrappedAppchainAssets,
+    WrappedAppchainAssetIds,
+    WrappedAppchainAssetsMap,
+    WrappedAppchainAssetContractWasm,
+    MessageBatchAttestations,
+    PendingMessageBatchHashes,
+    WitnessBonds,
+    WitnessedMessageBatches,
+    ChallengeableMessageBatchHashes,
+    GrandpaLightClientState,
+    RetainedBeefyCommitments,
+    RetainedBeefyCommitmentsMap,
+    NetLockedBalances,
+    NetLockedBalanceKeys,
+    NetLockedBalancesMap,
+    UnreturnedLockedBalances,
+    OwnersOfLockedNearNfts,
+    UnreturnedLockedNearNfts,
+    BridgeFeesOfNfts,
+    NftBridgeFeeDeposits,
+    PendingNftBridgeFeeCharges,
+    GrandpaKeysOfValidators,
+    EraNumbersOfGrandpaSets,
+    UnslashedOffences,
+    SlashedUnbondedStakeAmounts,
     WrappedAppchainNFTs,
     WrappedAppchainNFTsClassIds,
     WrappedAppchainNFTsNFTs,
@@ -57,6 +100,11 @@ pub enum StorageKey {
     UnprofitableValidatorIdsOfEra(u64),
     ValidatorRewardsOfEra(u64),
     DelegatorRewardsOfEra(u64),
+    RemovedDelegatorsOfEra(u64),
+    DelegatorCountsOfEra(u64),
+    ValidatorCountsOfEra(u64),
+    ValidatorCommissionsOfEra(u64),
+    GrandpaKeysOfEra(u64),
     DelegatorIdsInMapOfVToDOfEra {
         era_number: u64,
         validator_id: AccountId,
@@ -65,7 +113,12 @@ pub enum StorageKey {
         era_number: u64,
         delegator_id: AccountId,
     },
+    IdIndexOfInterval {
+        checkpoint_era_number: u64,
+        map_name: String,
+    },
     WrappedAppchainNFTsLockedTokenIdSet(String),
+    NearNFTCollectionLockedTokenIdSet(String),
 }
 
 impl StorageKey {
@@ -114,6 +167,49 @@ impl StorageKey {
             StorageKey::AppchainMessageProcessingResultsMap => "amprsm".to_string(),
             StorageKey::AppchainChallenges => "acs".to_string(),
             StorageKey::AppchainChallengesMap => "acsm".to_string(),
+            StorageKey::AppchainChallengeStates => "acss".to_string(),
+            StorageKey::EquivocationChallengeIndexes => "ecis".to_string(),
+            StorageKey::PreviousBeefyLightClientState => "pblcs".to_string(),
+            StorageKey::ValidatorCommissions => "vcms".to_string(),
+            StorageKey::RedelegationEras => "rdes".to_string(),
+            StorageKey::RebondedStakeAmounts => "rbsas".to_string(),
+            StorageKey::DelegatorRewardIndexes => "drixs".to_string(),
+            StorageKey::DelegatorRewardIndexesOfEra => "drixsoe".to_string(),
+            StorageKey::DelegatorRewardSnapshots => "drsss".to_string(),
+            StorageKey::PendingTransfers => "pts".to_string(),
+            StorageKey::StorageRegistrationOfTokens => "srots".to_string(),
+            StorageKey::FailedAppchainMessages => "fams".to_string(),
+            StorageKey::FailedAppchainMessageNonces => "famns".to_string(),
+            StorageKey::BridgeFeesOfTokens => "bfots".to_string(),
+            StorageKey::BridgeRateLimits => "brls".to_string(),
+            StorageKey::NearNFTCollections => "nnftcs".to_string(),
+            StorageKey::NearNFTCollectionContractAccounts => "nnftccas".to_string(),
+            StorageKey::NearNFTCollectionsMap => "nnftcsm".to_string(),
+            StorageKey::WrappedAppchainAssets => "waas".to_string(),
+            StorageKey::WrappedAppchainAssetIds => "waais".to_string(),
+            StorageKey::WrappedAppchainAssetsMap => "waasm".to_string(),
+            StorageKey::WrappedAppchainAssetContractWasm => "waawasm".to_string(),
+            StorageKey::MessageBatchAttestations => "mbas".to_string(),
+            StorageKey::PendingMessageBatchHashes => "pmbhs".to_string(),
+            StorageKey::WitnessBonds => "wbs".to_string(),
+            StorageKey::WitnessedMessageBatches => "wmbs".to_string(),
+            StorageKey::ChallengeableMessageBatchHashes => "cmbhs".to_string(),
+            StorageKey::GrandpaLightClientState => "glcs".to_string(),
+            StorageKey::RetainedBeefyCommitments => "rbcs".to_string(),
+            StorageKey::RetainedBeefyCommitmentsMap => "rbcsm".to_string(),
+            StorageKey::NetLockedBalances => "nlbs".to_string(),
+            StorageKey::NetLockedBalanceKeys => "nlbks".to_string(),
+            StorageKey::NetLockedBalancesMap => "nlbsm".to_string(),
+            StorageKey::UnreturnedLockedBalances => "ulbs".to_string(),
+            StorageKey::OwnersOfLockedNearNfts => "olnns".to_string(),
+            StorageKey::UnreturnedLockedNearNfts => "ulnns".to_string(),
+            StorageKey::BridgeFeesOfNfts => "bfons".to_string(),
+            StorageKey::NftBridgeFeeDeposits => "nbfds".to_string(),
+            StorageKey::PendingNftBridgeFeeCharges => "pnbfcs".to_string(),
+            StorageKey::GrandpaKeysOfValidators => "gkovs".to_string(),
+            StorageKey::EraNumbersOfGrandpaSets => "enogs".to_string(),
+            StorageKey::UnslashedOffences => "usos".to_string(),
+            StorageKey::SlashedUnbondedStakeAmounts => "susas".to_string(),
             StorageKey::WrappedAppchainNFTs => "wanfts".to_string(),
             StorageKey::WrappedAppchainNFTsClassIds => "wanftscis".to_string(),
             StorageKey::WrappedAppchainNFTsNFTs => "wanftsnfts".to_string(),
@@ -128,6 +224,11 @@ impl StorageKey {
             }
             StorageKey::ValidatorRewardsOfEra(era_number) => format!("{}vrs", era_number),
             StorageKey::DelegatorRewardsOfEra(era_number) => format!("{}drs", era_number),
+            StorageKey::RemovedDelegatorsOfEra(era_number) => format!("{}rmds", era_number),
+            StorageKey::DelegatorCountsOfEra(era_number) => format!("{}dcs", era_number),
+            StorageKey::ValidatorCountsOfEra(era_number) => format!("{}vcs", era_number),
+            StorageKey::ValidatorCommissionsOfEra(era_number) => format!("{}vcms", era_number),
+            StorageKey::GrandpaKeysOfEra(era_number) => format!("{}gks", era_number),
             StorageKey::DelegatorIdsInMapOfVToDOfEra {
                 era_number,
                 validator_id,
@@ -136,9 +237,16 @@ impl StorageKey {
                 era_number,
                 delegator_id,
             } => format!("{}lmdtv{}", era_number, delegator_id),
+            StorageKey::IdIndexOfInterval {
+                checkpoint_era_number,
+                map_name,
+            } => format!("{}ii{}", checkpoint_era_number, map_name),
             StorageKey::WrappedAppchainNFTsLockedTokenIdSet(class_id) => {
                 format!("{}wanltis", class_id)
             }
+            StorageKey::NearNFTCollectionLockedTokenIdSet(contract_account) => {
+                format!("{}nnftcltis", contract_account)
+            }
         }
     }
     pub fn into_bytes(&self) -> Vec<u8> {
@@ -0,0 +1,39 @@
+mod v2_1_0;
+mod v2_2_0;
+
+use crate::*;
+use near_sdk::borsh::{BorshDeserialize, BorshSerialize};
+use near_sdk::{env, near_bindgen};
+
+pub use v2_1_0::{
+    OldAnchorEventHistory, OldAppchainEvent, OldAppchainMessage, OldAppchainNotificationHistory,
+    OldStakingHistory,
+};
+pub use v2_2_0::{
+    OldAnchorSettings, OldAppchainChallenge, OldProtocolSettings, OldValidatorSetOfEra,
+    OldWrappedAppchainToken,
+};
+
+#[near_bindgen]
+impl AppchainAnchor {
+    #[init(ignore_state)]
+    pub fn migrate_state() -> Self {
+        near_sdk::assert_self();
+        //
+        let old_state = env::storage_read(b"STATE").expect("Old state doesn't exist");
+        // The state of v2.1.x can not be deserialized in the layout of v2.2.x,
+        // as the layouts of the two versions are not compatible.
+        let old_contract = match v2_2_0::OldAppchainAnchor::try_from_slice(&old_state) {
+            Ok(old_contract) => old_contract,
+            Err(_) => v2_2_0::OldAppchainAnchor::from_v2_1(
+                v2_1_0::OldAppchainAnchor::try_from_slice(&old_state)
+                    .expect("Unknown layout of old state."),
+            ),
+        };
+        old_contract.migrate_to_latest()
+    }
+}
+
+fn get_storage_key_in_lookup_array<T: BorshSerialize>(prefix: &StorageKey, index: &T) -> Vec<u8> {
+    [prefix.into_bytes(), index.try_to_vec().unwrap()].concat()
+}
@@ -1,3 +1,5 @@
+use super::get_storage_key_in_lookup_array;
+use super::v2_2_0::{self, OldAnchorSettings, OldProtocolSettings, OldWrappedAppchainToken};
 use crate::*;
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
 use near_sdk::collections::{LazyOption, LookupMap};
@@ -86,7 +88,7 @@ pub struct OldAppchainAnchor {
     /// The info of OCT token.
     oct_token: LazyOption<OctToken>,
     /// The info of wrapped appchain token in NEAR protocol.
-    wrapped_appchain_token: LazyOption<WrappedAppchainToken>,
+    wrapped_appchain_token: LazyOption<OldWrappedAppchainToken>,
     /// The NEP-141 tokens data.
     near_fungible_tokens: LazyOption<NearFungibleTokens>,
     /// The history data of validator set.
@@ -107,9 +109,9 @@ pub struct OldAppchainAnchor {
     /// The custom settings for appchain.
     appchain_settings: LazyOption<AppchainSettings>,
     /// The anchor settings for appchain.
-    anchor_settings: LazyOption<AnchorSettings>,
+    anchor_settings: LazyOption<OldAnchorSettings>,
     /// The protocol settings for appchain anchor.
-    protocol_settings: LazyOption<ProtocolSettings>,
+    protocol_settings: LazyOption<OldProtocolSettings>,
     /// The state of the corresponding appchain.
     appchain_state: AppchainState,
     /// The staking history data happened in this contract.
@@ -138,19 +140,12 @@ pub struct OldAppchainAnchor {
     wrapped_appchain_nfts: LazyOption<WrappedAppchainNFTs>,
 }
 
-#[near_bindgen]
-impl AppchainAnchor {
-    #[init(ignore_state)]
-    pub fn migrate_state() -> Self {
-        // Deserialize the state using the old contract structure.
-        let mut old_contract: OldAppchainAnchor =
-            env::state_read().expect("Old state doesn't exist");
-        //
-        near_sdk::assert_self();
-        //
+impl v2_2_0::OldAppchainAnchor {
+    /// Migrate the state of v2.1.x to the layout of v2.2.x.
+    pub fn from_v2_1(mut old_contract: OldAppchainAnchor) -> Self {
         old_contract.clear_anchor_events();
         // Create the new contract using the data from the old contract.
-        let new_contract = AppchainAnchor {
+        Self {
             appchain_id: old_contract.appchain_id,
             appchain_template_type: AppchainTemplateType::Barnacle,
             appchain_registry: old_contract.appchain_registry,
@@ -180,11 +175,12 @@ impl AppchainAnchor {
             appchain_messages: old_contract.appchain_messages,
             appchain_challenges: old_contract.appchain_challenges,
             wrapped_appchain_nfts: old_contract.wrapped_appchain_nfts,
-        };
-        //
-        //
-        new_contract
+        }
     }
+}
+
+#[near_bindgen]
+impl AppchainAnchor {
     ///
     pub fn migrate_staking_histories(
         &mut self,
@@ -282,10 +278,6 @@ impl AppchainAnchor {
     }
 }
 
-fn get_storage_key_in_lookup_array<T: BorshSerialize>(prefix: &StorageKey, index: &T) -> Vec<u8> {
-    [prefix.into_bytes(), index.try_to_vec().unwrap()].concat()
-}
-
 impl OldAppchainAnchor {
     ///
     pub fn clear_anchor_events(&mut self) {
@@ -0,0 +1,494 @@
+use super::get_storage_key_in_lookup_array;
+use crate::appchain_challenge::equivocation_challenge::EquivocationProof;
+use crate::*;
+use near_contract_standards::fungible_token::metadata::FungibleTokenMetadata;
+use near_sdk::borsh::maybestd::collections::HashMap;
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::{LazyOption, LookupMap, UnorderedSet};
+use near_sdk::json_types::I128;
+use near_sdk::{env, near_bindgen, AccountId, Balance, BlockHeight, Timestamp};
+use validator_set::ValidatorSet;
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct OldWrappedAppchainToken {
+    pub metadata: FungibleTokenMetadata,
+    pub contract_account: Option<AccountId>,
+    pub premined_beneficiary: Option<AccountId>,
+    pub premined_balance: U128,
+    pub changed_balance: I128,
+    pub price_in_usd: U128,
+    pub total_supply: U128,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct OldAnchorSettings {
+    pub token_price_maintainer_account: Option<AccountId>,
+    pub relayer_account: Option<AccountId>,
+    pub beefy_light_client_witness_mode: bool,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct OldProtocolSettings {
+    pub minimum_validator_deposit: U128,
+    pub minimum_validator_deposit_changing_amount: U128,
+    pub maximum_validator_stake_percent: u16,
+    pub minimum_delegator_deposit: U128,
+    pub minimum_delegator_deposit_changing_amount: U128,
+    pub minimum_total_stake_price_for_booting: U128,
+    pub maximum_market_value_percent_of_near_fungible_tokens: u16,
+    pub maximum_market_value_percent_of_wrapped_appchain_token: u16,
+    pub minimum_validator_count: U64,
+    pub maximum_validator_count: U64,
+    pub maximum_validators_per_delegator: U64,
+    pub unlock_period_of_validator_deposit: U64,
+    pub unlock_period_of_delegator_deposit: U64,
+    pub maximum_era_count_of_unwithdrawn_reward: U64,
+    pub maximum_era_count_of_valid_appchain_message: U64,
+    pub validator_commission_percent: u16,
+    pub maximum_allowed_unprofitable_era_count: u16,
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct OldValidatorSetOfEra {
+    pub validator_set: ValidatorSet,
+    pub start_block_height: BlockHeight,
+    pub start_timestamp: Timestamp,
+    pub staking_history_index: u64,
+    pub unprofitable_validator_id_set: UnorderedSet<AccountId>,
+    pub valid_total_stake: Balance,
+    pub validator_rewards: LookupMap<AccountId, Balance>,
+    pub delegator_rewards: LookupMap<(AccountId, AccountId), Balance>,
+    pub processing_status: ValidatorSetProcessingStatus,
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub enum OldAppchainChallenge {
+    EquivocationChallenge {
+        submitter_account: AccountId,
+        proof: EquivocationProof,
+    },
+    ConspiracyMmr {
+        submitter_account: AccountId,
+        block_number: u32,
+    },
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct OldAppchainAnchor {
+    /// The id of corresponding appchain.
+    pub appchain_id: AppchainId,
+    /// The type of appchain template of corresponding appchain.
+    pub appchain_template_type: AppchainTemplateType,
+    /// The account id of appchain registry contract.
+    pub appchain_registry: AccountId,
+    /// The owner account id.
+    pub owner: AccountId,
+    /// A certain public key of owner account
+    pub owner_pk: PublicKey,
+    /// The info of OCT token.
+    pub oct_token: LazyOption<OctToken>,
+    /// The info of wrapped appchain token in NEAR protocol.
+    pub wrapped_appchain_token: LazyOption<OldWrappedAppchainToken>,
+    /// The NEP-141 tokens data.
+    pub near_fungible_tokens: LazyOption<NearFungibleTokens>,
+    /// The history data of validator set.
+    pub validator_set_histories: LazyOption<LookupArray<ValidatorSetOfEra>>,
+    /// The validator set of the next era in appchain.
+    /// This validator set is only for checking staking rules.
+    pub next_validator_set: LazyOption<NextValidatorSet>,
+    /// The map of unwithdrawn validator rewards in eras, in unit of wrapped appchain token.
+    /// The key in map is `(era_number, account_id_of_validator)`
+    pub unwithdrawn_validator_rewards: LookupMap<(u64, AccountId), Balance>,
+    /// The map of unwithdrawn delegator rewards in eras, in unit of wrapped appchain token.
+    /// The key in map is `(era_number, account_id_of_delegator, account_id_of_validator)`
+    pub unwithdrawn_delegator_rewards: LookupMap<(u64, AccountId, AccountId), Balance>,
+    /// The map of unbonded stakes in eras.
+    pub unbonded_stakes: LookupMap<AccountId, Vec<UnbondedStakeReference>>,
+    /// The validators' profiles data.
+    pub validator_profiles: LazyOption<ValidatorProfiles>,
+    /// The custom settings for appchain.
+    pub appchain_settings: LazyOption<AppchainSettings>,
+    /// The anchor settings for appchain.
+    pub anchor_settings: LazyOption<OldAnchorSettings>,
+    /// The protocol settings for appchain anchor.
+    pub protocol_settings: LazyOption<OldProtocolSettings>,
+    /// The state of the corresponding appchain.
+    pub appchain_state: AppchainState,
+    /// The staking history data happened in this contract.
+    pub staking_histories: LazyOption<LookupArray<StakingHistory>>,
+    /// The appchain notification history data.
+    pub appchain_notification_histories: LazyOption<LookupArray<AppchainNotificationHistory>>,
+    /// The status of permissionless actions.
+    pub permissionless_actions_status: LazyOption<PermissionlessActionsStatus>,
+    /// The state of beefy light client
+    pub beefy_light_client_state: LazyOption<LightClient>,
+    /// The reward distribution records data
+    pub reward_distribution_records: LazyOption<RewardDistributionRecords>,
+    /// Whether the asset transfer is paused
+    pub asset_transfer_is_paused: bool,
+    /// The staking histories organized by account id
+    pub user_staking_histories: LazyOption<UserStakingHistories>,
+    /// Whether the rewards withdrawal is paused
+    pub rewards_withdrawal_is_paused: bool,
+    /// The processing result of appchain messages
+    pub appchain_messages: LazyOption<AppchainMessages>,
+    /// The appchain challenges
+    pub appchain_challenges: LazyOption<LookupArray<AppchainChallenge>>,
+    /// The wrapped appchain NFT data
+    pub wrapped_appchain_nfts: LazyOption<WrappedAppchainNFTs>,
+}
+
+impl OldAppchainAnchor {
+    /// Migrate the state of v2.2.x to the latest layout.
+    pub fn migrate_to_latest(self) -> AppchainAnchor {
+        let old_contract = self;
+        let mut anchor_settings = LazyOption::new(StorageKey::AnchorSettings.into_bytes(), None);
+        anchor_settings.set(&AnchorSettings::from_old_version(
+            old_contract.anchor_settings.get().unwrap(),
+        ));
+        let mut wrapped_appchain_token =
+            LazyOption::new(StorageKey::WrappedAppchainToken.into_bytes(), None);
+        wrapped_appchain_token.set(&WrappedAppchainToken::from_old_version(
+            old_contract.wrapped_appchain_token.get().unwrap(),
+        ));
+        let mut protocol_settings =
+            LazyOption::new(StorageKey::ProtocolSettings.into_bytes(), None);
+        protocol_settings.set(&ProtocolSettings::from_old_version(
+            old_contract.protocol_settings.get().unwrap(),
+        ));
+        // The stakes of delegators in the existing eras are read from validator sets
+        // when their reward snapshots are created.
+        let validator_set_histories = old_contract.validator_set_histories.get().unwrap();
+        let latest_era_number = validator_set_histories.index_range().end_index.0;
+        let delegator_reward_snapshot_start_era =
+            match validator_set_histories.contains(&latest_era_number) {
+                true => latest_era_number + 1,
+                false => latest_era_number,
+            };
+        // Create the new contract using the data from the old contract.
+        let mut new_contract = AppchainAnchor {
+            appchain_id: old_contract.appchain_id,
+            appchain_template_type: old_contract.appchain_template_type,
+            appchain_registry: old_contract.appchain_registry,
+            owner: old_contract.owner,
+            owner_pk: old_contract.owner_pk,
+            oct_token: old_contract.oct_token,
+            wrapped_appchain_token,
+            near_fungible_tokens: old_contract.near_fungible_tokens,
+            validator_set_histories: old_contract.validator_set_histories,
+            next_validator_set: old_contract.next_validator_set,
+            unwithdrawn_validator_rewards: old_contract.unwithdrawn_validator_rewards,
+            unwithdrawn_delegator_rewards: old_contract.unwithdrawn_delegator_rewards,
+            unbonded_stakes: old_contract.unbonded_stakes,
+            validator_profiles: old_contract.validator_profiles,
+            appchain_settings: old_contract.appchain_settings,
+            anchor_settings,
+            protocol_settings,
+            appchain_state: old_contract.appchain_state,
+            staking_histories: old_contract.staking_histories,
+            appchain_notification_histories: old_contract.appchain_notification_histories,
+            permissionless_actions_status: old_contract.permissionless_actions_status,
+            beefy_light_client_state: old_contract.beefy_light_client_state,
+            reward_distribution_records: old_contract.reward_distribution_records,
+            asset_transfer_is_paused: old_contract.asset_transfer_is_paused,
+            user_staking_histories: old_contract.user_staking_histories,
+            rewards_withdrawal_is_paused: old_contract.rewards_withdrawal_is_paused,
+            appchain_messages: old_contract.appchain_messages,
+            appchain_challenges: old_contract.appchain_challenges,
+            wrapped_appchain_nfts: old_contract.wrapped_appchain_nfts,
+            appchain_challenge_states: LookupMap::new(
+                StorageKey::AppchainChallengeStates.into_bytes(),
+            ),
+            equivocation_challenge_indexes: LookupMap::new(
+                StorageKey::EquivocationChallengeIndexes.into_bytes(),
+            ),
+            previous_beefy_light_client_state: LazyOption::new(
+                StorageKey::PreviousBeefyLightClientState.into_bytes(),
+                None,
+            ),
+            appchain_settlement_status: None,
+            appchain_liveness_status: AppchainLivenessStatus::Normal,
+            latest_message_staged_timestamp: env::block_timestamp(),
+            validator_commissions: LookupMap::new(StorageKey::ValidatorCommissions.into_bytes()),
+            redelegation_eras: LookupMap::new(StorageKey::RedelegationEras.into_bytes()),
+            rebonded_stake_amounts: LookupMap::new(StorageKey::RebondedStakeAmounts.into_bytes()),
+            delegator_reward_indexes: LookupMap::new(
+                StorageKey::DelegatorRewardIndexes.into_bytes(),
+            ),
+            delegator_reward_indexes_of_era: LookupMap::new(
+                StorageKey::DelegatorRewardIndexesOfEra.into_bytes(),
+            ),
+            delegator_reward_snapshots: LookupMap::new(
+                StorageKey::DelegatorRewardSnapshots.into_bytes(),
+            ),
+            delegator_reward_snapshot_start_era,
+            pending_transfers: LookupMap::new(StorageKey::PendingTransfers.into_bytes()),
+            bridge_storage_fund: BridgeStorageFund::default(),
+            storage_registration_of_tokens: LookupMap::new(
+                StorageKey::StorageRegistrationOfTokens.into_bytes(),
+            ),
+            failed_appchain_messages: LookupMap::new(
+                StorageKey::FailedAppchainMessages.into_bytes(),
+            ),
+            failed_appchain_message_nonces: UnorderedSet::new(
+                StorageKey::FailedAppchainMessageNonces.into_bytes(),
+            ),
+            bridge_fees_of_tokens: LookupMap::new(StorageKey::BridgeFeesOfTokens.into_bytes()),
+            bridge_rate_limits: LookupMap::new(StorageKey::BridgeRateLimits.into_bytes()),
+            native_near_token: NativeNearToken::default(),
+            near_nft_collections: LazyOption::new(
+                StorageKey::NearNFTCollections.into_bytes(),
+                Some(&NearNFTCollections::new()),
+            ),
+            wrapped_appchain_assets: LazyOption::new(
+                StorageKey::WrappedAppchainAssets.into_bytes(),
+                Some(&WrappedAppchainAssets::new()),
+            ),
+            message_batch_attestations: LookupMap::new(
+                StorageKey::MessageBatchAttestations.into_bytes(),
+            ),
+            pending_message_batch_hashes: UnorderedSet::new(
+                StorageKey::PendingMessageBatchHashes.into_bytes(),
+            ),
+            witness_bonds: LookupMap::new(StorageKey::WitnessBonds.into_bytes()),
+            witnessed_message_batches: LookupMap::new(
+                StorageKey::WitnessedMessageBatches.into_bytes(),
+            ),
+            challengeable_message_batch_hashes: UnorderedSet::new(
+                StorageKey::ChallengeableMessageBatchHashes.into_bytes(),
+            ),
+            grandpa_light_client_state: LazyOption::new(
+                StorageKey::GrandpaLightClientState.into_bytes(),
+                None,
+            ),
+            retained_beefy_commitments: LazyOption::new(
+                StorageKey::RetainedBeefyCommitments.into_bytes(),
+                Some(&RetainedBeefyCommitments::new()),
+            ),
+            net_locked_balances: LazyOption::new(
+                StorageKey::NetLockedBalances.into_bytes(),
+                Some(&NetLockedBalances::new()),
+            ),
+            unreturned_locked_balances: LookupMap::new(
+                StorageKey::UnreturnedLockedBalances.into_bytes(),
+            ),
+            owners_of_locked_near_nfts: UnorderedMap::new(
+                StorageKey::OwnersOfLockedNearNfts.into_bytes(),
+            ),
+            unreturned_locked_near_nfts: LookupMap::new(
+                StorageKey::UnreturnedLockedNearNfts.into_bytes(),
+            ),
+            bridge_fees_of_nfts: LookupMap::new(StorageKey::BridgeFeesOfNfts.into_bytes()),
+            nft_bridge_fee_deposits: LookupMap::new(StorageKey::NftBridgeFeeDeposits.into_bytes()),
+            pending_nft_bridge_fee_charges: LookupMap::new(
+                StorageKey::PendingNftBridgeFeeCharges.into_bytes(),
+            ),
+            nft_bridge_fees: NftBridgeFees::default(),
+            grandpa_keys_of_validators: LookupMap::new(
+                StorageKey::GrandpaKeysOfValidators.into_bytes(),
+            ),
+            era_numbers_of_grandpa_sets: LookupMap::new(
+                StorageKey::EraNumbersOfGrandpaSets.into_bytes(),
+            ),
+            unslashed_offences: LookupMap::new(StorageKey::UnslashedOffences.into_bytes()),
+            slashed_unbonded_stake_amounts: LookupMap::new(
+                StorageKey::SlashedUnbondedStakeAmounts.into_bytes(),
+            ),
+            untransferred_slashed_oct_amount: 0,
+        };
+        //
+        if let Some(light_client) = new_contract.beefy_light_client_state.get() {
+            new_contract.internal_retain_beefy_commitment(&light_client);
+        }
+        //
+        new_contract
+    }
+}
+
+#[near_bindgen]
+impl AppchainAnchor {
+    ///
+    pub fn migrate_validator_set_histories(
+        &mut self,
+        start_era_number: U64,
+    ) -> MultiTxsOperationProcessingResult {
+        near_sdk::assert_self();
+        let validator_set_histories = self.validator_set_histories.get().unwrap();
+        let index_range = validator_set_histories.index_range();
+        for era_number in start_era_number.0..index_range.end_index.0 + 1 {
+            if env::used_gas() > Gas::ONE_TERA.mul(T_GAS_CAP_FOR_MULTI_TXS_PROCESSING) {
+                return MultiTxsOperationProcessingResult::Error(format!(
+                    "Not all records are migrated. Call this function again with start_era_number '{}'.",
+                    era_number
+                ));
+            }
+            if let Some(old_data) = env::storage_read(&get_storage_key_in_lookup_array(
+                &StorageKey::ValidatorSetHistoriesMap,
+                &era_number,
+            )) {
+                if let Ok(old_version) = OldValidatorSetOfEra::try_from_slice(&old_data) {
+                    env::storage_write(
+                        &get_storage_key_in_lookup_array(
+                            &StorageKey::ValidatorSetHistoriesMap,
+                            &era_number,
+                        ),
+                        &ValidatorSetOfEra::from_old_version(old_version)
+                            .try_to_vec()
+                            .unwrap(),
+                    );
+                }
+            }
+        }
+        MultiTxsOperationProcessingResult::Ok
+    }
+    ///
+    pub fn migrate_appchain_challenges(
+        &mut self,
+        start_index: U64,
+    ) -> MultiTxsOperationProcessingResult {
+        near_sdk::assert_self();
+        let appchain_challenges = self.appchain_challenges.get().unwrap();
+        let index_range = appchain_challenges.index_range();
+        for index in start_index.0..index_range.end_index.0 + 1 {
+            if env::used_gas() > Gas::ONE_TERA.mul(T_GAS_CAP_FOR_MULTI_TXS_PROCESSING) {
+                return MultiTxsOperationProcessingResult::Error(format!(
+                    "Not all records are migrated. Call this function again with start_index '{}'.",
+                    index
+                ));
+            }
+            if let Some(old_data) = env::storage_read(&get_storage_key_in_lookup_array(
+                &StorageKey::AppchainChallengesMap,
+                &index,
+            )) {
+                if let Ok(old_version) = OldAppchainChallenge::try_from_slice(&old_data) {
+                    env::storage_write(
+                        &get_storage_key_in_lookup_array(
+                            &StorageKey::AppchainChallengesMap,
+                            &index,
+                        ),
+                        &AppchainChallenge::from_old_version(old_version)
+                            .try_to_vec()
+                            .unwrap(),
+                    );
+                }
+            }
+        }
+        MultiTxsOperationProcessingResult::Ok
+    }
+}
+
+impl WrappedAppchainToken {
+    //
+    pub fn from_old_version(old_version: OldWrappedAppchainToken) -> Self {
+        Self {
+            metadata: old_version.metadata,
+            contract_account: old_version.contract_account,
+            premined_beneficiary: old_version.premined_beneficiary,
+            premined_balance: old_version.premined_balance,
+            changed_balance: old_version.changed_balance,
+            price_in_usd: old_version.price_in_usd,
+            total_supply: old_version.total_supply,
+            bridging_state: BridgingState::Active,
+        }
+    }
+}
+
+impl AnchorSettings {
+    //
+    pub fn from_old_version(old_version: OldAnchorSettings) -> Self {
+        Self {
+            token_price_maintainer_account: old_version.token_price_maintainer_account,
+            relayer_account: old_version.relayer_account.clone(),
+            beefy_light_client_witness_mode: old_version.beefy_light_client_witness_mode,
+            witness_accounts: match old_version.relayer_account.clone() {
+                Some(relayer_account) => vec![relayer_account],
+                None => Vec::new(),
+            },
+            witness_threshold: match old_version.relayer_account.is_some() {
+                true => 1,
+                false => 0,
+            },
+            light_client_type: LightClientType::Beefy,
+            slashed_oct_receiver_account: None,
+            bridge_fee_collector_account: None,
+        }
+    }
+}
+
+impl ProtocolSettings {
+    //
+    pub fn from_old_version(old_version: OldProtocolSettings) -> Self {
+        Self {
+            minimum_validator_deposit: old_version.minimum_validator_deposit,
+            minimum_validator_deposit_changing_amount: old_version
+                .minimum_validator_deposit_changing_amount,
+            maximum_validator_stake_percent: old_version.maximum_validator_stake_percent,
+            minimum_delegator_deposit: old_version.minimum_delegator_deposit,
+            minimum_delegator_deposit_changing_amount: old_version
+                .minimum_delegator_deposit_changing_amount,
+            minimum_total_stake_price_for_booting: old_version
+                .minimum_total_stake_price_for_booting,
+            maximum_market_value_percent_of_near_fungible_tokens: old_version
+                .maximum_market_value_percent_of_near_fungible_tokens,
+            maximum_market_value_percent_of_wrapped_appchain_token: old_version
+                .maximum_market_value_percent_of_wrapped_appchain_token,
+            minimum_validator_count: old_version.minimum_validator_count,
+            maximum_validator_count: old_version.maximum_validator_count,
+            maximum_validators_per_delegator: old_version.maximum_validators_per_delegator,
+            unlock_period_of_validator_deposit: old_version.unlock_period_of_validator_deposit,
+            unlock_period_of_delegator_deposit: old_version.unlock_period_of_delegator_deposit,
+            maximum_era_count_of_unwithdrawn_reward: old_version
+                .maximum_era_count_of_unwithdrawn_reward,
+            maximum_era_count_of_valid_appchain_message: old_version
+                .maximum_era_count_of_valid_appchain_message,
+            validator_commission_percent: old_version.validator_commission_percent,
+            maximum_allowed_unprofitable_era_count: old_version
+                .maximum_allowed_unprofitable_era_count,
+            slashing_percent_of_offence_kinds: HashMap::new(),
+            challenge_bond_amount: U128::from(100 * OCT_DECIMALS_VALUE),
+            challenge_reward_percent: 10,
+            challenge_expiring_period: U64::from(14),
+            liveness_degraded_threshold: U64::from(2),
+            liveness_broken_threshold: U64::from(7),
+            minimum_validator_commission_percent: 0,
+            maximum_validator_commission_percent: 50,
+            maximum_validator_commission_change_per_era: 5,
+            validator_commission_change_notice_period: U64::from(2),
+            validator_set_checkpoint_interval: U64::from(10),
+            // Keep the existing witnesses working until the owner changes these settings
+            witness_bond_amount: U128::from(0),
+            challenge_period_of_witnessed_messages: U64::from(0),
+            pending_validator_commission_percent: None,
+            validator_commission_percent_effective_era_number: U64::from(0),
+        }
+    }
+}
+
+impl AppchainChallenge {
+    //
+    pub fn from_old_version(old_version: OldAppchainChallenge) -> Self {
+        match old_version {
+            OldAppchainChallenge::EquivocationChallenge {
+                submitter_account,
+                proof,
+            } => AppchainChallenge::EquivocationChallenge {
+                submitter_account,
+                proof,
+            },
+            OldAppchainChallenge::ConspiracyMmr {
+                submitter_account,
+                block_number,
+            } => AppchainChallenge::ConspiracyMmr {
+                submitter_account,
+                block_number,
+                signed_commitment: Vec::new(),
+                validator_proofs: Vec::new(),
+                mmr_leaf: Vec::new(),
+                mmr_proof: Vec::new(),
+            },
+        }
+    }
+}
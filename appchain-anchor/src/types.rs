@@ -102,6 +102,33 @@ pub enum AppchainState {
     Dead,
 }
 
+/// The liveness status of an active appchain.
+#[derive(Clone, Serialize, Deserialize, BorshDeserialize, BorshSerialize, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub enum AppchainLivenessStatus {
+    /// The appchain is producing new eras and messages normally.
+    Normal,
+    /// The appchain has not produced new era or message for a while.
+    Degraded,
+    /// The appchain is stalled, and the stakes are being unbonded for emergency withdrawal.
+    Stalled,
+}
+
+/// The status of the final settlement of a dead appchain.
+#[derive(Clone, Serialize, Deserialize, BorshDeserialize, BorshSerialize, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub enum AppchainSettlementStatus {
+    /// All validators and delegators in next validator set are being unbonded.
+    UnbondingStakes,
+    /// The locked NEAR fungible tokens and native NEAR token are being returned
+    /// to accounts in NEAR, by the net locked balances of the accounts.
+    ReturningLockedTokens,
+    /// The locked NFTs of NEAR NFT collections are being returned to their owners.
+    ReturningLockedNfts,
+    /// The settlement is completed.
+    Completed,
+}
+
 #[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
 #[serde(crate = "near_sdk::serde")]
 pub struct AppchainSettings {
@@ -117,6 +144,21 @@ pub struct AnchorSettings {
     pub token_price_maintainer_account: Option<AccountId>,
     pub relayer_account: Option<AccountId>,
     pub beefy_light_client_witness_mode: bool,
+    /// The accounts of the witness committee, which attest to the batches of
+    /// appchain messages while beefy light client is in witness mode.
+    pub witness_accounts: Vec<AccountId>,
+    /// The count of distinct witnesses which must attest to a batch of appchain messages
+    /// before it can be staged.
+    pub witness_threshold: u16,
+    /// The light client which is used to verify appchain messages
+    /// while beefy light client is not in witness mode.
+    pub light_client_type: LightClientType,
+    /// The account to receive the OCT token slashed from offenders.
+    /// If it is not set, the slashed OCT token will be transferred to the owner account.
+    pub slashed_oct_receiver_account: Option<AccountId>,
+    /// The account which can withdraw the collected bridge fees.
+    /// If it is not set, the bridge fees can be withdrawn by the owner account.
+    pub bridge_fee_collector_account: Option<AccountId>,
 }
 
 #[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
@@ -165,10 +207,47 @@ pub struct ProtocolSettings {
     /// If the era number of appchain message is smaller than the latest era number minus
     /// this value, the message will be considered as `invalid`.
     pub maximum_era_count_of_valid_appchain_message: U64,
-    /// The percent of commission fees of a validator's reward in an era
+    /// The default percent of commission fees of a validator's reward in an era,
+    /// for the validators which have not set their own commission
     pub validator_commission_percent: u16,
     /// The maximum unprofitable era count for auto-unbonding a validator
     pub maximum_allowed_unprofitable_era_count: u16,
+    /// The percent of stake to be slashed for each kind of offence reported by the appchain.
+    /// The offenders of a kind which is not in this map will not be slashed.
+    pub slashing_percent_of_offence_kinds: HashMap<String, u16>,
+    /// The minimum amount of OCT token to bond for committing an appchain challenge.
+    pub challenge_bond_amount: U128,
+    /// The percent of slashed OCT token rewarded to the submitter of an accepted challenge.
+    pub challenge_reward_percent: u16,
+    /// The period (in days) after which a challenge that is not adjudicated can be expired.
+    pub challenge_expiring_period: U64,
+    /// The period (in days) without any new era or staged appchain message,
+    /// after which the appchain is considered as degraded.
+    pub liveness_degraded_threshold: U64,
+    /// The period (in days) without any new era or staged appchain message,
+    /// after which the appchain is considered as stalled and will be changed to `broken`.
+    pub liveness_broken_threshold: U64,
+    /// The minimum commission percent that a validator can set
+    pub minimum_validator_commission_percent: u16,
+    /// The maximum commission percent that a validator can set
+    pub maximum_validator_commission_percent: u16,
+    /// The maximum change of commission percent of a validator in an era
+    pub maximum_validator_commission_change_per_era: u16,
+    /// The count of eras after which an increase of commission of a validator takes effect
+    pub validator_commission_change_notice_period: U64,
+    /// The count of eras between two checkpoints of validator set histories.
+    /// The validator set of an era between two checkpoints only stores the changes of delegators.
+    pub validator_set_checkpoint_interval: U64,
+    /// A witness has to bond a certain amount of OCT token to this contract for
+    /// attesting to batches of appchain messages.
+    pub witness_bond_amount: U128,
+    /// The period (in seconds) in which a batch of appchain messages staged by witnesses
+    /// can be challenged, and will not be processed.
+    pub challenge_period_of_witnessed_messages: U64,
+    /// The changed default commission percent which is waiting to take effect
+    pub pending_validator_commission_percent: Option<u16>,
+    /// The era from which the pending default commission percent takes effect
+    pub validator_commission_percent_effective_era_number: U64,
 }
 
 #[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
@@ -188,6 +267,7 @@ pub struct WrappedAppchainToken {
     pub changed_balance: I128,
     pub price_in_usd: U128,
     pub total_supply: U128,
+    pub bridging_state: BridgingState,
 }
 
 /// The bridging state of NEP-141 token.
@@ -198,6 +278,9 @@ pub enum BridgingState {
     Active,
     /// The state which this contract has stopped bridging the bridge token to the appchain.
     Closed,
+    /// The state which this contract has stopped bridging the bridge token in both directions
+    /// automatically, as the rate limit of the token is crossed.
+    Paused,
 }
 
 #[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
@@ -211,6 +294,33 @@ pub struct NearFungibleToken {
     pub bridging_state: BridgingState,
 }
 
+/// The status of native NEAR token bridged to the appchain.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct NativeNearToken {
+    pub price_in_usd: U128,
+    /// The total balance locked in this contract
+    pub locked_balance: U128,
+    pub bridging_state: BridgingState,
+}
+
+/// A fungible asset of the appchain, which is wrapped by a sub-account contract
+/// of this contract in NEAR protocol.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct WrappedAppchainAsset {
+    /// The id of the asset in the appchain
+    pub asset_id: String,
+    pub metadata: FungibleTokenMetadata,
+    pub contract_account: AccountId,
+    pub price_in_usd: U128,
+    /// The total supply of the wrapped asset in NEAR protocol
+    pub total_supply: U128,
+    /// The amount which is being minted, or failed to be minted and pending to be claimed
+    pub pending_mint_amount: U128,
+    pub bridging_state: BridgingState,
+}
+
 #[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
 #[serde(crate = "near_sdk::serde")]
 pub enum StakingFact {
@@ -280,6 +390,79 @@ pub enum StakingFact {
         validator_id: AccountId,
         validator_id_in_appchain: String,
     },
+    /// A validator's deposit is slashed for the offence reported by the appchain
+    ValidatorSlashed {
+        validator_id: AccountId,
+        amount: U128,
+    },
+    /// A delegator's delegation is slashed for the offence of the validator
+    DelegatorSlashed {
+        delegator_id: AccountId,
+        validator_id: AccountId,
+        amount: U128,
+    },
+    /// A validator's commission percent is changed, which takes effect from a certain era
+    ValidatorCommissionChanged {
+        validator_id: AccountId,
+        commission_percent: u16,
+        effective_era_number: U64,
+    },
+    /// A certain amount of a delegator's delegation is moved from a validator to another
+    DelegationRedelegated {
+        delegator_id: AccountId,
+        from_validator_id: AccountId,
+        to_validator_id: AccountId,
+        amount: U128,
+    },
+    /// A certain amount of a validator's unbonded stake is bonded again
+    StakeRebonded {
+        validator_id: AccountId,
+        amount: U128,
+        /// The index of staking history of the unbonded stake
+        unbonded_staking_history_index: U64,
+    },
+    /// A certain amount of a delegator's unbonded delegation is bonded again
+    DelegationRebonded {
+        delegator_id: AccountId,
+        validator_id: AccountId,
+        amount: U128,
+        /// The index of staking history of the unbonded delegation
+        unbonded_staking_history_index: U64,
+    },
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ValidatorCommission {
+    /// The commission percent which is in effect
+    pub commission_percent: u16,
+    /// The changed commission percent which is waiting to take effect
+    pub pending_commission_percent: Option<u16>,
+    /// The era from which the pending commission percent takes effect
+    pub effective_era_number: U64,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Clone)]
+pub struct RewardIndexOfEra {
+    /// The cumulative reward per stake of delegators of a validator before the era
+    pub index_before: u128,
+    /// The cumulative reward per stake of delegators of a validator after the era
+    pub index_after: u128,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Clone, Default)]
+pub struct DelegatorRewardSnapshot {
+    /// The stake of the delegator which is counted from `settled_index`
+    pub stake: Balance,
+    /// The changed stakes which are counted from the start of certain eras,
+    /// as `(era_number, stake)`
+    pub pending_stakes: Vec<(u64, Balance)>,
+    /// The cumulative reward per stake of the validator, up to which the reward is settled
+    pub settled_index: u128,
+    /// The cumulative reward per stake of the validator, up to which the reward is withdrawn
+    pub withdrawn_index: u128,
+    /// The settled reward which is not withdrawn yet
+    pub unwithdrawn_reward: Balance,
 }
 
 #[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
@@ -291,8 +474,10 @@ pub struct StakingHistory {
     pub index: U64,
 }
 
+/// The events emitted by this contract, in form of NEP-297 event logs.
 #[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
 #[serde(crate = "near_sdk::serde")]
+#[serde(tag = "event", content = "data", rename_all = "snake_case")]
 pub enum AnchorEvent {
     /// The event that a certain amount of a NEAR fungible token has been locked
     /// in appchain anchor.
@@ -358,6 +543,155 @@ pub enum AnchorEvent {
         appchain_message_nonce: u32,
         reason: String,
     },
+    /// The event that the liveness status of the appchain is changed.
+    AppchainLivenessStatusChanged {
+        status: AppchainLivenessStatus,
+        latest_activity_timestamp: U64,
+    },
+    /// The event that a staking fact is recorded as staking history.
+    StakingHistoryRecorded { staking_history: StakingHistory },
+    /// The event that an appchain notification is recorded, which will be
+    /// sent to the appchain.
+    AppchainNotificationRecorded {
+        appchain_notification_history: AppchainNotificationHistory,
+    },
+    /// The event that the processing of an appchain message is finished.
+    AppchainMessageProcessed {
+        processing_result: AppchainMessageProcessingResult,
+    },
+    /// The event that the validator set of a new era is generated.
+    EraSwitched {
+        era_number: U64,
+        total_stake: U128,
+        validator_count: U64,
+    },
+    /// The event that the reward of an era is distributed.
+    EraRewardDistributed {
+        era_number: U64,
+        era_reward: U128,
+        unprofitable_validator_ids: Vec<AccountId>,
+    },
+    /// The event that the protocol settings are changed.
+    ProtocolSettingsChanged { protocol_settings: ProtocolSettings },
+    /// The event that the appchain settings are changed.
+    AppchainSettingsChanged { appchain_settings: AppchainSettings },
+    /// The event that the anchor settings are changed.
+    AnchorSettingsChanged { anchor_settings: AnchorSettings },
+    /// The event that the state of the appchain is changed.
+    AppchainStateChanged {
+        previous_state: AppchainState,
+        state: AppchainState,
+    },
+    /// The event that a failed appchain message is retried.
+    AppchainMessageRetried { nonce: u32, retry_count: u32 },
+    /// The event that a failed appchain message is abandoned by the owner.
+    AppchainMessageAbandoned { nonce: u32, reason: String },
+    /// The event that bridge fee is charged from a transfer of a fungible token.
+    BridgeFeeCharged {
+        contract_account: AccountId,
+        amount: U128,
+        fee_amount: U128,
+    },
+    /// The event that the collected bridge fee of a fungible token is withdrawn.
+    BridgeFeeWithdrawn {
+        contract_account: AccountId,
+        receiver_id: AccountId,
+        amount: U128,
+    },
+    /// The event that bridge fee is charged from the deposit of a payer
+    /// for a transfer of an NFT.
+    NftBridgeFeeCharged {
+        contract_account: AccountId,
+        payer_id: AccountId,
+        fee_asset: NftBridgeFeeAsset,
+        fee_amount: U128,
+    },
+    /// The event that the collected bridge fee of NFTs is withdrawn.
+    NftBridgeFeeWithdrawn {
+        fee_asset: NftBridgeFeeAsset,
+        receiver_id: AccountId,
+        amount: U128,
+    },
+    /// The event that bridging of an asset is paused as its volume limit is crossed.
+    AssetBridgingPaused {
+        contract_account: AccountId,
+        reason: String,
+    },
+    /// The event that a certain amount of native NEAR token has been locked
+    /// in appchain anchor.
+    NativeNearLocked {
+        sender_id_in_near: AccountId,
+        receiver_id_in_appchain: String,
+        amount: U128,
+    },
+    /// The event that a certain amount of native NEAR token has been unlocked and
+    /// transfered to a receiver in NEAR protocol.
+    NativeNearUnlocked {
+        sender_id_in_appchain: String,
+        receiver_id_in_near: AccountId,
+        amount: U128,
+        /// The nonce of the appchain message
+        appchain_message_nonce: u32,
+    },
+    /// The event that the action for unlocking a certain amount of native NEAR token
+    /// had failed due to some reasons.
+    FailedToUnlockNativeNear {
+        sender_id_in_appchain: String,
+        receiver_id_in_near: AccountId,
+        amount: U128,
+        /// The nonce of the appchain message
+        appchain_message_nonce: u32,
+        reason: String,
+    },
+    /// The event that a certain amount of a wrapped appchain asset has been minted
+    /// in its contract in NEAR protocol.
+    WrappedAppchainAssetMinted {
+        asset_id: String,
+        sender_id_in_appchain: String,
+        receiver_id_in_near: AccountId,
+        amount: U128,
+        /// The nonce of the appchain message
+        appchain_message_nonce: u32,
+    },
+    /// The event that a certain amount of a wrapped appchain asset has been burnt
+    /// in its contract in NEAR protocol.
+    WrappedAppchainAssetBurnt {
+        asset_id: String,
+        sender_id_in_near: AccountId,
+        receiver_id_in_appchain: String,
+        amount: U128,
+    },
+    /// The event that a batch of appchain messages staged by witnesses is proved to be
+    /// fraudulent and reverted, and the bonds of the witnesses are slashed.
+    WitnessedMessageBatchReverted {
+        batch_hash: String,
+        slashed_witnesses: Vec<AccountId>,
+        slashed_amount: U128,
+        challenger_id: AccountId,
+    },
+}
+
+impl AnchorEvent {
+    /// Emit the event as a NEP-297 event log.
+    pub fn emit(&self) {
+        #[derive(Serialize)]
+        #[serde(crate = "near_sdk::serde")]
+        struct EventLog<'a> {
+            standard: &'a str,
+            version: &'a str,
+            #[serde(flatten)]
+            event: &'a AnchorEvent,
+        }
+        log!(
+            "EVENT_JSON:{}",
+            serde_json::to_string(&EventLog {
+                standard: ANCHOR_EVENT_STANDARD,
+                version: ANCHOR_EVENT_VERSION,
+                event: self,
+            })
+            .unwrap()
+        );
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -370,6 +704,7 @@ pub struct AppchainValidator {
     pub delegators_count: U64,
     pub can_be_delegated_to: bool,
     pub is_unbonding: bool,
+    pub commission_percent: u16,
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -389,6 +724,8 @@ pub struct UnbondedStake {
     pub account_id: AccountId,
     /// The amount of unbonded stake.
     pub amount: U128,
+    /// The index of staking history of the unbonded stake.
+    pub staking_history_index: U64,
     /// The unlock time of the stake.
     /// If the unlock time is not determined at the time, the value will be `None`.
     pub unlock_time: U64,
@@ -397,7 +734,7 @@ pub struct UnbondedStake {
 /// The actual processing order is:
 /// `CopyingFromLastEra` -> `UnbondingValidator`-> `AutoUnbondingValidator`
 /// -> `ApplyingStakingHistory` -> `ReadyForDistributingReward` -> `DistributingReward`
-/// -> `CheckingForAutoUnbondingValidator` -> `Completed`
+/// -> `CheckingForAutoUnbondingValidator` -> `SlashingOffender` -> `Completed`
 #[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Debug, Clone, PartialEq)]
 #[serde(crate = "near_sdk::serde")]
 pub enum ValidatorSetProcessingStatus {
@@ -426,6 +763,12 @@ pub enum ValidatorSetProcessingStatus {
     CheckingForAutoUnbondingValidator {
         unprofitable_validator_index: U64,
     },
+    SlashingOffender {
+        offender_index: U64,
+        slashing_staking_history_index: U64,
+        slashing_delegator_index: U64,
+        slashed_amount: U128,
+    },
 }
 
 #[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
@@ -458,6 +801,16 @@ pub struct RewardHistory {
     pub unwithdrawn_reward: U128,
 }
 
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct AppchainLiveness {
+    pub status: AppchainLivenessStatus,
+    /// The start timestamp of the latest era in anchor.
+    pub latest_era_start_timestamp: U64,
+    /// The timestamp of the latest staged appchain message.
+    pub latest_message_staged_timestamp: U64,
+}
+
 #[derive(Serialize, Deserialize, Clone)]
 #[serde(crate = "near_sdk::serde")]
 pub struct AnchorStatus {
@@ -544,6 +897,29 @@ pub enum AppchainNotification {
         sender_id_in_near: AccountId,
         message: String,
     },
+    /// A certain amount of native NEAR token has been locked in appchain anchor.
+    NativeNearLocked {
+        sender_id_in_near: AccountId,
+        receiver_id_in_appchain: String,
+        amount: U128,
+    },
+    /// A certain NFT of a NEAR NFT collection is locked in appchain anchor.
+    NearNFTLocked {
+        contract_account: AccountId,
+        token_id: String,
+        sender_id_in_near: AccountId,
+        owner_id_in_near: AccountId,
+        receiver_id_in_appchain: String,
+        token_metadata: TokenMetadata,
+    },
+    /// A certain amount of a wrapped appchain asset has been burnt in its contract
+    /// in NEAR protocol.
+    WrappedAppchainAssetBurnt {
+        asset_id: String,
+        sender_id_in_near: AccountId,
+        receiver_id_in_appchain: String,
+        amount: U128,
+    },
 }
 
 #[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
@@ -562,6 +938,226 @@ pub enum AppchainMessageProcessingResult {
     Error { nonce: u32, message: String },
 }
 
+/// The status of a failed appchain message.
+///
+/// The order of status is: `Failed` <-> `Retrying` -> `Resolved`, or `Failed` -> `Abandoned`
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub enum FailedAppchainMessageStatus {
+    /// The message is failed in processing, and can be retried or abandoned.
+    Failed,
+    /// The message is being retried, and is waiting for the result of cross contract calls.
+    Retrying,
+    /// The message is processed successfully by retrying.
+    Resolved,
+    /// The message is abandoned by the owner.
+    Abandoned,
+}
+
+/// The attestations of witnesses to a batch of appchain messages which is not staged yet.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct MessageBatchAttestation {
+    /// The hex string of the sha256 hash of the encoded messages
+    pub batch_hash: String,
+    /// The witnesses which have attested to the batch
+    pub witnesses: Vec<AccountId>,
+    /// The timestamp when the batch is attested at the first time
+    pub first_attested_at: U64,
+    /// The timestamp of the latest attestation
+    pub updated_at: U64,
+}
+
+/// A batch of appchain messages which is staged by witnesses and can be challenged
+/// in the challenge period.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct WitnessedMessageBatch {
+    /// The hex string of the sha256 hash of the encoded messages
+    pub batch_hash: String,
+    /// The witnesses which have attested to the batch
+    pub witnesses: Vec<AccountId>,
+    /// The nonces of the appchain messages staged by the batch
+    pub nonces: Vec<u32>,
+    /// The timestamp when the batch is staged
+    pub staged_at: U64,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct FailedAppchainMessage {
+    /// The nonce of the appchain message
+    pub nonce: u32,
+    /// The latest error result of processing the message
+    pub processing_result: AppchainMessageProcessingResult,
+    ///
+    pub status: FailedAppchainMessageStatus,
+    /// The count of retrying the message
+    pub retry_count: u32,
+    /// The timestamp when the message is failed at the first time
+    pub failed_at: U64,
+    /// The timestamp when the status of the message is changed
+    pub updated_at: U64,
+    /// The reason why the message is abandoned
+    pub abandon_reason: Option<String>,
+}
+
+/// The asset transfer which is failed in processing appchain message,
+/// and can be claimed again by anyone for the receiver.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub enum PendingTransfer {
+    /// The unlocking of a NEAR fungible token is failed.
+    NearFungibleTokenUnlocking {
+        symbol: String,
+        sender_id_in_appchain: String,
+        amount: U128,
+        appchain_message_nonce: u32,
+    },
+    /// The minting of wrapped appchain token is failed.
+    WrappedAppchainTokenMinting {
+        sender_id_in_appchain: Option<String>,
+        amount: U128,
+        appchain_message_nonce: u32,
+    },
+    /// The unlocking of native NEAR token is failed.
+    NativeNearUnlocking {
+        sender_id_in_appchain: String,
+        amount: U128,
+        appchain_message_nonce: u32,
+    },
+    /// The minting of a wrapped appchain asset is failed.
+    WrappedAppchainAssetMinting {
+        asset_id: String,
+        sender_id_in_appchain: String,
+        amount: U128,
+        appchain_message_nonce: u32,
+    },
+}
+
+/// The fund (in NEAR) held by this contract, for registering storage of
+/// receivers of bridge payouts in token contracts.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct BridgeStorageFund {
+    /// The remaining balance of the fund.
+    pub balance: U128,
+    /// The total amount spent for storage registration.
+    pub spent_amount: U128,
+}
+
+/// The fee charged for bridging a fungible token in one direction,
+/// which is taken from the transferred amount.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct BridgeFee {
+    /// The fixed amount of the fee.
+    pub fixed_amount: U128,
+    /// The percent of the transferred amount, in basis points (1/10000).
+    pub percent_in_basis_points: u16,
+}
+
+/// The bridge fee settings and the collected bridge fee of a fungible token.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct BridgeFeeOfToken {
+    /// The fee for transfers from NEAR protocol to the appchain (lock or burn).
+    pub fee_to_appchain: BridgeFee,
+    /// The fee for transfers from the appchain to NEAR protocol (unlock or mint).
+    pub fee_to_near: BridgeFee,
+    /// The amount of fee which is collected and not withdrawn yet.
+    pub collected_amount: U128,
+    /// The total amount of fee which is withdrawn.
+    pub withdrawn_amount: U128,
+}
+
+/// The asset in which the bridge fee of NFTs is paid.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub enum NftBridgeFeeAsset {
+    NativeNear,
+    OctToken,
+}
+
+/// The fixed bridge fee of an NFT contract, which is charged from the prepaid
+/// bridge fee deposit of the payer.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct NftBridgeFee {
+    /// The asset in which the fee is paid.
+    pub fee_asset: NftBridgeFeeAsset,
+    /// The fee for transfers from NEAR protocol to the appchain, paid by the sender.
+    pub fee_to_appchain: U128,
+    /// The fee for transfers from the appchain to NEAR protocol, paid by the receiver.
+    pub fee_to_near: U128,
+}
+
+/// The balance of NFT bridge fee in each fee asset.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct NftBridgeFeeBalance {
+    pub native_near: U128,
+    pub oct_token: U128,
+}
+
+/// The status of the bridge fee of NFTs.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Default)]
+#[serde(crate = "near_sdk::serde")]
+pub struct NftBridgeFees {
+    /// The total prepaid deposits of all payers, which are not charged yet.
+    pub deposited: NftBridgeFeeBalance,
+    /// The fee charged for the transfers to NEAR protocol which are not resolved yet.
+    pub pending: NftBridgeFeeBalance,
+    /// The fee which is collected and not withdrawn yet.
+    pub collected: NftBridgeFeeBalance,
+    /// The total fee which is withdrawn.
+    pub withdrawn: NftBridgeFeeBalance,
+}
+
+/// The volume of bridge transfers of an asset in one direction, in rolling time window.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct BridgeVolumeWindow {
+    /// The start timestamp of current window.
+    pub start_timestamp: U64,
+    /// The volume in current window.
+    pub current_volume: U128,
+    /// The volume in previous window.
+    pub previous_volume: U128,
+}
+
+/// The rate limits of bridge transfers of an asset (a NEAR fungible token,
+/// the wrapped appchain token or a class of wrapped appchain NFT).
+/// The amount of NFT transfers is the count of NFTs.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct BridgeRateLimit {
+    /// The maximum amount of a single transfer.
+    pub maximum_amount_per_transfer: Option<U128>,
+    /// The length of rolling time window, in seconds.
+    pub window_length_in_seconds: U64,
+    /// The maximum volume in rolling time window, from NEAR protocol to the appchain.
+    pub maximum_volume_per_window_to_appchain: Option<U128>,
+    /// The maximum volume in rolling time window, from the appchain to NEAR protocol.
+    pub maximum_volume_per_window_to_near: Option<U128>,
+    ///
+    pub volume_window_to_appchain: BridgeVolumeWindow,
+    ///
+    pub volume_window_to_near: BridgeVolumeWindow,
+}
+
+/// The setting and accounting of automatic storage registration of a token contract.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct StorageRegistrationOfToken {
+    /// Whether the receivers of bridge payouts are registered automatically.
+    pub enabled: bool,
+    /// The total amount spent for storage registration in the token contract.
+    pub spent_amount: U128,
+    /// The count of accounts registered in the token contract.
+    pub registered_account_count: U64,
+}
+
 #[derive(Serialize, Deserialize, Clone)]
 #[serde(crate = "near_sdk::serde")]
 pub enum MultiTxsOperationProcessingResult {
@@ -621,6 +1217,37 @@ pub enum BeefyLightClientStatus {
     Ready,
 }
 
+/// The kind of light client which verifies the finality of appchain headers.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub enum LightClientType {
+    /// Verify the commitments signed by BEEFY validators, with MMR proofs.
+    Beefy,
+    /// Verify the justifications of headers finalized by GRANDPA authorities.
+    Grandpa,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct GrandpaLightClientStatus {
+    pub set_id: U64,
+    pub authority_count: u32,
+    pub latest_finalized_block_number: Option<u32>,
+    /// The hex string of the hash of latest finalized header.
+    pub latest_finalized_block_hash: Option<String>,
+    /// The block number at which the pending change of authority set will be enacted.
+    pub pending_authority_set_change_enacted_at: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct LightClientStatus {
+    pub light_client_type: LightClientType,
+    pub beefy_light_client_status: BeefyLightClientStatus,
+    /// It is `None` if the GRANDPA light client is not initialized.
+    pub grandpa_light_client_status: Option<GrandpaLightClientStatus>,
+}
+
 #[derive(Serialize, Deserialize, Clone)]
 #[serde(crate = "near_sdk::serde")]
 pub struct AppchainCommitment {
@@ -628,6 +1255,15 @@ pub struct AppchainCommitment {
     pub validator_set_id: U64,
 }
 
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct RetainedAppchainCommitment {
+    pub block_number: u32,
+    /// The hex string of the MMR root in the commitment.
+    pub mmr_root: String,
+    pub validator_set_id: U64,
+}
+
 #[derive(Serialize, Deserialize, Clone)]
 #[serde(crate = "near_sdk::serde")]
 pub struct UserStakingHistory {
@@ -644,6 +1280,8 @@ pub enum FTDepositMessage {
         validator_id_in_appchain: String,
         can_be_delegated_to: bool,
         profile: HashMap<String, String>,
+        commission_percent: Option<u16>,
+        grandpa_key_in_appchain: Option<String>,
     },
     IncreaseStake,
     RegisterDelegator {
@@ -655,6 +1293,11 @@ pub enum FTDepositMessage {
     BridgeToAppchain {
         receiver_id_in_appchain: String,
     },
+    CommitChallenge {
+        appchain_challenge: AppchainChallenge,
+    },
+    BondAsWitness,
+    DepositNftBridgeFee,
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -672,3 +1315,12 @@ pub struct WrappedAppchainNFT {
     pub bridging_state: BridgingState,
     pub count_of_locked_tokens: U64,
 }
+
+/// The NFT collection native to NEAR protocol, which can be bridged to the appchain.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct NearNFTCollection {
+    pub contract_account: AccountId,
+    pub bridging_state: BridgingState,
+    pub count_of_locked_tokens: U64,
+}
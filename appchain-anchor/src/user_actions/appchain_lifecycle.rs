@@ -25,7 +25,7 @@ impl AppchainLifecycleManager for AppchainAnchor {
                 >= protocol_settings.minimum_total_stake_price_for_booting.0,
             "Not enough stake deposited in anchor."
         );
-        self.appchain_state = AppchainState::Booting;
+        self.internal_change_appchain_state(AppchainState::Booting);
         let mut processing_context = AppchainMessagesProcessingContext::new(
             self.permissionless_actions_status.get().unwrap(),
         );
@@ -70,8 +70,10 @@ impl AppchainLifecycleManager for AppchainAnchor {
                 || appchain_settings.era_reward.0 == 0),
             "Missing appchain settings."
         );
-        self.assert_light_client_initialized();
-        self.appchain_state = AppchainState::Active;
+        self.assert_light_client_of_type_initialized(
+            &self.anchor_settings.get().unwrap().light_client_type,
+        );
+        self.internal_change_appchain_state(AppchainState::Active);
         self.sync_state_to_registry();
     }
     //
@@ -89,4 +91,51 @@ impl AppchainLifecycleManager for AppchainAnchor {
         self.beefy_light_client_state
             .set(&beefy_light_client::new(initial_public_keys));
     }
+    //
+    fn initialize_grandpa_light_client(&mut self, initial_public_keys: Vec<String>, set_id: U64) {
+        self.assert_owner();
+        assert_eq!(
+            self.appchain_state,
+            AppchainState::Booting,
+            "Appchain state must be 'booting'."
+        );
+        assert!(
+            self.grandpa_light_client_state.is_none(),
+            "GRANDPA light client has already been initialized."
+        );
+        self.grandpa_light_client_state
+            .set(&GrandpaLightClient::new(initial_public_keys, set_id.0));
+    }
+    //
+    fn unfreeze_appchain(&mut self) {
+        self.assert_owner();
+        assert_eq!(
+            self.appchain_state,
+            AppchainState::Frozen,
+            "Appchain state must be 'frozen'."
+        );
+        self.internal_change_appchain_state(AppchainState::Active);
+        self.sync_state_to_registry();
+    }
+    //
+    fn go_broken(&mut self) {
+        self.assert_owner();
+        match self.appchain_state {
+            AppchainState::Active | AppchainState::Frozen => (),
+            _ => panic!("Appchain state must be 'active' or 'frozen'."),
+        }
+        self.internal_change_appchain_state(AppchainState::Broken);
+        self.sync_state_to_registry();
+    }
+    //
+    fn go_dead(&mut self) {
+        self.assert_owner();
+        match self.appchain_state {
+            AppchainState::Frozen | AppchainState::Broken => (),
+            _ => panic!("Appchain state must be 'frozen' or 'broken'."),
+        }
+        self.internal_change_appchain_state(AppchainState::Dead);
+        self.appchain_settlement_status = Some(AppchainSettlementStatus::UnbondingStakes);
+        self.sync_state_to_registry();
+    }
 }
@@ -6,12 +6,40 @@ impl OwnerActions for AppchainAnchor {
     fn remove_validator_set_before(&mut self, era_number: U64) {
         self.assert_owner();
         let mut validator_set_histories = self.validator_set_histories.get().unwrap();
+        if let Some(validator_set) = validator_set_histories.get(&era_number.0) {
+            assert!(
+                validator_set.is_checkpoint(),
+                "The validator set of era '{}' is not a checkpoint. The later validator sets depend on the validator set of era '{}'.",
+                era_number.0,
+                validator_set.checkpoint_era_number()
+            );
+        }
         validator_set_histories.remove_before(&era_number.0);
         self.validator_set_histories.set(&validator_set_histories);
     }
     //
     fn remove_staking_history_before(&mut self, index: U64) {
         self.assert_owner();
+        // The validator set of next era is generated by applying the staking histories
+        // to the latest checkpoint, so the histories after it can not be removed.
+        let validator_set_histories = self.validator_set_histories.get().unwrap();
+        let mut era_number = validator_set_histories.index_range().end_index.0;
+        if let Some(validator_set) = validator_set_histories.get(&era_number) {
+            if !validator_set.all_staking_histories_are_applied() && era_number > 0 {
+                era_number -= 1;
+            }
+        }
+        if let Some(validator_set) = validator_set_histories.get(&era_number) {
+            let checkpoint = validator_set_histories
+                .get(&validator_set.checkpoint_era_number())
+                .expect("The latest checkpoint of validator set is missing.");
+            assert!(
+                index.0 <= checkpoint.staking_history_index() + 1,
+                "The staking histories after index '{}' are needed by the checkpoint of era '{}'.",
+                checkpoint.staking_history_index(),
+                validator_set.checkpoint_era_number()
+            );
+        }
         let mut staking_histories = self.staking_histories.get().unwrap();
         staking_histories.remove_before(&index.0);
         self.staking_histories.set(&staking_histories);
@@ -25,4 +53,82 @@ impl OwnerActions for AppchainAnchor {
         self.appchain_notification_histories
             .set(&appchain_notification_histories);
     }
+    //
+    fn accept_appchain_challenge(&mut self, index: U64) {
+        self.assert_owner();
+        let mut challenge_state = self.get_submitted_appchain_challenge_state(&index);
+        self.accept_appchain_challenge_with(&mut challenge_state, None, 0);
+        self.appchain_challenge_states
+            .insert(&index.0, &challenge_state);
+        if let Some(AppchainChallenge::ConspiracyMmr { .. }) =
+            self.appchain_challenges.get().unwrap().get(&index.0)
+        {
+            self.internal_freeze_appchain();
+        }
+    }
+    //
+    fn reject_appchain_challenge(&mut self, index: U64, reason: String) {
+        self.assert_owner();
+        let mut challenge_state = self.get_submitted_appchain_challenge_state(&index);
+        self.reject_appchain_challenge_with(&mut challenge_state, reason);
+        self.appchain_challenge_states
+            .insert(&index.0, &challenge_state);
+    }
+    //
+    #[payable]
+    fn deposit_bridge_storage_fund(&mut self) {
+        let amount = env::attached_deposit();
+        assert!(amount > 0, "Attached deposit should be greater than 0.");
+        self.bridge_storage_fund.balance = U128::from(self.bridge_storage_fund.balance.0 + amount);
+    }
+    //
+    fn withdraw_bridge_storage_fund(&mut self, amount: U128) {
+        self.assert_owner();
+        assert!(
+            amount.0 <= self.bridge_storage_fund.balance.0,
+            "Not enough balance in bridge storage fund."
+        );
+        assert!(
+            self.near_balance_is_enough_for(0),
+            "Not enough NEAR balance of this contract to cover the bridge storage fund."
+        );
+        self.bridge_storage_fund.balance =
+            U128::from(self.bridge_storage_fund.balance.0 - amount.0);
+        Promise::new(self.owner.clone()).transfer(amount.0);
+    }
+    //
+    fn enable_storage_registration_of_token(&mut self, contract_account: AccountId) {
+        self.assert_owner();
+        let mut storage_registration = self
+            .storage_registration_of_tokens
+            .get(&contract_account)
+            .unwrap_or_default();
+        assert!(
+            !storage_registration.enabled,
+            "The storage registration is already enabled."
+        );
+        storage_registration.enabled = true;
+        self.storage_registration_of_tokens
+            .insert(&contract_account, &storage_registration);
+    }
+    //
+    fn disable_storage_registration_of_token(&mut self, contract_account: AccountId) {
+        self.assert_owner();
+        let mut storage_registration = self
+            .storage_registration_of_tokens
+            .get(&contract_account)
+            .unwrap_or_default();
+        assert!(
+            storage_registration.enabled,
+            "The storage registration is already disabled."
+        );
+        storage_registration.enabled = false;
+        self.storage_registration_of_tokens
+            .insert(&contract_account, &storage_registration);
+    }
+    //
+    fn mark_appchain_message_abandoned(&mut self, nonce: u32, reason: String) {
+        self.assert_owner();
+        self.internal_abandon_appchain_message(nonce, reason);
+    }
 }
@@ -2,6 +2,7 @@ use crate::{
     interfaces::{AnchorSettingsManager, AppchainSettingsManager, ProtocolSettingsManager},
     *,
 };
+use borsh::maybestd::collections::HashMap;
 use core::convert::From;
 
 impl Default for ProtocolSettings {
@@ -24,6 +25,21 @@ impl Default for ProtocolSettings {
             maximum_era_count_of_valid_appchain_message: U64::from(7),
             validator_commission_percent: 20,
             maximum_allowed_unprofitable_era_count: 3,
+            slashing_percent_of_offence_kinds: HashMap::new(),
+            challenge_bond_amount: U128::from(100 * OCT_DECIMALS_VALUE),
+            challenge_reward_percent: 10,
+            challenge_expiring_period: U64::from(14),
+            liveness_degraded_threshold: U64::from(2),
+            liveness_broken_threshold: U64::from(7),
+            minimum_validator_commission_percent: 0,
+            maximum_validator_commission_percent: 50,
+            maximum_validator_commission_change_per_era: 5,
+            validator_commission_change_notice_period: U64::from(2),
+            validator_set_checkpoint_interval: U64::from(10),
+            witness_bond_amount: U128::from(10_000 * OCT_DECIMALS_VALUE),
+            challenge_period_of_witnessed_messages: U64::from(3600),
+            pending_validator_commission_percent: None,
+            validator_commission_percent_effective_era_number: U64::from(0),
         }
     }
 }
@@ -34,6 +50,11 @@ impl Default for AnchorSettings {
             token_price_maintainer_account: None,
             relayer_account: None,
             beefy_light_client_witness_mode: false,
+            witness_accounts: Vec::new(),
+            witness_threshold: 0,
+            light_client_type: LightClientType::Beefy,
+            slashed_oct_receiver_account: None,
+            bridge_fee_collector_account: None,
         }
     }
 }
@@ -67,7 +88,7 @@ impl ProtocolSettingsManager for AppchainAnchor {
             "The value should be greater than `minimum_validator_deposit_changing_amount`."
         );
         protocol_settings.minimum_validator_deposit = value;
-        self.protocol_settings.set(&protocol_settings);
+        self.internal_set_protocol_settings(&protocol_settings);
     }
     //
     fn change_minimum_validator_deposit_changing_amount(&mut self, value: U128) {
@@ -85,7 +106,7 @@ impl ProtocolSettingsManager for AppchainAnchor {
             "The value should be less than `minimum_validator_deposit`."
         );
         protocol_settings.minimum_validator_deposit_changing_amount = value;
-        self.protocol_settings.set(&protocol_settings);
+        self.internal_set_protocol_settings(&protocol_settings);
     }
     //
     fn change_maximum_validator_stake_percent(&mut self, value: u16) {
@@ -97,7 +118,7 @@ impl ProtocolSettingsManager for AppchainAnchor {
             "The value is not changed."
         );
         protocol_settings.maximum_validator_stake_percent = value;
-        self.protocol_settings.set(&protocol_settings);
+        self.internal_set_protocol_settings(&protocol_settings);
     }
     //
     fn change_minimum_delegator_deposit(&mut self, value: U128) {
@@ -115,7 +136,7 @@ impl ProtocolSettingsManager for AppchainAnchor {
             "The value should be greater than `minimum_delegator_deposit_changing_amount`."
         );
         protocol_settings.minimum_delegator_deposit = value;
-        self.protocol_settings.set(&protocol_settings);
+        self.internal_set_protocol_settings(&protocol_settings);
     }
     //
     fn change_minimum_delegator_deposit_changing_amount(&mut self, value: U128) {
@@ -133,7 +154,7 @@ impl ProtocolSettingsManager for AppchainAnchor {
             "The value should be less than `minimum_delegator_deposit`."
         );
         protocol_settings.minimum_delegator_deposit_changing_amount = value;
-        self.protocol_settings.set(&protocol_settings);
+        self.internal_set_protocol_settings(&protocol_settings);
     }
     //
     fn change_minimum_total_stake_price_for_booting(&mut self, value: U128) {
@@ -144,7 +165,7 @@ impl ProtocolSettingsManager for AppchainAnchor {
             "The value is not changed."
         );
         protocol_settings.minimum_total_stake_price_for_booting = value;
-        self.protocol_settings.set(&protocol_settings);
+        self.internal_set_protocol_settings(&protocol_settings);
     }
     //
     fn change_maximum_market_value_percent_of_near_fungible_tokens(&mut self, value: u16) {
@@ -155,7 +176,7 @@ impl ProtocolSettingsManager for AppchainAnchor {
             "The value is not changed."
         );
         protocol_settings.maximum_market_value_percent_of_near_fungible_tokens = value;
-        self.protocol_settings.set(&protocol_settings);
+        self.internal_set_protocol_settings(&protocol_settings);
     }
     //
     fn change_maximum_market_value_percent_of_wrapped_appchain_token(&mut self, value: u16) {
@@ -166,7 +187,7 @@ impl ProtocolSettingsManager for AppchainAnchor {
             "The value is not changed."
         );
         protocol_settings.maximum_market_value_percent_of_wrapped_appchain_token = value;
-        self.protocol_settings.set(&protocol_settings);
+        self.internal_set_protocol_settings(&protocol_settings);
     }
     //
     fn change_minimum_validator_count(&mut self, value: U64) {
@@ -181,7 +202,7 @@ impl ProtocolSettingsManager for AppchainAnchor {
             "The value should be less than `maximum_validator_count`."
         );
         protocol_settings.minimum_validator_count = value;
-        self.protocol_settings.set(&protocol_settings);
+        self.internal_set_protocol_settings(&protocol_settings);
     }
     //
     fn change_maximum_validator_count(&mut self, value: U64) {
@@ -196,7 +217,7 @@ impl ProtocolSettingsManager for AppchainAnchor {
             "The value should be greater than `minimum_validator_count`."
         );
         protocol_settings.maximum_validator_count = value;
-        self.protocol_settings.set(&protocol_settings);
+        self.internal_set_protocol_settings(&protocol_settings);
     }
     //
     fn change_maximum_validators_per_delegator(&mut self, value: U64) {
@@ -207,7 +228,7 @@ impl ProtocolSettingsManager for AppchainAnchor {
             "The value is not changed."
         );
         protocol_settings.maximum_validators_per_delegator = value;
-        self.protocol_settings.set(&protocol_settings);
+        self.internal_set_protocol_settings(&protocol_settings);
     }
     //
     fn change_unlock_period_of_validator_deposit(&mut self, value: U64) {
@@ -218,7 +239,7 @@ impl ProtocolSettingsManager for AppchainAnchor {
             "The value is not changed."
         );
         protocol_settings.unlock_period_of_validator_deposit = value;
-        self.protocol_settings.set(&protocol_settings);
+        self.internal_set_protocol_settings(&protocol_settings);
     }
     //
     fn change_unlock_period_of_delegator_deposit(&mut self, value: U64) {
@@ -229,7 +250,7 @@ impl ProtocolSettingsManager for AppchainAnchor {
             "The value is not changed."
         );
         protocol_settings.unlock_period_of_delegator_deposit = value;
-        self.protocol_settings.set(&protocol_settings);
+        self.internal_set_protocol_settings(&protocol_settings);
     }
     //
     fn change_maximum_era_count_of_unwithdrawn_reward(&mut self, value: U64) {
@@ -240,7 +261,7 @@ impl ProtocolSettingsManager for AppchainAnchor {
             "The value is not changed."
         );
         protocol_settings.maximum_era_count_of_unwithdrawn_reward = value;
-        self.protocol_settings.set(&protocol_settings);
+        self.internal_set_protocol_settings(&protocol_settings);
     }
     //
     fn change_maximum_era_count_of_valid_appchain_message(&mut self, value: U64) {
@@ -254,19 +275,59 @@ impl ProtocolSettingsManager for AppchainAnchor {
             "The value is not changed."
         );
         protocol_settings.maximum_era_count_of_valid_appchain_message = value;
-        self.protocol_settings.set(&protocol_settings);
+        self.internal_set_protocol_settings(&protocol_settings);
     }
     //
     fn change_validator_commission_percent(&mut self, value: u16) {
         self.assert_owner();
         assert!(value < 100, "Invalid percent value.");
         let mut protocol_settings = self.protocol_settings.get().unwrap();
+        let next_era_number = self.get_effective_era_number_of_staking_facts();
+        if let Some(pending_commission_percent) =
+            protocol_settings.pending_validator_commission_percent
+        {
+            assert!(
+                next_era_number
+                    > protocol_settings
+                        .validator_commission_percent_effective_era_number
+                        .0
+                        + 1,
+                "The last change of commission has not taken effect yet. Please try again in era {}.",
+                protocol_settings
+                    .validator_commission_percent_effective_era_number
+                    .0
+                    + 1
+            );
+            protocol_settings.validator_commission_percent = pending_commission_percent;
+            protocol_settings.pending_validator_commission_percent = None;
+        }
         assert!(
             value != protocol_settings.validator_commission_percent,
             "The value is not changed."
         );
-        protocol_settings.validator_commission_percent = value;
-        self.protocol_settings.set(&protocol_settings);
+        // The validators which have not set their own commission are changed together,
+        // so the change follows the notice period of the change of a validator.
+        let effective_era_number = match self.appchain_state {
+            AppchainState::Active => {
+                protocol_settings.pending_validator_commission_percent = Some(value);
+                match value > protocol_settings.validator_commission_percent {
+                    true => {
+                        next_era_number
+                            + protocol_settings
+                                .validator_commission_change_notice_period
+                                .0
+                    }
+                    false => next_era_number,
+                }
+            }
+            _ => {
+                protocol_settings.validator_commission_percent = value;
+                0
+            }
+        };
+        protocol_settings.validator_commission_percent_effective_era_number =
+            U64::from(effective_era_number);
+        self.internal_set_protocol_settings(&protocol_settings);
     }
     //
     fn change_maximum_allowed_unprofitable_era_count(&mut self, value: u16) {
@@ -281,7 +342,186 @@ impl ProtocolSettingsManager for AppchainAnchor {
             "The value is not changed."
         );
         protocol_settings.maximum_allowed_unprofitable_era_count = value;
-        self.protocol_settings.set(&protocol_settings);
+        self.internal_set_protocol_settings(&protocol_settings);
+    }
+    //
+    fn change_slashing_percent_of_offence_kind(&mut self, kind: String, value: u16) {
+        self.assert_owner();
+        assert!(value <= 100, "Invalid percent value.");
+        let mut protocol_settings = self.protocol_settings.get().unwrap();
+        assert!(
+            value
+                != protocol_settings
+                    .slashing_percent_of_offence_kinds
+                    .get(&kind)
+                    .map_or(0, |percent| *percent),
+            "The value is not changed."
+        );
+        if value > 0 {
+            protocol_settings
+                .slashing_percent_of_offence_kinds
+                .insert(kind, value);
+        } else {
+            protocol_settings
+                .slashing_percent_of_offence_kinds
+                .remove(&kind);
+        }
+        self.internal_set_protocol_settings(&protocol_settings);
+    }
+    //
+    fn change_challenge_bond_amount(&mut self, value: U128) {
+        self.assert_owner();
+        let mut protocol_settings = self.protocol_settings.get().unwrap();
+        assert!(
+            value.0 != protocol_settings.challenge_bond_amount.0,
+            "The value is not changed."
+        );
+        protocol_settings.challenge_bond_amount = value;
+        self.internal_set_protocol_settings(&protocol_settings);
+    }
+    //
+    fn change_challenge_reward_percent(&mut self, value: u16) {
+        self.assert_owner();
+        assert!(value <= 100, "Invalid percent value.");
+        let mut protocol_settings = self.protocol_settings.get().unwrap();
+        assert!(
+            value != protocol_settings.challenge_reward_percent,
+            "The value is not changed."
+        );
+        protocol_settings.challenge_reward_percent = value;
+        self.internal_set_protocol_settings(&protocol_settings);
+    }
+    //
+    fn change_challenge_expiring_period(&mut self, value: U64) {
+        self.assert_owner();
+        let mut protocol_settings = self.protocol_settings.get().unwrap();
+        assert!(
+            value.0 != protocol_settings.challenge_expiring_period.0,
+            "The value is not changed."
+        );
+        protocol_settings.challenge_expiring_period = value;
+        self.internal_set_protocol_settings(&protocol_settings);
+    }
+    //
+    fn change_liveness_degraded_threshold(&mut self, value: U64) {
+        self.assert_owner();
+        let mut protocol_settings = self.protocol_settings.get().unwrap();
+        assert!(
+            value.0 != protocol_settings.liveness_degraded_threshold.0,
+            "The value is not changed."
+        );
+        assert!(
+            value.0 < protocol_settings.liveness_broken_threshold.0,
+            "The value should be less than the broken threshold."
+        );
+        protocol_settings.liveness_degraded_threshold = value;
+        self.internal_set_protocol_settings(&protocol_settings);
+    }
+    //
+    fn change_liveness_broken_threshold(&mut self, value: U64) {
+        self.assert_owner();
+        let mut protocol_settings = self.protocol_settings.get().unwrap();
+        assert!(
+            value.0 != protocol_settings.liveness_broken_threshold.0,
+            "The value is not changed."
+        );
+        assert!(
+            value.0 > protocol_settings.liveness_degraded_threshold.0,
+            "The value should be greater than the degraded threshold."
+        );
+        protocol_settings.liveness_broken_threshold = value;
+        self.internal_set_protocol_settings(&protocol_settings);
+    }
+    //
+    fn change_minimum_validator_commission_percent(&mut self, value: u16) {
+        self.assert_owner();
+        let mut protocol_settings = self.protocol_settings.get().unwrap();
+        assert!(
+            value != protocol_settings.minimum_validator_commission_percent,
+            "The value is not changed."
+        );
+        assert!(
+            value <= protocol_settings.maximum_validator_commission_percent,
+            "The value should not be greater than the maximum validator commission percent."
+        );
+        protocol_settings.minimum_validator_commission_percent = value;
+        self.internal_set_protocol_settings(&protocol_settings);
+    }
+    //
+    fn change_maximum_validator_commission_percent(&mut self, value: u16) {
+        self.assert_owner();
+        assert!(value <= 100, "Invalid percent value.");
+        let mut protocol_settings = self.protocol_settings.get().unwrap();
+        assert!(
+            value != protocol_settings.maximum_validator_commission_percent,
+            "The value is not changed."
+        );
+        assert!(
+            value >= protocol_settings.minimum_validator_commission_percent,
+            "The value should not be less than the minimum validator commission percent."
+        );
+        protocol_settings.maximum_validator_commission_percent = value;
+        self.internal_set_protocol_settings(&protocol_settings);
+    }
+    //
+    fn change_maximum_validator_commission_change_per_era(&mut self, value: u16) {
+        self.assert_owner();
+        assert!(value <= 100, "Invalid percent value.");
+        let mut protocol_settings = self.protocol_settings.get().unwrap();
+        assert!(
+            value != protocol_settings.maximum_validator_commission_change_per_era,
+            "The value is not changed."
+        );
+        protocol_settings.maximum_validator_commission_change_per_era = value;
+        self.internal_set_protocol_settings(&protocol_settings);
+    }
+    //
+    fn change_validator_commission_change_notice_period(&mut self, value: U64) {
+        self.assert_owner();
+        let mut protocol_settings = self.protocol_settings.get().unwrap();
+        assert!(
+            value.0
+                != protocol_settings
+                    .validator_commission_change_notice_period
+                    .0,
+            "The value is not changed."
+        );
+        protocol_settings.validator_commission_change_notice_period = value;
+        self.internal_set_protocol_settings(&protocol_settings);
+    }
+    //
+    fn change_validator_set_checkpoint_interval(&mut self, value: U64) {
+        self.assert_owner();
+        assert!(value.0 > 0, "The value should be greater than 0.");
+        let mut protocol_settings = self.protocol_settings.get().unwrap();
+        assert!(
+            value.0 != protocol_settings.validator_set_checkpoint_interval.0,
+            "The value is not changed."
+        );
+        protocol_settings.validator_set_checkpoint_interval = value;
+        self.internal_set_protocol_settings(&protocol_settings);
+    }
+    //
+    fn change_witness_bond_amount(&mut self, value: U128) {
+        self.assert_owner();
+        let mut protocol_settings = self.protocol_settings.get().unwrap();
+        assert!(
+            value.0 != protocol_settings.witness_bond_amount.0,
+            "The value is not changed."
+        );
+        protocol_settings.witness_bond_amount = value;
+        self.internal_set_protocol_settings(&protocol_settings);
+    }
+    //
+    fn change_challenge_period_of_witnessed_messages(&mut self, value: U64) {
+        self.assert_owner();
+        let mut protocol_settings = self.protocol_settings.get().unwrap();
+        assert!(
+            value.0 != protocol_settings.challenge_period_of_witnessed_messages.0,
+            "The value is not changed."
+        );
+        protocol_settings.challenge_period_of_witnessed_messages = value;
+        self.internal_set_protocol_settings(&protocol_settings);
     }
 }
 
@@ -292,28 +532,28 @@ impl AppchainSettingsManager for AppchainAnchor {
         self.assert_owner();
         let mut appchain_settings = self.appchain_settings.get().unwrap();
         appchain_settings.rpc_endpoint = rpc_endpoint;
-        self.appchain_settings.set(&appchain_settings);
+        self.internal_set_appchain_settings(&appchain_settings);
     }
     //
     fn set_subql_endpoint(&mut self, subql_endpoint: String) {
         self.assert_owner();
         let mut appchain_settings = self.appchain_settings.get().unwrap();
         appchain_settings.subql_endpoint = subql_endpoint;
-        self.appchain_settings.set(&appchain_settings);
+        self.internal_set_appchain_settings(&appchain_settings);
     }
     //
     fn set_era_reward(&mut self, era_reward: U128) {
         self.assert_owner();
         let mut appchain_settings = self.appchain_settings.get().unwrap();
         appchain_settings.era_reward = era_reward;
-        self.appchain_settings.set(&appchain_settings);
+        self.internal_set_appchain_settings(&appchain_settings);
     }
     //
     fn set_bonus_for_new_validator(&mut self, bonus_amount: U128) {
         self.assert_owner();
         let mut appchain_settings = self.appchain_settings.get().unwrap();
         appchain_settings.bonus_for_new_validator = bonus_amount;
-        self.appchain_settings.set(&appchain_settings);
+        self.internal_set_appchain_settings(&appchain_settings);
     }
 }
 
@@ -328,7 +568,7 @@ impl AnchorSettingsManager for AppchainAnchor {
         );
         let mut anchor_settings = self.anchor_settings.get().unwrap();
         anchor_settings.token_price_maintainer_account = Some(account_id);
-        self.anchor_settings.set(&anchor_settings);
+        self.internal_set_anchor_settings(&anchor_settings);
     }
     //
     fn set_relayer_account(&mut self, account_id: AccountId) {
@@ -339,7 +579,7 @@ impl AnchorSettingsManager for AppchainAnchor {
         );
         let mut anchor_settings = self.anchor_settings.get().unwrap();
         anchor_settings.relayer_account = Some(account_id);
-        self.anchor_settings.set(&anchor_settings);
+        self.internal_set_anchor_settings(&anchor_settings);
     }
     //
     fn turn_on_beefy_light_client_witness_mode(&mut self) {
@@ -349,8 +589,14 @@ impl AnchorSettingsManager for AppchainAnchor {
             !anchor_settings.beefy_light_client_witness_mode,
             "Witness mode is already turned on."
         );
+        assert!(
+            anchor_settings.witness_threshold > 0
+                && anchor_settings.witness_accounts.len()
+                    >= usize::from(anchor_settings.witness_threshold),
+            "Witness committee is not configured."
+        );
         anchor_settings.beefy_light_client_witness_mode = true;
-        self.anchor_settings.set(&anchor_settings);
+        self.internal_set_anchor_settings(&anchor_settings);
     }
     //
     fn turn_off_beefy_light_client_witness_mode(&mut self) {
@@ -361,6 +607,111 @@ impl AnchorSettingsManager for AppchainAnchor {
             "Witness mode is already turned off."
         );
         anchor_settings.beefy_light_client_witness_mode = false;
-        self.anchor_settings.set(&anchor_settings);
+        self.internal_set_anchor_settings(&anchor_settings);
+    }
+    //
+    fn set_light_client_type(&mut self, light_client_type: LightClientType) {
+        self.assert_owner();
+        let mut anchor_settings = self.anchor_settings.get().unwrap();
+        assert!(
+            anchor_settings.light_client_type.ne(&light_client_type),
+            "The light client type is not changed."
+        );
+        self.assert_light_client_of_type_initialized(&light_client_type);
+        anchor_settings.light_client_type = light_client_type;
+        self.internal_set_anchor_settings(&anchor_settings);
+    }
+    //
+    fn set_slashed_oct_receiver_account(&mut self, account_id: AccountId) {
+        self.assert_owner();
+        let mut anchor_settings = self.anchor_settings.get().unwrap();
+        anchor_settings.slashed_oct_receiver_account = Some(account_id);
+        self.internal_set_anchor_settings(&anchor_settings);
+    }
+    //
+    fn set_bridge_fee_collector_account(&mut self, account_id: AccountId) {
+        self.assert_owner();
+        let mut anchor_settings = self.anchor_settings.get().unwrap();
+        anchor_settings.bridge_fee_collector_account = Some(account_id);
+        self.internal_set_anchor_settings(&anchor_settings);
+    }
+    //
+    fn add_witness_account(&mut self, account_id: AccountId) {
+        self.assert_owner();
+        let mut anchor_settings = self.anchor_settings.get().unwrap();
+        assert!(
+            !anchor_settings.witness_accounts.contains(&account_id),
+            "The account is already a witness."
+        );
+        anchor_settings.witness_accounts.push(account_id);
+        self.internal_set_anchor_settings(&anchor_settings);
+    }
+    //
+    fn remove_witness_account(&mut self, account_id: AccountId) {
+        self.assert_owner();
+        let mut anchor_settings = self.anchor_settings.get().unwrap();
+        assert!(
+            anchor_settings.witness_accounts.contains(&account_id),
+            "The account is not a witness."
+        );
+        anchor_settings
+            .witness_accounts
+            .retain(|witness_id| !witness_id.eq(&account_id));
+        assert!(
+            !anchor_settings.beefy_light_client_witness_mode
+                || anchor_settings.witness_accounts.len()
+                    >= usize::from(anchor_settings.witness_threshold),
+            "The count of witnesses should not be less than the threshold in witness mode."
+        );
+        self.internal_set_anchor_settings(&anchor_settings);
+    }
+    //
+    fn set_witness_threshold(&mut self, value: u16) {
+        self.assert_owner();
+        assert!(value > 0, "The value should be greater than 0.");
+        let mut anchor_settings = self.anchor_settings.get().unwrap();
+        assert!(
+            usize::from(value) <= anchor_settings.witness_accounts.len(),
+            "The value should not be greater than the count of witnesses."
+        );
+        anchor_settings.witness_threshold = value;
+        self.internal_set_anchor_settings(&anchor_settings);
+    }
+    //
+    fn remove_pending_message_batch_attestation(&mut self, batch_hash: String) {
+        self.assert_owner();
+        assert!(
+            self.pending_message_batch_hashes.contains(&batch_hash),
+            "No pending attestation of message batch '{}'.",
+            batch_hash
+        );
+        self.internal_remove_message_batch_attestation(&batch_hash);
+    }
+}
+
+impl AppchainAnchor {
+    //
+    fn internal_set_protocol_settings(&mut self, protocol_settings: &ProtocolSettings) {
+        self.protocol_settings.set(protocol_settings);
+        AnchorEvent::ProtocolSettingsChanged {
+            protocol_settings: protocol_settings.clone(),
+        }
+        .emit();
+    }
+    //
+    fn internal_set_appchain_settings(&mut self, appchain_settings: &AppchainSettings) {
+        self.appchain_settings.set(appchain_settings);
+        AnchorEvent::AppchainSettingsChanged {
+            appchain_settings: appchain_settings.clone(),
+        }
+        .emit();
+    }
+    //
+    pub(crate) fn internal_set_anchor_settings(&mut self, anchor_settings: &AnchorSettings) {
+        self.anchor_settings.set(anchor_settings);
+        AnchorEvent::AnchorSettingsChanged {
+            anchor_settings: anchor_settings.clone(),
+        }
+        .emit();
     }
 }
@@ -1,3 +1,4 @@
+use super::validator_actions::parse_grandpa_key;
 use crate::{interfaces::StakingManager, *};
 use borsh::maybestd::collections::HashMap;
 use near_contract_standards::fungible_token::core::ext_ft_core;
@@ -19,6 +20,8 @@ impl AppchainAnchor {
                 validator_id_in_appchain,
                 can_be_delegated_to,
                 profile,
+                commission_percent,
+                grandpa_key_in_appchain,
             } => {
                 self.register_validator(
                     sender_id,
@@ -26,6 +29,8 @@ impl AppchainAnchor {
                     profile,
                     amount,
                     can_be_delegated_to,
+                    commission_percent,
+                    grandpa_key_in_appchain,
                 );
                 PromiseOrValue::Value(0.into())
             }
@@ -54,6 +59,8 @@ impl AppchainAnchor {
         profile: HashMap<String, String>,
         deposit_amount: U128,
         can_be_delegated_to: bool,
+        commission_percent: Option<u16>,
+        grandpa_key_in_appchain: Option<String>,
     ) {
         match self.appchain_state {
             AppchainState::Staging | AppchainState::Active => (),
@@ -93,6 +100,18 @@ impl AppchainAnchor {
             next_validator_set.validator_count() < protocol_settings.maximum_validator_count.0,
             "The count of registered validators exceeds the upper limit."
         );
+        if let Some(commission_percent) = commission_percent {
+            self.assert_validator_commission_percent_is_valid(
+                &protocol_settings,
+                commission_percent,
+            );
+        }
+        // The account id in appchain is used as GRANDPA key if it is not specified.
+        let grandpa_key = parse_grandpa_key(
+            &grandpa_key_in_appchain.unwrap_or(formatted_validator_id_in_appchain.to_string()),
+        )
+        .expect("A valid GRANDPA key in appchain is required.");
+        self.assert_grandpa_key_is_not_used(&next_validator_set, &validator_id, &grandpa_key);
         //
         if self.appchain_state.eq(&AppchainState::Active) {
             let validator_set_histories = self.validator_set_histories.get().unwrap();
@@ -119,6 +138,25 @@ impl AppchainAnchor {
         //
         self.sync_state_to_registry();
         //
+        self.grandpa_keys_of_validators
+            .insert(&validator_id, &grandpa_key);
+        //
+        match commission_percent {
+            Some(commission_percent) => {
+                self.validator_commissions.insert(
+                    &validator_id,
+                    &ValidatorCommission {
+                        commission_percent,
+                        pending_commission_percent: None,
+                        effective_era_number: U64::from(0),
+                    },
+                );
+            }
+            None => {
+                self.validator_commissions.remove(&validator_id);
+            }
+        }
+        //
         validator_profiles.insert(ValidatorProfile {
             validator_id,
             validator_id_in_appchain: formatted_validator_id_in_appchain.to_string(),
@@ -250,6 +288,8 @@ impl AppchainAnchor {
     }
     //
     pub fn record_staking_fact(&mut self, staking_fact: StakingFact) -> StakingHistory {
+        //
+        self.update_delegator_reward_snapshots(&staking_fact);
         //
         let mut staking_histories = self.staking_histories.get().unwrap();
         let staking_history = staking_histories.append(&mut StakingHistory {
@@ -264,8 +304,28 @@ impl AppchainAnchor {
         user_staking_histories.add_staking_history(&staking_history);
         self.user_staking_histories.set(&user_staking_histories);
         //
+        AnchorEvent::StakingHistoryRecorded {
+            staking_history: staking_history.clone(),
+        }
+        .emit();
         staking_history
     }
+    /// Get the number of the era in which the staking facts recorded now take effect.
+    pub fn get_effective_era_number_of_staking_facts(&self) -> u64 {
+        let validator_set_histories = self.validator_set_histories.get().unwrap();
+        let latest_era_number = validator_set_histories.index_range().end_index.0;
+        match validator_set_histories.get(&latest_era_number) {
+            // The staking histories applied to the validator set are determined
+            // after the validators are unbonded.
+            Some(validator_set) => match validator_set.processing_status() {
+                ValidatorSetProcessingStatus::CopyingFromLastEra { .. }
+                | ValidatorSetProcessingStatus::UnbondingValidator { .. }
+                | ValidatorSetProcessingStatus::AutoUnbondingValidator { .. } => latest_era_number,
+                _ => latest_era_number + 1,
+            },
+            None => latest_era_number,
+        }
+    }
     //
     fn increase_delegation(
         &mut self,
@@ -484,12 +544,244 @@ impl StakingManager for AppchainAnchor {
         self.sync_state_to_registry();
     }
     //
+    fn redelegate(
+        &mut self,
+        from_validator_id: AccountId,
+        to_validator_id: AccountId,
+        amount: U128,
+    ) {
+        match self.appchain_state {
+            AppchainState::Staging | AppchainState::Active => (),
+            _ => panic!(
+                "Cannot redelegate while appchain state is '{}'.",
+                serde_json::to_string(&self.appchain_state).unwrap()
+            ),
+        };
+        assert!(
+            !from_validator_id.eq(&to_validator_id),
+            "Cannot redelegate to the same validator."
+        );
+        assert!(amount.0 > 0, "The amount to redelegate should not be 0.");
+        let mut next_validator_set = self.next_validator_set.get().unwrap();
+        let delegator_id = env::predecessor_account_id();
+        self.assert_delegator_id(&delegator_id, &from_validator_id, &next_validator_set);
+        self.assert_validator_id(&to_validator_id, &next_validator_set);
+        let era_number = self.get_effective_era_number_of_staking_facts();
+        if let Some(redelegation_era) = self.redelegation_eras.get(&delegator_id) {
+            assert!(
+                redelegation_era < era_number,
+                "The delegation has already been redelegated in this era."
+            );
+        }
+        assert!(
+            !self.unslashed_offences.contains_key(&from_validator_id),
+            "Validator '{}' has offences which are not slashed yet.",
+            &from_validator_id
+        );
+        let protocol_settings = self.protocol_settings.get().unwrap();
+        let delegator = next_validator_set
+            .get_delegator(&delegator_id, &from_validator_id)
+            .unwrap();
+        assert!(
+            delegator.deposit_amount == amount.0
+                || delegator.deposit_amount
+                    >= protocol_settings.minimum_delegator_deposit.0 + amount.0,
+            "Unable to redelegate so much stake."
+        );
+        let to_validator = next_validator_set.get_validator(&to_validator_id).unwrap();
+        assert!(
+            to_validator.can_be_delegated_to,
+            "Validator '{}' cannot be delegated to.",
+            &to_validator_id
+        );
+        self.assert_validator_is_not_unbonding(&to_validator_id, &next_validator_set);
+        if !next_validator_set.contains_delegator(&delegator_id, &to_validator_id) {
+            assert!(
+                amount.0 >= protocol_settings.minimum_delegator_deposit.0,
+                "The amount for delegating to a new validator is too few."
+            );
+            let validator_count = match delegator.deposit_amount == amount.0 {
+                true => next_validator_set.get_validator_count_of(&delegator_id) - 1,
+                false => next_validator_set.get_validator_count_of(&delegator_id),
+            };
+            assert!(
+                validator_count < protocol_settings.maximum_validators_per_delegator.0,
+                "Too many validators delegated."
+            );
+        }
+        self.assert_validator_stake_is_valid(
+            to_validator.deposit_amount,
+            Some(to_validator.total_stake + amount.0),
+        );
+        let staking_history = self.record_staking_fact(StakingFact::DelegationRedelegated {
+            delegator_id: delegator_id.clone(),
+            from_validator_id: from_validator_id.clone(),
+            to_validator_id,
+            amount,
+        });
+        //
+        next_validator_set.apply_staking_fact(&staking_history.staking_fact);
+        self.next_validator_set.set(&next_validator_set);
+        self.redelegation_eras.insert(&delegator_id, &era_number);
+        //
+        self.sync_state_to_registry();
+    }
+    //
+    fn rebond(&mut self, staking_history_index: U64, amount: Option<U128>) {
+        match self.appchain_state {
+            AppchainState::Staging | AppchainState::Active => (),
+            _ => panic!(
+                "Cannot rebond while appchain state is '{}'.",
+                serde_json::to_string(&self.appchain_state).unwrap()
+            ),
+        };
+        let account_id = env::predecessor_account_id();
+        let mut unbonded_stake_references =
+            self.unbonded_stakes.get(&account_id).unwrap_or_default();
+        let reference_index = unbonded_stake_references
+            .iter()
+            .position(|reference| reference.staking_history_index == staking_history_index.0)
+            .expect("The unbonded stake is not existed.");
+        let staking_history = self
+            .staking_histories
+            .get()
+            .unwrap()
+            .get(&staking_history_index.0)
+            .unwrap();
+        let rebonded_amount = self.get_rebonded_amount_of(staking_history_index.0);
+        let slashed_amount = self.get_slashed_amount_of_unbonded_stake(staking_history_index.0);
+        let mut next_validator_set = self.next_validator_set.get().unwrap();
+        let protocol_settings = self.protocol_settings.get().unwrap();
+        let (staking_fact, unbonded_amount) = match staking_history.staking_fact {
+            StakingFact::ValidatorUnbonded { .. } | StakingFact::ValidatorAutoUnbonded { .. } => {
+                panic!(
+                    "The stake of an unbonded validator cannot be rebonded. Please register as a validator again."
+                )
+            }
+            StakingFact::StakeDecreased {
+                validator_id,
+                amount: unbonded_amount,
+            } => {
+                let amount = amount.unwrap_or(U128::from(
+                    unbonded_amount.0 - rebonded_amount - slashed_amount,
+                ));
+                self.assert_validator_id(&validator_id, &next_validator_set);
+                self.assert_validator_is_not_unbonding(&validator_id, &next_validator_set);
+                let validator = next_validator_set.get_validator(&validator_id).unwrap();
+                self.assert_validator_stake_is_valid(
+                    validator.deposit_amount + amount.0,
+                    Some(validator.total_stake + amount.0),
+                );
+                (
+                    StakingFact::StakeRebonded {
+                        validator_id,
+                        amount,
+                        unbonded_staking_history_index: staking_history_index,
+                    },
+                    unbonded_amount.0,
+                )
+            }
+            StakingFact::DelegationDecreased {
+                delegator_id,
+                validator_id,
+                amount: unbonded_amount,
+            }
+            | StakingFact::DelegatorUnbonded {
+                delegator_id,
+                validator_id,
+                amount: unbonded_amount,
+            }
+            | StakingFact::DelegatorAutoUnbonded {
+                delegator_id,
+                validator_id,
+                amount: unbonded_amount,
+            } => {
+                let amount = amount.unwrap_or(U128::from(
+                    unbonded_amount.0 - rebonded_amount - slashed_amount,
+                ));
+                self.assert_validator_id(&validator_id, &next_validator_set);
+                self.assert_validator_is_not_unbonding(&validator_id, &next_validator_set);
+                let validator = next_validator_set.get_validator(&validator_id).unwrap();
+                if !next_validator_set.contains_delegator(&delegator_id, &validator_id) {
+                    assert!(
+                        validator.can_be_delegated_to,
+                        "Validator '{}' cannot be delegated to.",
+                        &validator_id
+                    );
+                    assert!(
+                        amount.0 >= protocol_settings.minimum_delegator_deposit.0,
+                        "The amount for delegating to a new validator is too few."
+                    );
+                    assert!(
+                        next_validator_set.get_validator_count_of(&delegator_id)
+                            < protocol_settings.maximum_validators_per_delegator.0,
+                        "Too many validators delegated."
+                    );
+                }
+                self.assert_validator_stake_is_valid(
+                    validator.deposit_amount,
+                    Some(validator.total_stake + amount.0),
+                );
+                (
+                    StakingFact::DelegationRebonded {
+                        delegator_id,
+                        validator_id,
+                        amount,
+                        unbonded_staking_history_index: staking_history_index,
+                    },
+                    unbonded_amount.0,
+                )
+            }
+            _ => panic!("The staking history is not an unbonded stake."),
+        };
+        let amount = match &staking_fact {
+            StakingFact::StakeRebonded { amount, .. }
+            | StakingFact::DelegationRebonded { amount, .. } => amount.0,
+            _ => 0,
+        };
+        assert!(amount > 0, "The amount to rebond should not be 0.");
+        assert!(
+            rebonded_amount + slashed_amount + amount <= unbonded_amount,
+            "Unable to rebond so much stake."
+        );
+        let staking_history = self.record_staking_fact(staking_fact);
+        //
+        next_validator_set.apply_staking_fact(&staking_history.staking_fact);
+        self.next_validator_set.set(&next_validator_set);
+        //
+        if rebonded_amount + slashed_amount + amount < unbonded_amount {
+            self.rebonded_stake_amounts
+                .insert(&staking_history_index.0, &(rebonded_amount + amount));
+        } else {
+            self.rebonded_stake_amounts.remove(&staking_history_index.0);
+            self.slashed_unbonded_stake_amounts
+                .remove(&staking_history_index.0);
+            unbonded_stake_references.remove(reference_index);
+            if unbonded_stake_references.len() > 0 {
+                self.unbonded_stakes
+                    .insert(&account_id, &unbonded_stake_references);
+            } else {
+                self.unbonded_stakes.remove(&account_id);
+            }
+        }
+        //
+        self.sync_state_to_registry();
+    }
+    //
     fn withdraw_stake(&mut self, account_id: AccountId) {
         self.assert_asset_transfer_is_not_paused();
+        self.assert_appchain_is_not_frozen();
         let protocol_settings = self.protocol_settings.get().unwrap();
         let mut balance_to_withdraw: u128 = 0;
         let mut remained_stakes = Vec::<UnbondedStakeReference>::new();
+        let mut partially_reduced_stake_indexes = Vec::<u64>::new();
         if let Some(unbonded_stake_references) = self.unbonded_stakes.get(&account_id) {
+            // All unbonded stakes can be withdrawn immediately after the appchain is dead
+            // or stalled.
+            let unlock_period_is_skipped = self.appchain_state.eq(&AppchainState::Dead)
+                || self
+                    .appchain_liveness_status
+                    .eq(&AppchainLivenessStatus::Stalled);
             unbonded_stake_references.iter().for_each(|reference| {
                 let validator_set = self
                     .validator_set_histories
@@ -503,6 +795,14 @@ impl StakingManager for AppchainAnchor {
                     .unwrap()
                     .get(&reference.staking_history_index)
                     .unwrap();
+                let reduced_amount = self.get_rebonded_amount_of(reference.staking_history_index)
+                    + self.get_slashed_amount_of_unbonded_stake(reference.staking_history_index);
+                let unlock_period_is_ended = |unlock_period: u64| {
+                    unlock_period_is_skipped
+                        || validator_set.start_timestamp()
+                            + unlock_period * SECONDS_OF_A_DAY * NANO_SECONDS_MULTIPLE
+                            < env::block_timestamp()
+                };
                 match staking_history.staking_fact {
                     StakingFact::StakeDecreased {
                         validator_id: _,
@@ -516,13 +816,14 @@ impl StakingManager for AppchainAnchor {
                         validator_id: _,
                         amount,
                     } => {
-                        if validator_set.start_timestamp()
-                            + protocol_settings.unlock_period_of_validator_deposit.0
-                                * SECONDS_OF_A_DAY
-                                * NANO_SECONDS_MULTIPLE
-                            < env::block_timestamp()
-                        {
-                            balance_to_withdraw += amount.0;
+                        if unlock_period_is_ended(
+                            protocol_settings.unlock_period_of_validator_deposit.0,
+                        ) {
+                            balance_to_withdraw += amount.0 - reduced_amount;
+                            if reduced_amount > 0 {
+                                partially_reduced_stake_indexes
+                                    .push(reference.staking_history_index);
+                            }
                         } else {
                             remained_stakes.push(reference.clone());
                         }
@@ -542,13 +843,14 @@ impl StakingManager for AppchainAnchor {
                         validator_id: _,
                         amount,
                     } => {
-                        if validator_set.start_timestamp()
-                            + protocol_settings.unlock_period_of_delegator_deposit.0
-                                * SECONDS_OF_A_DAY
-                                * NANO_SECONDS_MULTIPLE
-                            < env::block_timestamp()
-                        {
-                            balance_to_withdraw += amount.0;
+                        if unlock_period_is_ended(
+                            protocol_settings.unlock_period_of_delegator_deposit.0,
+                        ) {
+                            balance_to_withdraw += amount.0 - reduced_amount;
+                            if reduced_amount > 0 {
+                                partially_reduced_stake_indexes
+                                    .push(reference.staking_history_index);
+                            }
                         } else {
                             remained_stakes.push(reference.clone());
                         }
@@ -561,6 +863,10 @@ impl StakingManager for AppchainAnchor {
             } else {
                 self.unbonded_stakes.remove(&account_id);
             }
+            partially_reduced_stake_indexes.iter().for_each(|index| {
+                self.rebonded_stake_amounts.remove(index);
+                self.slashed_unbonded_stake_amounts.remove(index);
+            });
             if balance_to_withdraw > 0 {
                 ext_ft_core::ext(self.oct_token.get().unwrap().contract_account)
                     .with_attached_deposit(1)
@@ -573,6 +879,7 @@ impl StakingManager for AppchainAnchor {
     //
     fn withdraw_validator_rewards(&mut self, validator_id: AccountId) {
         self.assert_asset_transfer_is_not_paused();
+        self.assert_appchain_is_not_frozen();
         self.assert_rewards_withdrawal_is_not_paused();
         self.assert_contract_account_of_wrapped_appchain_token_is_set();
         let end_era = self
@@ -616,36 +923,15 @@ impl StakingManager for AppchainAnchor {
     //
     fn withdraw_delegator_rewards(&mut self, delegator_id: AccountId, validator_id: AccountId) {
         self.assert_asset_transfer_is_not_paused();
+        self.assert_appchain_is_not_frozen();
         self.assert_rewards_withdrawal_is_not_paused();
         self.assert_contract_account_of_wrapped_appchain_token_is_set();
-        let end_era = self
-            .validator_set_histories
-            .get()
-            .unwrap()
-            .index_range()
-            .end_index
-            .0;
-        let protocol_settings = self.protocol_settings.get().unwrap();
-        let start_era = match end_era > protocol_settings.maximum_era_count_of_unwithdrawn_reward.0
-        {
-            true => end_era - protocol_settings.maximum_era_count_of_unwithdrawn_reward.0 + 1,
-            false => 0,
-        };
-        let mut reward_to_withdraw: u128 = 0;
-        for era_number in start_era..end_era {
-            if let Some(reward) = self.unwithdrawn_delegator_rewards.get(&(
-                era_number,
-                delegator_id.clone(),
-                validator_id.clone(),
-            )) {
-                reward_to_withdraw += reward;
-                self.unwithdrawn_delegator_rewards.remove(&(
-                    era_number,
-                    delegator_id.clone(),
-                    validator_id.clone(),
-                ));
-            }
-        }
+        let mut snapshot = self.get_settled_delegator_reward_snapshot(&delegator_id, &validator_id);
+        let reward_to_withdraw = snapshot.unwithdrawn_reward;
+        snapshot.unwithdrawn_reward = 0;
+        snapshot.withdrawn_index = snapshot.settled_index;
+        self.delegator_reward_snapshots
+            .insert(&(delegator_id.clone(), validator_id.clone()), &snapshot);
         if reward_to_withdraw > 0 {
             ext_ft_core::ext(
                 self.wrapped_appchain_token
@@ -682,6 +968,35 @@ impl AppchainAnchor {
         );
     }
     //
+    fn assert_validator_is_not_unbonding(
+        &self,
+        validator_id: &AccountId,
+        next_validator_set: &NextValidatorSet,
+    ) {
+        assert!(
+            !next_validator_set
+                .get_unbonding_validator_ids()
+                .contains(validator_id)
+                && !next_validator_set
+                    .get_auto_unbonding_validator_ids()
+                    .contains(validator_id),
+            "Validator '{}' is unbonding.",
+            validator_id
+        );
+    }
+    ///
+    pub fn get_rebonded_amount_of(&self, staking_history_index: u64) -> u128 {
+        self.rebonded_stake_amounts
+            .get(&staking_history_index)
+            .unwrap_or(0)
+    }
+    //
+    pub fn get_slashed_amount_of_unbonded_stake(&self, staking_history_index: u64) -> u128 {
+        self.slashed_unbonded_stake_amounts
+            .get(&staking_history_index)
+            .unwrap_or(0)
+    }
+    //
     pub fn record_unbonding_validator(
         &self,
         protocol_settings: &ProtocolSettings,
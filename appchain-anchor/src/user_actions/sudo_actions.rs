@@ -52,6 +52,17 @@ impl SudoActions for AppchainAnchor {
         self.assert_owner();
         self.beefy_light_client_state
             .set(&beefy_light_client::new(initial_public_keys));
+        self.previous_beefy_light_client_state.remove();
+        let mut retained_beefy_commitments = self.retained_beefy_commitments.get().unwrap();
+        retained_beefy_commitments.clear();
+        self.retained_beefy_commitments
+            .set(&retained_beefy_commitments);
+    }
+    //
+    fn reset_grandpa_light_client(&mut self, initial_public_keys: Vec<String>, set_id: U64) {
+        self.assert_owner();
+        self.grandpa_light_client_state
+            .set(&GrandpaLightClient::new(initial_public_keys, set_id.0));
     }
     //
     fn pause_asset_transfer(&mut self) {
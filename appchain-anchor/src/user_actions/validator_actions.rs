@@ -23,9 +23,191 @@ impl ValidatorActions for AppchainAnchor {
         validator_profiles.insert(validator_profile);
         self.validator_profiles.set(&validator_profiles);
     }
+    //
+    fn change_validator_commission(&mut self, commission_percent: u16) {
+        match self.appchain_state {
+            AppchainState::Staging | AppchainState::Active => (),
+            _ => panic!(
+                "Cannot change validator commission while appchain state is '{}'.",
+                serde_json::to_string(&self.appchain_state).unwrap()
+            ),
+        };
+        let validator_id = env::predecessor_account_id();
+        let next_validator_set = self.next_validator_set.get().unwrap();
+        self.assert_validator_id(&validator_id, &next_validator_set);
+        let protocol_settings = self.protocol_settings.get().unwrap();
+        self.assert_validator_commission_percent_is_valid(&protocol_settings, commission_percent);
+        // The changes are counted from the era in which the staking facts
+        // recorded now take effect.
+        let next_era_number = self.get_effective_era_number_of_staking_facts();
+        let mut validator_commission = match self.validator_commissions.get(&validator_id) {
+            Some(validator_commission) => validator_commission,
+            None => ValidatorCommission {
+                commission_percent: self
+                    .get_default_validator_commission_percent_of_era(next_era_number),
+                pending_commission_percent: None,
+                effective_era_number: U64::from(0),
+            },
+        };
+        if let Some(pending_commission_percent) = validator_commission.pending_commission_percent {
+            // Keep the pending change until the reward of the era before it
+            // has been distributed.
+            assert!(
+                next_era_number > validator_commission.effective_era_number.0 + 1,
+                "The last change of commission has not taken effect yet. Please try again in era {}.",
+                validator_commission.effective_era_number.0 + 1
+            );
+            validator_commission.commission_percent = pending_commission_percent;
+            validator_commission.pending_commission_percent = None;
+        }
+        assert!(
+            commission_percent != validator_commission.commission_percent,
+            "The value is not changed."
+        );
+        let change = match commission_percent > validator_commission.commission_percent {
+            true => commission_percent - validator_commission.commission_percent,
+            false => validator_commission.commission_percent - commission_percent,
+        };
+        assert!(
+            change <= protocol_settings.maximum_validator_commission_change_per_era,
+            "The change of commission should not be greater than {} percent.",
+            protocol_settings.maximum_validator_commission_change_per_era
+        );
+        let effective_era_number = match self.appchain_state {
+            AppchainState::Staging => {
+                // No reward is distributed before the appchain is booted,
+                // so the change takes effect immediately.
+                validator_commission.commission_percent = commission_percent;
+                0
+            }
+            _ => {
+                validator_commission.pending_commission_percent = Some(commission_percent);
+                match commission_percent > validator_commission.commission_percent {
+                    true => {
+                        next_era_number
+                            + protocol_settings
+                                .validator_commission_change_notice_period
+                                .0
+                    }
+                    false => next_era_number,
+                }
+            }
+        };
+        validator_commission.effective_era_number = U64::from(effective_era_number);
+        self.validator_commissions
+            .insert(&validator_id, &validator_commission);
+        self.record_staking_fact(StakingFact::ValidatorCommissionChanged {
+            validator_id,
+            commission_percent,
+            effective_era_number: U64::from(effective_era_number),
+        });
+    }
+    //
+    fn set_grandpa_key_in_appchain(&mut self, grandpa_key: String) {
+        let validator_id = env::predecessor_account_id();
+        let next_validator_set = self.next_validator_set.get().unwrap();
+        self.assert_validator_id(&validator_id, &next_validator_set);
+        let grandpa_key = parse_grandpa_key(&grandpa_key).expect("Invalid GRANDPA key.");
+        self.assert_grandpa_key_is_not_used(&next_validator_set, &validator_id, &grandpa_key);
+        self.grandpa_keys_of_validators
+            .insert(&validator_id, &grandpa_key);
+    }
 }
 
 impl AppchainAnchor {
+    ///
+    pub fn assert_validator_commission_percent_is_valid(
+        &self,
+        protocol_settings: &ProtocolSettings,
+        commission_percent: u16,
+    ) {
+        assert!(
+            commission_percent >= protocol_settings.minimum_validator_commission_percent
+                && commission_percent <= protocol_settings.maximum_validator_commission_percent,
+            "The commission percent should be between {} and {}.",
+            protocol_settings.minimum_validator_commission_percent,
+            protocol_settings.maximum_validator_commission_percent
+        );
+    }
+    /// Get the commission percent of a validator which is in effect in a certain era.
+    pub fn get_validator_commission_percent_of_era(
+        &self,
+        validator_id: &AccountId,
+        era_number: u64,
+    ) -> u16 {
+        match self.validator_commissions.get(validator_id) {
+            Some(ValidatorCommission {
+                pending_commission_percent: Some(pending_commission_percent),
+                effective_era_number,
+                ..
+            }) if era_number >= effective_era_number.0 => pending_commission_percent,
+            Some(validator_commission) => validator_commission.commission_percent,
+            None => self.get_default_validator_commission_percent_of_era(era_number),
+        }
+    }
+    /// Get the commission percent of a validator in the validator set of an era,
+    /// which is recorded when the set is generated.
+    pub fn get_validator_commission_percent_in_validator_set(
+        &self,
+        validator_set: &ValidatorSetOfEra,
+        validator_id: &AccountId,
+    ) -> u16 {
+        validator_set
+            .get_validator_commission_percent(validator_id)
+            .unwrap_or_else(|| {
+                self.get_validator_commission_percent_of_era(
+                    validator_id,
+                    validator_set.era_number(),
+                )
+            })
+    }
+    /// Get the GRANDPA key of a validator in appchain. If the key is not recorded,
+    /// the account id of the validator in appchain is used.
+    pub fn get_grandpa_key_of_validator(&self, validator_id: &AccountId) -> Option<String> {
+        self.grandpa_keys_of_validators
+            .get(validator_id)
+            .or_else(|| {
+                self.validator_profiles
+                    .get()
+                    .unwrap()
+                    .get(validator_id)
+                    .and_then(|profile| parse_grandpa_key(&profile.validator_id_in_appchain))
+            })
+    }
+    //
+    pub fn assert_grandpa_key_is_not_used(
+        &self,
+        next_validator_set: &NextValidatorSet,
+        validator_id: &AccountId,
+        grandpa_key: &String,
+    ) {
+        assert!(
+            !next_validator_set
+                .get_validator_ids()
+                .iter()
+                .any(|id| !id.eq(validator_id)
+                    && self
+                        .get_grandpa_key_of_validator(id)
+                        .map_or(false, |key| key.eq(grandpa_key))),
+            "The GRANDPA key is already used by another validator."
+        );
+    }
+    /// Get the default commission percent which is in effect in a certain era,
+    /// for the validators which have not set their own commission.
+    pub fn get_default_validator_commission_percent_of_era(&self, era_number: u64) -> u16 {
+        let protocol_settings = self.protocol_settings.get().unwrap();
+        match protocol_settings.pending_validator_commission_percent {
+            Some(pending_commission_percent)
+                if era_number
+                    >= protocol_settings
+                        .validator_commission_percent_effective_era_number
+                        .0 =>
+            {
+                pending_commission_percent
+            }
+            _ => protocol_settings.validator_commission_percent,
+        }
+    }
     ///
     pub fn internal_change_account_id_in_appchain_of_validator(
         &mut self,
@@ -57,3 +239,12 @@ impl AppchainAnchor {
         self.sync_state_to_registry();
     }
 }
+
+/// Parse the given GRANDPA key (ed25519 public key in hex) to the format
+/// recorded in this contract.
+pub fn parse_grandpa_key(grandpa_key: &String) -> Option<String> {
+    match hex::decode(grandpa_key.trim_start_matches("0x")) {
+        Ok(key_bytes) if key_bytes.len() == 32 => Some(format!("0x{}", hex::encode(key_bytes))),
+        _ => None,
+    }
+}
@@ -27,12 +27,18 @@ impl UserStakingHistories {
             | StakingFact::ValidatorAutoUnbonded { validator_id, .. }
             | StakingFact::ValidatorDelegationEnabled { validator_id }
             | StakingFact::ValidatorDelegationDisabled { validator_id }
-            | StakingFact::ValidatorIdInAppchainChanged { validator_id, .. } => validator_id,
+            | StakingFact::ValidatorIdInAppchainChanged { validator_id, .. }
+            | StakingFact::ValidatorSlashed { validator_id, .. }
+            | StakingFact::ValidatorCommissionChanged { validator_id, .. }
+            | StakingFact::StakeRebonded { validator_id, .. } => validator_id,
             StakingFact::DelegatorRegistered { delegator_id, .. }
             | StakingFact::DelegationIncreased { delegator_id, .. }
             | StakingFact::DelegationDecreased { delegator_id, .. }
             | StakingFact::DelegatorUnbonded { delegator_id, .. }
-            | StakingFact::DelegatorAutoUnbonded { delegator_id, .. } => delegator_id,
+            | StakingFact::DelegatorAutoUnbonded { delegator_id, .. }
+            | StakingFact::DelegatorSlashed { delegator_id, .. }
+            | StakingFact::DelegationRedelegated { delegator_id, .. }
+            | StakingFact::DelegationRebonded { delegator_id, .. } => delegator_id,
         };
         self.account_id_set.insert(account_id);
         let mut staking_histories_indexes = match self.staking_histories_map.get(account_id) {
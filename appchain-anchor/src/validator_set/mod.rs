@@ -183,6 +183,10 @@ impl ValidatorSet {
             StakingFact::StakeDecreased {
                 validator_id,
                 amount,
+            }
+            | StakingFact::ValidatorSlashed {
+                validator_id,
+                amount,
             } => {
                 let mut validator = self.validators.get(validator_id).unwrap();
                 validator.deposit_amount -= amount.0;
@@ -304,6 +308,11 @@ impl ValidatorSet {
                 delegator_id,
                 validator_id,
                 amount,
+            }
+            | StakingFact::DelegatorSlashed {
+                delegator_id,
+                validator_id,
+                amount,
             } => {
                 let mut delegator = self
                     .delegators
@@ -366,6 +375,72 @@ impl ValidatorSet {
                 validator.validator_id_in_appchain = validator_id_in_appchain.to_string();
                 self.validators.insert(validator_id, &validator);
             }
+            StakingFact::ValidatorCommissionChanged { .. } => (),
+            StakingFact::StakeRebonded {
+                validator_id,
+                amount,
+                ..
+            } => self.apply_staking_fact(&StakingFact::StakeIncreased {
+                validator_id: validator_id.clone(),
+                amount: amount.clone(),
+            }),
+            StakingFact::DelegationRebonded {
+                delegator_id,
+                validator_id,
+                amount,
+                ..
+            } => {
+                if self.contains_delegator(delegator_id, validator_id) {
+                    self.apply_staking_fact(&StakingFact::DelegationIncreased {
+                        delegator_id: delegator_id.clone(),
+                        validator_id: validator_id.clone(),
+                        amount: amount.clone(),
+                    });
+                } else {
+                    self.apply_staking_fact(&StakingFact::DelegatorRegistered {
+                        delegator_id: delegator_id.clone(),
+                        validator_id: validator_id.clone(),
+                        amount: amount.clone(),
+                    });
+                }
+            }
+            StakingFact::DelegationRedelegated {
+                delegator_id,
+                from_validator_id,
+                to_validator_id,
+                amount,
+            } => {
+                let delegator = self
+                    .delegators
+                    .get(&(delegator_id.clone(), from_validator_id.clone()))
+                    .unwrap();
+                if delegator.deposit_amount > amount.0 {
+                    self.apply_staking_fact(&StakingFact::DelegationDecreased {
+                        delegator_id: delegator_id.clone(),
+                        validator_id: from_validator_id.clone(),
+                        amount: amount.clone(),
+                    });
+                } else {
+                    self.apply_staking_fact(&StakingFact::DelegatorUnbonded {
+                        delegator_id: delegator_id.clone(),
+                        validator_id: from_validator_id.clone(),
+                        amount: amount.clone(),
+                    });
+                }
+                if self.contains_delegator(delegator_id, to_validator_id) {
+                    self.apply_staking_fact(&StakingFact::DelegationIncreased {
+                        delegator_id: delegator_id.clone(),
+                        validator_id: to_validator_id.clone(),
+                        amount: amount.clone(),
+                    });
+                } else {
+                    self.apply_staking_fact(&StakingFact::DelegatorRegistered {
+                        delegator_id: delegator_id.clone(),
+                        validator_id: to_validator_id.clone(),
+                        amount: amount.clone(),
+                    });
+                }
+            }
         }
     }
 }
@@ -465,7 +540,12 @@ impl ValidatorSetViewer for ValidatorSet {
 
 impl AppchainValidator {
     ///
-    pub fn from_validator(validator: Validator, delegators_count: u64, is_unbonding: bool) -> Self {
+    pub fn from_validator(
+        validator: Validator,
+        delegators_count: u64,
+        is_unbonding: bool,
+        commission_percent: u16,
+    ) -> Self {
         Self {
             validator_id: validator.validator_id,
             validator_id_in_appchain: validator.validator_id_in_appchain,
@@ -474,6 +554,7 @@ impl AppchainValidator {
             delegators_count: U64::from(delegators_count),
             can_be_delegated_to: validator.can_be_delegated_to,
             is_unbonding,
+            commission_percent,
         }
     }
 }
@@ -75,7 +75,10 @@ impl NextValidatorSet {
         self.auto_unbonding_validator_ids.clear();
     }
     ///
-    pub fn get_validator_list(&self) -> Vec<AppchainValidator> {
+    pub fn get_validator_list<F>(&self, commission_percent_of: F) -> Vec<AppchainValidator>
+    where
+        F: Fn(&AccountId) -> u16,
+    {
         self.validator_set
             .get_validator_ids()
             .iter()
@@ -85,6 +88,7 @@ impl NextValidatorSet {
                     self.validator_set.get_delegator_count_of(validator_id),
                     self.unbonding_validator_ids.contains(validator_id)
                         || self.auto_unbonding_validator_ids.contains(validator_id),
+                    commission_percent_of(validator_id),
                 )
             })
             .collect()
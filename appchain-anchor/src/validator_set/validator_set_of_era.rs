@@ -1,6 +1,8 @@
 use near_sdk::BlockHeight;
 
+use crate::storage_migration::OldValidatorSetOfEra;
 use crate::*;
+use core::convert::TryFrom;
 
 use super::{Delegator, Validator, ValidatorSet};
 
@@ -20,15 +22,48 @@ pub struct ValidatorSetOfEra {
     valid_total_stake: Balance,
     /// The rewards of validators in this era
     validator_rewards: LookupMap<AccountId, Balance>,
-    /// The rewards of delegators in this era
+    /// The rewards of delegators in this era.
+    /// Only used by the eras distributed before the cumulative reward per stake is applied.
     delegator_rewards: LookupMap<(AccountId, AccountId), Balance>,
     /// The status of creation of this set
     processing_status: ValidatorSetProcessingStatus,
+    /// The era number of the checkpoint which this set is based on.
+    /// A checkpoint stores all delegators, while the set of an era after the checkpoint
+    /// only stores the delegators changed in the era. All validators are stored in every set.
+    checkpoint_era_number: u64,
+    /// The delegators removed in this era, in form of `(delegator_id, validator_id)`.
+    /// Only used by the set which is not a checkpoint.
+    removed_delegators: UnorderedSet<(AccountId, AccountId)>,
+    /// The count of delegators of the validators whose delegators are changed in this era.
+    /// Only used by the set which is not a checkpoint.
+    delegator_counts: LookupMap<AccountId, u64>,
+    /// The count of validators of the delegators whose delegations are changed in this era.
+    /// Only used by the set which is not a checkpoint.
+    validator_counts: LookupMap<AccountId, u64>,
+    /// The commission percent of each validator in effect in this era,
+    /// recorded when the set is generated.
+    validator_commissions: LookupMap<AccountId, u16>,
+    /// The GRANDPA key (in hex format) of each validator in this era,
+    /// recorded when the set is generated.
+    grandpa_keys: LookupMap<AccountId, String>,
+}
+
+/// The index of ids related to an id (the delegator ids of a validator, or the validator ids
+/// of a delegator) which appear in the sets after a checkpoint, shared by the sets of
+/// the checkpoint interval. The ids in the checkpoint itself are not included.
+/// An id is never removed from the index until the sets of the interval are cleared.
+struct IdIndexOfInterval {
+    /// The related ids, mapped by `(id, position)`.
+    related_ids: LookupMap<(AccountId, u64), AccountId>,
+    /// The positions of related ids, mapped by `(id, related_id)`.
+    positions: LookupMap<(AccountId, AccountId), u64>,
+    /// The count of related ids of an id.
+    lengths: LookupMap<AccountId, u64>,
 }
 
 impl ValidatorSetOfEra {
     ///
-    pub fn new(era_number: u64, staking_history_index: u64) -> Self {
+    pub fn new(era_number: u64, staking_history_index: u64, checkpoint_era_number: u64) -> Self {
         Self {
             start_block_height: env::block_height(),
             start_timestamp: env::block_timestamp(),
@@ -48,9 +83,60 @@ impl ValidatorSetOfEra {
                 copying_validator_index: U64::from(0),
                 copying_delegator_index: U64::from(0),
             },
+            checkpoint_era_number,
+            removed_delegators: UnorderedSet::new(
+                StorageKey::RemovedDelegatorsOfEra(era_number).into_bytes(),
+            ),
+            delegator_counts: LookupMap::new(
+                StorageKey::DelegatorCountsOfEra(era_number).into_bytes(),
+            ),
+            validator_counts: LookupMap::new(
+                StorageKey::ValidatorCountsOfEra(era_number).into_bytes(),
+            ),
+            validator_commissions: LookupMap::new(
+                StorageKey::ValidatorCommissionsOfEra(era_number).into_bytes(),
+            ),
+            grandpa_keys: LookupMap::new(StorageKey::GrandpaKeysOfEra(era_number).into_bytes()),
         }
     }
     ///
+    pub fn from_old_version(old_version: OldValidatorSetOfEra) -> Self {
+        let era_number = old_version.validator_set.era_number();
+        Self {
+            validator_set: old_version.validator_set,
+            start_block_height: old_version.start_block_height,
+            start_timestamp: old_version.start_timestamp,
+            staking_history_index: old_version.staking_history_index,
+            unprofitable_validator_id_set: old_version.unprofitable_validator_id_set,
+            valid_total_stake: old_version.valid_total_stake,
+            validator_rewards: old_version.validator_rewards,
+            delegator_rewards: old_version.delegator_rewards,
+            processing_status: old_version.processing_status,
+            checkpoint_era_number: era_number,
+            removed_delegators: UnorderedSet::new(
+                StorageKey::RemovedDelegatorsOfEra(era_number).into_bytes(),
+            ),
+            delegator_counts: LookupMap::new(
+                StorageKey::DelegatorCountsOfEra(era_number).into_bytes(),
+            ),
+            validator_counts: LookupMap::new(
+                StorageKey::ValidatorCountsOfEra(era_number).into_bytes(),
+            ),
+            validator_commissions: LookupMap::new(
+                StorageKey::ValidatorCommissionsOfEra(era_number).into_bytes(),
+            ),
+            grandpa_keys: LookupMap::new(StorageKey::GrandpaKeysOfEra(era_number).into_bytes()),
+        }
+    }
+    ///
+    pub fn checkpoint_era_number(&self) -> u64 {
+        self.checkpoint_era_number
+    }
+    ///
+    pub fn is_checkpoint(&self) -> bool {
+        self.checkpoint_era_number == self.validator_set.era_number()
+    }
+    ///
     pub fn start_timestamp(&self) -> Timestamp {
         self.start_timestamp
     }
@@ -63,6 +149,42 @@ impl ValidatorSetOfEra {
         self.validator_rewards.get(validator_id)
     }
     ///
+    pub fn get_validator_commission_percent(&self, validator_id: &AccountId) -> Option<u16> {
+        self.validator_commissions.get(validator_id)
+    }
+    ///
+    pub fn set_validator_commission_percent(
+        &mut self,
+        validator_id: &AccountId,
+        commission_percent: u16,
+    ) {
+        self.validator_commissions
+            .insert(validator_id, &commission_percent);
+    }
+    ///
+    pub fn remove_validator_commission_percent(&mut self, validator_id: &AccountId) {
+        self.validator_commissions.remove(validator_id);
+    }
+    ///
+    pub fn set_grandpa_key_of_validator(&mut self, validator_id: &AccountId, grandpa_key: &String) {
+        self.grandpa_keys.insert(validator_id, grandpa_key);
+    }
+    ///
+    pub fn remove_grandpa_key_of_validator(&mut self, validator_id: &AccountId) {
+        self.grandpa_keys.remove(validator_id);
+    }
+    /// Get the id of the validator whose GRANDPA key in this era is the given key.
+    pub fn get_validator_id_by_grandpa_key(&self, grandpa_key: &String) -> Option<AccountId> {
+        self.validator_set
+            .get_validator_ids()
+            .into_iter()
+            .find(|validator_id| {
+                self.grandpa_keys
+                    .get(validator_id)
+                    .map_or(false, |key| key.eq(grandpa_key))
+            })
+    }
+    ///
     pub fn get_delegator_rewards_of(
         &self,
         delegator_id: &AccountId,
@@ -83,12 +205,13 @@ impl ValidatorSetOfEra {
     pub fn valid_total_stake(&self) -> u128 {
         self.valid_total_stake
     }
-    //
-    fn all_staking_histories_are_applied(&self) -> bool {
+    ///
+    pub fn all_staking_histories_are_applied(&self) -> bool {
         match self.processing_status {
             ValidatorSetProcessingStatus::ReadyForDistributingReward
             | ValidatorSetProcessingStatus::DistributingReward { .. }
             | ValidatorSetProcessingStatus::CheckingForAutoUnbondingValidator { .. }
+            | ValidatorSetProcessingStatus::SlashingOffender { .. }
             | ValidatorSetProcessingStatus::Completed => true,
             _ => false,
         }
@@ -184,16 +307,6 @@ impl ValidatorSetOfEra {
         self.validator_rewards.insert(validator_id, &amount);
     }
     ///
-    pub fn set_delegator_reward(
-        &mut self,
-        delegator_id: &AccountId,
-        validator_id: &AccountId,
-        amount: u128,
-    ) {
-        self.delegator_rewards
-            .insert(&(delegator_id.clone(), validator_id.clone()), &amount);
-    }
-    ///
     pub fn calculate_valid_total_stake(&mut self) {
         let unprofitable_validator_ids = self.unprofitable_validator_id_set.to_vec();
         self.valid_total_stake = self.validator_set.total_stake;
@@ -203,11 +316,14 @@ impl ValidatorSetOfEra {
         });
     }
     ///
-    pub fn to_validator_set_info(&self) -> ValidatorSetInfo {
+    pub fn to_validator_set_info<F>(&self, commission_percent_of: F) -> ValidatorSetInfo
+    where
+        F: Fn(&AccountId) -> u16,
+    {
         ValidatorSetInfo {
             era_number: U64::from(self.validator_set.era_number),
             total_stake: U128::from(self.validator_set.total_stake),
-            validator_list: self.get_validator_list(),
+            validator_list: self.get_validator_list(commission_percent_of),
             start_block_height: U64::from(self.start_block_height),
             start_timestamp: U64::from(self.start_timestamp),
             staking_history_index: U64::from(self.staking_history_index),
@@ -220,6 +336,8 @@ impl ValidatorSetOfEra {
     pub fn clear_reward_distribution_records(&mut self) -> MultiTxsOperationProcessingResult {
         let validator_ids = self.validator_set.validator_id_set.to_vec();
         for validator_id in validator_ids {
+            self.validator_commissions.remove(&validator_id);
+            self.grandpa_keys.remove(&validator_id);
             if self.unprofitable_validator_id_set.contains(&validator_id) {
                 continue;
             }
@@ -248,17 +366,237 @@ impl ValidatorSetOfEra {
     ///
     pub fn clear(&mut self) -> MultiTxsOperationProcessingResult {
         let mut result = self.clear_reward_distribution_records();
+        if result.is_ok() && !self.is_checkpoint() {
+            result = self.clear_changes_of_delegators();
+        }
         if result.is_ok() {
             result = self.validator_set.clear();
         }
+        if result.is_ok() {
+            self.removed_delegators.clear();
+        }
         result
     }
+    // Clear the counts and id indexes of the delegators changed in this era.
+    // The id indexes are shared by the sets of the checkpoint interval, which are
+    // always removed together.
+    fn clear_changes_of_delegators(&mut self) -> MultiTxsOperationProcessingResult {
+        let mut delegator_keys = self.removed_delegators.to_vec();
+        self.validator_set
+            .get_validator_ids()
+            .iter()
+            .for_each(|validator_id| {
+                self.validator_set
+                    .get_delegator_ids_of(validator_id)
+                    .into_iter()
+                    .for_each(|delegator_id| {
+                        delegator_keys.push((delegator_id, validator_id.clone()))
+                    });
+            });
+        let mut delegator_id_index = self.delegator_id_index();
+        let mut validator_id_index = self.validator_id_index();
+        for (delegator_id, validator_id) in delegator_keys {
+            self.delegator_counts.remove(&validator_id);
+            self.validator_counts.remove(&delegator_id);
+            delegator_id_index.remove(&validator_id, &delegator_id);
+            validator_id_index.remove(&delegator_id, &validator_id);
+            if env::used_gas() > Gas::ONE_TERA.mul(T_GAS_CAP_FOR_MULTI_TXS_PROCESSING) {
+                return MultiTxsOperationProcessingResult::NeedMoreGas;
+            }
+        }
+        MultiTxsOperationProcessingResult::Ok
+    }
     //
     pub fn apply_staking_fact(&mut self, staking_fact: &StakingFact) {
+        if self.is_checkpoint() {
+            self.validator_set.apply_staking_fact(staking_fact);
+            return;
+        }
+        let delegator_keys = get_delegator_keys_of(staking_fact);
+        // Copy the delegators changed by the staking fact to this set, before applying it.
+        let existences: Vec<bool> = delegator_keys
+            .iter()
+            .map(|(delegator_id, validator_id)| {
+                match self.get_delegator_in_deltas(delegator_id, validator_id) {
+                    Some(delegator) => {
+                        if !self
+                            .validator_set
+                            .contains_delegator(delegator_id, validator_id)
+                        {
+                            self.insert_delegator(&delegator);
+                        }
+                        true
+                    }
+                    None => false,
+                }
+            })
+            .collect();
         self.validator_set.apply_staking_fact(staking_fact);
+        delegator_keys
+            .iter()
+            .zip(existences)
+            .for_each(|(delegator_key, existed)| {
+                let (delegator_id, validator_id) = delegator_key;
+                let exists = self
+                    .validator_set
+                    .contains_delegator(delegator_id, validator_id);
+                if exists {
+                    self.removed_delegators.remove(delegator_key);
+                    self.index_delegator(delegator_id, validator_id);
+                } else {
+                    self.removed_delegators.insert(delegator_key);
+                }
+                if exists != existed {
+                    let delegator_count = self.get_delegator_count_in_deltas(validator_id);
+                    let validator_count = self.get_validator_count_in_deltas(delegator_id);
+                    match exists {
+                        true => {
+                            self.delegator_counts
+                                .insert(validator_id, &(delegator_count + 1));
+                            self.validator_counts
+                                .insert(delegator_id, &(validator_count + 1));
+                        }
+                        false => {
+                            self.delegator_counts
+                                .insert(validator_id, &(delegator_count - 1));
+                            self.validator_counts
+                                .insert(delegator_id, &(validator_count - 1));
+                        }
+                    }
+                }
+            });
+    }
+    // Append a delegator to the id indexes of the checkpoint interval,
+    // if it is not in the checkpoint.
+    fn index_delegator(&mut self, delegator_id: &AccountId, validator_id: &AccountId) {
+        let is_in_checkpoint = match get_validator_set_of_era(self.checkpoint_era_number) {
+            Some(checkpoint) => checkpoint
+                .validator_set
+                .contains_delegator(delegator_id, validator_id),
+            None => false,
+        };
+        if !is_in_checkpoint {
+            self.delegator_id_index().append(validator_id, delegator_id);
+            self.validator_id_index().append(delegator_id, validator_id);
+        }
+    }
+    //
+    fn delegator_id_index(&self) -> IdIndexOfInterval {
+        IdIndexOfInterval::new(self.checkpoint_era_number, "vtd")
+    }
+    //
+    fn validator_id_index(&self) -> IdIndexOfInterval {
+        IdIndexOfInterval::new(self.checkpoint_era_number, "dtv")
+    }
+    // Get a delegator from this set, or from the sets of previous eras until the checkpoint.
+    fn get_delegator_in_deltas(
+        &self,
+        delegator_id: &AccountId,
+        validator_id: &AccountId,
+    ) -> Option<Delegator> {
+        let delegator_key = (delegator_id.clone(), validator_id.clone());
+        if let Some(delegator) = self.validator_set.get_delegator(delegator_id, validator_id) {
+            return Some(delegator);
+        }
+        if self.is_checkpoint() || self.removed_delegators.contains(&delegator_key) {
+            return None;
+        }
+        let mut era_number = self.era_number();
+        while era_number > self.checkpoint_era_number {
+            era_number -= 1;
+            let validator_set = get_validator_set_of_era(era_number)?;
+            if let Some(delegator) = validator_set
+                .validator_set
+                .get_delegator(delegator_id, validator_id)
+            {
+                return Some(delegator);
+            }
+            if validator_set.removed_delegators.contains(&delegator_key) {
+                return None;
+            }
+        }
+        None
+    }
+    // Get a count in this set, or in the sets of previous eras until the checkpoint.
+    // The set which is not a checkpoint only stores the counts changed in its era.
+    fn get_count_in_deltas<F, G>(&self, changed_count_of: F, count_of: G) -> u64
+    where
+        F: Fn(&ValidatorSetOfEra) -> Option<u64>,
+        G: Fn(&ValidatorSet) -> u64,
+    {
+        if self.is_checkpoint() {
+            return count_of(&self.validator_set);
+        }
+        if let Some(count) = changed_count_of(self) {
+            return count;
+        }
+        let mut era_number = self.era_number();
+        while era_number > self.checkpoint_era_number {
+            era_number -= 1;
+            let validator_set = match get_validator_set_of_era(era_number) {
+                Some(validator_set) => validator_set,
+                None => return 0,
+            };
+            if validator_set.is_checkpoint() {
+                return count_of(&validator_set.validator_set);
+            }
+            if let Some(count) = changed_count_of(&validator_set) {
+                return count;
+            }
+        }
+        0
+    }
+    //
+    fn get_delegator_count_in_deltas(&self, validator_id: &AccountId) -> u64 {
+        self.get_count_in_deltas(
+            |validator_set| validator_set.delegator_counts.get(validator_id),
+            |validator_set| validator_set.get_delegator_count_of(validator_id),
+        )
+    }
+    //
+    fn get_validator_count_in_deltas(&self, delegator_id: &AccountId) -> u64 {
+        self.get_count_in_deltas(
+            |validator_set| validator_set.validator_counts.get(delegator_id),
+            |validator_set| validator_set.get_validator_count_of(delegator_id),
+        )
+    }
+    //
+    fn get_delegator_ids_in_deltas(&self, validator_id: &AccountId) -> Vec<AccountId> {
+        if self.is_checkpoint() {
+            return self.validator_set.get_delegator_ids_of(validator_id);
+        }
+        let mut delegator_ids = match get_validator_set_of_era(self.checkpoint_era_number) {
+            Some(checkpoint) => checkpoint.validator_set.get_delegator_ids_of(validator_id),
+            None => Vec::new(),
+        };
+        delegator_ids.append(&mut self.delegator_id_index().get_all_of(validator_id));
+        delegator_ids.retain(|delegator_id| {
+            self.get_delegator_in_deltas(delegator_id, validator_id)
+                .is_some()
+        });
+        delegator_ids
+    }
+    //
+    fn get_validator_ids_in_deltas(&self, delegator_id: &AccountId) -> Vec<AccountId> {
+        if self.is_checkpoint() {
+            return self.validator_set.get_validator_ids_of(delegator_id);
+        }
+        let mut validator_ids = match get_validator_set_of_era(self.checkpoint_era_number) {
+            Some(checkpoint) => checkpoint.validator_set.get_validator_ids_of(delegator_id),
+            None => Vec::new(),
+        };
+        validator_ids.append(&mut self.validator_id_index().get_all_of(delegator_id));
+        validator_ids.retain(|validator_id| {
+            self.get_delegator_in_deltas(delegator_id, validator_id)
+                .is_some()
+        });
+        validator_ids
     }
     ///
-    pub fn get_validator_list(&self) -> Vec<AppchainValidator> {
+    pub fn get_validator_list<F>(&self, commission_percent_of: F) -> Vec<AppchainValidator>
+    where
+        F: Fn(&AccountId) -> u16,
+    {
         if !self.all_staking_histories_are_applied() {
             return Vec::new();
         }
@@ -268,8 +606,9 @@ impl ValidatorSetOfEra {
             .map(|validator_id| {
                 AppchainValidator::from_validator(
                     self.validator_set.get_validator(validator_id).unwrap(),
-                    self.validator_set.get_delegator_count_of(validator_id),
+                    self.get_delegator_count_of(validator_id),
                     false,
+                    commission_percent_of(validator_id),
                 )
             })
             .collect()
@@ -289,8 +628,8 @@ impl ValidatorSetViewer for ValidatorSetOfEra {
         if !self.all_staking_histories_are_applied() {
             return false;
         }
-        self.validator_set
-            .contains_delegator(delegator_id, validator_id)
+        self.get_delegator_in_deltas(delegator_id, validator_id)
+            .is_some()
     }
     //
     fn get_validator(&self, validator_id: &AccountId) -> Option<Validator> {
@@ -315,15 +654,25 @@ impl ValidatorSetViewer for ValidatorSetOfEra {
         if !self.all_staking_histories_are_applied() {
             return None;
         }
-        self.validator_set.get_delegator(delegator_id, validator_id)
+        self.get_delegator_in_deltas(delegator_id, validator_id)
     }
     //
     fn get_delegator_by_index(&self, index: &u64, validator_id: &AccountId) -> Option<Delegator> {
         if !self.all_staking_histories_are_applied() {
             return None;
         }
-        self.validator_set
-            .get_delegator_by_index(index, validator_id)
+        if self.is_checkpoint() {
+            return self
+                .validator_set
+                .get_delegator_by_index(index, validator_id);
+        }
+        match self
+            .get_delegator_ids_in_deltas(validator_id)
+            .get(usize::try_from(*index).unwrap_or(usize::MAX))
+        {
+            Some(delegator_id) => self.get_delegator_in_deltas(delegator_id, validator_id),
+            None => None,
+        }
     }
     //
     fn get_validator_ids(&self) -> Vec<AccountId> {
@@ -337,28 +686,28 @@ impl ValidatorSetViewer for ValidatorSetOfEra {
         if !self.all_staking_histories_are_applied() {
             return Vec::new();
         }
-        self.validator_set.get_validator_ids_of(delegator_id)
+        self.get_validator_ids_in_deltas(delegator_id)
     }
     //
     fn get_delegator_ids_of(&self, validator_id: &AccountId) -> Vec<AccountId> {
         if !self.all_staking_histories_are_applied() {
             return Vec::new();
         }
-        self.validator_set.get_delegator_ids_of(validator_id)
+        self.get_delegator_ids_in_deltas(validator_id)
     }
     //
     fn get_validator_count_of(&self, delegator_id: &AccountId) -> u64 {
         if !self.all_staking_histories_are_applied() {
             return 0;
         }
-        self.validator_set.get_validator_count_of(delegator_id)
+        self.get_validator_count_in_deltas(delegator_id)
     }
     //
     fn get_delegator_count_of(&self, validator_id: &AccountId) -> u64 {
         if !self.all_staking_histories_are_applied() {
             return 0;
         }
-        self.validator_set.get_delegator_count_of(validator_id)
+        self.get_delegator_count_in_deltas(validator_id)
     }
     //
     fn era_number(&self) -> u64 {
@@ -383,7 +732,11 @@ impl ValidatorSetViewer for ValidatorSetOfEra {
         if !self.all_staking_histories_are_applied() {
             return 0;
         }
-        self.validator_set.delegator_count()
+        self.validator_set
+            .get_validator_ids()
+            .iter()
+            .map(|validator_id| self.get_delegator_count_of(validator_id))
+            .sum()
     }
 }
 
@@ -397,3 +750,129 @@ impl IndexedAndClearable for ValidatorSetOfEra {
         self.clear()
     }
 }
+
+impl IdIndexOfInterval {
+    ///
+    pub fn new(checkpoint_era_number: u64, name: &str) -> Self {
+        Self {
+            related_ids: LookupMap::new(
+                StorageKey::IdIndexOfInterval {
+                    checkpoint_era_number,
+                    map_name: format!("{}ids", name),
+                }
+                .into_bytes(),
+            ),
+            positions: LookupMap::new(
+                StorageKey::IdIndexOfInterval {
+                    checkpoint_era_number,
+                    map_name: format!("{}pos", name),
+                }
+                .into_bytes(),
+            ),
+            lengths: LookupMap::new(
+                StorageKey::IdIndexOfInterval {
+                    checkpoint_era_number,
+                    map_name: format!("{}len", name),
+                }
+                .into_bytes(),
+            ),
+        }
+    }
+    ///
+    pub fn get_all_of(&self, id: &AccountId) -> Vec<AccountId> {
+        (0..self.lengths.get(id).unwrap_or(0))
+            .filter_map(|position| self.related_ids.get(&(id.clone(), position)))
+            .collect()
+    }
+    ///
+    pub fn append(&mut self, id: &AccountId, related_id: &AccountId) {
+        let key = (id.clone(), related_id.clone());
+        if self.positions.contains_key(&key) {
+            return;
+        }
+        let length = self.lengths.get(id).unwrap_or(0);
+        self.related_ids.insert(&(id.clone(), length), related_id);
+        self.positions.insert(&key, &length);
+        self.lengths.insert(id, &(length + 1));
+    }
+    ///
+    pub fn remove(&mut self, id: &AccountId, related_id: &AccountId) {
+        if let Some(position) = self.positions.remove(&(id.clone(), related_id.clone())) {
+            // Move the last related id to the removed position, to keep the index compact.
+            let last_position = self.lengths.get(id).unwrap_or(1) - 1;
+            if position < last_position {
+                if let Some(last_related_id) = self.related_ids.get(&(id.clone(), last_position)) {
+                    self.related_ids
+                        .insert(&(id.clone(), position), &last_related_id);
+                    self.positions
+                        .insert(&(id.clone(), last_related_id), &position);
+                }
+            }
+            self.related_ids.remove(&(id.clone(), last_position));
+            match last_position {
+                0 => self.lengths.remove(id),
+                _ => self.lengths.insert(id, &last_position),
+            };
+        }
+    }
+}
+
+// Get the validator set of an era from the validator set histories.
+fn get_validator_set_of_era(era_number: u64) -> Option<ValidatorSetOfEra> {
+    LookupArray::<ValidatorSetOfEra>::get_by_storage_key(
+        StorageKey::ValidatorSetHistoriesMap,
+        &era_number,
+    )
+}
+
+// Get the keys of delegators which will be changed by a staking fact,
+// in form of `(delegator_id, validator_id)`.
+fn get_delegator_keys_of(staking_fact: &StakingFact) -> Vec<(AccountId, AccountId)> {
+    match staking_fact {
+        StakingFact::DelegatorRegistered {
+            delegator_id,
+            validator_id,
+            ..
+        }
+        | StakingFact::DelegationIncreased {
+            delegator_id,
+            validator_id,
+            ..
+        }
+        | StakingFact::DelegationDecreased {
+            delegator_id,
+            validator_id,
+            ..
+        }
+        | StakingFact::DelegatorSlashed {
+            delegator_id,
+            validator_id,
+            ..
+        }
+        | StakingFact::DelegatorUnbonded {
+            delegator_id,
+            validator_id,
+            ..
+        }
+        | StakingFact::DelegatorAutoUnbonded {
+            delegator_id,
+            validator_id,
+            ..
+        }
+        | StakingFact::DelegationRebonded {
+            delegator_id,
+            validator_id,
+            ..
+        } => vec![(delegator_id.clone(), validator_id.clone())],
+        StakingFact::DelegationRedelegated {
+            delegator_id,
+            from_validator_id,
+            to_validator_id,
+            ..
+        } => vec![
+            (delegator_id.clone(), from_validator_id.clone()),
+            (delegator_id.clone(), to_validator_id.clone()),
+        ],
+        _ => Vec::new(),
+    }
+}
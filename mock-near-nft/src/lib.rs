@@ -0,0 +1,53 @@
+use near_contract_standards::non_fungible_token::metadata::TokenMetadata;
+use near_contract_standards::non_fungible_token::{NonFungibleToken, Token, TokenId};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::{env, near_bindgen, AccountId, PanicOnDefault, PromiseOrValue};
+
+#[near_bindgen]
+#[derive(BorshSerialize, BorshDeserialize, PanicOnDefault)]
+pub struct MockNearNft {
+    tokens: NonFungibleToken,
+}
+
+#[near_bindgen]
+impl MockNearNft {
+    /// The metadata of tokens is optional in NEP-177,
+    /// it is not stored if `with_token_metadata` is false.
+    #[init]
+    pub fn new(owner_id: AccountId, with_token_metadata: bool) -> Self {
+        assert!(!env::state_exists(), "Already initialized");
+        Self {
+            tokens: NonFungibleToken::new(
+                b"o".to_vec(),
+                owner_id,
+                match with_token_metadata {
+                    true => Some(b"m".to_vec()),
+                    false => None,
+                },
+                None::<Vec<u8>>,
+                None::<Vec<u8>>,
+            ),
+        }
+    }
+    #[payable]
+    pub fn nft_mint(
+        &mut self,
+        token_id: TokenId,
+        token_owner_id: AccountId,
+        token_metadata: Option<TokenMetadata>,
+    ) -> Token {
+        assert_eq!(
+            env::predecessor_account_id(),
+            self.tokens.owner_id,
+            "Unauthorized"
+        );
+        let token_metadata = match self.tokens.token_metadata_by_id {
+            Some(_) => token_metadata,
+            None => None,
+        };
+        self.tokens
+            .internal_mint(token_id, token_owner_id, token_metadata)
+    }
+}
+
+near_contract_standards::impl_non_fungible_token_core!(MockNearNft, tokens);
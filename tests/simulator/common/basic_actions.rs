@@ -206,6 +206,8 @@ pub async fn initialize_contracts_and_users(
         .transact()
         .await?
         .unwrap();
+    register_user_to_ft_contract(worker, &relayer, &oct_token).await?;
+    super::call_ft_transfer(worker, &root, &relayer, total_supply / 100, &oct_token).await?;
     users.push(relayer);
     // Return initialized UserAccounts
     Ok((
@@ -283,3 +285,32 @@ pub async fn register_user_to_ft_contract(
         .await?;
     Ok(())
 }
+
+// Deploy a mock NFT collection in NEAR protocol, which is owned by `root`
+pub async fn deploy_mock_near_nft_contract(
+    worker: &Worker<Sandbox>,
+    root: &Account,
+    name: &str,
+    with_token_metadata: bool,
+) -> anyhow::Result<Contract> {
+    let mock_near_nft = root
+        .create_subaccount(worker, name)
+        .initial_balance(parse_near!("50 N"))
+        .transact()
+        .await?
+        .unwrap();
+    let mock_near_nft = mock_near_nft
+        .deploy(worker, &std::fs::read(format!("res/mock_near_nft.wasm"))?)
+        .await?
+        .unwrap();
+    mock_near_nft
+        .call(worker, "new")
+        .args_json(json!({
+            "owner_id": root.id(),
+            "with_token_metadata": with_token_metadata,
+        }))?
+        .gas(300_000_000_000_000)
+        .transact()
+        .await?;
+    Ok(mock_near_nft)
+}
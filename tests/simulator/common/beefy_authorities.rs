@@ -0,0 +1,110 @@
+use appchain_anchor::types::ValidatorMerkleProof;
+use beefy_light_client::commitment::{Commitment, Payload, Signature, SignedCommitment};
+use beefy_light_client::mmr::MmrLeafProof;
+use beefy_merkle_tree::{merkle_proof, merkle_root, Keccak256};
+use near_sdk::env;
+use parity_scale_codec::Encode;
+use secp256k1_test::{rand::thread_rng, Message as SecpMessage, PublicKey, Secp256k1, SecretKey};
+use std::convert::TryInto;
+
+const MMR_ROOT_ID: [u8; 2] = *b"mh";
+
+/// A beefy authority set of appchain with random keys,
+/// for signing the commitments of beefy light client in testing.
+pub struct BeefyAuthoritySet {
+    keypairs: Vec<(SecretKey, PublicKey)>,
+}
+
+impl BeefyAuthoritySet {
+    /// Create the authority set with the given count of random keypairs.
+    pub fn new(count: usize) -> Self {
+        let secp = Secp256k1::new();
+        Self {
+            keypairs: (0..count)
+                .map(|_| secp.generate_keypair(&mut thread_rng()))
+                .collect(),
+        }
+    }
+    ///
+    pub fn public_keys(&self) -> Vec<String> {
+        self.keypairs
+            .iter()
+            .map(|(_, pubkey)| format!("0x{}", hex::encode(pubkey.serialize())))
+            .collect()
+    }
+    //
+    fn addresses(&self) -> Vec<Vec<u8>> {
+        self.keypairs
+            .iter()
+            .map(|(_, pubkey)| beefy_light_client::beefy_ecdsa_to_ethereum(&pubkey.serialize()))
+            .collect()
+    }
+    /// Make the MMR leaf of the given parent header, with the next authority set
+    /// which is the same as this authority set.
+    pub fn make_mmr_leaf(&self, parent_number: u32, parent_hash: [u8; 32]) -> Vec<u8> {
+        let authority_root = merkle_root::<Keccak256, _, _>(self.addresses());
+        (
+            0_u8,
+            (parent_number, parent_hash),
+            (0_u64, self.keypairs.len() as u32, authority_root),
+            [0_u8; 32],
+        )
+            .encode()
+    }
+    /// Sign the commitment of an MMR with the only given leaf at the given block number.
+    ///
+    /// Return the encoded signed commitment and the merkle proofs of the authorities.
+    pub fn sign_commitment(
+        &self,
+        block_number: u32,
+        mmr_leaf: &Vec<u8>,
+    ) -> (Vec<u8>, Vec<ValidatorMerkleProof>) {
+        let secp = Secp256k1::new();
+        let commitment = Commitment {
+            payload: Payload(vec![(MMR_ROOT_ID, env::keccak256(mmr_leaf))]),
+            block_number,
+            validator_set_id: 0,
+        };
+        let msg = SecpMessage::from_slice(&commitment.hash()[..]).unwrap();
+        let signatures = self
+            .keypairs
+            .iter()
+            .map(|(privkey, _)| {
+                let (recover_id, signature) =
+                    secp.sign_recoverable(&msg, privkey).serialize_compact();
+                let mut buf = [0_u8; 65];
+                buf[0..64].copy_from_slice(&signature[..]);
+                buf[64] = recover_id.to_i32() as u8;
+                Some(Signature(buf))
+            })
+            .collect();
+        let addresses = self.addresses();
+        let validator_proofs = (0..addresses.len())
+            .map(|index| {
+                let proof = merkle_proof::<Keccak256, _, _>(addresses.clone(), index);
+                ValidatorMerkleProof {
+                    proof: proof.proof,
+                    number_of_leaves: proof.number_of_leaves.try_into().unwrap(),
+                    leaf_index: proof.leaf_index.try_into().unwrap(),
+                    leaf: proof.leaf,
+                }
+            })
+            .collect();
+        (
+            codec::Encode::encode(&SignedCommitment {
+                commitment,
+                signatures,
+            }),
+            validator_proofs,
+        )
+    }
+}
+
+/// The proof of the only leaf in an MMR, whose root is the hash of the leaf.
+pub fn single_leaf_mmr_proof() -> Vec<u8> {
+    codec::Encode::encode(&MmrLeafProof {
+        leaf_index: 0,
+        leaf_count: 1,
+        items: Vec::new(),
+    })
+}
@@ -1,21 +1,27 @@
 use crate::{
     common::{
         self,
+        beefy_authorities::{single_leaf_mmr_proof, BeefyAuthoritySet},
         complex_viewer::{
             print_anchor_status, print_appchain_notifications, print_delegator_reward_histories,
             print_unbonded_stakes_of, print_validator_reward_histories,
             print_wat_balance_of_anchor,
         },
     },
-    contract_interfaces::{anchor_viewer, permissionless_actions, staking_actions},
+    contract_interfaces::{
+        anchor_viewer, near_fungible_token_manager, permissionless_actions, staking_actions,
+    },
 };
 use appchain_anchor::{
-    appchain_messages::{EraPayoutPayload, RawMessage},
+    appchain_messages::{BurnAssetPayload, EraPayoutPayload, RawMessage},
     appchain_messages::{PayloadType, PlanNewEraPayload},
     types::{AnchorStatus, MultiTxsOperationProcessingResult, ValidatorSetInfo},
 };
 use near_primitives::borsh::BorshSerialize;
-use near_sdk::{json_types::U64, serde_json};
+use near_sdk::{
+    json_types::{U128, U64},
+    serde_json::{self, json},
+};
 use parity_scale_codec::Encode;
 use workspaces::network::Sandbox;
 use workspaces::{Account, Contract, Worker};
@@ -45,6 +51,32 @@ pub async fn process_appchain_messages(
     Ok(())
 }
 
+// Submit the messages by each of the given witnesses, the messages will be staged
+// once the count of attestations reaches the witness threshold
+pub async fn stage_appchain_messages_by_witnesses(
+    worker: &Worker<Sandbox>,
+    witnesses: &[&Account],
+    anchor: &Contract,
+    raw_messages: Vec<RawMessage>,
+) -> anyhow::Result<()> {
+    let encoded_messages = raw_messages.encode();
+    for witness in witnesses {
+        let result = permissionless_actions::verify_and_stage_appchain_messages(
+            worker,
+            witness,
+            anchor,
+            encoded_messages.clone(),
+            Vec::new(),
+            Vec::new(),
+            Vec::new(),
+        )
+        .await
+        .expect("Failed to call 'verify_and_stage_appchain_messages'");
+        assert!(result.is_success());
+    }
+    Ok(())
+}
+
 pub async fn switch_era(
     worker: &Worker<Sandbox>,
     relayer: &Account,
@@ -62,19 +94,7 @@ pub async fn switch_era(
             payload_type: PayloadType::PlanNewEra,
             payload: payload.try_to_vec().unwrap(),
         };
-        let mut raw_messages = Vec::new();
-        raw_messages.push(raw_message);
-        permissionless_actions::verify_and_stage_appchain_messages(
-            worker,
-            relayer,
-            anchor,
-            raw_messages.encode(),
-            Vec::new(),
-            Vec::new(),
-            Vec::new(),
-        )
-        .await
-        .expect("Failed to call 'verify_and_stage_appchain_messages'");
+        stage_appchain_messages_by_witnesses(worker, &[relayer], anchor, vec![raw_message]).await?;
     }
     process_appchain_messages(worker, relayer, anchor).await?;
     if to_confirm_view_result {
@@ -122,19 +142,7 @@ pub async fn distribute_reward_of(
         payload_type: PayloadType::EraPayout,
         payload: payload.try_to_vec().unwrap(),
     };
-    let mut raw_messages = Vec::new();
-    raw_messages.push(raw_message);
-    permissionless_actions::verify_and_stage_appchain_messages(
-        worker,
-        relayer,
-        anchor,
-        raw_messages.encode(),
-        Vec::new(),
-        Vec::new(),
-        Vec::new(),
-    )
-    .await
-    .expect("Failed to call 'verify_and_stage_appchain_messages'");
+    stage_appchain_messages_by_witnesses(worker, &[relayer], anchor, vec![raw_message]).await?;
     if to_confirm_view_result {
         let anchor_status = anchor_viewer::get_anchor_status(worker, anchor).await?;
         println!(
@@ -259,3 +267,123 @@ pub async fn withdraw_stake_of(
     print_unbonded_stakes_of(worker, anchor, user).await?;
     Ok(())
 }
+
+/// Sign the commitment of an MMR with the only given leaf at the given block number,
+/// and update the state of beefy light client with it.
+pub async fn update_state_of_beefy_light_client(
+    worker: &Worker<Sandbox>,
+    signer: &Account,
+    anchor: &Contract,
+    authorities: &BeefyAuthoritySet,
+    block_number: u32,
+    mmr_leaf: &Vec<u8>,
+) -> anyhow::Result<()> {
+    let (signed_commitment, validator_proofs) = authorities.sign_commitment(block_number, mmr_leaf);
+    let result = permissionless_actions::start_updating_state_of_beefy_light_client(
+        worker,
+        signer,
+        anchor,
+        signed_commitment,
+        validator_proofs,
+        mmr_leaf.encode(),
+        single_leaf_mmr_proof(),
+    )
+    .await?;
+    assert!(result.is_success());
+    while permissionless_actions::try_complete_updating_state_of_beefy_light_client(
+        worker, signer, anchor,
+    )
+    .await?
+    .is_need_more_gas()
+    {}
+    Ok(())
+}
+
+/// Register OCT token as a NEAR fungible token in anchor, and open the bridging of it.
+pub async fn open_bridging_of_oct_token(
+    worker: &Worker<Sandbox>,
+    root: &Account,
+    anchor: &Contract,
+    oct_token: &Contract,
+) -> anyhow::Result<()> {
+    let result = near_fungible_token_manager::register_near_fungible_token(
+        worker,
+        root,
+        anchor,
+        "OCT".to_string(),
+        "Oct token".to_string(),
+        18,
+        oct_token.id().to_string().parse().unwrap(),
+        U128::from(1000000),
+    )
+    .await?;
+    assert!(result.is_success());
+    let result = near_fungible_token_manager::open_bridging_of_near_fungible_token(
+        worker,
+        root,
+        anchor,
+        "OCT".to_string(),
+    )
+    .await?;
+    assert!(result.is_success());
+    Ok(())
+}
+
+/// Lock a certain amount of OCT token of the user in anchor for bridging to the appchain.
+pub async fn lock_oct_to_appchain(
+    worker: &Worker<Sandbox>,
+    user: &Account,
+    oct_token: &Contract,
+    anchor: &Contract,
+    amount: u128,
+) -> anyhow::Result<()> {
+    let result = common::call_ft_transfer_call(
+        worker,
+        user,
+        &anchor.as_account(),
+        common::to_actual_amount(amount, 18),
+        json!({
+            "BridgeToAppchain": {
+                "receiver_id_in_appchain": "0xd43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d".to_string(),
+            }
+        })
+        .to_string(),
+        oct_token,
+    )
+    .await?;
+    assert!(result.is_success());
+    Ok(())
+}
+
+/// Stage and process the appchain message which burns a certain amount of OCT token
+/// in the appchain and unlocks it to the receiver in NEAR protocol.
+pub async fn unlock_oct_from_appchain(
+    worker: &Worker<Sandbox>,
+    relayer: &Account,
+    anchor: &Contract,
+    oct_token: &Contract,
+    receiver: &Account,
+    amount: u128,
+    appchain_message_nonce: u32,
+) -> anyhow::Result<()> {
+    stage_appchain_messages_by_witnesses(
+        worker,
+        &[relayer],
+        anchor,
+        vec![RawMessage {
+            nonce: appchain_message_nonce as u64,
+            payload_type: PayloadType::BurnAsset,
+            payload: BurnAssetPayload {
+                token_id: oct_token.id().to_string(),
+                sender: "0xd43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d"
+                    .to_string(),
+                receiver_id: receiver.id().to_string().parse().unwrap(),
+                amount: common::to_actual_amount(amount, 18),
+            }
+            .try_to_vec()
+            .unwrap(),
+        }],
+    )
+    .await?;
+    process_appchain_messages(worker, relayer, anchor).await
+}
@@ -0,0 +1,109 @@
+use appchain_anchor::appchain_challenge::equivocation_challenge::{
+    GrandpaMessage, GrandpaPrecommit, Hash,
+};
+use appchain_anchor::grandpa_light_client::header::{
+    ConsensusLog, Digest, DigestItem, Header, ScheduledChange, GRANDPA_ENGINE_ID,
+};
+use appchain_anchor::grandpa_light_client::justification::{
+    Commit, GrandpaJustification, SignedPrecommit,
+};
+use ed25519_dalek::{Keypair, PublicKey, SecretKey, Signer};
+use near_sdk::env;
+use parity_scale_codec::Encode;
+
+/// A GRANDPA authority set of appchain with known keys,
+/// for signing the justifications of appchain headers in testing.
+pub struct GrandpaAuthoritySet {
+    pub set_id: u64,
+    keypairs: Vec<Keypair>,
+}
+
+impl GrandpaAuthoritySet {
+    /// Create the authority set with a keypair for each of the given seeds.
+    pub fn new(set_id: u64, seeds: &[u8]) -> Self {
+        Self {
+            set_id,
+            keypairs: seeds
+                .iter()
+                .map(|seed| {
+                    let secret = SecretKey::from_bytes(&[*seed; 32]).unwrap();
+                    let public = PublicKey::from(&secret);
+                    Keypair { secret, public }
+                })
+                .collect(),
+        }
+    }
+    ///
+    pub fn public_keys(&self) -> Vec<String> {
+        self.keypairs
+            .iter()
+            .map(|keypair| format!("0x{}", hex::encode(keypair.public.to_bytes())))
+            .collect()
+    }
+    /// Get the digest item which schedules the change to this authority set
+    /// after the given delay.
+    pub fn scheduled_change(&self, delay: u32) -> DigestItem {
+        DigestItem::Consensus(
+            GRANDPA_ENGINE_ID,
+            ConsensusLog::ScheduledChange(ScheduledChange {
+                next_authorities: self
+                    .keypairs
+                    .iter()
+                    .map(|keypair| (keypair.public.to_bytes(), 1))
+                    .collect(),
+                delay,
+            })
+            .encode(),
+        )
+    }
+    /// Sign the precommits of the header by the first `signer_count` authorities,
+    /// and return the encoded justification.
+    pub fn sign_justification(&self, header: &Header, signer_count: usize) -> Vec<u8> {
+        let round: u64 = 1;
+        let precommit = GrandpaPrecommit {
+            target_hash: Hash(header.hash()),
+            target_number: header.number,
+        };
+        let payload = (
+            GrandpaMessage::Precommit(precommit.clone()),
+            round,
+            self.set_id,
+        )
+            .encode();
+        GrandpaJustification {
+            round,
+            commit: Commit {
+                target_hash: Hash(header.hash()),
+                target_number: header.number,
+                precommits: self
+                    .keypairs
+                    .iter()
+                    .take(signer_count)
+                    .map(|keypair| SignedPrecommit {
+                        precommit: precommit.clone(),
+                        signature: keypair.sign(&payload).to_bytes(),
+                        id: keypair.public.to_bytes(),
+                    })
+                    .collect(),
+            },
+            votes_ancestries: Vec::new(),
+        }
+        .encode()
+    }
+}
+
+/// Make an appchain header with the given number and digest items.
+pub fn make_header(number: u32, logs: Vec<DigestItem>) -> Header {
+    Header {
+        parent_hash: Hash([0; 32]),
+        number,
+        state_root: Hash([0; 32]),
+        extrinsics_root: Hash([0; 32]),
+        digest: Digest { logs },
+    }
+}
+
+/// Get the digest item which commits the given encoded appchain messages.
+pub fn messages_commitment(encoded_messages: &[u8]) -> DigestItem {
+    DigestItem::Other(env::keccak256(encoded_messages))
+}
@@ -1,15 +1,17 @@
 pub mod basic_actions;
+pub mod beefy_authorities;
 pub mod complex_actions;
 pub mod complex_viewer;
+pub mod grandpa_authorities;
 
 use crate::contract_interfaces::{
     anchor_viewer, lifecycle_actions, settings_manager, staking_actions, validator_actions,
-    wrapped_appchain_token_manager,
+    witness_actions, wrapped_appchain_token_manager,
 };
 use appchain_anchor::types::AppchainState;
 use near_sdk::json_types::U128;
 use near_sdk::json_types::U64;
-use near_sdk::serde_json::json;
+use near_sdk::serde_json::{self, json};
 use near_sdk::AccountId;
 use std::collections::HashMap;
 use std::str::FromStr;
@@ -18,6 +20,7 @@ use workspaces::result::CallExecutionDetails;
 use workspaces::{Account, Contract, Worker};
 
 const TOTAL_SUPPLY: u128 = 100_000_000;
+pub const WITNESS_BOND_AMOUNT: u128 = 10_000;
 
 pub async fn call_ft_transfer(
     worker: &Worker<Sandbox>,
@@ -77,6 +80,24 @@ pub async fn get_ft_balance_of(
         .json::<U128>()
 }
 
+/// Get the data of the NEP-297 events of appchain anchor with the given name,
+/// in the logs of a transaction.
+pub fn get_anchor_events(result: &CallExecutionDetails, event: &str) -> Vec<serde_json::Value> {
+    result
+        .logs()
+        .iter()
+        .filter_map(|log| log.strip_prefix("EVENT_JSON:"))
+        .map(|event_log| serde_json::from_str::<serde_json::Value>(event_log).unwrap())
+        .filter(|event_log| {
+            event_log["standard"] == "appchain_anchor" && event_log["event"] == event
+        })
+        .map(|event_log| {
+            assert_eq!(event_log["version"], "1.0.0");
+            event_log["data"].clone()
+        })
+        .collect()
+}
+
 pub fn to_actual_amount(amount: u128, decimals: u32) -> u128 {
     let bt_decimals_base = (10 as u128).pow(decimals);
     amount * bt_decimals_base
@@ -522,6 +543,30 @@ pub async fn test_normal_actions(
     lifecycle_actions::initialize_beefy_light_client(worker, &root, &anchor, initial_public_keys)
         .await
         .expect("Failed in calling 'initialize_beefy_light_client'");
+    //
+    // Set the relayer as the only witness, and make the witnessed messages
+    // can be processed immediately
+    //
+    if !with_old_anchor {
+        settings_manager::add_witness_account(worker, &root, &anchor, &users[5])
+            .await
+            .expect("Failed in calling 'add_witness_account'");
+        settings_manager::set_witness_threshold(worker, &root, &anchor, 1)
+            .await
+            .expect("Failed in calling 'set_witness_threshold'");
+        settings_manager::change_challenge_period_of_witnessed_messages(worker, &root, &anchor, 0)
+            .await
+            .expect("Failed in calling 'change_challenge_period_of_witnessed_messages'");
+        let result = witness_actions::bond_as_witness(
+            worker,
+            &users[5],
+            &oct_token,
+            &anchor,
+            to_actual_amount(WITNESS_BOND_AMOUNT, 18),
+        )
+        .await?;
+        assert!(result.is_success());
+    }
     settings_manager::turn_on_beefy_light_client_witness_mode(&worker, &root, &anchor)
         .await
         .expect("Failed in calling 'turn_on_beefy_light_client_witness_mode'");
@@ -1,12 +1,18 @@
-use appchain_anchor::appchain_challenge::AppchainChallenge;
+use appchain_anchor::appchain_challenge::{
+    AppchainChallenge, AppchainChallengeState, AppchainChallengeStatus,
+};
 use appchain_anchor::types::{
-    AnchorSettings, AnchorStatus, AppchainCommitment, AppchainDelegator,
-    AppchainMessageProcessingResult, AppchainNotificationHistory, AppchainSettings, AppchainState,
-    AppchainValidator, IndexRange, NearFungibleToken, RewardHistory, StakingHistory, UnbondedStake,
-    UserStakingHistory, ValidatorProfile, ValidatorSetInfo, WrappedAppchainToken,
+    AnchorSettings, AnchorStatus, AppchainCommitment, AppchainDelegator, AppchainLiveness,
+    AppchainMessageProcessingResult, AppchainNotificationHistory, AppchainSettings,
+    AppchainSettlementStatus, AppchainState, AppchainValidator, BridgeFeeOfToken, BridgeRateLimit,
+    BridgeStorageFund, FailedAppchainMessage, IndexRange, LightClientStatus,
+    MessageBatchAttestation, NativeNearToken, NearFungibleToken, NearNFTCollection,
+    NftBridgeFeeBalance, PendingTransfer, ProtocolSettings, RetainedAppchainCommitment,
+    RewardHistory, StakingHistory, StorageRegistrationOfToken, UnbondedStake, UserStakingHistory,
+    ValidatorProfile, ValidatorSetInfo, WrappedAppchainAsset, WrappedAppchainToken,
 };
 use appchain_anchor::AppchainMessage;
-use near_sdk::json_types::U64;
+use near_sdk::json_types::{U128, U64};
 use near_sdk::serde_json::json;
 use near_sdk::AccountId;
 use workspaces::{network::Sandbox, Account, Contract, Worker};
@@ -22,6 +28,17 @@ pub async fn get_anchor_settings(
         .json::<AnchorSettings>()
 }
 
+pub async fn get_protocol_settings(
+    worker: &Worker<Sandbox>,
+    anchor: &Contract,
+) -> anyhow::Result<ProtocolSettings> {
+    anchor
+        .call(worker, "get_protocol_settings")
+        .view()
+        .await?
+        .json::<ProtocolSettings>()
+}
+
 pub async fn get_appchain_settings(
     worker: &Worker<Sandbox>,
     anchor: &Contract,
@@ -358,3 +375,268 @@ pub async fn get_appchain_challenges(
         .await?
         .json::<Vec<AppchainChallenge>>()
 }
+
+pub async fn get_appchain_challenge_state(
+    worker: &Worker<Sandbox>,
+    anchor: &Contract,
+    index: u64,
+) -> anyhow::Result<Option<AppchainChallengeState>> {
+    anchor
+        .call(worker, "get_appchain_challenge_state")
+        .args_json(json!({ "index": U64::from(index) }))?
+        .view()
+        .await?
+        .json::<Option<AppchainChallengeState>>()
+}
+
+pub async fn get_appchain_challenge_states(
+    worker: &Worker<Sandbox>,
+    anchor: &Contract,
+    start_index: u64,
+    quantity: Option<U64>,
+    status: Option<AppchainChallengeStatus>,
+    submitter_account: Option<String>,
+) -> anyhow::Result<Vec<AppchainChallengeState>> {
+    anchor
+        .call(worker, "get_appchain_challenge_states")
+        .args_json(json!({
+            "start_index": U64::from(start_index),
+            "quantity": quantity,
+            "status": status,
+            "submitter_account": submitter_account
+        }))?
+        .view()
+        .await?
+        .json::<Vec<AppchainChallengeState>>()
+}
+
+pub async fn get_pending_message_batch_attestations(
+    worker: &Worker<Sandbox>,
+    anchor: &Contract,
+) -> anyhow::Result<Vec<MessageBatchAttestation>> {
+    anchor
+        .call(worker, "get_pending_message_batch_attestations")
+        .view()
+        .await?
+        .json::<Vec<MessageBatchAttestation>>()
+}
+
+pub async fn get_witness_bond_of(
+    worker: &Worker<Sandbox>,
+    anchor: &Contract,
+    account_id: AccountId,
+) -> anyhow::Result<U128> {
+    anchor
+        .call(worker, "get_witness_bond_of")
+        .args_json(json!({ "account_id": account_id }))?
+        .view()
+        .await?
+        .json::<U128>()
+}
+
+pub async fn get_appchain_settlement_status(
+    worker: &Worker<Sandbox>,
+    anchor: &Contract,
+) -> anyhow::Result<Option<AppchainSettlementStatus>> {
+    anchor
+        .call(worker, "get_appchain_settlement_status")
+        .view()
+        .await?
+        .json::<Option<AppchainSettlementStatus>>()
+}
+
+pub async fn get_net_locked_balance_of(
+    worker: &Worker<Sandbox>,
+    anchor: &Contract,
+    contract_account: AccountId,
+    account_id: AccountId,
+) -> anyhow::Result<U128> {
+    anchor
+        .call(worker, "get_net_locked_balance_of")
+        .args_json(json!({
+            "contract_account": contract_account,
+            "account_id": account_id
+        }))?
+        .view()
+        .await?
+        .json::<U128>()
+}
+
+pub async fn get_retained_commitments_of_appchain(
+    worker: &Worker<Sandbox>,
+    anchor: &Contract,
+) -> anyhow::Result<Vec<RetainedAppchainCommitment>> {
+    anchor
+        .call(worker, "get_retained_commitments_of_appchain")
+        .view()
+        .await?
+        .json::<Vec<RetainedAppchainCommitment>>()
+}
+
+pub async fn get_light_client_status(
+    worker: &Worker<Sandbox>,
+    anchor: &Contract,
+) -> anyhow::Result<LightClientStatus> {
+    anchor
+        .call(worker, "get_light_client_status")
+        .view()
+        .await?
+        .json::<LightClientStatus>()
+}
+
+pub async fn get_bridge_fee_of_token(
+    worker: &Worker<Sandbox>,
+    anchor: &Contract,
+    contract_account: AccountId,
+) -> anyhow::Result<Option<BridgeFeeOfToken>> {
+    anchor
+        .call(worker, "get_bridge_fee_of_token")
+        .args_json(json!({ "contract_account": contract_account }))?
+        .view()
+        .await?
+        .json::<Option<BridgeFeeOfToken>>()
+}
+
+pub async fn get_nft_bridge_fee_deposit_of(
+    worker: &Worker<Sandbox>,
+    anchor: &Contract,
+    account_id: AccountId,
+) -> anyhow::Result<NftBridgeFeeBalance> {
+    anchor
+        .call(worker, "get_nft_bridge_fee_deposit_of")
+        .args_json(json!({ "account_id": account_id }))?
+        .view()
+        .await?
+        .json::<NftBridgeFeeBalance>()
+}
+
+pub async fn get_era_number_of_grandpa_set(
+    worker: &Worker<Sandbox>,
+    anchor: &Contract,
+    set_id: u64,
+) -> anyhow::Result<Option<U64>> {
+    anchor
+        .call(worker, "get_era_number_of_grandpa_set")
+        .args_json(json!({ "set_id": U64::from(set_id) }))?
+        .view()
+        .await?
+        .json::<Option<U64>>()
+}
+
+pub async fn get_appchain_liveness(
+    worker: &Worker<Sandbox>,
+    anchor: &Contract,
+) -> anyhow::Result<AppchainLiveness> {
+    anchor
+        .call(worker, "get_appchain_liveness")
+        .view()
+        .await?
+        .json::<AppchainLiveness>()
+}
+
+pub async fn get_pending_transfers_of(
+    worker: &Worker<Sandbox>,
+    anchor: &Contract,
+    account: &Account,
+) -> anyhow::Result<Vec<PendingTransfer>> {
+    anchor
+        .call(worker, "get_pending_transfers_of")
+        .args_json(json!({ "account_id": account.id() }))?
+        .view()
+        .await?
+        .json::<Vec<PendingTransfer>>()
+}
+
+pub async fn get_bridge_storage_fund(
+    worker: &Worker<Sandbox>,
+    anchor: &Contract,
+) -> anyhow::Result<BridgeStorageFund> {
+    anchor
+        .call(worker, "get_bridge_storage_fund")
+        .view()
+        .await?
+        .json::<BridgeStorageFund>()
+}
+
+pub async fn get_storage_registration_of_token(
+    worker: &Worker<Sandbox>,
+    anchor: &Contract,
+    contract_account: &Contract,
+) -> anyhow::Result<Option<StorageRegistrationOfToken>> {
+    anchor
+        .call(worker, "get_storage_registration_of_token")
+        .args_json(json!({ "contract_account": contract_account.id() }))?
+        .view()
+        .await?
+        .json::<Option<StorageRegistrationOfToken>>()
+}
+
+pub async fn get_failed_appchain_messages(
+    worker: &Worker<Sandbox>,
+    anchor: &Contract,
+) -> anyhow::Result<Vec<FailedAppchainMessage>> {
+    anchor
+        .call(worker, "get_failed_appchain_messages")
+        .view()
+        .await?
+        .json::<Vec<FailedAppchainMessage>>()
+}
+
+pub async fn get_failed_appchain_message(
+    worker: &Worker<Sandbox>,
+    anchor: &Contract,
+    nonce: u32,
+) -> anyhow::Result<Option<FailedAppchainMessage>> {
+    anchor
+        .call(worker, "get_failed_appchain_message")
+        .args_json(json!({ "nonce": nonce }))?
+        .view()
+        .await?
+        .json::<Option<FailedAppchainMessage>>()
+}
+
+pub async fn get_bridge_rate_limit_of_asset(
+    worker: &Worker<Sandbox>,
+    anchor: &Contract,
+    contract_account: &Contract,
+) -> anyhow::Result<Option<BridgeRateLimit>> {
+    anchor
+        .call(worker, "get_bridge_rate_limit_of_asset")
+        .args_json(json!({ "contract_account": contract_account.id() }))?
+        .view()
+        .await?
+        .json::<Option<BridgeRateLimit>>()
+}
+
+pub async fn get_native_near_token(
+    worker: &Worker<Sandbox>,
+    anchor: &Contract,
+) -> anyhow::Result<NativeNearToken> {
+    anchor
+        .call(worker, "get_native_near_token")
+        .view()
+        .await?
+        .json::<NativeNearToken>()
+}
+
+pub async fn get_near_nft_collections(
+    worker: &Worker<Sandbox>,
+    anchor: &Contract,
+) -> anyhow::Result<Vec<NearNFTCollection>> {
+    anchor
+        .call(worker, "get_near_nft_collections")
+        .view()
+        .await?
+        .json::<Vec<NearNFTCollection>>()
+}
+
+pub async fn get_wrapped_appchain_assets(
+    worker: &Worker<Sandbox>,
+    anchor: &Contract,
+) -> anyhow::Result<Vec<WrappedAppchainAsset>> {
+    anchor
+        .call(worker, "get_wrapped_appchain_assets")
+        .view()
+        .await?
+        .json::<Vec<WrappedAppchainAsset>>()
+}
@@ -0,0 +1,69 @@
+use appchain_anchor::types::{BridgeFee, NftBridgeFeeAsset};
+use near_sdk::{json_types::U128, serde_json::json, AccountId};
+use workspaces::{network::Sandbox, result::CallExecutionDetails, Account, Contract, Worker};
+
+pub async fn set_bridge_fee_of_token(
+    worker: &Worker<Sandbox>,
+    signer: &Account,
+    anchor: &Contract,
+    contract_account: AccountId,
+    fee_to_appchain: BridgeFee,
+    fee_to_near: BridgeFee,
+) -> anyhow::Result<CallExecutionDetails> {
+    signer
+        .call(worker, anchor.id(), "set_bridge_fee_of_token")
+        .args_json(json!({
+            "contract_account": contract_account,
+            "fee_to_appchain": fee_to_appchain,
+            "fee_to_near": fee_to_near,
+        }))?
+        .gas(200_000_000_000_000)
+        .transact()
+        .await
+}
+
+pub async fn withdraw_bridge_fee_of_token(
+    worker: &Worker<Sandbox>,
+    signer: &Account,
+    anchor: &Contract,
+    contract_account: AccountId,
+) -> anyhow::Result<CallExecutionDetails> {
+    signer
+        .call(worker, anchor.id(), "withdraw_bridge_fee_of_token")
+        .args_json(json!({ "contract_account": contract_account }))?
+        .gas(200_000_000_000_000)
+        .transact()
+        .await
+}
+
+pub async fn deposit_nft_bridge_fee(
+    worker: &Worker<Sandbox>,
+    signer: &Account,
+    anchor: &Contract,
+    amount: u128,
+) -> anyhow::Result<CallExecutionDetails> {
+    signer
+        .call(worker, anchor.id(), "deposit_nft_bridge_fee")
+        .gas(200_000_000_000_000)
+        .deposit(amount)
+        .transact()
+        .await
+}
+
+pub async fn withdraw_nft_bridge_fee_deposit(
+    worker: &Worker<Sandbox>,
+    signer: &Account,
+    anchor: &Contract,
+    fee_asset: NftBridgeFeeAsset,
+    amount: u128,
+) -> anyhow::Result<CallExecutionDetails> {
+    signer
+        .call(worker, anchor.id(), "withdraw_nft_bridge_fee_deposit")
+        .args_json(json!({
+            "fee_asset": fee_asset,
+            "amount": U128::from(amount),
+        }))?
+        .gas(200_000_000_000_000)
+        .transact()
+        .await
+}
@@ -0,0 +1,44 @@
+use near_sdk::{
+    json_types::{U128, U64},
+    serde_json::json,
+    AccountId,
+};
+use workspaces::{network::Sandbox, result::CallExecutionDetails, Account, Contract, Worker};
+
+pub async fn set_bridge_rate_limit_of_asset(
+    worker: &Worker<Sandbox>,
+    signer: &Account,
+    anchor: &Contract,
+    contract_account: AccountId,
+    maximum_amount_per_transfer: Option<U128>,
+    window_length_in_seconds: U64,
+    maximum_volume_per_window_to_appchain: Option<U128>,
+    maximum_volume_per_window_to_near: Option<U128>,
+) -> anyhow::Result<CallExecutionDetails> {
+    signer
+        .call(worker, anchor.id(), "set_bridge_rate_limit_of_asset")
+        .args_json(json!({
+            "contract_account": contract_account,
+            "maximum_amount_per_transfer": maximum_amount_per_transfer,
+            "window_length_in_seconds": window_length_in_seconds,
+            "maximum_volume_per_window_to_appchain": maximum_volume_per_window_to_appchain,
+            "maximum_volume_per_window_to_near": maximum_volume_per_window_to_near,
+        }))?
+        .gas(200_000_000_000_000)
+        .transact()
+        .await
+}
+
+pub async fn remove_bridge_rate_limit_of_asset(
+    worker: &Worker<Sandbox>,
+    signer: &Account,
+    anchor: &Contract,
+    contract_account: AccountId,
+) -> anyhow::Result<CallExecutionDetails> {
+    signer
+        .call(worker, anchor.id(), "remove_bridge_rate_limit_of_asset")
+        .args_json(json!({ "contract_account": contract_account }))?
+        .gas(200_000_000_000_000)
+        .transact()
+        .await
+}
@@ -38,3 +38,27 @@ pub async fn initialize_beefy_light_client(
         .transact()
         .await
 }
+
+pub async fn go_broken(
+    worker: &Worker<Sandbox>,
+    signer: &Account,
+    anchor: &Contract,
+) -> anyhow::Result<CallExecutionDetails> {
+    signer
+        .call(worker, anchor.id(), "go_broken")
+        .gas(200_000_000_000_000)
+        .transact()
+        .await
+}
+
+pub async fn go_dead(
+    worker: &Worker<Sandbox>,
+    signer: &Account,
+    anchor: &Contract,
+) -> anyhow::Result<CallExecutionDetails> {
+    signer
+        .call(worker, anchor.id(), "go_dead")
+        .gas(200_000_000_000_000)
+        .transact()
+        .await
+}
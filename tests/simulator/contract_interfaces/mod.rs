@@ -1,9 +1,17 @@
 pub mod anchor_viewer;
+pub mod bridge_fee_manager;
+pub mod bridge_rate_limit_manager;
 pub mod lifecycle_actions;
+pub mod native_near_token_manager;
 pub mod near_fungible_token_manager;
+pub mod near_nft_collection_manager;
+pub mod owner_actions;
 pub mod permissionless_actions;
 pub mod settings_manager;
 pub mod staking_actions;
+pub mod sudo_actions;
 pub mod validator_actions;
+pub mod witness_actions;
+pub mod wrapped_appchain_asset_manager;
 pub mod wrapped_appchain_nft_manager;
 pub mod wrapped_appchain_token_manager;
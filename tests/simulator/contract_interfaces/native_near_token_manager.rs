@@ -0,0 +1,42 @@
+use near_sdk::serde_json::json;
+use workspaces::{network::Sandbox, result::CallExecutionDetails, Account, Contract, Worker};
+
+pub async fn open_bridging_of_native_near_token(
+    worker: &Worker<Sandbox>,
+    signer: &Account,
+    anchor: &Contract,
+) -> anyhow::Result<CallExecutionDetails> {
+    signer
+        .call(worker, anchor.id(), "open_bridging_of_native_near_token")
+        .gas(200_000_000_000_000)
+        .transact()
+        .await
+}
+
+pub async fn close_bridging_of_native_near_token(
+    worker: &Worker<Sandbox>,
+    signer: &Account,
+    anchor: &Contract,
+) -> anyhow::Result<CallExecutionDetails> {
+    signer
+        .call(worker, anchor.id(), "close_bridging_of_native_near_token")
+        .gas(200_000_000_000_000)
+        .transact()
+        .await
+}
+
+pub async fn lock_near_to_appchain(
+    worker: &Worker<Sandbox>,
+    signer: &Account,
+    anchor: &Contract,
+    receiver_id_in_appchain: String,
+    amount: u128,
+) -> anyhow::Result<CallExecutionDetails> {
+    signer
+        .call(worker, anchor.id(), "lock_near_to_appchain")
+        .args_json(json!({ "receiver_id_in_appchain": receiver_id_in_appchain }))?
+        .gas(200_000_000_000_000)
+        .deposit(amount)
+        .transact()
+        .await
+}
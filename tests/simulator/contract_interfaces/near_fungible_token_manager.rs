@@ -24,3 +24,17 @@ pub async fn register_near_fungible_token(
         .transact()
         .await
 }
+
+pub async fn open_bridging_of_near_fungible_token(
+    worker: &Worker<Sandbox>,
+    signer: &Account,
+    anchor: &Contract,
+    symbol: String,
+) -> anyhow::Result<CallExecutionDetails> {
+    signer
+        .call(worker, anchor.id(), "open_bridging_of_near_fungible_token")
+        .args_json(json!({ "symbol": symbol }))?
+        .gas(200_000_000_000_000)
+        .transact()
+        .await
+}
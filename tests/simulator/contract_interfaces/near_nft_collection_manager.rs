@@ -0,0 +1,30 @@
+use near_sdk::{serde_json::json, AccountId};
+use workspaces::{network::Sandbox, result::CallExecutionDetails, Account, Contract, Worker};
+
+pub async fn register_near_nft_collection(
+    worker: &Worker<Sandbox>,
+    signer: &Account,
+    anchor: &Contract,
+    contract_account: AccountId,
+) -> anyhow::Result<CallExecutionDetails> {
+    signer
+        .call(worker, anchor.id(), "register_near_nft_collection")
+        .args_json(json!({ "contract_account": contract_account }))?
+        .gas(200_000_000_000_000)
+        .transact()
+        .await
+}
+
+pub async fn open_bridging_of_near_nft_collection(
+    worker: &Worker<Sandbox>,
+    signer: &Account,
+    anchor: &Contract,
+    contract_account: AccountId,
+) -> anyhow::Result<CallExecutionDetails> {
+    signer
+        .call(worker, anchor.id(), "open_bridging_of_near_nft_collection")
+        .args_json(json!({ "contract_account": contract_account }))?
+        .gas(200_000_000_000_000)
+        .transact()
+        .await
+}
@@ -0,0 +1,82 @@
+use near_sdk::{json_types::U64, serde_json::json};
+use workspaces::{network::Sandbox, result::CallExecutionDetails, Account, Contract, Worker};
+
+pub async fn remove_validator_set_before(
+    worker: &Worker<Sandbox>,
+    signer: &Account,
+    anchor: &Contract,
+    era_number: u64,
+) -> anyhow::Result<CallExecutionDetails> {
+    signer
+        .call(worker, anchor.id(), "remove_validator_set_before")
+        .args_json(json!({
+            "era_number": U64::from(era_number),
+        }))?
+        .gas(200_000_000_000_000)
+        .transact()
+        .await
+}
+
+pub async fn remove_staking_history_before(
+    worker: &Worker<Sandbox>,
+    signer: &Account,
+    anchor: &Contract,
+    index: u64,
+) -> anyhow::Result<CallExecutionDetails> {
+    signer
+        .call(worker, anchor.id(), "remove_staking_history_before")
+        .args_json(json!({
+            "index": U64::from(index),
+        }))?
+        .gas(200_000_000_000_000)
+        .transact()
+        .await
+}
+
+pub async fn deposit_bridge_storage_fund(
+    worker: &Worker<Sandbox>,
+    signer: &Account,
+    anchor: &Contract,
+    amount: u128,
+) -> anyhow::Result<CallExecutionDetails> {
+    signer
+        .call(worker, anchor.id(), "deposit_bridge_storage_fund")
+        .gas(200_000_000_000_000)
+        .deposit(amount)
+        .transact()
+        .await
+}
+
+pub async fn enable_storage_registration_of_token(
+    worker: &Worker<Sandbox>,
+    signer: &Account,
+    anchor: &Contract,
+    contract_account: &Contract,
+) -> anyhow::Result<CallExecutionDetails> {
+    signer
+        .call(worker, anchor.id(), "enable_storage_registration_of_token")
+        .args_json(json!({
+            "contract_account": contract_account.id(),
+        }))?
+        .gas(200_000_000_000_000)
+        .transact()
+        .await
+}
+
+pub async fn mark_appchain_message_abandoned(
+    worker: &Worker<Sandbox>,
+    signer: &Account,
+    anchor: &Contract,
+    nonce: u32,
+    reason: &str,
+) -> anyhow::Result<CallExecutionDetails> {
+    signer
+        .call(worker, anchor.id(), "mark_appchain_message_abandoned")
+        .args_json(json!({
+            "nonce": nonce,
+            "reason": reason,
+        }))?
+        .gas(200_000_000_000_000)
+        .transact()
+        .await
+}
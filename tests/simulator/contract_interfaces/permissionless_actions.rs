@@ -87,3 +87,150 @@ pub async fn try_complete_updating_state_of_beefy_light_client(
     println!("{:?}", result);
     result.json::<MultiTxsOperationProcessingResult>()
 }
+
+pub async fn attest_appchain_message_batch(
+    worker: &Worker<Sandbox>,
+    signer: &Account,
+    anchor: &Contract,
+    batch_hash: String,
+) -> anyhow::Result<CallExecutionDetails> {
+    signer
+        .call(worker, anchor.id(), "attest_appchain_message_batch")
+        .gas(200_000_000_000_000)
+        .args_json(json!({ "batch_hash": batch_hash }))?
+        .transact()
+        .await
+}
+
+pub async fn settle_dead_appchain(
+    worker: &Worker<Sandbox>,
+    signer: &Account,
+    anchor: &Contract,
+) -> anyhow::Result<MultiTxsOperationProcessingResult> {
+    let result = signer
+        .call(worker, anchor.id(), "settle_dead_appchain")
+        .gas(300_000_000_000_000)
+        .transact()
+        .await?;
+    println!("{:?}", result);
+    println!();
+    result.json::<MultiTxsOperationProcessingResult>()
+}
+
+pub async fn verify_and_stage_appchain_messages_with_justification(
+    worker: &Worker<Sandbox>,
+    signer: &Account,
+    anchor: &Contract,
+    encoded_messages: Vec<u8>,
+    header: Vec<u8>,
+    justification: Vec<u8>,
+) -> anyhow::Result<CallExecutionDetails> {
+    let result = signer
+        .call(worker, anchor.id(), "verify_and_stage_appchain_messages")
+        .gas(300_000_000_000_000)
+        .args_json(json!({
+            "encoded_messages": encoded_messages,
+            "header": header,
+            "mmr_leaf": Vec::<u8>::new(),
+            "mmr_proof": Vec::<u8>::new(),
+            "justification": justification
+        }))?
+        .transact()
+        .await;
+    println!("{:?}", result);
+    println!();
+    result
+}
+
+pub async fn verify_and_stage_appchain_messages_with_commitment(
+    worker: &Worker<Sandbox>,
+    signer: &Account,
+    anchor: &Contract,
+    encoded_messages: Vec<u8>,
+    header: Vec<u8>,
+    mmr_leaf: Vec<u8>,
+    mmr_proof: Vec<u8>,
+    commitment_block_number: u32,
+) -> anyhow::Result<CallExecutionDetails> {
+    let result = signer
+        .call(worker, anchor.id(), "verify_and_stage_appchain_messages")
+        .gas(300_000_000_000_000)
+        .args_json(json!({
+            "encoded_messages": encoded_messages,
+            "header": header,
+            "mmr_leaf": mmr_leaf,
+            "mmr_proof": mmr_proof,
+            "commitment_block_number": commitment_block_number
+        }))?
+        .transact()
+        .await;
+    println!("{:?}", result);
+    println!();
+    result
+}
+
+pub async fn update_state_of_grandpa_light_client(
+    worker: &Worker<Sandbox>,
+    signer: &Account,
+    anchor: &Contract,
+    header: Vec<u8>,
+    justification: Vec<u8>,
+) -> anyhow::Result<CallExecutionDetails> {
+    let result = signer
+        .call(worker, anchor.id(), "update_state_of_grandpa_light_client")
+        .gas(300_000_000_000_000)
+        .args_json(json!({
+            "header": header,
+            "justification": justification
+        }))?
+        .transact()
+        .await;
+    println!("{:?}", result);
+    println!();
+    result
+}
+
+pub async fn check_appchain_liveness(
+    worker: &Worker<Sandbox>,
+    signer: &Account,
+    anchor: &Contract,
+) -> anyhow::Result<MultiTxsOperationProcessingResult> {
+    signer
+        .call(worker, anchor.id(), "check_appchain_liveness")
+        .gas(300_000_000_000_000)
+        .transact()
+        .await?
+        .json::<MultiTxsOperationProcessingResult>()
+}
+
+pub async fn claim_pending_transfer(
+    worker: &Worker<Sandbox>,
+    signer: &Account,
+    anchor: &Contract,
+    receiver_id_in_near: &Account,
+    appchain_message_nonce: u32,
+) -> anyhow::Result<CallExecutionDetails> {
+    signer
+        .call(worker, anchor.id(), "claim_pending_transfer")
+        .args_json(json!({
+            "receiver_id_in_near": receiver_id_in_near.id(),
+            "appchain_message_nonce": appchain_message_nonce
+        }))?
+        .gas(300_000_000_000_000)
+        .transact()
+        .await
+}
+
+pub async fn retry_appchain_message(
+    worker: &Worker<Sandbox>,
+    signer: &Account,
+    anchor: &Contract,
+    nonce: u32,
+) -> anyhow::Result<CallExecutionDetails> {
+    signer
+        .call(worker, anchor.id(), "retry_appchain_message")
+        .args_json(json!({ "nonce": nonce }))?
+        .gas(300_000_000_000_000)
+        .transact()
+        .await
+}
@@ -1,3 +1,4 @@
+use appchain_anchor::types::LightClientType;
 use near_sdk::{
     json_types::{U128, U64},
     serde_json::json,
@@ -90,6 +91,22 @@ pub async fn set_token_price_maintainer_account(
         .await
 }
 
+pub async fn set_bridge_fee_collector_account(
+    worker: &Worker<Sandbox>,
+    signer: &Account,
+    anchor: &Contract,
+    account: &Account,
+) -> anyhow::Result<CallExecutionDetails> {
+    signer
+        .call(worker, anchor.id(), "set_bridge_fee_collector_account")
+        .args_json(json!({
+            "account_id": account.id()
+        }))?
+        .gas(200_000_000_000_000)
+        .transact()
+        .await
+}
+
 pub async fn set_relayer_account(
     worker: &Worker<Sandbox>,
     signer: &Account,
@@ -187,3 +204,158 @@ pub async fn turn_off_beefy_light_client_witness_mode(
         .transact()
         .await
 }
+
+pub async fn add_witness_account(
+    worker: &Worker<Sandbox>,
+    signer: &Account,
+    anchor: &Contract,
+    account: &Account,
+) -> anyhow::Result<CallExecutionDetails> {
+    signer
+        .call(worker, anchor.id(), "add_witness_account")
+        .args_json(json!({
+            "account_id": account.id()
+        }))?
+        .gas(200_000_000_000_000)
+        .transact()
+        .await
+}
+
+pub async fn set_witness_threshold(
+    worker: &Worker<Sandbox>,
+    signer: &Account,
+    anchor: &Contract,
+    value: u16,
+) -> anyhow::Result<CallExecutionDetails> {
+    signer
+        .call(worker, anchor.id(), "set_witness_threshold")
+        .args_json(json!({ "value": value }))?
+        .gas(200_000_000_000_000)
+        .transact()
+        .await
+}
+
+pub async fn change_challenge_period_of_witnessed_messages(
+    worker: &Worker<Sandbox>,
+    signer: &Account,
+    anchor: &Contract,
+    value: u64,
+) -> anyhow::Result<CallExecutionDetails> {
+    signer
+        .call(
+            worker,
+            anchor.id(),
+            "change_challenge_period_of_witnessed_messages",
+        )
+        .args_json(json!({ "value": U64::from(value) }))?
+        .gas(200_000_000_000_000)
+        .transact()
+        .await
+}
+
+pub async fn change_witness_bond_amount(
+    worker: &Worker<Sandbox>,
+    signer: &Account,
+    anchor: &Contract,
+    value: u128,
+) -> anyhow::Result<CallExecutionDetails> {
+    signer
+        .call(worker, anchor.id(), "change_witness_bond_amount")
+        .args_json(json!({ "value": U128::from(value) }))?
+        .gas(200_000_000_000_000)
+        .transact()
+        .await
+}
+
+pub async fn set_light_client_type(
+    worker: &Worker<Sandbox>,
+    signer: &Account,
+    anchor: &Contract,
+    light_client_type: LightClientType,
+) -> anyhow::Result<CallExecutionDetails> {
+    signer
+        .call(worker, anchor.id(), "set_light_client_type")
+        .args_json(json!({ "light_client_type": light_client_type }))?
+        .gas(200_000_000_000_000)
+        .transact()
+        .await
+}
+
+pub async fn change_validator_set_checkpoint_interval(
+    worker: &Worker<Sandbox>,
+    signer: &Account,
+    anchor: &Contract,
+    value: u64,
+) -> anyhow::Result<CallExecutionDetails> {
+    signer
+        .call(
+            worker,
+            anchor.id(),
+            "change_validator_set_checkpoint_interval",
+        )
+        .args_json(json!({ "value": U64::from(value) }))?
+        .gas(200_000_000_000_000)
+        .transact()
+        .await
+}
+
+pub async fn change_validator_commission_percent(
+    worker: &Worker<Sandbox>,
+    signer: &Account,
+    anchor: &Contract,
+    value: u16,
+) -> anyhow::Result<CallExecutionDetails> {
+    signer
+        .call(worker, anchor.id(), "change_validator_commission_percent")
+        .args_json(json!({ "value": value }))?
+        .gas(200_000_000_000_000)
+        .transact()
+        .await
+}
+
+pub async fn change_slashing_percent_of_offence_kind(
+    worker: &Worker<Sandbox>,
+    signer: &Account,
+    anchor: &Contract,
+    kind: &str,
+    value: u16,
+) -> anyhow::Result<CallExecutionDetails> {
+    signer
+        .call(
+            worker,
+            anchor.id(),
+            "change_slashing_percent_of_offence_kind",
+        )
+        .args_json(json!({ "kind": kind, "value": value }))?
+        .gas(200_000_000_000_000)
+        .transact()
+        .await
+}
+
+pub async fn change_liveness_degraded_threshold(
+    worker: &Worker<Sandbox>,
+    signer: &Account,
+    anchor: &Contract,
+    value: u64,
+) -> anyhow::Result<CallExecutionDetails> {
+    signer
+        .call(worker, anchor.id(), "change_liveness_degraded_threshold")
+        .args_json(json!({ "value": U64::from(value) }))?
+        .gas(200_000_000_000_000)
+        .transact()
+        .await
+}
+
+pub async fn change_liveness_broken_threshold(
+    worker: &Worker<Sandbox>,
+    signer: &Account,
+    anchor: &Contract,
+    value: u64,
+) -> anyhow::Result<CallExecutionDetails> {
+    signer
+        .call(worker, anchor.id(), "change_liveness_broken_threshold")
+        .args_json(json!({ "value": U64::from(value) }))?
+        .gas(200_000_000_000_000)
+        .transact()
+        .await
+}
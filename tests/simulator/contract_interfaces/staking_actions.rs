@@ -1,5 +1,9 @@
 use crate::common;
-use near_sdk::{json_types::U128, serde_json::json, AccountId};
+use near_sdk::{
+    json_types::{U128, U64},
+    serde_json::json,
+    AccountId,
+};
 use std::collections::HashMap;
 use workspaces::{network::Sandbox, result::CallExecutionDetails, Account, Contract, Worker};
 
@@ -203,3 +207,41 @@ pub async fn withdraw_delegator_rewards(
         .transact()
         .await
 }
+
+pub async fn redelegate(
+    worker: &Worker<Sandbox>,
+    signer: &Account,
+    anchor: &Contract,
+    from_validator_id: &AccountId,
+    to_validator_id: &AccountId,
+    amount: u128,
+) -> anyhow::Result<CallExecutionDetails> {
+    signer
+        .call(worker, anchor.id(), "redelegate")
+        .args_json(json!({
+            "from_validator_id": from_validator_id,
+            "to_validator_id": to_validator_id,
+            "amount": U128::from(amount)
+        }))?
+        .gas(200_000_000_000_000)
+        .transact()
+        .await
+}
+
+pub async fn rebond(
+    worker: &Worker<Sandbox>,
+    signer: &Account,
+    anchor: &Contract,
+    staking_history_index: U64,
+    amount: Option<u128>,
+) -> anyhow::Result<CallExecutionDetails> {
+    signer
+        .call(worker, anchor.id(), "rebond")
+        .args_json(json!({
+            "staking_history_index": staking_history_index,
+            "amount": amount.map(U128::from)
+        }))?
+        .gas(200_000_000_000_000)
+        .transact()
+        .await
+}
@@ -0,0 +1,20 @@
+use near_sdk::{json_types::U64, serde_json::json};
+use workspaces::{network::Sandbox, result::CallExecutionDetails, Account, Contract, Worker};
+
+pub async fn reset_grandpa_light_client(
+    worker: &Worker<Sandbox>,
+    signer: &Account,
+    anchor: &Contract,
+    initial_public_keys: Vec<String>,
+    set_id: u64,
+) -> anyhow::Result<CallExecutionDetails> {
+    signer
+        .call(worker, anchor.id(), "reset_grandpa_light_client")
+        .args_json(json!({
+            "initial_public_keys": initial_public_keys,
+            "set_id": U64::from(set_id),
+        }))?
+        .gas(200_000_000_000_000)
+        .transact()
+        .await
+}
@@ -31,3 +31,31 @@ pub async fn set_validator_profile(
         .transact()
         .await
 }
+
+pub async fn change_validator_commission(
+    worker: &Worker<Sandbox>,
+    signer: &Account,
+    anchor: &Contract,
+    commission_percent: u16,
+) -> anyhow::Result<CallExecutionDetails> {
+    signer
+        .call(worker, anchor.id(), "change_validator_commission")
+        .args_json(json!({ "commission_percent": commission_percent }))?
+        .gas(200_000_000_000_000)
+        .transact()
+        .await
+}
+
+pub async fn set_grandpa_key_in_appchain(
+    worker: &Worker<Sandbox>,
+    signer: &Account,
+    anchor: &Contract,
+    grandpa_key: &String,
+) -> anyhow::Result<CallExecutionDetails> {
+    signer
+        .call(worker, anchor.id(), "set_grandpa_key_in_appchain")
+        .args_json(json!({ "grandpa_key": grandpa_key }))?
+        .gas(200_000_000_000_000)
+        .transact()
+        .await
+}
@@ -0,0 +1,36 @@
+use crate::common;
+use near_sdk::serde_json::json;
+use workspaces::{network::Sandbox, result::CallExecutionDetails, Account, Contract, Worker};
+
+pub async fn bond_as_witness(
+    worker: &Worker<Sandbox>,
+    signer: &Account,
+    oct_token: &Contract,
+    anchor: &Contract,
+    amount: u128,
+) -> anyhow::Result<CallExecutionDetails> {
+    let result = common::call_ft_transfer_call(
+        worker,
+        signer,
+        &anchor.as_account(),
+        amount,
+        json!("BondAsWitness").to_string(),
+        oct_token,
+    )
+    .await;
+    println!("Result of 'bond_as_witness': {:?}", result);
+    println!();
+    result
+}
+
+pub async fn withdraw_witness_bond(
+    worker: &Worker<Sandbox>,
+    signer: &Account,
+    anchor: &Contract,
+) -> anyhow::Result<CallExecutionDetails> {
+    signer
+        .call(worker, anchor.id(), "withdraw_witness_bond")
+        .gas(200_000_000_000_000)
+        .transact()
+        .await
+}
@@ -0,0 +1,61 @@
+use near_contract_standards::fungible_token::metadata::FungibleTokenMetadata;
+use near_sdk::{json_types::U128, serde_json::json};
+use workspaces::{network::Sandbox, result::CallExecutionDetails, Account, Contract, Worker};
+
+pub async fn register_wrapped_appchain_asset(
+    worker: &Worker<Sandbox>,
+    signer: &Account,
+    anchor: &Contract,
+    asset_id: String,
+    metadata: FungibleTokenMetadata,
+    price: U128,
+) -> anyhow::Result<CallExecutionDetails> {
+    signer
+        .call(worker, anchor.id(), "register_wrapped_appchain_asset")
+        .args_json(json!({
+            "asset_id": asset_id,
+            "metadata": metadata,
+            "price": price
+        }))?
+        .gas(300_000_000_000_000)
+        .transact()
+        .await
+}
+
+pub async fn open_bridging_of_wrapped_appchain_asset(
+    worker: &Worker<Sandbox>,
+    signer: &Account,
+    anchor: &Contract,
+    asset_id: String,
+) -> anyhow::Result<CallExecutionDetails> {
+    signer
+        .call(
+            worker,
+            anchor.id(),
+            "open_bridging_of_wrapped_appchain_asset",
+        )
+        .args_json(json!({ "asset_id": asset_id }))?
+        .gas(200_000_000_000_000)
+        .transact()
+        .await
+}
+
+pub async fn burn_wrapped_appchain_asset(
+    worker: &Worker<Sandbox>,
+    signer: &Account,
+    anchor: &Contract,
+    asset_id: String,
+    receiver_id: String,
+    amount: U128,
+) -> anyhow::Result<CallExecutionDetails> {
+    signer
+        .call(worker, anchor.id(), "burn_wrapped_appchain_asset")
+        .args_json(json!({
+            "asset_id": asset_id,
+            "receiver_id": receiver_id,
+            "amount": amount
+        }))?
+        .gas(200_000_000_000_000)
+        .transact()
+        .await
+}
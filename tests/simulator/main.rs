@@ -2,10 +2,32 @@ mod common;
 mod contract_interfaces;
 
 mod test_anchor_actions;
+mod test_appchain_liveness;
 mod test_beefy_light_client;
 mod test_beefy_light_client_2;
+mod test_bridge_fees;
+mod test_bridge_rate_limits;
+mod test_conspiracy_mmr_challenge;
+mod test_delegator_rewards;
 mod test_equivocation_challenge;
+mod test_grandpa_light_client;
+mod test_message_verifiers;
 mod test_migration;
+mod test_native_near_token;
+mod test_near_nft_collections;
+mod test_pending_transfers;
+mod test_rebond;
+mod test_redelegation;
+mod test_retained_beefy_commitments;
+mod test_retrying_failed_messages;
+mod test_settling_dead_appchain;
+mod test_slashing_offenders;
+mod test_storage_registration;
 mod test_transfer_nft;
 mod test_transfer_oct_to_appchain;
+mod test_validator_commissions;
+mod test_validator_set_checkpoints;
+mod test_witness_committee;
+mod test_witnessed_message_batch_challenge;
+mod test_wrapped_appchain_assets;
 mod test_wrapped_appchain_token;
@@ -0,0 +1,104 @@
+use crate::{
+    common,
+    contract_interfaces::{
+        anchor_viewer, permissionless_actions, settings_manager, staking_actions,
+    },
+};
+use appchain_anchor::types::{AppchainLivenessStatus, AppchainState};
+
+const SECONDS_OF_A_DAY: u64 = 86400;
+
+#[tokio::test]
+async fn test_appchain_liveness() -> anyhow::Result<()> {
+    let worker = workspaces::sandbox().await?;
+    let (root, oct_token, _, _, anchor, _wat_faucet, users, _) =
+        common::test_normal_actions(&worker, false, false, vec!["0x00".to_string()]).await?;
+    //
+    // The unbonded stake can not be withdrawn in unlock period
+    //
+    let result = staking_actions::decrease_delegation(
+        &worker,
+        &users[3],
+        &anchor,
+        &users[0].id().to_string().parse().unwrap(),
+        common::to_actual_amount(500, 18),
+    )
+    .await?;
+    assert!(result.is_success());
+    let user3_balance = common::get_ft_balance_of(&worker, &users[3], &oct_token).await?;
+    let result = staking_actions::withdraw_stake(
+        &worker,
+        &users[3],
+        &anchor,
+        &users[3].id().to_string().parse().unwrap(),
+    )
+    .await?;
+    assert!(result.is_success());
+    assert_eq!(
+        common::get_ft_balance_of(&worker, &users[3], &oct_token)
+            .await?
+            .0,
+        user3_balance.0
+    );
+    //
+    // The appchain is degraded if it is inactive for longer than the degraded threshold
+    //
+    let result =
+        settings_manager::change_liveness_degraded_threshold(&worker, &root, &anchor, 0).await?;
+    assert!(result.is_success());
+    let result =
+        settings_manager::change_liveness_broken_threshold(&worker, &root, &anchor, 1).await?;
+    assert!(result.is_success());
+    assert!(
+        permissionless_actions::check_appchain_liveness(&worker, &users[5], &anchor)
+            .await?
+            .is_ok()
+    );
+    assert_eq!(
+        anchor_viewer::get_appchain_liveness(&worker, &anchor)
+            .await?
+            .status,
+        AppchainLivenessStatus::Degraded
+    );
+    assert_eq!(
+        anchor_viewer::get_appchain_state(&worker, &anchor).await?,
+        AppchainState::Active
+    );
+    //
+    // The appchain is stalled if it is inactive for longer than the broken threshold,
+    // and all stakes are unbonded
+    //
+    worker.fast_forward(SECONDS_OF_A_DAY * 2).await?;
+    while permissionless_actions::check_appchain_liveness(&worker, &users[5], &anchor)
+        .await?
+        .is_need_more_gas()
+    {}
+    assert_eq!(
+        anchor_viewer::get_appchain_liveness(&worker, &anchor)
+            .await?
+            .status,
+        AppchainLivenessStatus::Stalled
+    );
+    assert_eq!(
+        anchor_viewer::get_appchain_state(&worker, &anchor).await?,
+        AppchainState::Broken
+    );
+    //
+    // All unbonded stakes can be withdrawn without waiting for the unlock period
+    //
+    let result = staking_actions::withdraw_stake(
+        &worker,
+        &users[3],
+        &anchor,
+        &users[3].id().to_string().parse().unwrap(),
+    )
+    .await?;
+    assert!(result.is_success());
+    assert_eq!(
+        common::get_ft_balance_of(&worker, &users[3], &oct_token)
+            .await?
+            .0,
+        user3_balance.0 + common::to_actual_amount(2000, 18)
+    );
+    Ok(())
+}
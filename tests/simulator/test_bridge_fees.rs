@@ -0,0 +1,183 @@
+use crate::{
+    common,
+    contract_interfaces::{
+        anchor_viewer, bridge_fee_manager, near_fungible_token_manager, settings_manager,
+    },
+};
+use appchain_anchor::types::{AppchainNotification, BridgeFee, NftBridgeFeeAsset};
+use near_sdk::{json_types::U128, serde_json::json};
+
+#[tokio::test]
+async fn test_bridge_fees() -> anyhow::Result<()> {
+    //
+    let worker = workspaces::sandbox().await?;
+    let (root, oct_token, _, _, anchor, _wat_faucet, users, _) =
+        common::test_normal_actions(&worker, false, false, vec!["0x00".to_string()]).await?;
+    //
+    near_fungible_token_manager::register_near_fungible_token(
+        &worker,
+        &root,
+        &anchor,
+        "OCT".to_string(),
+        "Oct token".to_string(),
+        18,
+        oct_token.id().to_string().parse().unwrap(),
+        U128::from(1000000),
+    )
+    .await
+    .expect("Failed to register NEAR fungible token");
+    let result = near_fungible_token_manager::open_bridging_of_near_fungible_token(
+        &worker,
+        &root,
+        &anchor,
+        "OCT".to_string(),
+    )
+    .await?;
+    assert!(result.is_success());
+    let result =
+        settings_manager::set_bridge_fee_collector_account(&worker, &root, &anchor, &users[5])
+            .await?;
+    assert!(result.is_success());
+    //
+    // The fee to appchain is 1 OCT plus 1% of the transferred amount
+    //
+    let result = bridge_fee_manager::set_bridge_fee_of_token(
+        &worker,
+        &root,
+        &anchor,
+        oct_token.id().to_string().parse().unwrap(),
+        BridgeFee {
+            fixed_amount: U128::from(common::to_actual_amount(1, 18)),
+            percent_in_basis_points: 100,
+        },
+        BridgeFee {
+            fixed_amount: U128::from(0),
+            percent_in_basis_points: 0,
+        },
+    )
+    .await?;
+    assert!(result.is_success());
+    //
+    // The notification of a transfer of 200 OCT carries the net amount 197 OCT
+    //
+    let result = common::call_ft_transfer_call(
+        &worker,
+        &users[0],
+        &anchor.as_account(),
+        common::to_actual_amount(200, 18),
+        json!({
+            "BridgeToAppchain": {
+                "receiver_id_in_appchain": "0xd43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d".to_string(),
+            }
+        })
+        .to_string(),
+        &oct_token,
+    )
+    .await?;
+    assert!(result.is_success());
+    let index_range =
+        anchor_viewer::get_index_range_of_appchain_notification_history(&worker, &anchor).await?;
+    let notification_history =
+        anchor_viewer::get_appchain_notification_history(&worker, &anchor, index_range.end_index.0)
+            .await?
+            .unwrap();
+    match notification_history.appchain_notification {
+        AppchainNotification::NearFungibleTokenLocked { amount, .. } => {
+            assert_eq!(amount.0, common::to_actual_amount(197, 18))
+        }
+        _ => panic!("The latest notification should be 'NearFungibleTokenLocked'."),
+    }
+    let bridge_fee = anchor_viewer::get_bridge_fee_of_token(
+        &worker,
+        &anchor,
+        oct_token.id().to_string().parse().unwrap(),
+    )
+    .await?
+    .unwrap();
+    assert_eq!(
+        bridge_fee.collected_amount.0,
+        common::to_actual_amount(3, 18)
+    );
+    //
+    // Only the fee collector can withdraw the collected fee
+    //
+    let result = bridge_fee_manager::withdraw_bridge_fee_of_token(
+        &worker,
+        &root,
+        &anchor,
+        oct_token.id().to_string().parse().unwrap(),
+    )
+    .await?;
+    assert!(!result.is_success());
+    let collector_balance = common::get_ft_balance_of(&worker, &users[5], &oct_token).await?;
+    let result = bridge_fee_manager::withdraw_bridge_fee_of_token(
+        &worker,
+        &users[5],
+        &anchor,
+        oct_token.id().to_string().parse().unwrap(),
+    )
+    .await?;
+    assert!(result.is_success());
+    assert_eq!(
+        common::get_ft_balance_of(&worker, &users[5], &oct_token)
+            .await?
+            .0,
+        collector_balance.0 + common::to_actual_amount(3, 18)
+    );
+    let bridge_fee = anchor_viewer::get_bridge_fee_of_token(
+        &worker,
+        &anchor,
+        oct_token.id().to_string().parse().unwrap(),
+    )
+    .await?
+    .unwrap();
+    assert_eq!(bridge_fee.collected_amount.0, 0);
+    assert_eq!(
+        bridge_fee.withdrawn_amount.0,
+        common::to_actual_amount(3, 18)
+    );
+    //
+    // The prepaid bridge fee deposit of NFT transfers can be withdrawn by the depositor
+    //
+    let result = bridge_fee_manager::deposit_nft_bridge_fee(
+        &worker,
+        &users[1],
+        &anchor,
+        common::to_actual_amount(1, 24),
+    )
+    .await?;
+    assert!(result.is_success());
+    let deposit = anchor_viewer::get_nft_bridge_fee_deposit_of(
+        &worker,
+        &anchor,
+        users[1].id().to_string().parse().unwrap(),
+    )
+    .await?;
+    assert_eq!(deposit.native_near.0, common::to_actual_amount(1, 24));
+    let result = bridge_fee_manager::withdraw_nft_bridge_fee_deposit(
+        &worker,
+        &users[1],
+        &anchor,
+        NftBridgeFeeAsset::NativeNear,
+        common::to_actual_amount(2, 24),
+    )
+    .await?;
+    assert!(!result.is_success());
+    let result = bridge_fee_manager::withdraw_nft_bridge_fee_deposit(
+        &worker,
+        &users[1],
+        &anchor,
+        NftBridgeFeeAsset::NativeNear,
+        common::to_actual_amount(1, 24),
+    )
+    .await?;
+    assert!(result.is_success());
+    let deposit = anchor_viewer::get_nft_bridge_fee_deposit_of(
+        &worker,
+        &anchor,
+        users[1].id().to_string().parse().unwrap(),
+    )
+    .await?;
+    assert_eq!(deposit.native_near.0, 0);
+    Ok(())
+}
@@ -0,0 +1,142 @@
+use crate::{
+    common::{self, complex_actions},
+    contract_interfaces::{anchor_viewer, bridge_rate_limit_manager, near_fungible_token_manager},
+};
+use appchain_anchor::types::BridgingState;
+use near_sdk::json_types::{U128, U64};
+
+const WINDOW_LENGTH_IN_SECONDS: u64 = 3600;
+
+#[tokio::test]
+async fn test_bridge_rate_limits() -> anyhow::Result<()> {
+    let worker = workspaces::sandbox().await?;
+    let (root, oct_token, _, _, anchor, _wat_faucet, users, _) =
+        common::test_normal_actions(&worker, false, false, vec!["0x00".to_string()]).await?;
+    complex_actions::open_bridging_of_oct_token(&worker, &root, &anchor, &oct_token).await?;
+    //
+    // Only the owner can set the rate limits of an asset
+    //
+    let result = bridge_rate_limit_manager::set_bridge_rate_limit_of_asset(
+        &worker,
+        &users[0],
+        &anchor,
+        oct_token.id().to_string().parse().unwrap(),
+        Some(U128::from(common::to_actual_amount(100, 18))),
+        U64::from(WINDOW_LENGTH_IN_SECONDS),
+        Some(U128::from(common::to_actual_amount(150, 18))),
+        None,
+    )
+    .await?;
+    assert!(!result.is_success());
+    let result = bridge_rate_limit_manager::set_bridge_rate_limit_of_asset(
+        &worker,
+        &root,
+        &anchor,
+        oct_token.id().to_string().parse().unwrap(),
+        Some(U128::from(common::to_actual_amount(100, 18))),
+        U64::from(WINDOW_LENGTH_IN_SECONDS),
+        Some(U128::from(common::to_actual_amount(150, 18))),
+        None,
+    )
+    .await?;
+    assert!(result.is_success());
+    //
+    // The transfer which exceeds the maximum amount per transfer is refunded
+    //
+    let user0_balance = common::get_ft_balance_of(&worker, &users[0], &oct_token).await?;
+    complex_actions::lock_oct_to_appchain(&worker, &users[0], &oct_token, &anchor, 120).await?;
+    assert_eq!(
+        common::get_ft_balance_of(&worker, &users[0], &oct_token)
+            .await?
+            .0,
+        user0_balance.0
+    );
+    //
+    // The transfer within the limits is recorded in the volume of current window
+    //
+    complex_actions::lock_oct_to_appchain(&worker, &users[0], &oct_token, &anchor, 100).await?;
+    assert_eq!(
+        common::get_ft_balance_of(&worker, &users[0], &oct_token)
+            .await?
+            .0,
+        user0_balance.0 - common::to_actual_amount(100, 18)
+    );
+    assert_eq!(
+        anchor_viewer::get_bridge_rate_limit_of_asset(&worker, &anchor, &oct_token)
+            .await?
+            .unwrap()
+            .volume_window_to_appchain
+            .current_volume
+            .0,
+        common::to_actual_amount(100, 18)
+    );
+    //
+    // The transfer which crosses the volume limit in the window is refunded,
+    // and the bridging of the asset is paused
+    //
+    complex_actions::lock_oct_to_appchain(&worker, &users[0], &oct_token, &anchor, 100).await?;
+    assert_eq!(
+        common::get_ft_balance_of(&worker, &users[0], &oct_token)
+            .await?
+            .0,
+        user0_balance.0 - common::to_actual_amount(100, 18)
+    );
+    assert!(
+        anchor_viewer::get_near_fungible_tokens(&worker, &anchor).await?[0].bridging_state
+            == BridgingState::Paused
+    );
+    //
+    // The volume is reset after the window is passed,
+    // and the transfer is accepted after the owner reopens the bridging
+    //
+    let result = near_fungible_token_manager::open_bridging_of_near_fungible_token(
+        &worker,
+        &root,
+        &anchor,
+        "OCT".to_string(),
+    )
+    .await?;
+    assert!(result.is_success());
+    worker.fast_forward(WINDOW_LENGTH_IN_SECONDS * 2).await?;
+    complex_actions::lock_oct_to_appchain(&worker, &users[0], &oct_token, &anchor, 100).await?;
+    assert_eq!(
+        common::get_ft_balance_of(&worker, &users[0], &oct_token)
+            .await?
+            .0,
+        user0_balance.0 - common::to_actual_amount(200, 18)
+    );
+    let bridge_rate_limit =
+        anchor_viewer::get_bridge_rate_limit_of_asset(&worker, &anchor, &oct_token)
+            .await?
+            .unwrap();
+    assert_eq!(
+        bridge_rate_limit
+            .volume_window_to_appchain
+            .previous_volume
+            .0,
+        0
+    );
+    assert_eq!(
+        bridge_rate_limit.volume_window_to_appchain.current_volume.0,
+        common::to_actual_amount(100, 18)
+    );
+    //
+    // The transfer is not limited after the rate limits are removed
+    //
+    let result = bridge_rate_limit_manager::remove_bridge_rate_limit_of_asset(
+        &worker,
+        &root,
+        &anchor,
+        oct_token.id().to_string().parse().unwrap(),
+    )
+    .await?;
+    assert!(result.is_success());
+    complex_actions::lock_oct_to_appchain(&worker, &users[0], &oct_token, &anchor, 200).await?;
+    assert_eq!(
+        common::get_ft_balance_of(&worker, &users[0], &oct_token)
+            .await?
+            .0,
+        user0_balance.0 - common::to_actual_amount(400, 18)
+    );
+    Ok(())
+}
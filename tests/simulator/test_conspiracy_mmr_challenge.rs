@@ -0,0 +1,143 @@
+use crate::{
+    common::{
+        self,
+        beefy_authorities::{single_leaf_mmr_proof, BeefyAuthoritySet},
+        complex_actions::update_state_of_beefy_light_client,
+        grandpa_authorities::make_header,
+    },
+    contract_interfaces::anchor_viewer,
+};
+use appchain_anchor::appchain_challenge::{AppchainChallenge, AppchainChallengeStatus};
+use appchain_anchor::types::AppchainState;
+use near_sdk::serde_json::json;
+use workspaces::{network::Sandbox, result::CallExecutionDetails, Account, Contract, Worker};
+
+const CHALLENGE_BOND_AMOUNT: u128 = 100;
+
+#[tokio::test]
+async fn test_conspiracy_mmr_challenge() -> anyhow::Result<()> {
+    let worker = workspaces::sandbox().await?;
+    let authorities = BeefyAuthoritySet::new(5);
+    let (_, oct_token, _, _, anchor, _, users, _) =
+        common::test_normal_actions(&worker, false, true, authorities.public_keys()).await?;
+    //
+    // The beefy light client is updated while the witness mode is on
+    //
+    let leaf = authorities.make_mmr_leaf(8, make_header(8, Vec::new()).hash());
+    update_state_of_beefy_light_client(&worker, &users[4], &anchor, &authorities, 9, &leaf).await?;
+    let forged_leaf = authorities.make_mmr_leaf(8, [1; 32]);
+    //
+    // The challenge is kept for the owner if the commitment at the block is not retained
+    //
+    let user3_balance = common::get_ft_balance_of(&worker, &users[3], &oct_token).await?;
+    let result = commit_conspiracy_mmr_challenge(
+        &worker,
+        &users[3],
+        &oct_token,
+        &anchor,
+        &authorities,
+        5,
+        &forged_leaf,
+    )
+    .await?;
+    assert!(result.is_success());
+    let challenge_state = anchor_viewer::get_appchain_challenge_state(&worker, &anchor, 0)
+        .await?
+        .unwrap();
+    assert_eq!(challenge_state.status, AppchainChallengeStatus::Submitted);
+    //
+    // The challenge is rejected if it does not conflict with the retained commitment,
+    // and the bond is forfeited
+    //
+    let result = commit_conspiracy_mmr_challenge(
+        &worker,
+        &users[3],
+        &oct_token,
+        &anchor,
+        &authorities,
+        9,
+        &leaf,
+    )
+    .await?;
+    assert!(result.is_success());
+    let challenge_state = anchor_viewer::get_appchain_challenge_state(&worker, &anchor, 1)
+        .await?
+        .unwrap();
+    assert_eq!(challenge_state.status, AppchainChallengeStatus::Rejected);
+    assert_eq!(
+        common::get_ft_balance_of(&worker, &users[3], &oct_token)
+            .await?
+            .0,
+        user3_balance.0 - 2 * common::to_actual_amount(CHALLENGE_BOND_AMOUNT, 18)
+    );
+    assert_eq!(
+        anchor_viewer::get_appchain_state(&worker, &anchor).await?,
+        AppchainState::Active
+    );
+    //
+    // The challenge is accepted if the validators signed a conflicting commitment,
+    // the bond is refunded and the appchain is frozen
+    //
+    let result = commit_conspiracy_mmr_challenge(
+        &worker,
+        &users[3],
+        &oct_token,
+        &anchor,
+        &authorities,
+        9,
+        &forged_leaf,
+    )
+    .await?;
+    assert!(result.is_success());
+    let challenge_state = anchor_viewer::get_appchain_challenge_state(&worker, &anchor, 2)
+        .await?
+        .unwrap();
+    assert_eq!(challenge_state.status, AppchainChallengeStatus::Accepted);
+    assert_eq!(
+        common::get_ft_balance_of(&worker, &users[3], &oct_token)
+            .await?
+            .0,
+        user3_balance.0 - 2 * common::to_actual_amount(CHALLENGE_BOND_AMOUNT, 18)
+    );
+    assert_eq!(
+        anchor_viewer::get_appchain_state(&worker, &anchor).await?,
+        AppchainState::Frozen
+    );
+    Ok(())
+}
+
+/// Commit a conspiracy MMR challenge with the commitment of the given MMR leaf,
+/// which is signed by the given authorities.
+async fn commit_conspiracy_mmr_challenge(
+    worker: &Worker<Sandbox>,
+    signer: &Account,
+    oct_token: &Contract,
+    anchor: &Contract,
+    authorities: &BeefyAuthoritySet,
+    block_number: u32,
+    mmr_leaf: &Vec<u8>,
+) -> anyhow::Result<CallExecutionDetails> {
+    let (signed_commitment, validator_proofs) = authorities.sign_commitment(block_number, mmr_leaf);
+    let appchain_challenge = AppchainChallenge::ConspiracyMmr {
+        submitter_account: signer.id().to_string().parse().unwrap(),
+        block_number,
+        signed_commitment,
+        validator_proofs,
+        mmr_leaf: codec::Encode::encode(mmr_leaf),
+        mmr_proof: single_leaf_mmr_proof(),
+    };
+    common::call_ft_transfer_call(
+        worker,
+        signer,
+        &anchor.as_account(),
+        common::to_actual_amount(CHALLENGE_BOND_AMOUNT, 18),
+        json!({
+            "CommitChallenge": {
+                "appchain_challenge": appchain_challenge
+            }
+        })
+        .to_string(),
+        oct_token,
+    )
+    .await
+}
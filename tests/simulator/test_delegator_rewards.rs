@@ -0,0 +1,243 @@
+use crate::{
+    common,
+    contract_interfaces::{anchor_viewer, staking_actions},
+};
+use appchain_anchor::types::RewardHistory;
+use workspaces::{network::Sandbox, Account, Contract, Worker};
+
+#[tokio::test]
+async fn test_delegator_rewards() -> anyhow::Result<()> {
+    let worker = workspaces::sandbox().await?;
+    let (_, _, wrapped_appchain_token, _, anchor, _wat_faucet, users, mut appchain_message_nonce) =
+        common::test_normal_actions(&worker, false, false, vec!["0x00".to_string()]).await?;
+    //
+    // Switch to era 1 and distribute the reward of era 0
+    //
+    appchain_message_nonce += 1;
+    common::complex_actions::switch_era(
+        &worker,
+        &users[5],
+        &anchor,
+        1,
+        appchain_message_nonce,
+        false,
+    )
+    .await?;
+    appchain_message_nonce += 1;
+    common::complex_actions::distribute_reward_of(
+        &worker,
+        &users[5],
+        &anchor,
+        &wrapped_appchain_token,
+        appchain_message_nonce,
+        0,
+        Vec::new(),
+        false,
+    )
+    .await?;
+    let reward_histories = delegator_rewards_of(&worker, &anchor, 1, &users[2], &users[0]).await?;
+    let reward_of_era_0 = reward_histories[0].total_reward.0;
+    assert!(reward_of_era_0 > 0);
+    assert_eq!(reward_histories[0].unwithdrawn_reward.0, reward_of_era_0);
+    //
+    // The reward of era 0 can only be withdrawn once
+    //
+    assert_eq!(
+        withdraw_delegator_rewards(
+            &worker,
+            &anchor,
+            &wrapped_appchain_token,
+            &users[2],
+            &users[0]
+        )
+        .await?,
+        reward_of_era_0
+    );
+    assert_eq!(
+        withdraw_delegator_rewards(
+            &worker,
+            &anchor,
+            &wrapped_appchain_token,
+            &users[2],
+            &users[0]
+        )
+        .await?,
+        0
+    );
+    let reward_histories = delegator_rewards_of(&worker, &anchor, 1, &users[2], &users[0]).await?;
+    assert_eq!(reward_histories[0].total_reward.0, reward_of_era_0);
+    assert_eq!(reward_histories[0].unwithdrawn_reward.0, 0);
+    //
+    // Only the reward of era 1 is withdrawn after it is distributed
+    //
+    appchain_message_nonce += 1;
+    common::complex_actions::switch_era(
+        &worker,
+        &users[5],
+        &anchor,
+        2,
+        appchain_message_nonce,
+        false,
+    )
+    .await?;
+    appchain_message_nonce += 1;
+    common::complex_actions::distribute_reward_of(
+        &worker,
+        &users[5],
+        &anchor,
+        &wrapped_appchain_token,
+        appchain_message_nonce,
+        1,
+        Vec::new(),
+        false,
+    )
+    .await?;
+    let reward_histories = delegator_rewards_of(&worker, &anchor, 1, &users[2], &users[0]).await?;
+    let reward_of_era_1 = reward_histories[1].total_reward.0;
+    assert!(reward_of_era_1 > 0);
+    assert_eq!(reward_histories[0].unwithdrawn_reward.0, 0);
+    assert_eq!(reward_histories[1].unwithdrawn_reward.0, reward_of_era_1);
+    assert_eq!(
+        withdraw_delegator_rewards(
+            &worker,
+            &anchor,
+            &wrapped_appchain_token,
+            &users[2],
+            &users[0]
+        )
+        .await?,
+        reward_of_era_1
+    );
+    let reward_histories = delegator_rewards_of(&worker, &anchor, 1, &users[2], &users[0]).await?;
+    assert_eq!(reward_histories[1].unwithdrawn_reward.0, 0);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_delegator_rewards_of_fractional_deposit() -> anyhow::Result<()> {
+    let worker = workspaces::sandbox().await?;
+    let (
+        _,
+        oct_token,
+        wrapped_appchain_token,
+        _,
+        anchor,
+        _wat_faucet,
+        users,
+        mut appchain_message_nonce,
+    ) = common::test_normal_actions(&worker, false, false, vec!["0x00".to_string()]).await?;
+    //
+    // user2 delegates 1000 OCT and user3 delegates 1000.5 OCT to user4
+    //
+    let integer_deposit = common::to_actual_amount(1000, 18);
+    let fractional_deposit = common::to_actual_amount(1000, 18) + common::to_actual_amount(5, 17);
+    for (delegator, amount) in [
+        (&users[2], integer_deposit),
+        (&users[3], fractional_deposit),
+    ] {
+        let result = staking_actions::register_delegator(
+            &worker,
+            delegator,
+            &oct_token,
+            &anchor,
+            &users[4].id().to_string().parse().unwrap(),
+            amount,
+        )
+        .await?;
+        assert!(result.is_success());
+    }
+    //
+    // Switch to era 2 and distribute the reward of era 1
+    //
+    for era_number in [1, 2] {
+        appchain_message_nonce += 1;
+        common::complex_actions::switch_era(
+            &worker,
+            &users[5],
+            &anchor,
+            era_number,
+            appchain_message_nonce,
+            false,
+        )
+        .await?;
+    }
+    appchain_message_nonce += 1;
+    common::complex_actions::distribute_reward_of(
+        &worker,
+        &users[5],
+        &anchor,
+        &wrapped_appchain_token,
+        appchain_message_nonce,
+        1,
+        Vec::new(),
+        false,
+    )
+    .await?;
+    //
+    // The reward is in proportion to the whole deposit, including the fractional part
+    //
+    let reward_of_integer_deposit =
+        anchor_viewer::get_delegator_rewards_of(&worker, &anchor, 1, 1, &users[2], &users[4])
+            .await?[0]
+            .total_reward
+            .0;
+    let reward_of_fractional_deposit =
+        anchor_viewer::get_delegator_rewards_of(&worker, &anchor, 1, 1, &users[3], &users[4])
+            .await?[0]
+            .total_reward
+            .0;
+    assert!(reward_of_integer_deposit > 0);
+    assert!(reward_of_fractional_deposit > reward_of_integer_deposit);
+    assert!(
+        (reward_of_fractional_deposit * 2000).abs_diff(reward_of_integer_deposit * 2001) <= 2001
+    );
+    assert_eq!(
+        withdraw_delegator_rewards(
+            &worker,
+            &anchor,
+            &wrapped_appchain_token,
+            &users[3],
+            &users[4]
+        )
+        .await?,
+        reward_of_fractional_deposit
+    );
+    Ok(())
+}
+
+async fn delegator_rewards_of(
+    worker: &Worker<Sandbox>,
+    anchor: &Contract,
+    end_era: u64,
+    delegator: &Account,
+    validator: &Account,
+) -> anyhow::Result<Vec<RewardHistory>> {
+    anchor_viewer::get_delegator_rewards_of(worker, anchor, 0, end_era, delegator, validator).await
+}
+
+/// Withdraw the delegator rewards and return the withdrawn amount.
+async fn withdraw_delegator_rewards(
+    worker: &Worker<Sandbox>,
+    anchor: &Contract,
+    wrapped_appchain_token: &Contract,
+    delegator: &Account,
+    validator: &Account,
+) -> anyhow::Result<u128> {
+    let balance_before =
+        common::get_ft_balance_of(worker, delegator, wrapped_appchain_token).await?;
+    let result = staking_actions::withdraw_delegator_rewards(
+        worker,
+        delegator,
+        anchor,
+        &delegator.id().to_string().parse().unwrap(),
+        &validator.id().to_string().parse().unwrap(),
+    )
+    .await?;
+    assert!(result.is_success());
+    Ok(
+        common::get_ft_balance_of(worker, delegator, wrapped_appchain_token)
+            .await?
+            .0
+            - balance_before.0,
+    )
+}
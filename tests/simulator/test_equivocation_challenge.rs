@@ -1,32 +1,153 @@
-use crate::{common, contract_interfaces::anchor_viewer};
-use appchain_anchor::appchain_challenge::AppchainChallenge;
+use crate::{
+    common,
+    contract_interfaces::{anchor_viewer, staking_actions, validator_actions},
+};
+use appchain_anchor::appchain_challenge::{AppchainChallenge, AppchainChallengeStatus};
+use appchain_anchor::appchain_messages::{AuthoritySetChangedPayload, PayloadType, RawMessage};
+use near_primitives::borsh::BorshSerialize;
 use near_sdk::serde_json::{self, json};
+use std::collections::HashMap;
+use workspaces::{network::Sandbox, result::CallExecutionDetails, Account, Contract, Worker};
+
+const CHALLENGE_BOND_AMOUNT: u128 = 100;
+const GRANDPA_KEY_OF_OFFENDER: &str =
+    "0xd17c2d7823ebf260fd138f2d7e27d114c0145d968b5ff5006125f2414fadae69";
 
 #[tokio::test]
 async fn test_equivocation_challenge() -> anyhow::Result<()> {
     //
     let worker = workspaces::sandbox().await?;
-    let (_root, _, _, _, anchor, _wat_faucet, users, _) =
+    let (_, oct_token, _, _, anchor, _wat_faucet, users, mut appchain_message_nonce) =
         common::test_normal_actions(&worker, false, false, vec!["0x00".to_string()]).await?;
     //
-    if let Ok(challenge) = serde_json::from_str::<AppchainChallenge>("{\"EquivocationChallenge\":{\"submitter_account\":\"tt.testnet\",\"proof\":{\"set_id\":0,\"equivocation\":{\"Prevote\":{\"round_number\":2,\"identity\":[209,124,45,120,35,235,242,96,253,19,143,45,126,39,209,20,192,20,93,150,139,95,245,0,97,37,242,65,79,173,174,105],\"first\":[{\"target_hash\":[96,43,40,162,148,136,214,36,237,150,130,159,164,176,134,217,188,7,156,28,26,245,153,173,235,220,148,113,142,54,86,172],\"target_number\":2},[160,8,125,180,57,254,58,164,29,247,251,21,218,43,228,81,110,42,54,245,139,100,113,120,8,169,186,72,79,1,10,44,124,214,240,57,158,28,5,246,112,141,249,88,85,136,172,109,27,246,217,212,175,90,35,66,230,60,7,116,132,238,222,10]],\"second\":[{\"target_hash\":[55,65,109,173,2,87,56,21,245,65,225,251,11,255,55,219,64,83,133,115,5,161,227,232,204,172,40,117,127,126,63,225],\"target_number\":1},[182,116,59,76,20,131,229,152,169,61,221,96,84,126,111,231,69,122,21,132,2,242,18,172,118,22,204,130,230,203,228,28,91,196,141,105,180,223,209,205,3,210,217,106,135,148,174,214,169,196,82,106,255,89,109,197,73,142,237,71,179,42,184,7]]}}}}}") {
-        let result = users[3]
-            .call(&worker, anchor.id(), "commit_appchain_challenge")
-            .args_json(json!({
-                "appchain_challenge": challenge
-            }))?
-            .gas(300_000_000_000_000)
-            .transact()
-            .await?;
+    // User2 registers as a validator without GRANDPA key, its account id in appchain
+    // is the identity in testing data and is used as its GRANDPA key
+    //
+    let result = staking_actions::register_validator(
+        &worker,
+        &users[2],
+        &oct_token,
+        &anchor,
+        &GRANDPA_KEY_OF_OFFENDER.to_string(),
+        common::to_actual_amount(13_000, 18),
+        true,
+        HashMap::new(),
+    )
+    .await?;
+    assert!(result.is_success());
+    //
+    // The GRANDPA key can not be used by another validator
+    //
+    let result = validator_actions::set_grandpa_key_in_appchain(
+        &worker,
+        &users[1],
+        &anchor,
+        &GRANDPA_KEY_OF_OFFENDER.to_string(),
+    )
+    .await?;
+    assert!(!result.is_success());
+    appchain_message_nonce += 1;
+    common::complex_actions::switch_era(
+        &worker,
+        &users[5],
+        &anchor,
+        1,
+        appchain_message_nonce,
+        false,
+    )
+    .await?;
+    //
+    let challenge_data = CHALLENGE_DATA.replace("tt.testnet", users[3].id().as_str());
+    if let Ok(challenge) = serde_json::from_str::<AppchainChallenge>(&challenge_data) {
+        //
+        // The challenge is rejected if the GRANDPA authority set is not tracked
+        //
+        let result =
+            commit_appchain_challenge(&worker, &users[3], &oct_token, &anchor, &challenge).await?;
+        assert!(result.is_success());
+        let challenge_state = anchor_viewer::get_appchain_challenge_state(&worker, &anchor, 0)
+            .await?
+            .unwrap();
+        assert_eq!(challenge_state.status, AppchainChallengeStatus::Rejected);
+        //
+        // The appchain reports that the authority set 0 is formed by the validators of era 1
+        //
+        appchain_message_nonce += 1;
+        common::complex_actions::stage_appchain_messages_by_witnesses(
+            &worker,
+            &[&users[5]],
+            &anchor,
+            vec![RawMessage {
+                nonce: appchain_message_nonce as u64,
+                payload_type: PayloadType::AuthoritySetChanged,
+                payload: AuthoritySetChangedPayload { set_id: 0, era: 1 }
+                    .try_to_vec()
+                    .unwrap(),
+            }],
+        )
+        .await?;
+        common::complex_actions::process_appchain_messages(&worker, &users[5], &anchor).await?;
+        assert_eq!(
+            anchor_viewer::get_era_number_of_grandpa_set(&worker, &anchor, 0)
+                .await?
+                .map(|era_number| era_number.0),
+            Some(1)
+        );
+        let user3_balance = common::get_ft_balance_of(&worker, &users[3], &oct_token).await?;
+        let result =
+            commit_appchain_challenge(&worker, &users[3], &oct_token, &anchor, &challenge).await?;
         assert!(result.is_success());
         //
-        let appchain_challenge = anchor_viewer::get_appchain_challenge(&worker, &anchor, 0).await?;
+        let challenge_state = anchor_viewer::get_appchain_challenge_state(&worker, &anchor, 1)
+            .await?
+            .unwrap();
+        assert_eq!(challenge_state.status, AppchainChallengeStatus::Accepted);
+        assert_eq!(
+            challenge_state.punished_validator_id.unwrap().to_string(),
+            users[2].id().to_string()
+        );
+        // The bond should be refunded to the submitter
+        assert_eq!(
+            common::get_ft_balance_of(&worker, &users[3], &oct_token)
+                .await?
+                .0,
+            user3_balance.0 + challenge_state.reward_amount.0
+        );
+        //
+        // A duplicated proof should be refused, and the bond is returned
+        //
+        let user3_balance = common::get_ft_balance_of(&worker, &users[3], &oct_token).await?;
+        commit_appchain_challenge(&worker, &users[3], &oct_token, &anchor, &challenge).await?;
+        assert!(
+            anchor_viewer::get_appchain_challenge_state(&worker, &anchor, 2)
+                .await?
+                .is_none()
+        );
+        assert_eq!(
+            common::get_ft_balance_of(&worker, &users[3], &oct_token)
+                .await?
+                .0,
+            user3_balance.0
+        );
+        let challenge_states = anchor_viewer::get_appchain_challenge_states(
+            &worker,
+            &anchor,
+            0,
+            None,
+            Some(AppchainChallengeStatus::Rejected),
+            Some(users[3].id().to_string()),
+        )
+        .await?;
+        assert_eq!(challenge_states.len(), 1);
+        //
+        let appchain_challenge = anchor_viewer::get_appchain_challenge(&worker, &anchor, 1).await?;
         println!(
-            "Appchain challenge 0: {}",
+            "Appchain challenge 1: {}",
             serde_json::to_string(&appchain_challenge).unwrap()
         );
-    //
-        let appchain_challenges = anchor_viewer::get_appchain_challenges(&worker, &anchor, 0, None).await?;
+        //
+        let appchain_challenges =
+            anchor_viewer::get_appchain_challenges(&worker, &anchor, 0, None).await?;
         let mut index = 0;
         for appchain_challenge in appchain_challenges {
             println!(
@@ -41,3 +162,28 @@ async fn test_equivocation_challenge() -> anyhow::Result<()> {
     }
     Ok(())
 }
+
+async fn commit_appchain_challenge(
+    worker: &Worker<Sandbox>,
+    signer: &Account,
+    oct_token: &Contract,
+    anchor: &Contract,
+    appchain_challenge: &AppchainChallenge,
+) -> anyhow::Result<CallExecutionDetails> {
+    common::call_ft_transfer_call(
+        worker,
+        signer,
+        &anchor.as_account(),
+        common::to_actual_amount(CHALLENGE_BOND_AMOUNT, 18),
+        json!({
+            "CommitChallenge": {
+                "appchain_challenge": appchain_challenge
+            }
+        })
+        .to_string(),
+        oct_token,
+    )
+    .await
+}
+
+const CHALLENGE_DATA: &str = "{\"EquivocationChallenge\":{\"submitter_account\":\"tt.testnet\",\"proof\":{\"set_id\":0,\"equivocation\":{\"Prevote\":{\"round_number\":2,\"identity\":[209,124,45,120,35,235,242,96,253,19,143,45,126,39,209,20,192,20,93,150,139,95,245,0,97,37,242,65,79,173,174,105],\"first\":[{\"target_hash\":[96,43,40,162,148,136,214,36,237,150,130,159,164,176,134,217,188,7,156,28,26,245,153,173,235,220,148,113,142,54,86,172],\"target_number\":2},[160,8,125,180,57,254,58,164,29,247,251,21,218,43,228,81,110,42,54,245,139,100,113,120,8,169,186,72,79,1,10,44,124,214,240,57,158,28,5,246,112,141,249,88,85,136,172,109,27,246,217,212,175,90,35,66,230,60,7,116,132,238,222,10]],\"second\":[{\"target_hash\":[55,65,109,173,2,87,56,21,245,65,225,251,11,255,55,219,64,83,133,115,5,161,227,232,204,172,40,117,127,126,63,225],\"target_number\":1},[182,116,59,76,20,131,229,152,169,61,221,96,84,126,111,231,69,122,21,132,2,242,18,172,118,22,204,130,230,203,228,28,91,196,141,105,180,223,209,205,3,210,217,106,135,148,174,214,169,196,82,106,255,89,109,197,73,142,237,71,179,42,184,7]]}}}}}";
@@ -0,0 +1,239 @@
+use crate::{
+    common::{
+        self,
+        grandpa_authorities::{make_header, messages_commitment, GrandpaAuthoritySet},
+    },
+    contract_interfaces::{anchor_viewer, permissionless_actions, settings_manager, sudo_actions},
+};
+use appchain_anchor::{
+    appchain_messages::{PayloadType, PlanNewEraPayload, RawMessage},
+    grandpa_light_client::header::Header,
+    types::{GrandpaLightClientStatus, LightClientType},
+};
+use near_primitives::borsh::BorshSerialize;
+use parity_scale_codec::Encode;
+use workspaces::{network::Sandbox, result::CallExecutionDetails, Account, Contract, Worker};
+
+#[tokio::test]
+async fn test_grandpa_light_client() -> anyhow::Result<()> {
+    //
+    let worker = workspaces::sandbox().await?;
+    let (root, _, _, _, anchor, _wat_faucet, users, appchain_message_nonce) =
+        common::test_normal_actions(&worker, false, false, vec!["0x00".to_string()]).await?;
+    let set_0 = GrandpaAuthoritySet::new(0, &[1, 2, 3]);
+    let set_1 = GrandpaAuthoritySet::new(1, &[4, 5, 6]);
+    let result = sudo_actions::reset_grandpa_light_client(
+        &worker,
+        &root,
+        &anchor,
+        set_0.public_keys(),
+        set_0.set_id,
+    )
+    .await?;
+    assert!(result.is_success());
+    let result =
+        settings_manager::set_light_client_type(&worker, &root, &anchor, LightClientType::Grandpa)
+            .await?;
+    assert!(result.is_success());
+    settings_manager::turn_off_beefy_light_client_witness_mode(&worker, &root, &anchor)
+        .await
+        .expect("Failed to call 'turn_off_beefy_light_client_witness_mode'");
+    //
+    // A justification is rejected if it is not signed by the supermajority of current set,
+    // or it does not finalize the given header
+    //
+    let header_1 = make_header(1, Vec::new());
+    for justification in [
+        set_0.sign_justification(&header_1, 2),
+        set_1.sign_justification(&header_1, 3),
+        set_0.sign_justification(&make_header(2, Vec::new()), 3),
+    ] {
+        let result = update_state(&worker, &users[4], &anchor, &header_1, justification).await?;
+        assert!(!result.is_success());
+    }
+    let result = update_state(
+        &worker,
+        &users[4],
+        &anchor,
+        &header_1,
+        set_0.sign_justification(&header_1, 3),
+    )
+    .await?;
+    assert!(result.is_success());
+    let result = update_state(
+        &worker,
+        &users[4],
+        &anchor,
+        &header_1,
+        set_0.sign_justification(&header_1, 3),
+    )
+    .await?;
+    assert!(!result.is_success());
+    let status = grandpa_light_client_status(&worker, &anchor).await?;
+    assert_eq!(status.set_id.0, 0);
+    assert_eq!(status.latest_finalized_block_number, Some(1));
+    //
+    // Header 2 schedules the change to set 1, which is enacted at header 4
+    //
+    let header_2 = make_header(2, vec![set_1.scheduled_change(2)]);
+    let result = update_state(
+        &worker,
+        &users[4],
+        &anchor,
+        &header_2,
+        set_0.sign_justification(&header_2, 3),
+    )
+    .await?;
+    assert!(result.is_success());
+    let status = grandpa_light_client_status(&worker, &anchor).await?;
+    assert_eq!(status.pending_authority_set_change_enacted_at, Some(4));
+    //
+    // The messages are committed in header 3, which is finalized by set 0
+    //
+    let messages_of_set_0 = plan_new_era_messages(appchain_message_nonce + 1, 1);
+    let header_3 = make_header(3, vec![messages_commitment(&messages_of_set_0)]);
+    let justification_of_header_3 = set_0.sign_justification(&header_3, 3);
+    //
+    // A header after the pending change can not be submitted before the change is enacted
+    //
+    let header_5 = make_header(5, Vec::new());
+    for justification in [
+        set_0.sign_justification(&header_5, 3),
+        set_1.sign_justification(&header_5, 3),
+    ] {
+        let result = update_state(&worker, &users[4], &anchor, &header_5, justification).await?;
+        assert!(!result.is_success());
+    }
+    let header_4 = make_header(4, Vec::new());
+    let result = update_state(
+        &worker,
+        &users[4],
+        &anchor,
+        &header_4,
+        set_0.sign_justification(&header_4, 3),
+    )
+    .await?;
+    assert!(result.is_success());
+    let status = grandpa_light_client_status(&worker, &anchor).await?;
+    assert_eq!(status.set_id.0, 1);
+    assert_eq!(status.latest_finalized_block_number, Some(4));
+    assert_eq!(status.pending_authority_set_change_enacted_at, None);
+    //
+    // Headers after the change are finalized by set 1
+    //
+    let result = update_state(
+        &worker,
+        &users[4],
+        &anchor,
+        &header_5,
+        set_0.sign_justification(&header_5, 3),
+    )
+    .await?;
+    assert!(!result.is_success());
+    let result = update_state(
+        &worker,
+        &users[4],
+        &anchor,
+        &header_5,
+        set_1.sign_justification(&header_5, 3),
+    )
+    .await?;
+    assert!(result.is_success());
+    //
+    // The messages committed in header 3 can still be verified by the previous set
+    //
+    let result = permissionless_actions::verify_and_stage_appchain_messages_with_justification(
+        &worker,
+        &users[4],
+        &anchor,
+        messages_of_set_0,
+        header_3.encode(),
+        justification_of_header_3,
+    )
+    .await?;
+    assert!(result.is_success());
+    assert_eq!(
+        max_nonce_of_staged_messages(&worker, &anchor).await?,
+        appchain_message_nonce + 1
+    );
+    //
+    // The messages committed in header 6 must be finalized by set 1
+    //
+    let messages_of_set_1 = plan_new_era_messages(appchain_message_nonce + 2, 2);
+    let header_6 = make_header(6, vec![messages_commitment(&messages_of_set_1)]);
+    let result = permissionless_actions::verify_and_stage_appchain_messages_with_justification(
+        &worker,
+        &users[4],
+        &anchor,
+        messages_of_set_1.clone(),
+        header_6.encode(),
+        set_0.sign_justification(&header_6, 3),
+    )
+    .await?;
+    assert!(!result.is_success());
+    let result = permissionless_actions::verify_and_stage_appchain_messages_with_justification(
+        &worker,
+        &users[4],
+        &anchor,
+        messages_of_set_1,
+        header_6.encode(),
+        set_1.sign_justification(&header_6, 3),
+    )
+    .await?;
+    assert!(result.is_success());
+    assert_eq!(
+        max_nonce_of_staged_messages(&worker, &anchor).await?,
+        appchain_message_nonce + 2
+    );
+    Ok(())
+}
+
+async fn update_state(
+    worker: &Worker<Sandbox>,
+    signer: &Account,
+    anchor: &Contract,
+    header: &Header,
+    justification: Vec<u8>,
+) -> anyhow::Result<CallExecutionDetails> {
+    permissionless_actions::update_state_of_grandpa_light_client(
+        worker,
+        signer,
+        anchor,
+        header.encode(),
+        justification,
+    )
+    .await
+}
+
+async fn grandpa_light_client_status(
+    worker: &Worker<Sandbox>,
+    anchor: &Contract,
+) -> anyhow::Result<GrandpaLightClientStatus> {
+    Ok(anchor_viewer::get_light_client_status(worker, anchor)
+        .await?
+        .grandpa_light_client_status
+        .unwrap())
+}
+
+async fn max_nonce_of_staged_messages(
+    worker: &Worker<Sandbox>,
+    anchor: &Contract,
+) -> anyhow::Result<u32> {
+    Ok(anchor_viewer::get_anchor_status(worker, anchor)
+        .await?
+        .permissionless_actions_status
+        .max_nonce_of_staged_appchain_messages)
+}
+
+fn plan_new_era_messages(nonce: u32, era_number: u32) -> Vec<u8> {
+    vec![RawMessage {
+        nonce: u64::from(nonce),
+        payload_type: PayloadType::PlanNewEra,
+        payload: PlanNewEraPayload {
+            new_era: era_number,
+        }
+        .try_to_vec()
+        .unwrap(),
+    }]
+    .encode()
+}
@@ -0,0 +1,199 @@
+use crate::{
+    common::{
+        self,
+        beefy_authorities::{single_leaf_mmr_proof, BeefyAuthoritySet},
+        complex_actions::update_state_of_beefy_light_client,
+        grandpa_authorities::{make_header, messages_commitment, GrandpaAuthoritySet},
+    },
+    contract_interfaces::{
+        anchor_viewer, permissionless_actions, settings_manager, sudo_actions, witness_actions,
+    },
+};
+use appchain_anchor::{
+    appchain_messages::{PayloadType, PlanNewEraPayload, RawMessage},
+    types::LightClientType,
+};
+use near_primitives::borsh::BorshSerialize;
+use parity_scale_codec::Encode;
+use workspaces::{network::Sandbox, Contract, Worker};
+
+#[tokio::test]
+async fn test_message_verifiers() -> anyhow::Result<()> {
+    let worker = workspaces::sandbox().await?;
+    let authorities = BeefyAuthoritySet::new(5);
+    let (root, oct_token, _, _, anchor, _, users, appchain_message_nonce) =
+        common::test_normal_actions(&worker, false, false, authorities.public_keys()).await?;
+    let messages_1 = plan_new_era_messages(appchain_message_nonce + 1, 1);
+    let header_of_messages_1 = make_header(8, vec![messages_commitment(&messages_1)]);
+    let leaf_of_messages_1 = authorities.make_mmr_leaf(8, header_of_messages_1.hash());
+    update_state_of_beefy_light_client(
+        &worker,
+        &users[4],
+        &anchor,
+        &authorities,
+        9,
+        &leaf_of_messages_1,
+    )
+    .await?;
+    //
+    // The witness committee verifier is used in witness mode, and the messages
+    // are pending until the attestations reach the witness threshold
+    //
+    let result = settings_manager::add_witness_account(&worker, &root, &anchor, &users[3]).await?;
+    assert!(result.is_success());
+    let result = witness_actions::bond_as_witness(
+        &worker,
+        &users[3],
+        &oct_token,
+        &anchor,
+        common::to_actual_amount(common::WITNESS_BOND_AMOUNT, 18),
+    )
+    .await?;
+    assert!(result.is_success());
+    let result = settings_manager::set_witness_threshold(&worker, &root, &anchor, 2).await?;
+    assert!(result.is_success());
+    let result = permissionless_actions::verify_and_stage_appchain_messages(
+        &worker,
+        &users[5],
+        &anchor,
+        messages_1.clone(),
+        Vec::new(),
+        Vec::new(),
+        Vec::new(),
+    )
+    .await?;
+    assert!(result.is_success());
+    assert_eq!(
+        anchor_viewer::get_pending_message_batch_attestations(&worker, &anchor)
+            .await?
+            .len(),
+        1
+    );
+    assert_eq!(
+        max_nonce_of_staged_messages(&worker, &anchor).await?,
+        appchain_message_nonce
+    );
+    //
+    // The beefy light client verifier is used out of witness mode,
+    // and the messages are staged with the proof of MMR
+    //
+    settings_manager::turn_off_beefy_light_client_witness_mode(&worker, &root, &anchor)
+        .await
+        .expect("Failed to call 'turn_off_beefy_light_client_witness_mode'");
+    let result = permissionless_actions::verify_and_stage_appchain_messages(
+        &worker,
+        &users[4],
+        &anchor,
+        messages_1.clone(),
+        Vec::new(),
+        Vec::new(),
+        Vec::new(),
+    )
+    .await?;
+    assert!(!result.is_success());
+    let result = permissionless_actions::verify_and_stage_appchain_messages(
+        &worker,
+        &users[4],
+        &anchor,
+        messages_1,
+        header_of_messages_1.encode(),
+        leaf_of_messages_1.encode(),
+        single_leaf_mmr_proof(),
+    )
+    .await?;
+    assert!(result.is_success());
+    assert_eq!(
+        max_nonce_of_staged_messages(&worker, &anchor).await?,
+        appchain_message_nonce + 1
+    );
+    //
+    // The grandpa light client verifier is used if it is selected in anchor settings,
+    // and the proof of beefy light client is no longer accepted
+    //
+    let messages_2 = plan_new_era_messages(appchain_message_nonce + 2, 2);
+    let header_of_messages_2 = make_header(16, vec![messages_commitment(&messages_2)]);
+    let leaf_of_messages_2 = authorities.make_mmr_leaf(16, header_of_messages_2.hash());
+    update_state_of_beefy_light_client(
+        &worker,
+        &users[4],
+        &anchor,
+        &authorities,
+        17,
+        &leaf_of_messages_2,
+    )
+    .await?;
+    let grandpa_set = GrandpaAuthoritySet::new(0, &[1, 2, 3]);
+    let result = sudo_actions::reset_grandpa_light_client(
+        &worker,
+        &root,
+        &anchor,
+        grandpa_set.public_keys(),
+        grandpa_set.set_id,
+    )
+    .await?;
+    assert!(result.is_success());
+    let result =
+        settings_manager::set_light_client_type(&worker, &root, &anchor, LightClientType::Grandpa)
+            .await?;
+    assert!(result.is_success());
+    let result = permissionless_actions::verify_and_stage_appchain_messages(
+        &worker,
+        &users[4],
+        &anchor,
+        messages_2.clone(),
+        header_of_messages_2.encode(),
+        leaf_of_messages_2.encode(),
+        single_leaf_mmr_proof(),
+    )
+    .await?;
+    assert!(!result.is_success());
+    let header_1 = make_header(1, vec![messages_commitment(&messages_2)]);
+    let justification_of_header_1 = grandpa_set.sign_justification(&header_1, 3);
+    let result = permissionless_actions::update_state_of_grandpa_light_client(
+        &worker,
+        &users[4],
+        &anchor,
+        header_1.encode(),
+        justification_of_header_1.clone(),
+    )
+    .await?;
+    assert!(result.is_success());
+    let result = permissionless_actions::verify_and_stage_appchain_messages_with_justification(
+        &worker,
+        &users[4],
+        &anchor,
+        messages_2,
+        header_1.encode(),
+        justification_of_header_1,
+    )
+    .await?;
+    assert!(result.is_success());
+    assert_eq!(
+        max_nonce_of_staged_messages(&worker, &anchor).await?,
+        appchain_message_nonce + 2
+    );
+    Ok(())
+}
+
+async fn max_nonce_of_staged_messages(
+    worker: &Worker<Sandbox>,
+    anchor: &Contract,
+) -> anyhow::Result<u32> {
+    Ok(anchor_viewer::get_anchor_status(worker, anchor)
+        .await?
+        .permissionless_actions_status
+        .max_nonce_of_staged_appchain_messages)
+}
+
+fn plan_new_era_messages(nonce: u32, era_number: u32) -> Vec<u8> {
+    vec![RawMessage {
+        nonce: u64::from(nonce),
+        payload_type: PayloadType::PlanNewEra,
+        payload: PlanNewEraPayload {
+            new_era: era_number,
+        }
+        .try_to_vec()
+        .unwrap(),
+    }]
+    .encode()
+}
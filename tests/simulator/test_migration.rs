@@ -1,4 +1,4 @@
-use crate::common;
+use crate::{common, contract_interfaces::anchor_viewer};
 use appchain_anchor::{
     storage_migration::{OldAppchainEvent, OldAppchainMessage},
     AppchainEvent, AppchainMessage,
@@ -108,6 +108,54 @@ async fn test_migration() -> anyhow::Result<()> {
         .expect("Failed to call 'migrate_appchain_messages'");
     common::complex_viewer::print_appchain_messages(&worker, &anchor).await?;
     //
+    let result = anchor
+        .call(&worker, "migrate_validator_set_histories")
+        .args_json(json!({
+            "start_era_number": "0"
+        }))?
+        .gas(200_000_000_000_000)
+        .transact()
+        .await?;
+    assert!(result.is_success());
+    let result = anchor
+        .call(&worker, "migrate_appchain_challenges")
+        .args_json(json!({
+            "start_index": "0"
+        }))?
+        .gas(200_000_000_000_000)
+        .transact()
+        .await?;
+    assert!(result.is_success());
+    for era_number in [0, 1] {
+        let mut delegators: Vec<(String, u128)> =
+            anchor_viewer::get_delegators_of_validator_in_era(
+                &worker, &anchor, era_number, &users[0],
+            )
+            .await?
+            .iter()
+            .map(|delegator| {
+                (
+                    delegator.delegator_id.to_string(),
+                    delegator.delegation_amount.0,
+                )
+            })
+            .collect();
+        delegators.sort();
+        assert_eq!(
+            delegators,
+            vec![
+                (
+                    users[2].id().to_string(),
+                    common::to_actual_amount(1500, 18)
+                ),
+                (
+                    users[3].id().to_string(),
+                    common::to_actual_amount(2000, 18)
+                ),
+            ]
+        );
+    }
+    //
     //
     //
     common::complex_viewer::print_anchor_status(&worker, &anchor).await?;
@@ -0,0 +1,119 @@
+use crate::{
+    common::{self, complex_actions},
+    contract_interfaces::{anchor_viewer, native_near_token_manager},
+};
+use appchain_anchor::appchain_messages::{BurnNativeNearPayload, PayloadType, RawMessage};
+use near_primitives::borsh::BorshSerialize;
+use near_units::parse_near;
+
+const RECEIVER_ID_IN_APPCHAIN: &str =
+    "0xd43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d";
+
+#[tokio::test]
+async fn test_native_near_token() -> anyhow::Result<()> {
+    let worker = workspaces::sandbox().await?;
+    let (root, _, _, _, anchor, _wat_faucet, users, appchain_message_nonce) =
+        common::test_normal_actions(&worker, false, false, vec!["0x00".to_string()]).await?;
+    //
+    // NEAR can not be locked before the bridging is opened by the owner
+    //
+    let result = native_near_token_manager::lock_near_to_appchain(
+        &worker,
+        &users[0],
+        &anchor,
+        RECEIVER_ID_IN_APPCHAIN.to_string(),
+        parse_near!("5 N"),
+    )
+    .await?;
+    assert!(!result.is_success());
+    let result =
+        native_near_token_manager::open_bridging_of_native_near_token(&worker, &users[0], &anchor)
+            .await?;
+    assert!(!result.is_success());
+    let result =
+        native_near_token_manager::open_bridging_of_native_near_token(&worker, &root, &anchor)
+            .await?;
+    assert!(result.is_success());
+    //
+    // NEAR is locked in anchor on the way to the appchain
+    //
+    let user0_near_balance = users[0].view_account(&worker).await?.balance;
+    let result = native_near_token_manager::lock_near_to_appchain(
+        &worker,
+        &users[0],
+        &anchor,
+        RECEIVER_ID_IN_APPCHAIN.to_string(),
+        parse_near!("5 N"),
+    )
+    .await?;
+    assert!(result.is_success());
+    assert_eq!(
+        common::get_anchor_events(&result, "native_near_locked").len(),
+        1
+    );
+    assert!(
+        users[0].view_account(&worker).await?.balance < user0_near_balance - parse_near!("5 N")
+    );
+    assert_eq!(
+        anchor_viewer::get_native_near_token(&worker, &anchor)
+            .await?
+            .locked_balance
+            .0,
+        parse_near!("5 N")
+    );
+    //
+    // NEAR is unlocked to the receiver on the way back from the appchain
+    //
+    let receiver = root
+        .create_subaccount(&worker, "receiver")
+        .initial_balance(parse_near!("10 N"))
+        .transact()
+        .await?
+        .unwrap();
+    complex_actions::stage_appchain_messages_by_witnesses(
+        &worker,
+        &[&users[5]],
+        &anchor,
+        vec![RawMessage {
+            nonce: (appchain_message_nonce + 1) as u64,
+            payload_type: PayloadType::BurnNativeNear,
+            payload: BurnNativeNearPayload {
+                sender: RECEIVER_ID_IN_APPCHAIN.to_string(),
+                receiver_id: receiver.id().to_string().parse().unwrap(),
+                amount: parse_near!("2 N"),
+            }
+            .try_to_vec()
+            .unwrap(),
+        }],
+    )
+    .await?;
+    complex_actions::process_appchain_messages(&worker, &users[5], &anchor).await?;
+    assert_eq!(
+        receiver.view_account(&worker).await?.balance,
+        parse_near!("12 N")
+    );
+    assert_eq!(
+        anchor_viewer::get_native_near_token(&worker, &anchor)
+            .await?
+            .locked_balance
+            .0,
+        parse_near!("3 N")
+    );
+    //
+    // NEAR can not be locked after the bridging is closed
+    //
+    let result =
+        native_near_token_manager::close_bridging_of_native_near_token(&worker, &root, &anchor)
+            .await?;
+    assert!(result.is_success());
+    let result = native_near_token_manager::lock_near_to_appchain(
+        &worker,
+        &users[0],
+        &anchor,
+        RECEIVER_ID_IN_APPCHAIN.to_string(),
+        parse_near!("1 N"),
+    )
+    .await?;
+    assert!(!result.is_success());
+    Ok(())
+}
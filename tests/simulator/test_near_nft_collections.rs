@@ -0,0 +1,195 @@
+use crate::{
+    common::{self, basic_actions, complex_actions},
+    contract_interfaces::{anchor_viewer, near_nft_collection_manager},
+};
+use appchain_anchor::{
+    appchain_messages::{BurnNearNftPayload, PayloadType, RawMessage},
+    types::{AppchainNotification, NFTTransferMessage},
+};
+use near_contract_standards::non_fungible_token::{metadata::TokenMetadata, Token};
+use near_primitives::borsh::BorshSerialize;
+use near_sdk::serde_json::{self, json};
+use near_units::parse_near;
+use workspaces::{network::Sandbox, Account, Contract, Worker};
+
+const RECEIVER_ID_IN_APPCHAIN: &str =
+    "0xd43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d";
+
+#[tokio::test]
+async fn test_near_nft_collections() -> anyhow::Result<()> {
+    let worker = workspaces::sandbox().await?;
+    let (root, _, _, _, anchor, _wat_faucet, users, appchain_message_nonce) =
+        common::test_normal_actions(&worker, false, false, vec!["0x00".to_string()]).await?;
+    let nft1 = basic_actions::deploy_mock_near_nft_contract(&worker, &root, "nft1", true).await?;
+    let nft2 = basic_actions::deploy_mock_near_nft_contract(&worker, &root, "nft2", false).await?;
+    for nft in [&nft1, &nft2] {
+        let result = root
+            .call(&worker, nft.id(), "nft_mint")
+            .args_json(json!({
+                "token_id": "1",
+                "token_owner_id": users[0].id(),
+                "token_metadata": TokenMetadata {
+                    title: Some("token 1".to_string()),
+                    description: None,
+                    media: None,
+                    media_hash: None,
+                    copies: Some(1),
+                    issued_at: None,
+                    expires_at: None,
+                    starts_at: None,
+                    updated_at: None,
+                    extra: None,
+                    reference: None,
+                    reference_hash: None,
+                },
+            }))?
+            .gas(300_000_000_000_000)
+            .deposit(parse_near!("0.1 N"))
+            .transact()
+            .await?;
+        assert!(result.is_success());
+    }
+    //
+    // Only the owner can register and open the bridging of NEAR NFT collections
+    //
+    let result = near_nft_collection_manager::register_near_nft_collection(
+        &worker,
+        &users[0],
+        &anchor,
+        nft1.id().to_string().parse().unwrap(),
+    )
+    .await?;
+    assert!(!result.is_success());
+    for nft in [&nft1, &nft2] {
+        let result = near_nft_collection_manager::register_near_nft_collection(
+            &worker,
+            &root,
+            &anchor,
+            nft.id().to_string().parse().unwrap(),
+        )
+        .await?;
+        assert!(result.is_success());
+        let result = near_nft_collection_manager::open_bridging_of_near_nft_collection(
+            &worker,
+            &root,
+            &anchor,
+            nft.id().to_string().parse().unwrap(),
+        )
+        .await?;
+        assert!(result.is_success());
+    }
+    //
+    // The NFT is locked in anchor with the metadata queried from the collection
+    //
+    let token_metadata = lock_near_nft_to_appchain(&worker, &users[0], &anchor, &nft1).await?;
+    assert_eq!(token_metadata.title, Some("token 1".to_string()));
+    assert_eq!(
+        get_owner_of_nft(&worker, &nft1).await?,
+        anchor.id().to_string()
+    );
+    //
+    // Empty metadata is used if the collection does not store the metadata of tokens
+    //
+    let token_metadata = lock_near_nft_to_appchain(&worker, &users[0], &anchor, &nft2).await?;
+    assert_eq!(token_metadata.title, None);
+    assert_eq!(
+        get_owner_of_nft(&worker, &nft2).await?,
+        anchor.id().to_string()
+    );
+    assert!(anchor_viewer::get_near_nft_collections(&worker, &anchor)
+        .await?
+        .iter()
+        .all(|collection| collection.count_of_locked_tokens.0 == 1));
+    //
+    // The NFT is unlocked to the receiver when it is burnt in the appchain
+    //
+    complex_actions::stage_appchain_messages_by_witnesses(
+        &worker,
+        &[&users[5]],
+        &anchor,
+        vec![RawMessage {
+            nonce: (appchain_message_nonce + 1) as u64,
+            payload_type: PayloadType::BurnNearNft,
+            payload: BurnNearNftPayload {
+                sender: RECEIVER_ID_IN_APPCHAIN.to_string(),
+                receiver_id: users[0].id().to_string().parse().unwrap(),
+                collection: nft2.id().to_string(),
+                token_id: "1".to_string(),
+            }
+            .try_to_vec()
+            .unwrap(),
+        }],
+    )
+    .await?;
+    complex_actions::process_appchain_messages(&worker, &users[5], &anchor).await?;
+    assert_eq!(
+        get_owner_of_nft(&worker, &nft2).await?,
+        users[0].id().to_string()
+    );
+    let collections = anchor_viewer::get_near_nft_collections(&worker, &anchor).await?;
+    let collection2 = collections
+        .iter()
+        .find(|collection| collection.contract_account.to_string() == nft2.id().to_string())
+        .unwrap();
+    assert_eq!(collection2.count_of_locked_tokens.0, 0);
+    Ok(())
+}
+
+/// Transfer the token '1' of the collection to anchor for bridging to the appchain,
+/// and return the token metadata in the appchain notification.
+async fn lock_near_nft_to_appchain(
+    worker: &Worker<Sandbox>,
+    owner: &Account,
+    anchor: &Contract,
+    nft: &Contract,
+) -> anyhow::Result<TokenMetadata> {
+    let result = owner
+        .call(worker, nft.id(), "nft_transfer_call")
+        .args_json(json!({
+            "receiver_id": anchor.id(),
+            "token_id": "1",
+            "approval_id": Option::<u64>::None,
+            "memo": Option::<String>::None,
+            "msg": serde_json::to_string(&NFTTransferMessage::BridgeToAppchain {
+                receiver_id_in_appchain: RECEIVER_ID_IN_APPCHAIN.to_string(),
+            })
+            .unwrap(),
+        }))?
+        .gas(300_000_000_000_000)
+        .deposit(1)
+        .transact()
+        .await?;
+    assert!(result.is_success());
+    let index_range =
+        anchor_viewer::get_index_range_of_appchain_notification_history(worker, anchor).await?;
+    let notification_history =
+        anchor_viewer::get_appchain_notification_history(worker, anchor, index_range.end_index.0)
+            .await?
+            .unwrap();
+    match notification_history.appchain_notification {
+        AppchainNotification::NearNFTLocked {
+            contract_account,
+            token_id,
+            token_metadata,
+            ..
+        } => {
+            assert_eq!(contract_account.to_string(), nft.id().to_string());
+            assert_eq!(token_id, "1");
+            Ok(token_metadata)
+        }
+        _ => panic!("Unexpected appchain notification."),
+    }
+}
+
+//
+async fn get_owner_of_nft(worker: &Worker<Sandbox>, nft: &Contract) -> anyhow::Result<String> {
+    Ok(nft
+        .call(worker, "nft_token")
+        .args_json(json!({ "token_id": "1" }))?
+        .view()
+        .await?
+        .json::<Option<Token>>()?
+        .unwrap()
+        .owner_id
+        .to_string())
+}
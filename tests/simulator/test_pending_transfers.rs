@@ -0,0 +1,94 @@
+use crate::{
+    common::{self, basic_actions::register_user_to_ft_contract, complex_actions},
+    contract_interfaces::{anchor_viewer, permissionless_actions},
+};
+use appchain_anchor::types::PendingTransfer;
+use near_units::parse_near;
+
+#[tokio::test]
+async fn test_pending_transfers() -> anyhow::Result<()> {
+    let worker = workspaces::sandbox().await?;
+    let (root, oct_token, _, _, anchor, _wat_faucet, users, appchain_message_nonce) =
+        common::test_normal_actions(&worker, false, false, vec!["0x00".to_string()]).await?;
+    complex_actions::open_bridging_of_oct_token(&worker, &root, &anchor, &oct_token).await?;
+    complex_actions::lock_oct_to_appchain(&worker, &users[0], &oct_token, &anchor, 200).await?;
+    //
+    // The unlocking to an account which is not registered in OCT token contract is failed,
+    // and it becomes a pending transfer of the receiver
+    //
+    let receiver = root
+        .create_subaccount(&worker, "receiver")
+        .initial_balance(parse_near!("10 N"))
+        .transact()
+        .await?
+        .unwrap();
+    complex_actions::unlock_oct_from_appchain(
+        &worker,
+        &users[5],
+        &anchor,
+        &oct_token,
+        &receiver,
+        100,
+        appchain_message_nonce + 1,
+    )
+    .await?;
+    let pending_transfers =
+        anchor_viewer::get_pending_transfers_of(&worker, &anchor, &receiver).await?;
+    assert_eq!(pending_transfers.len(), 1);
+    match &pending_transfers[0] {
+        PendingTransfer::NearFungibleTokenUnlocking {
+            symbol,
+            amount,
+            appchain_message_nonce: nonce,
+            ..
+        } => {
+            assert_eq!(symbol, "OCT");
+            assert_eq!(amount.0, common::to_actual_amount(100, 18));
+            assert_eq!(*nonce, appchain_message_nonce + 1);
+        }
+        _ => panic!("Unexpected pending transfer."),
+    }
+    //
+    // Anyone can claim the pending transfer after the receiver is registered
+    //
+    register_user_to_ft_contract(&worker, &receiver, &oct_token).await?;
+    let result = permissionless_actions::claim_pending_transfer(
+        &worker,
+        &users[5],
+        &anchor,
+        &receiver,
+        appchain_message_nonce + 1,
+    )
+    .await?;
+    assert!(result.is_success());
+    assert_eq!(
+        common::get_ft_balance_of(&worker, &receiver, &oct_token)
+            .await?
+            .0,
+        common::to_actual_amount(100, 18)
+    );
+    assert!(
+        anchor_viewer::get_pending_transfers_of(&worker, &anchor, &receiver)
+            .await?
+            .is_empty()
+    );
+    //
+    // The pending transfer can not be claimed twice
+    //
+    let result = permissionless_actions::claim_pending_transfer(
+        &worker,
+        &users[5],
+        &anchor,
+        &receiver,
+        appchain_message_nonce + 1,
+    )
+    .await?;
+    assert!(!result.is_success());
+    assert_eq!(
+        common::get_ft_balance_of(&worker, &receiver, &oct_token)
+            .await?
+            .0,
+        common::to_actual_amount(100, 18)
+    );
+    Ok(())
+}
@@ -0,0 +1,109 @@
+use crate::{
+    common,
+    contract_interfaces::{anchor_viewer, staking_actions},
+};
+use appchain_anchor::types::UnbondedStake;
+use workspaces::{network::Sandbox, Account, Contract, Worker};
+
+#[tokio::test]
+async fn test_rebond() -> anyhow::Result<()> {
+    let worker = workspaces::sandbox().await?;
+    let (_, _, _, _, anchor, _wat_faucet, users, mut appchain_message_nonce) =
+        common::test_normal_actions(&worker, false, false, vec!["0x00".to_string()]).await?;
+    //
+    // The decreased delegation can be rebonded partially and then totally
+    //
+    let result = staking_actions::decrease_delegation(
+        &worker,
+        &users[3],
+        &anchor,
+        &users[0].id().to_string().parse().unwrap(),
+        common::to_actual_amount(500, 18),
+    )
+    .await?;
+    assert!(result.is_success());
+    appchain_message_nonce += 1;
+    common::complex_actions::switch_era(
+        &worker,
+        &users[5],
+        &anchor,
+        1,
+        appchain_message_nonce,
+        false,
+    )
+    .await?;
+    let unbonded_stakes = unbonded_stakes_of(&worker, &anchor, &users[3]).await?;
+    assert_eq!(unbonded_stakes.len(), 1);
+    assert_eq!(
+        unbonded_stakes[0].amount.0,
+        common::to_actual_amount(500, 18)
+    );
+    let staking_history_index = unbonded_stakes[0].staking_history_index;
+    let result = staking_actions::rebond(
+        &worker,
+        &users[3],
+        &anchor,
+        staking_history_index,
+        Some(common::to_actual_amount(200, 18)),
+    )
+    .await?;
+    assert!(result.is_success());
+    let unbonded_stakes = unbonded_stakes_of(&worker, &anchor, &users[3]).await?;
+    assert_eq!(
+        unbonded_stakes[0].amount.0,
+        common::to_actual_amount(300, 18)
+    );
+    let result = staking_actions::rebond(
+        &worker,
+        &users[3],
+        &anchor,
+        staking_history_index,
+        Some(common::to_actual_amount(400, 18)),
+    )
+    .await?;
+    assert!(!result.is_success());
+    let result =
+        staking_actions::rebond(&worker, &users[3], &anchor, staking_history_index, None).await?;
+    assert!(result.is_success());
+    assert!(unbonded_stakes_of(&worker, &anchor, &users[3])
+        .await?
+        .is_empty());
+    //
+    // Neither the stake of an unbonded validator nor the delegations to it
+    // can be rebonded
+    //
+    let result = staking_actions::unbond_stake(&worker, &users[1], &anchor).await?;
+    assert!(result.is_success());
+    appchain_message_nonce += 1;
+    common::complex_actions::switch_era(
+        &worker,
+        &users[5],
+        &anchor,
+        2,
+        appchain_message_nonce,
+        false,
+    )
+    .await?;
+    for account in [&users[1], &users[2]] {
+        let unbonded_stakes = unbonded_stakes_of(&worker, &anchor, account).await?;
+        assert_eq!(unbonded_stakes.len(), 1);
+        let result = staking_actions::rebond(
+            &worker,
+            account,
+            &anchor,
+            unbonded_stakes[0].staking_history_index,
+            None,
+        )
+        .await?;
+        assert!(!result.is_success());
+    }
+    Ok(())
+}
+
+async fn unbonded_stakes_of(
+    worker: &Worker<Sandbox>,
+    anchor: &Contract,
+    account: &Account,
+) -> anyhow::Result<Vec<UnbondedStake>> {
+    anchor_viewer::get_unbonded_stakes_of(worker, anchor, account).await
+}
@@ -0,0 +1,92 @@
+use crate::{common, contract_interfaces::staking_actions};
+use appchain_anchor::appchain_messages::{EraPayoutPayload, Offender, PayloadType, RawMessage};
+use near_primitives::borsh::BorshSerialize;
+use workspaces::{network::Sandbox, result::CallExecutionDetails, Account, Contract, Worker};
+
+#[tokio::test]
+async fn test_redelegation() -> anyhow::Result<()> {
+    let worker = workspaces::sandbox().await?;
+    let (_, _, _, _, anchor, _wat_faucet, users, mut appchain_message_nonce) =
+        common::test_normal_actions(&worker, false, false, vec!["0x00".to_string()]).await?;
+    //
+    // A delegator can only redelegate once in an era, even from another validator
+    //
+    let result = redelegate(&worker, &anchor, &users[3], &users[0], &users[4], 500).await?;
+    assert!(result.is_success());
+    let result = redelegate(&worker, &anchor, &users[3], &users[4], &users[0], 500).await?;
+    assert!(!result.is_success());
+    appchain_message_nonce += 1;
+    common::complex_actions::switch_era(
+        &worker,
+        &users[5],
+        &anchor,
+        1,
+        appchain_message_nonce,
+        false,
+    )
+    .await?;
+    let result = redelegate(&worker, &anchor, &users[3], &users[4], &users[0], 500).await?;
+    assert!(result.is_success());
+    //
+    // The delegation can not be moved away from a validator whose offences are
+    // reported but not slashed yet
+    //
+    appchain_message_nonce += 1;
+    common::complex_actions::stage_appchain_messages_by_witnesses(
+        &worker,
+        &[&users[5]],
+        &anchor,
+        vec![RawMessage {
+            nonce: appchain_message_nonce as u64,
+            payload_type: PayloadType::EraPayout,
+            payload: EraPayoutPayload {
+                end_era: 0,
+                excluded_validators: Vec::new(),
+                offenders: vec![Offender {
+                    kind: "im_online".to_string(),
+                    who: "0xd43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d"
+                        .to_string(),
+                    offences: 1,
+                }],
+            }
+            .try_to_vec()
+            .unwrap(),
+        }],
+    )
+    .await?;
+    let result = redelegate(&worker, &anchor, &users[2], &users[0], &users[4], 500).await?;
+    assert!(!result.is_success());
+    common::complex_actions::process_appchain_messages(&worker, &users[5], &anchor).await?;
+    let result = redelegate(&worker, &anchor, &users[2], &users[0], &users[4], 500).await?;
+    assert!(result.is_success());
+    let events = common::get_anchor_events(&result, "staking_history_recorded");
+    assert_eq!(events.len(), 1);
+    let staking_fact = &events[0]["staking_history"]["staking_fact"]["DelegationRedelegated"];
+    assert_eq!(staking_fact["delegator_id"], users[2].id().as_str());
+    assert_eq!(staking_fact["from_validator_id"], users[0].id().as_str());
+    assert_eq!(staking_fact["to_validator_id"], users[4].id().as_str());
+    assert_eq!(
+        staking_fact["amount"],
+        common::to_actual_amount(500, 18).to_string()
+    );
+    Ok(())
+}
+
+async fn redelegate(
+    worker: &Worker<Sandbox>,
+    anchor: &Contract,
+    delegator: &Account,
+    from_validator: &Account,
+    to_validator: &Account,
+    amount: u128,
+) -> anyhow::Result<CallExecutionDetails> {
+    staking_actions::redelegate(
+        worker,
+        delegator,
+        anchor,
+        &from_validator.id().to_string().parse().unwrap(),
+        &to_validator.id().to_string().parse().unwrap(),
+        common::to_actual_amount(amount, 18),
+    )
+    .await
+}
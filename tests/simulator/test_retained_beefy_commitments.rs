@@ -0,0 +1,87 @@
+use crate::{
+    common::{
+        self,
+        beefy_authorities::{single_leaf_mmr_proof, BeefyAuthoritySet},
+        complex_actions::update_state_of_beefy_light_client,
+        grandpa_authorities::{make_header, messages_commitment},
+    },
+    contract_interfaces::{anchor_viewer, permissionless_actions, settings_manager},
+};
+use appchain_anchor::appchain_messages::{PayloadType, PlanNewEraPayload, RawMessage};
+use near_sdk::{borsh::BorshSerialize, env};
+use parity_scale_codec::Encode;
+
+#[tokio::test]
+async fn test_retained_beefy_commitments() -> anyhow::Result<()> {
+    let worker = workspaces::sandbox().await?;
+    let authorities = BeefyAuthoritySet::new(5);
+    let (root, _, _, _, anchor, _, users, appchain_message_nonce) =
+        common::test_normal_actions(&worker, false, true, authorities.public_keys()).await?;
+    settings_manager::turn_off_beefy_light_client_witness_mode(&worker, &root, &anchor)
+        .await
+        .expect("Failed to call 'turn_off_beefy_light_client_witness_mode'");
+    //
+    // The messages are committed in header 8, which is in the MMR of commitment at block 9
+    //
+    let encoded_messages = vec![RawMessage {
+        nonce: u64::from(appchain_message_nonce + 1),
+        payload_type: PayloadType::PlanNewEra,
+        payload: PlanNewEraPayload { new_era: 1 }.try_to_vec().unwrap(),
+    }]
+    .encode();
+    let header = make_header(8, vec![messages_commitment(&encoded_messages)]);
+    let leaf_1 = authorities.make_mmr_leaf(8, header.hash());
+    let leaf_2 = authorities.make_mmr_leaf(16, make_header(16, Vec::new()).hash());
+    update_state_of_beefy_light_client(&worker, &users[4], &anchor, &authorities, 9, &leaf_1)
+        .await?;
+    update_state_of_beefy_light_client(&worker, &users[4], &anchor, &authorities, 17, &leaf_2)
+        .await?;
+    assert_eq!(
+        anchor_viewer::get_retained_commitments_of_appchain(&worker, &anchor)
+            .await?
+            .iter()
+            .map(|commitment| (commitment.block_number, commitment.mmr_root.clone()))
+            .collect::<Vec<(u32, String)>>(),
+        vec![
+            (9, hex::encode(env::keccak256(&leaf_1))),
+            (17, hex::encode(env::keccak256(&leaf_2)))
+        ]
+    );
+    //
+    // The proof of header 8 is not valid against the latest commitment
+    //
+    let result = permissionless_actions::verify_and_stage_appchain_messages(
+        &worker,
+        &users[4],
+        &anchor,
+        encoded_messages.clone(),
+        header.encode(),
+        leaf_1.encode(),
+        single_leaf_mmr_proof(),
+    )
+    .await?;
+    assert!(!result.is_success());
+    //
+    // But it is still valid against the older commitment at block 9
+    //
+    let result = permissionless_actions::verify_and_stage_appchain_messages_with_commitment(
+        &worker,
+        &users[4],
+        &anchor,
+        encoded_messages,
+        header.encode(),
+        leaf_1.encode(),
+        single_leaf_mmr_proof(),
+        9,
+    )
+    .await?;
+    assert!(result.is_success());
+    assert_eq!(
+        anchor_viewer::get_anchor_status(&worker, &anchor)
+            .await?
+            .permissionless_actions_status
+            .max_nonce_of_staged_appchain_messages,
+        appchain_message_nonce + 1
+    );
+    Ok(())
+}
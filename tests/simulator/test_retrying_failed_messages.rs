@@ -0,0 +1,168 @@
+use crate::{
+    common::{self, basic_actions::register_user_to_ft_contract, complex_actions},
+    contract_interfaces::{anchor_viewer, owner_actions, permissionless_actions},
+};
+use appchain_anchor::types::FailedAppchainMessageStatus;
+use near_units::parse_near;
+
+#[tokio::test]
+async fn test_retrying_failed_messages() -> anyhow::Result<()> {
+    let worker = workspaces::sandbox().await?;
+    let (root, oct_token, _, _, anchor, _wat_faucet, users, appchain_message_nonce) =
+        common::test_normal_actions(&worker, false, false, vec!["0x00".to_string()]).await?;
+    complex_actions::open_bridging_of_oct_token(&worker, &root, &anchor, &oct_token).await?;
+    complex_actions::lock_oct_to_appchain(&worker, &users[0], &oct_token, &anchor, 300).await?;
+    //
+    // The unlockings to the accounts which are not registered in OCT token contract
+    // are recorded as failed appchain messages
+    //
+    let receiver1 = root
+        .create_subaccount(&worker, "receiver1")
+        .initial_balance(parse_near!("10 N"))
+        .transact()
+        .await?
+        .unwrap();
+    let receiver2 = root
+        .create_subaccount(&worker, "receiver2")
+        .initial_balance(parse_near!("10 N"))
+        .transact()
+        .await?
+        .unwrap();
+    complex_actions::unlock_oct_from_appchain(
+        &worker,
+        &users[5],
+        &anchor,
+        &oct_token,
+        &receiver1,
+        100,
+        appchain_message_nonce + 1,
+    )
+    .await?;
+    complex_actions::unlock_oct_from_appchain(
+        &worker,
+        &users[5],
+        &anchor,
+        &oct_token,
+        &receiver2,
+        100,
+        appchain_message_nonce + 2,
+    )
+    .await?;
+    let failed_messages = anchor_viewer::get_failed_appchain_messages(&worker, &anchor).await?;
+    assert_eq!(failed_messages.len(), 2);
+    assert!(failed_messages
+        .iter()
+        .all(|message| message.status == FailedAppchainMessageStatus::Failed));
+    //
+    // Only the owner can abandon a failed message,
+    // and a message which is not failed can not be retried
+    //
+    let result = owner_actions::mark_appchain_message_abandoned(
+        &worker,
+        &users[0],
+        &anchor,
+        appchain_message_nonce + 2,
+        "receiver is gone",
+    )
+    .await?;
+    assert!(!result.is_success());
+    let result = permissionless_actions::retry_appchain_message(
+        &worker,
+        &users[5],
+        &anchor,
+        appchain_message_nonce,
+    )
+    .await?;
+    assert!(!result.is_success());
+    //
+    // The retrying is failed again if the receiver is still not registered
+    //
+    let result = permissionless_actions::retry_appchain_message(
+        &worker,
+        &users[0],
+        &anchor,
+        appchain_message_nonce + 1,
+    )
+    .await?;
+    assert!(result.is_success());
+    let failed_message =
+        anchor_viewer::get_failed_appchain_message(&worker, &anchor, appchain_message_nonce + 1)
+            .await?
+            .unwrap();
+    assert_eq!(failed_message.status, FailedAppchainMessageStatus::Failed);
+    assert_eq!(failed_message.retry_count, 1);
+    //
+    // Anyone can retry the failed message after the receiver is registered
+    //
+    register_user_to_ft_contract(&worker, &receiver1, &oct_token).await?;
+    let result = permissionless_actions::retry_appchain_message(
+        &worker,
+        &users[5],
+        &anchor,
+        appchain_message_nonce + 1,
+    )
+    .await?;
+    assert!(result.is_success());
+    assert_eq!(
+        common::get_ft_balance_of(&worker, &receiver1, &oct_token)
+            .await?
+            .0,
+        common::to_actual_amount(100, 18)
+    );
+    let failed_message =
+        anchor_viewer::get_failed_appchain_message(&worker, &anchor, appchain_message_nonce + 1)
+            .await?
+            .unwrap();
+    assert_eq!(failed_message.status, FailedAppchainMessageStatus::Resolved);
+    assert_eq!(failed_message.retry_count, 2);
+    //
+    // The owner can abandon a failed message, and its pending transfer is removed
+    //
+    let result = owner_actions::mark_appchain_message_abandoned(
+        &worker,
+        &root,
+        &anchor,
+        appchain_message_nonce + 2,
+        "receiver is gone",
+    )
+    .await?;
+    assert!(result.is_success());
+    let failed_message =
+        anchor_viewer::get_failed_appchain_message(&worker, &anchor, appchain_message_nonce + 2)
+            .await?
+            .unwrap();
+    assert_eq!(
+        failed_message.status,
+        FailedAppchainMessageStatus::Abandoned
+    );
+    assert_eq!(
+        failed_message.abandon_reason,
+        Some("receiver is gone".to_string())
+    );
+    assert!(
+        anchor_viewer::get_pending_transfers_of(&worker, &anchor, &receiver2)
+            .await?
+            .is_empty()
+    );
+    //
+    // The abandoned message can neither be retried nor abandoned again
+    //
+    let result = permissionless_actions::retry_appchain_message(
+        &worker,
+        &users[5],
+        &anchor,
+        appchain_message_nonce + 2,
+    )
+    .await?;
+    assert!(!result.is_success());
+    let result = owner_actions::mark_appchain_message_abandoned(
+        &worker,
+        &root,
+        &anchor,
+        appchain_message_nonce + 2,
+        "receiver is gone",
+    )
+    .await?;
+    assert!(!result.is_success());
+    Ok(())
+}
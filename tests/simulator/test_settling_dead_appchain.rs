@@ -0,0 +1,197 @@
+use crate::{
+    common,
+    contract_interfaces::{
+        anchor_viewer, lifecycle_actions, near_fungible_token_manager, permissionless_actions,
+    },
+};
+use appchain_anchor::{
+    appchain_messages::{BurnAssetPayload, PayloadType, RawMessage},
+    types::{
+        AppchainSettlementStatus, AppchainState, MultiTxsOperationProcessingResult, NativeNearToken,
+    },
+};
+use near_primitives::borsh::BorshSerialize;
+use near_sdk::{json_types::U128, serde_json::json};
+use near_units::parse_near;
+use workspaces::{network::Sandbox, Account, Contract, Worker};
+
+#[tokio::test]
+async fn test_settling_dead_appchain() -> anyhow::Result<()> {
+    //
+    let worker = workspaces::sandbox().await?;
+    let (root, oct_token, _, _, anchor, _wat_faucet, users, appchain_message_nonce) =
+        common::test_normal_actions(&worker, false, false, vec!["0x00".to_string()]).await?;
+    //
+    near_fungible_token_manager::register_near_fungible_token(
+        &worker,
+        &root,
+        &anchor,
+        "OCT".to_string(),
+        "Oct token".to_string(),
+        18,
+        oct_token.id().to_string().parse().unwrap(),
+        U128::from(1000000),
+    )
+    .await
+    .expect("Failed to register NEAR fungible token");
+    let result = near_fungible_token_manager::open_bridging_of_near_fungible_token(
+        &worker,
+        &root,
+        &anchor,
+        "OCT".to_string(),
+    )
+    .await?;
+    assert!(result.is_success());
+    let result = root
+        .call(&worker, anchor.id(), "open_bridging_of_native_near_token")
+        .gas(200_000_000_000_000)
+        .transact()
+        .await?;
+    assert!(result.is_success());
+    //
+    // User0 locks 200 OCT, then user1 locks 100 OCT and 10 NEAR
+    //
+    common::complex_actions::lock_oct_to_appchain(&worker, &users[0], &oct_token, &anchor, 200)
+        .await?;
+    common::complex_actions::lock_oct_to_appchain(&worker, &users[1], &oct_token, &anchor, 100)
+        .await?;
+    lock_near_to_appchain(&worker, &users[1], &anchor, parse_near!("10 N")).await?;
+    //
+    // The appchain burns 100 OCT and unlocks them to user1
+    //
+    let raw_messages = vec![RawMessage {
+        nonce: (appchain_message_nonce + 1) as u64,
+        payload_type: PayloadType::BurnAsset,
+        payload: BurnAssetPayload {
+            token_id: oct_token.id().to_string(),
+            sender: "0xd43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d"
+                .to_string(),
+            receiver_id: users[1].id().to_string().parse().unwrap(),
+            amount: common::to_actual_amount(100, 18),
+        }
+        .try_to_vec()
+        .unwrap(),
+    }];
+    common::complex_actions::stage_appchain_messages_by_witnesses(
+        &worker,
+        &[&users[5]],
+        &anchor,
+        raw_messages,
+    )
+    .await?;
+    common::complex_actions::process_appchain_messages(&worker, &users[5], &anchor).await?;
+    assert_eq!(
+        net_locked_balance_of(&worker, &anchor, &oct_token, &users[0]).await?,
+        common::to_actual_amount(200, 18)
+    );
+    assert_eq!(
+        net_locked_balance_of(&worker, &anchor, &oct_token, &users[1]).await?,
+        0
+    );
+    //
+    // Kill the appchain and settle it
+    //
+    let result = lifecycle_actions::go_broken(&worker, &root, &anchor).await?;
+    assert!(result.is_success());
+    let events = common::get_anchor_events(&result, "appchain_state_changed");
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0]["previous_state"], "Active");
+    assert_eq!(events[0]["state"], "Broken");
+    let result = lifecycle_actions::go_dead(&worker, &root, &anchor).await?;
+    assert!(result.is_success());
+    let events = common::get_anchor_events(&result, "appchain_state_changed");
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0]["previous_state"], "Broken");
+    assert_eq!(events[0]["state"], "Dead");
+    assert_eq!(
+        anchor_viewer::get_appchain_state(&worker, &anchor).await?,
+        AppchainState::Dead
+    );
+    let user0_balance = common::get_ft_balance_of(&worker, &users[0], &oct_token).await?;
+    let user1_balance = common::get_ft_balance_of(&worker, &users[1], &oct_token).await?;
+    let user1_near_balance = users[1].view_account(&worker).await?.balance;
+    loop {
+        let result = permissionless_actions::settle_dead_appchain(&worker, &root, &anchor).await?;
+        match result {
+            MultiTxsOperationProcessingResult::Ok => break,
+            MultiTxsOperationProcessingResult::NeedMoreGas => (),
+            MultiTxsOperationProcessingResult::Error(message) => {
+                panic!("Failed to settle dead appchain: {}", &message);
+            }
+        }
+    }
+    assert_eq!(
+        anchor_viewer::get_appchain_settlement_status(&worker, &anchor).await?,
+        Some(AppchainSettlementStatus::Completed)
+    );
+    //
+    // All the remaining locked OCT belongs to user0 in the final state of appchain
+    //
+    assert_eq!(
+        common::get_ft_balance_of(&worker, &users[0], &oct_token)
+            .await?
+            .0,
+        user0_balance.0 + common::to_actual_amount(200, 18)
+    );
+    assert_eq!(
+        common::get_ft_balance_of(&worker, &users[1], &oct_token)
+            .await?
+            .0,
+        user1_balance.0
+    );
+    assert_eq!(
+        net_locked_balance_of(&worker, &anchor, &oct_token, &users[0]).await?,
+        0
+    );
+    let near_fungible_tokens = anchor_viewer::get_near_fungible_tokens(&worker, &anchor).await?;
+    assert_eq!(near_fungible_tokens[0].locked_balance.0, 0);
+    //
+    // The locked native NEAR token is returned to user1
+    //
+    assert_eq!(
+        users[1].view_account(&worker).await?.balance,
+        user1_near_balance + parse_near!("10 N")
+    );
+    let native_near_token: NativeNearToken = anchor
+        .call(&worker, "get_native_near_token")
+        .view()
+        .await?
+        .json()?;
+    assert_eq!(native_near_token.locked_balance.0, 0);
+    Ok(())
+}
+
+async fn lock_near_to_appchain(
+    worker: &Worker<Sandbox>,
+    user: &Account,
+    anchor: &Contract,
+    amount: u128,
+) -> anyhow::Result<()> {
+    let result = user
+        .call(worker, anchor.id(), "lock_near_to_appchain")
+        .args_json(json!({
+            "receiver_id_in_appchain": "0xd43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d".to_string(),
+        }))?
+        .gas(200_000_000_000_000)
+        .deposit(amount)
+        .transact()
+        .await?;
+    assert!(result.is_success());
+    Ok(())
+}
+
+async fn net_locked_balance_of(
+    worker: &Worker<Sandbox>,
+    anchor: &Contract,
+    oct_token: &Contract,
+    user: &Account,
+) -> anyhow::Result<u128> {
+    Ok(anchor_viewer::get_net_locked_balance_of(
+        worker,
+        anchor,
+        oct_token.id().to_string().parse().unwrap(),
+        user.id().to_string().parse().unwrap(),
+    )
+    .await?
+    .0)
+}
@@ -0,0 +1,128 @@
+use crate::{
+    common,
+    contract_interfaces::{anchor_viewer, settings_manager, staking_actions},
+};
+use appchain_anchor::appchain_messages::{EraPayoutPayload, Offender, PayloadType, RawMessage};
+use near_primitives::borsh::BorshSerialize;
+use workspaces::{network::Sandbox, Account, Contract, Worker};
+
+#[tokio::test]
+async fn test_slashing_offenders() -> anyhow::Result<()> {
+    let worker = workspaces::sandbox().await?;
+    let (root, oct_token, _, _, anchor, _wat_faucet, users, mut appchain_message_nonce) =
+        common::test_normal_actions(&worker, false, false, vec!["0x00".to_string()]).await?;
+    let result = settings_manager::change_slashing_percent_of_offence_kind(
+        &worker,
+        &root,
+        &anchor,
+        "im_online",
+        10,
+    )
+    .await?;
+    assert!(result.is_success());
+    //
+    // Unbond part of the delegation to user0 in era 0
+    //
+    let result = staking_actions::decrease_delegation(
+        &worker,
+        &users[3],
+        &anchor,
+        &users[0].id().to_string().parse().unwrap(),
+        common::to_actual_amount(500, 18),
+    )
+    .await?;
+    assert!(result.is_success());
+    //
+    // Redelegate part of the delegation to user0 to user4 in era 0
+    //
+    let result = staking_actions::redelegate(
+        &worker,
+        &users[2],
+        &anchor,
+        &users[0].id().to_string().parse().unwrap(),
+        &users[4].id().to_string().parse().unwrap(),
+        common::to_actual_amount(500, 18),
+    )
+    .await?;
+    assert!(result.is_success());
+    appchain_message_nonce += 1;
+    common::complex_actions::switch_era(
+        &worker,
+        &users[5],
+        &anchor,
+        1,
+        appchain_message_nonce,
+        false,
+    )
+    .await?;
+    let total_stake_of_user0 = get_total_stake_in_next_era(&worker, &anchor, &users[0]).await?;
+    let total_stake_of_user4 = get_total_stake_in_next_era(&worker, &anchor, &users[4]).await?;
+    let oct_balance_of_root = common::get_ft_balance_of(&worker, &root, &oct_token).await?;
+    //
+    // User0 is reported as an offender of era 0, the stake in next validator set,
+    // the stake unbonded in era 0 and the stake redelegated in era 0 are slashed
+    //
+    appchain_message_nonce += 1;
+    common::complex_actions::stage_appchain_messages_by_witnesses(
+        &worker,
+        &[&users[5]],
+        &anchor,
+        vec![RawMessage {
+            nonce: appchain_message_nonce as u64,
+            payload_type: PayloadType::EraPayout,
+            payload: EraPayoutPayload {
+                end_era: 0,
+                excluded_validators: Vec::new(),
+                offenders: vec![Offender {
+                    kind: "im_online".to_string(),
+                    who: "0xd43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d"
+                        .to_string(),
+                    offences: 1,
+                }],
+            }
+            .try_to_vec()
+            .unwrap(),
+        }],
+    )
+    .await?;
+    common::complex_actions::process_appchain_messages(&worker, &users[5], &anchor).await?;
+    let unbonded_stakes =
+        anchor_viewer::get_unbonded_stakes_of(&worker, &anchor, &users[3]).await?;
+    assert_eq!(unbonded_stakes.len(), 1);
+    assert_eq!(
+        unbonded_stakes[0].amount.0,
+        common::to_actual_amount(450, 18)
+    );
+    assert_eq!(
+        get_total_stake_in_next_era(&worker, &anchor, &users[0]).await?,
+        total_stake_of_user0 - total_stake_of_user0 / 10
+    );
+    assert_eq!(
+        get_total_stake_in_next_era(&worker, &anchor, &users[4]).await?,
+        total_stake_of_user4 - common::to_actual_amount(50, 18)
+    );
+    //
+    // The slashed OCT token is transferred to the owner of anchor
+    //
+    assert_eq!(
+        common::get_ft_balance_of(&worker, &root, &oct_token)
+            .await?
+            .0,
+        oct_balance_of_root.0 + total_stake_of_user0 / 10 + common::to_actual_amount(100, 18)
+    );
+    Ok(())
+}
+
+async fn get_total_stake_in_next_era(
+    worker: &Worker<Sandbox>,
+    anchor: &Contract,
+    validator: &Account,
+) -> anyhow::Result<u128> {
+    Ok(anchor_viewer::get_validator_list_of(worker, anchor, None)
+        .await?
+        .iter()
+        .find(|validator_info| validator_info.validator_id.as_str() == validator.id().as_str())
+        .unwrap()
+        .total_stake
+        .0)
+}
@@ -0,0 +1,108 @@
+use crate::{
+    common::{self, complex_actions},
+    contract_interfaces::{anchor_viewer, owner_actions},
+};
+use near_units::parse_near;
+
+#[tokio::test]
+async fn test_storage_registration() -> anyhow::Result<()> {
+    let worker = workspaces::sandbox().await?;
+    let (root, oct_token, _, _, anchor, _wat_faucet, users, appchain_message_nonce) =
+        common::test_normal_actions(&worker, false, false, vec!["0x00".to_string()]).await?;
+    complex_actions::open_bridging_of_oct_token(&worker, &root, &anchor, &oct_token).await?;
+    complex_actions::lock_oct_to_appchain(&worker, &users[0], &oct_token, &anchor, 200).await?;
+    let result =
+        owner_actions::enable_storage_registration_of_token(&worker, &root, &anchor, &oct_token)
+            .await?;
+    assert!(result.is_success());
+    //
+    // The receiver is not registered if the bridge storage fund is not enough,
+    // and the transfer becomes a pending transfer
+    //
+    let receiver1 = root
+        .create_subaccount(&worker, "receiver1")
+        .initial_balance(parse_near!("10 N"))
+        .transact()
+        .await?
+        .unwrap();
+    complex_actions::unlock_oct_from_appchain(
+        &worker,
+        &users[5],
+        &anchor,
+        &oct_token,
+        &receiver1,
+        100,
+        appchain_message_nonce + 1,
+    )
+    .await?;
+    assert_eq!(
+        common::get_ft_balance_of(&worker, &receiver1, &oct_token)
+            .await?
+            .0,
+        0
+    );
+    assert_eq!(
+        anchor_viewer::get_pending_transfers_of(&worker, &anchor, &receiver1)
+            .await?
+            .len(),
+        1
+    );
+    assert_eq!(
+        anchor_viewer::get_storage_registration_of_token(&worker, &anchor, &oct_token)
+            .await?
+            .unwrap()
+            .registered_account_count
+            .0,
+        0
+    );
+    //
+    // The receiver is registered by the bridge storage fund before the payout
+    //
+    let result =
+        owner_actions::deposit_bridge_storage_fund(&worker, &root, &anchor, parse_near!("1 N"))
+            .await?;
+    assert!(result.is_success());
+    let receiver2 = root
+        .create_subaccount(&worker, "receiver2")
+        .initial_balance(parse_near!("10 N"))
+        .transact()
+        .await?
+        .unwrap();
+    complex_actions::unlock_oct_from_appchain(
+        &worker,
+        &users[5],
+        &anchor,
+        &oct_token,
+        &receiver2,
+        100,
+        appchain_message_nonce + 2,
+    )
+    .await?;
+    assert_eq!(
+        common::get_ft_balance_of(&worker, &receiver2, &oct_token)
+            .await?
+            .0,
+        common::to_actual_amount(100, 18)
+    );
+    assert!(
+        anchor_viewer::get_pending_transfers_of(&worker, &anchor, &receiver2)
+            .await?
+            .is_empty()
+    );
+    let storage_registration =
+        anchor_viewer::get_storage_registration_of_token(&worker, &anchor, &oct_token)
+            .await?
+            .unwrap();
+    assert_eq!(storage_registration.registered_account_count.0, 1);
+    assert!(storage_registration.spent_amount.0 > 0);
+    let bridge_storage_fund = anchor_viewer::get_bridge_storage_fund(&worker, &anchor).await?;
+    assert_eq!(
+        bridge_storage_fund.spent_amount.0,
+        storage_registration.spent_amount.0
+    );
+    assert_eq!(
+        bridge_storage_fund.balance.0,
+        parse_near!("1 N") - bridge_storage_fund.spent_amount.0
+    );
+    Ok(())
+}
@@ -22,7 +22,7 @@ async fn test_transfer_oct_to_appchain() -> anyhow::Result<()> {
     .expect("Failed to register NEAR fungible token");
     common::complex_viewer::print_near_fungible_tokens(&worker, &anchor).await?;
     //
-    common::call_ft_transfer_call(
+    let result = common::call_ft_transfer_call(
         &worker,
         &users[0],
         &anchor.as_account(),
@@ -35,6 +35,15 @@ async fn test_transfer_oct_to_appchain() -> anyhow::Result<()> {
         .to_string(),
         &oct_token,
     ).await?;
+    assert!(result.is_success());
+    let events = common::get_anchor_events(&result, "near_fungible_token_locked");
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0]["symbol"], "OCT");
+    assert_eq!(events[0]["sender_id_in_near"], users[0].id().as_str());
+    assert_eq!(
+        events[0]["receiver_id_in_appchain"],
+        "0xd43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d"
+    );
     common::complex_viewer::print_appchain_notifications(&worker, &anchor).await?;
     Ok(())
 }
@@ -0,0 +1,114 @@
+use crate::{
+    common,
+    contract_interfaces::{anchor_viewer, settings_manager, validator_actions},
+};
+use workspaces::{network::Sandbox, Account, Contract, Worker};
+
+#[tokio::test]
+async fn test_validator_commissions() -> anyhow::Result<()> {
+    //
+    let worker = workspaces::sandbox().await?;
+    let (root, _, _, _, anchor, _wat_faucet, users, mut appchain_message_nonce) =
+        common::test_normal_actions(&worker, false, false, vec!["0x00".to_string()]).await?;
+    //
+    // The increase of commission of user0 takes effect from era 3,
+    // and the decrease of default commission takes effect from era 1
+    //
+    let result =
+        validator_actions::change_validator_commission(&worker, &users[0], &anchor, 25).await?;
+    assert!(result.is_success());
+    let result =
+        settings_manager::change_validator_commission_percent(&worker, &root, &anchor, 15).await?;
+    assert!(result.is_success());
+    let protocol_settings = anchor_viewer::get_protocol_settings(&worker, &anchor).await?;
+    assert_eq!(protocol_settings.validator_commission_percent, 20);
+    assert_eq!(
+        protocol_settings.pending_validator_commission_percent,
+        Some(15)
+    );
+    for era_number in 1..4 {
+        appchain_message_nonce += 1;
+        common::complex_actions::switch_era(
+            &worker,
+            &users[5],
+            &anchor,
+            era_number,
+            appchain_message_nonce,
+            false,
+        )
+        .await?;
+        if era_number == 1 {
+            //
+            // The last change of default commission has not taken effect in era 1
+            //
+            let result =
+                settings_manager::change_validator_commission_percent(&worker, &root, &anchor, 10)
+                    .await?;
+            assert!(!result.is_success());
+        }
+    }
+    for (era_number, commission_of_user0, commission_of_user1) in
+        [(0, 20, 20), (1, 20, 15), (2, 20, 15), (3, 25, 15)]
+    {
+        assert_eq!(
+            commission_percent_of(&worker, &anchor, era_number, &users[0]).await?,
+            commission_of_user0
+        );
+        assert_eq!(
+            commission_percent_of(&worker, &anchor, era_number, &users[1]).await?,
+            commission_of_user1
+        );
+    }
+    //
+    // The increase of default commission takes effect after the notice period,
+    // and does not change the commission recorded in previous eras
+    //
+    let result =
+        settings_manager::change_validator_commission_percent(&worker, &root, &anchor, 20).await?;
+    assert!(result.is_success());
+    let protocol_settings = anchor_viewer::get_protocol_settings(&worker, &anchor).await?;
+    assert_eq!(protocol_settings.validator_commission_percent, 15);
+    assert_eq!(
+        protocol_settings
+            .validator_commission_percent_effective_era_number
+            .0,
+        6
+    );
+    appchain_message_nonce += 1;
+    common::complex_actions::switch_era(
+        &worker,
+        &users[5],
+        &anchor,
+        4,
+        appchain_message_nonce,
+        false,
+    )
+    .await?;
+    assert_eq!(
+        commission_percent_of(&worker, &anchor, 4, &users[1]).await?,
+        15
+    );
+    assert_eq!(
+        commission_percent_of(&worker, &anchor, 1, &users[0]).await?,
+        20
+    );
+    Ok(())
+}
+
+async fn commission_percent_of(
+    worker: &Worker<Sandbox>,
+    anchor: &Contract,
+    era_number: u64,
+    validator: &Account,
+) -> anyhow::Result<u16> {
+    Ok(
+        anchor_viewer::get_validator_list_of(worker, anchor, Some(era_number))
+            .await?
+            .iter()
+            .find(|validator_of_era| {
+                validator_of_era.validator_id.as_str() == validator.id().as_str()
+            })
+            .unwrap()
+            .commission_percent,
+    )
+}
@@ -0,0 +1,287 @@
+use crate::{
+    common,
+    contract_interfaces::{anchor_viewer, owner_actions, settings_manager, staking_actions},
+};
+use near_sdk::json_types::U64;
+use workspaces::{network::Sandbox, Account, Contract, Worker};
+
+#[tokio::test]
+async fn test_validator_set_checkpoints() -> anyhow::Result<()> {
+    //
+    let worker = workspaces::sandbox().await?;
+    let (root, oct_token, _, _, anchor, _wat_faucet, users, mut appchain_message_nonce) =
+        common::test_normal_actions(&worker, false, false, vec!["0x00".to_string()]).await?;
+    //
+    // Era 0 is a checkpoint, era 1 and era 2 only store the changes of delegators,
+    // and era 3 is the next checkpoint
+    //
+    let result =
+        settings_manager::change_validator_set_checkpoint_interval(&worker, &root, &anchor, 3)
+            .await?;
+    assert!(result.is_success());
+    //
+    // In era 1, user3 increases delegation to user0, and both user2 and user3 delegate to user4
+    //
+    let result = staking_actions::increase_delegation(
+        &worker,
+        &users[3],
+        &oct_token,
+        &anchor,
+        &users[0].id().to_string().parse().unwrap(),
+        common::to_actual_amount(100, 18),
+    )
+    .await?;
+    assert!(result.is_success());
+    let result = staking_actions::register_delegator(
+        &worker,
+        &users[2],
+        &oct_token,
+        &anchor,
+        &users[4].id().to_string().parse().unwrap(),
+        common::to_actual_amount(1000, 18),
+    )
+    .await?;
+    assert!(result.is_success());
+    let result = staking_actions::register_delegator(
+        &worker,
+        &users[3],
+        &oct_token,
+        &anchor,
+        &users[4].id().to_string().parse().unwrap(),
+        common::to_actual_amount(1000, 18),
+    )
+    .await?;
+    assert!(result.is_success());
+    appchain_message_nonce += 1;
+    common::complex_actions::switch_era(
+        &worker,
+        &users[5],
+        &anchor,
+        1,
+        appchain_message_nonce,
+        false,
+    )
+    .await?;
+    //
+    // In era 2, user2 unbonds delegation from user4, while user3 remains
+    //
+    let result = staking_actions::unbond_delegation(
+        &worker,
+        &users[2],
+        &anchor,
+        &users[4].id().to_string().parse().unwrap(),
+    )
+    .await?;
+    assert!(result.is_success());
+    appchain_message_nonce += 1;
+    common::complex_actions::switch_era(
+        &worker,
+        &users[5],
+        &anchor,
+        2,
+        appchain_message_nonce,
+        false,
+    )
+    .await?;
+    appchain_message_nonce += 1;
+    common::complex_actions::switch_era(
+        &worker,
+        &users[5],
+        &anchor,
+        3,
+        appchain_message_nonce,
+        false,
+    )
+    .await?;
+    //
+    // Era 0 (checkpoint)
+    //
+    assert_eq!(
+        delegators_of(&worker, &anchor, 0, &users[0]).await?,
+        vec![
+            (
+                users[2].id().to_string(),
+                common::to_actual_amount(1500, 18)
+            ),
+            (
+                users[3].id().to_string(),
+                common::to_actual_amount(2000, 18)
+            ),
+        ]
+    );
+    assert_eq!(delegator_count_of(&worker, &anchor, 0, &users[0]).await?, 2);
+    //
+    // Era 1 (changes only)
+    //
+    assert_eq!(
+        delegators_of(&worker, &anchor, 1, &users[0]).await?,
+        vec![
+            (
+                users[2].id().to_string(),
+                common::to_actual_amount(1500, 18)
+            ),
+            (
+                users[3].id().to_string(),
+                common::to_actual_amount(2100, 18)
+            ),
+        ]
+    );
+    assert_eq!(delegator_count_of(&worker, &anchor, 1, &users[0]).await?, 2);
+    assert_eq!(
+        delegators_of(&worker, &anchor, 1, &users[4]).await?,
+        vec![
+            (
+                users[2].id().to_string(),
+                common::to_actual_amount(1000, 18)
+            ),
+            (
+                users[3].id().to_string(),
+                common::to_actual_amount(1000, 18)
+            ),
+        ]
+    );
+    assert_eq!(delegator_count_of(&worker, &anchor, 1, &users[4]).await?, 2);
+    //
+    // Era 2 (changes only) and era 3 (checkpoint) have the same delegators
+    //
+    for era_number in [2, 3] {
+        assert_eq!(
+            delegators_of(&worker, &anchor, era_number, &users[0]).await?,
+            vec![
+                (
+                    users[2].id().to_string(),
+                    common::to_actual_amount(1500, 18)
+                ),
+                (
+                    users[3].id().to_string(),
+                    common::to_actual_amount(2100, 18)
+                ),
+            ]
+        );
+        assert_eq!(
+            delegator_count_of(&worker, &anchor, era_number, &users[0]).await?,
+            2
+        );
+        assert_eq!(
+            delegators_of(&worker, &anchor, era_number, &users[4]).await?,
+            vec![(
+                users[3].id().to_string(),
+                common::to_actual_amount(1000, 18)
+            )]
+        );
+        assert_eq!(
+            delegator_count_of(&worker, &anchor, era_number, &users[4]).await?,
+            1
+        );
+    }
+    //
+    // The validator sets before the checkpoint of era 3 are removed,
+    // the staking histories needed by the checkpoint can not be removed
+    //
+    let result = owner_actions::remove_validator_set_before(&worker, &root, &anchor, 2).await?;
+    assert!(!result.is_success());
+    let result = owner_actions::remove_validator_set_before(&worker, &root, &anchor, 3).await?;
+    assert!(result.is_success());
+    let staking_history_index =
+        anchor_viewer::get_validator_set_info_of(&worker, &anchor, U64::from(3))
+            .await?
+            .staking_history_index
+            .0;
+    let result = owner_actions::remove_staking_history_before(
+        &worker,
+        &root,
+        &anchor,
+        staking_history_index + 2,
+    )
+    .await?;
+    assert!(!result.is_success());
+    let result = owner_actions::remove_staking_history_before(
+        &worker,
+        &root,
+        &anchor,
+        staking_history_index + 1,
+    )
+    .await?;
+    assert!(result.is_success());
+    //
+    // In era 4, user2 delegates to user4 again, the delegators of the
+    // remaining validator sets are not affected by the removal
+    //
+    let result = staking_actions::register_delegator(
+        &worker,
+        &users[2],
+        &oct_token,
+        &anchor,
+        &users[4].id().to_string().parse().unwrap(),
+        common::to_actual_amount(500, 18),
+    )
+    .await?;
+    assert!(result.is_success());
+    appchain_message_nonce += 1;
+    common::complex_actions::switch_era(
+        &worker,
+        &users[5],
+        &anchor,
+        4,
+        appchain_message_nonce,
+        false,
+    )
+    .await?;
+    assert_eq!(
+        delegators_of(&worker, &anchor, 3, &users[4]).await?,
+        vec![(
+            users[3].id().to_string(),
+            common::to_actual_amount(1000, 18)
+        )]
+    );
+    assert_eq!(
+        delegators_of(&worker, &anchor, 4, &users[4]).await?,
+        vec![
+            (users[2].id().to_string(), common::to_actual_amount(500, 18)),
+            (
+                users[3].id().to_string(),
+                common::to_actual_amount(1000, 18)
+            ),
+        ]
+    );
+    assert_eq!(delegator_count_of(&worker, &anchor, 4, &users[4]).await?, 2);
+    Ok(())
+}
+
+async fn delegators_of(
+    worker: &Worker<Sandbox>,
+    anchor: &Contract,
+    era_number: u64,
+    validator: &Account,
+) -> anyhow::Result<Vec<(String, u128)>> {
+    let mut delegators: Vec<(String, u128)> =
+        anchor_viewer::get_delegators_of_validator_in_era(worker, anchor, era_number, validator)
+            .await?
+            .iter()
+            .map(|delegator| {
+                (
+                    delegator.delegator_id.to_string(),
+                    delegator.delegation_amount.0,
+                )
+            })
+            .collect();
+    delegators.sort();
+    Ok(delegators)
+}
+
+async fn delegator_count_of(
+    worker: &Worker<Sandbox>,
+    anchor: &Contract,
+    era_number: u64,
+    validator: &Account,
+) -> anyhow::Result<u64> {
+    Ok(
+        anchor_viewer::get_validator_list_of(worker, anchor, Some(era_number))
+            .await?
+            .iter()
+            .find(|validator_in_list| {
+                validator_in_list.validator_id.to_string() == validator.id().to_string()
+            })
+            .map_or(0, |validator_in_list| validator_in_list.delegators_count.0),
+    )
+}
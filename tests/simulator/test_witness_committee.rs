@@ -0,0 +1,182 @@
+use crate::{
+    common,
+    contract_interfaces::{
+        anchor_viewer, permissionless_actions, settings_manager, witness_actions,
+    },
+};
+use appchain_anchor::appchain_messages::{PayloadType, PlanNewEraPayload, RawMessage};
+use near_primitives::borsh::BorshSerialize;
+use near_sdk::env;
+use parity_scale_codec::Encode;
+
+#[tokio::test]
+async fn test_witness_committee() -> anyhow::Result<()> {
+    //
+    let worker = workspaces::sandbox().await?;
+    let (root, oct_token, _, _, anchor, _wat_faucet, users, appchain_message_nonce) =
+        common::test_normal_actions(&worker, false, false, vec!["0x00".to_string()]).await?;
+    //
+    // Add user3 and user4 to the witness committee, only user3 bonds OCT token
+    //
+    for user in [&users[3], &users[4]] {
+        settings_manager::add_witness_account(&worker, &root, &anchor, user)
+            .await
+            .expect("Failed in calling 'add_witness_account'");
+    }
+    let result = witness_actions::bond_as_witness(
+        &worker,
+        &users[3],
+        &oct_token,
+        &anchor,
+        common::to_actual_amount(common::WITNESS_BOND_AMOUNT, 18),
+    )
+    .await?;
+    assert!(result.is_success());
+    assert_eq!(
+        anchor_viewer::get_witness_bond_of(
+            &worker,
+            &anchor,
+            users[3].id().to_string().parse().unwrap()
+        )
+        .await?
+        .0,
+        common::to_actual_amount(common::WITNESS_BOND_AMOUNT, 18)
+    );
+    //
+    // The bond of an account out of the committee is returned
+    //
+    let user2_balance = common::get_ft_balance_of(&worker, &users[2], &oct_token).await?;
+    witness_actions::bond_as_witness(
+        &worker,
+        &users[2],
+        &oct_token,
+        &anchor,
+        common::to_actual_amount(common::WITNESS_BOND_AMOUNT, 18),
+    )
+    .await?;
+    assert_eq!(
+        common::get_ft_balance_of(&worker, &users[2], &oct_token)
+            .await?
+            .0,
+        user2_balance.0
+    );
+    assert_eq!(
+        anchor_viewer::get_witness_bond_of(
+            &worker,
+            &anchor,
+            users[2].id().to_string().parse().unwrap()
+        )
+        .await?
+        .0,
+        0
+    );
+    let result = settings_manager::set_witness_threshold(&worker, &root, &anchor, 2).await?;
+    assert!(result.is_success());
+    //
+    let raw_messages = vec![RawMessage {
+        nonce: (appchain_message_nonce + 1) as u64,
+        payload_type: PayloadType::PlanNewEra,
+        payload: PlanNewEraPayload { new_era: 1 }.try_to_vec().unwrap(),
+    }];
+    let encoded_messages = raw_messages.encode();
+    //
+    // The first attestation of relayer should not stage the messages
+    //
+    common::complex_actions::stage_appchain_messages_by_witnesses(
+        &worker,
+        &[&users[5]],
+        &anchor,
+        raw_messages.clone(),
+    )
+    .await?;
+    let attestations =
+        anchor_viewer::get_pending_message_batch_attestations(&worker, &anchor).await?;
+    assert_eq!(attestations.len(), 1);
+    assert_eq!(attestations[0].witnesses.len(), 1);
+    assert_eq!(
+        anchor_viewer::get_anchor_status(&worker, &anchor)
+            .await?
+            .permissionless_actions_status
+            .max_nonce_of_staged_appchain_messages,
+        appchain_message_nonce
+    );
+    //
+    // A repeated attestation of the same witness should not be counted
+    //
+    common::complex_actions::stage_appchain_messages_by_witnesses(
+        &worker,
+        &[&users[5]],
+        &anchor,
+        raw_messages.clone(),
+    )
+    .await?;
+    let attestations =
+        anchor_viewer::get_pending_message_batch_attestations(&worker, &anchor).await?;
+    assert_eq!(attestations[0].witnesses.len(), 1);
+    //
+    // Accounts out of the committee and witnesses without bond can not attest
+    //
+    for user in [&users[0], &users[4]] {
+        let result = permissionless_actions::verify_and_stage_appchain_messages(
+            &worker,
+            user,
+            &anchor,
+            encoded_messages.clone(),
+            Vec::new(),
+            Vec::new(),
+            Vec::new(),
+        )
+        .await?;
+        assert!(!result.is_success());
+    }
+    //
+    // The attestation of a second distinct witness reaches the threshold,
+    // but the messages are staged only when they are submitted again
+    //
+    let result = permissionless_actions::attest_appchain_message_batch(
+        &worker,
+        &users[3],
+        &anchor,
+        hex::encode(env::sha256(&encoded_messages)),
+    )
+    .await?;
+    assert!(result.is_success());
+    let attestations =
+        anchor_viewer::get_pending_message_batch_attestations(&worker, &anchor).await?;
+    assert_eq!(attestations[0].witnesses.len(), 2);
+    assert_eq!(
+        anchor_viewer::get_anchor_status(&worker, &anchor)
+            .await?
+            .permissionless_actions_status
+            .max_nonce_of_staged_appchain_messages,
+        appchain_message_nonce
+    );
+    common::complex_actions::stage_appchain_messages_by_witnesses(
+        &worker,
+        &[&users[5]],
+        &anchor,
+        raw_messages,
+    )
+    .await?;
+    assert!(
+        anchor_viewer::get_pending_message_batch_attestations(&worker, &anchor)
+            .await?
+            .is_empty()
+    );
+    assert_eq!(
+        anchor_viewer::get_anchor_status(&worker, &anchor)
+            .await?
+            .permissionless_actions_status
+            .max_nonce_of_staged_appchain_messages,
+        appchain_message_nonce + 1
+    );
+    common::complex_actions::process_appchain_messages(&worker, &users[5], &anchor).await?;
+    assert_eq!(
+        anchor_viewer::get_anchor_status(&worker, &anchor)
+            .await?
+            .permissionless_actions_status
+            .latest_applied_appchain_message_nonce,
+        appchain_message_nonce + 1
+    );
+    Ok(())
+}
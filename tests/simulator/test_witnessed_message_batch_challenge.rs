@@ -0,0 +1,287 @@
+use crate::{
+    common::{
+        self,
+        grandpa_authorities::{self, GrandpaAuthoritySet},
+    },
+    contract_interfaces::{
+        anchor_viewer, permissionless_actions, settings_manager, sudo_actions, witness_actions,
+    },
+};
+use appchain_anchor::{
+    appchain_challenge::{AppchainChallenge, AppchainChallengeStatus},
+    appchain_messages::{BurnAssetPayload, PayloadType, PlanNewEraPayload, RawMessage},
+    types::{LightClientType, MultiTxsOperationProcessingResult},
+};
+use near_primitives::borsh::BorshSerialize;
+use near_sdk::{env, serde_json::json};
+use parity_scale_codec::Encode;
+use workspaces::{network::Sandbox, result::CallExecutionDetails, Account, Contract, Worker};
+
+const CHALLENGE_BOND_AMOUNT: u128 = 100;
+
+#[tokio::test]
+async fn test_witnessed_message_batch_challenge() -> anyhow::Result<()> {
+    //
+    let worker = workspaces::sandbox().await?;
+    let (root, oct_token, _, _, anchor, _wat_faucet, users, appchain_message_nonce) =
+        common::test_normal_actions(&worker, false, false, vec!["0x00".to_string()]).await?;
+    //
+    // A witness can not attest until it bonds enough OCT token,
+    // and can not withdraw the bond while it is in the committee
+    //
+    settings_manager::add_witness_account(&worker, &root, &anchor, &users[3])
+        .await
+        .expect("Failed in calling 'add_witness_account'");
+    let result = settings_manager::change_witness_bond_amount(
+        &worker,
+        &root,
+        &anchor,
+        common::to_actual_amount(common::WITNESS_BOND_AMOUNT, 18),
+    )
+    .await?;
+    assert!(result.is_success());
+    let result = witness_actions::bond_as_witness(
+        &worker,
+        &users[3],
+        &oct_token,
+        &anchor,
+        common::to_actual_amount(common::WITNESS_BOND_AMOUNT / 2, 18),
+    )
+    .await?;
+    assert!(result.is_success());
+    let result = permissionless_actions::attest_appchain_message_batch(
+        &worker,
+        &users[3],
+        &anchor,
+        hex::encode(env::sha256(&[0])),
+    )
+    .await?;
+    assert!(!result.is_success());
+    let result = witness_actions::bond_as_witness(
+        &worker,
+        &users[3],
+        &oct_token,
+        &anchor,
+        common::to_actual_amount(common::WITNESS_BOND_AMOUNT / 2, 18),
+    )
+    .await?;
+    assert!(result.is_success());
+    assert_eq!(
+        anchor_viewer::get_witness_bond_of(
+            &worker,
+            &anchor,
+            users[3].id().to_string().parse().unwrap()
+        )
+        .await?
+        .0,
+        common::to_actual_amount(common::WITNESS_BOND_AMOUNT, 18)
+    );
+    let result = witness_actions::withdraw_witness_bond(&worker, &users[3], &anchor).await?;
+    assert!(!result.is_success());
+    //
+    // Use GRANDPA light client with known authorities for verifying the committed messages
+    //
+    let authorities = GrandpaAuthoritySet::new(0, &[1, 2, 3]);
+    let result = sudo_actions::reset_grandpa_light_client(
+        &worker,
+        &root,
+        &anchor,
+        authorities.public_keys(),
+        authorities.set_id,
+    )
+    .await?;
+    assert!(result.is_success());
+    let result =
+        settings_manager::set_light_client_type(&worker, &root, &anchor, LightClientType::Grandpa)
+            .await?;
+    assert!(result.is_success());
+    let result = settings_manager::change_challenge_period_of_witnessed_messages(
+        &worker, &root, &anchor, 3600,
+    )
+    .await?;
+    assert!(result.is_success());
+    //
+    // The relayer (as a witness) stages a forged message
+    //
+    let nonce = (appchain_message_nonce + 1) as u64;
+    let forged_messages = vec![RawMessage {
+        nonce,
+        payload_type: PayloadType::BurnAsset,
+        payload: BurnAssetPayload {
+            token_id: oct_token.id().to_string(),
+            sender: "0xd43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d"
+                .to_string(),
+            receiver_id: users[5].id().to_string().parse().unwrap(),
+            amount: common::to_actual_amount(1000, 18),
+        }
+        .try_to_vec()
+        .unwrap(),
+    }];
+    let encoded_forged_messages = forged_messages.encode();
+    common::complex_actions::stage_appchain_messages_by_witnesses(
+        &worker,
+        &[&users[5]],
+        &anchor,
+        forged_messages,
+    )
+    .await?;
+    assert_eq!(
+        anchor_viewer::get_anchor_status(&worker, &anchor)
+            .await?
+            .permissionless_actions_status
+            .max_nonce_of_staged_appchain_messages,
+        appchain_message_nonce + 1
+    );
+    let result =
+        permissionless_actions::process_appchain_messages(&worker, &users[4], &anchor).await?;
+    assert!(matches!(
+        result,
+        MultiTxsOperationProcessingResult::Error(_)
+    ));
+    //
+    // A challenge with committed messages which do not cover the witnessed nonce is rejected
+    //
+    let uncovering_messages = vec![RawMessage {
+        nonce: nonce + 1,
+        payload_type: PayloadType::PlanNewEra,
+        payload: PlanNewEraPayload { new_era: 1 }.try_to_vec().unwrap(),
+    }]
+    .encode();
+    let header = grandpa_authorities::make_header(
+        1,
+        vec![grandpa_authorities::messages_commitment(
+            &uncovering_messages,
+        )],
+    );
+    let challenge = AppchainChallenge::FraudulentMessageBatch {
+        submitter_account: users[0].id().to_string().parse().unwrap(),
+        batch_hash: hex::encode(env::sha256(&encoded_forged_messages)),
+        witnessed_messages: encoded_forged_messages.clone(),
+        encoded_messages: uncovering_messages,
+        header: header.encode(),
+        mmr_leaf: Vec::new(),
+        mmr_proof: Vec::new(),
+        justification: Some(authorities.sign_justification(&header, 3)),
+    };
+    let result =
+        commit_appchain_challenge(&worker, &users[0], &oct_token, &anchor, &challenge).await?;
+    assert!(result.is_success());
+    let challenge_state = anchor_viewer::get_appchain_challenge_state(&worker, &anchor, 0)
+        .await?
+        .unwrap();
+    assert_eq!(challenge_state.status, AppchainChallengeStatus::Rejected);
+    //
+    // A challenge with the committed message of the same nonce is accepted,
+    // the batch is reverted and the bond of the witness is rewarded to the submitter
+    //
+    let committed_messages = vec![RawMessage {
+        nonce,
+        payload_type: PayloadType::PlanNewEra,
+        payload: PlanNewEraPayload { new_era: 1 }.try_to_vec().unwrap(),
+    }]
+    .encode();
+    let header = grandpa_authorities::make_header(
+        2,
+        vec![grandpa_authorities::messages_commitment(
+            &committed_messages,
+        )],
+    );
+    let justification = authorities.sign_justification(&header, 3);
+    let challenge = AppchainChallenge::FraudulentMessageBatch {
+        submitter_account: users[0].id().to_string().parse().unwrap(),
+        batch_hash: hex::encode(env::sha256(&encoded_forged_messages)),
+        witnessed_messages: encoded_forged_messages,
+        encoded_messages: committed_messages.clone(),
+        header: header.encode(),
+        mmr_leaf: Vec::new(),
+        mmr_proof: Vec::new(),
+        justification: Some(justification.clone()),
+    };
+    let user0_balance = common::get_ft_balance_of(&worker, &users[0], &oct_token).await?;
+    let result =
+        commit_appchain_challenge(&worker, &users[0], &oct_token, &anchor, &challenge).await?;
+    assert!(result.is_success());
+    let challenge_state = anchor_viewer::get_appchain_challenge_state(&worker, &anchor, 1)
+        .await?
+        .unwrap();
+    assert_eq!(challenge_state.status, AppchainChallengeStatus::Accepted);
+    assert_eq!(
+        challenge_state.slashed_amount.0,
+        common::to_actual_amount(common::WITNESS_BOND_AMOUNT, 18)
+    );
+    assert_eq!(
+        common::get_ft_balance_of(&worker, &users[0], &oct_token)
+            .await?
+            .0,
+        user0_balance.0 + common::to_actual_amount(common::WITNESS_BOND_AMOUNT, 18)
+    );
+    assert_eq!(
+        anchor_viewer::get_witness_bond_of(
+            &worker,
+            &anchor,
+            users[5].id().to_string().parse().unwrap()
+        )
+        .await?
+        .0,
+        0
+    );
+    assert!(!anchor_viewer::get_anchor_settings(&worker, &anchor)
+        .await?
+        .witness_accounts
+        .contains(&users[5].id().to_string().parse().unwrap()));
+    assert_eq!(
+        anchor_viewer::get_anchor_status(&worker, &anchor)
+            .await?
+            .permissionless_actions_status
+            .max_nonce_of_staged_appchain_messages,
+        appchain_message_nonce
+    );
+    //
+    // The committed messages can be staged by GRANDPA light client and processed
+    //
+    settings_manager::turn_off_beefy_light_client_witness_mode(&worker, &root, &anchor)
+        .await
+        .expect("Failed to call 'turn_off_beefy_light_client_witness_mode'");
+    let result = permissionless_actions::verify_and_stage_appchain_messages_with_justification(
+        &worker,
+        &users[4],
+        &anchor,
+        committed_messages,
+        header.encode(),
+        justification,
+    )
+    .await?;
+    assert!(result.is_success());
+    common::complex_actions::process_appchain_messages(&worker, &users[4], &anchor).await?;
+    assert_eq!(
+        anchor_viewer::get_anchor_status(&worker, &anchor)
+            .await?
+            .permissionless_actions_status
+            .latest_applied_appchain_message_nonce,
+        appchain_message_nonce + 1
+    );
+    Ok(())
+}
+
+async fn commit_appchain_challenge(
+    worker: &Worker<Sandbox>,
+    signer: &Account,
+    oct_token: &Contract,
+    anchor: &Contract,
+    appchain_challenge: &AppchainChallenge,
+) -> anyhow::Result<CallExecutionDetails> {
+    common::call_ft_transfer_call(
+        worker,
+        signer,
+        &anchor.as_account(),
+        common::to_actual_amount(CHALLENGE_BOND_AMOUNT, 18),
+        json!({
+            "CommitChallenge": {
+                "appchain_challenge": appchain_challenge
+            }
+        })
+        .to_string(),
+        oct_token,
+    )
+    .await
+}
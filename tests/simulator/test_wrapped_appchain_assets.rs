@@ -0,0 +1,164 @@
+use crate::{
+    common::{self, complex_actions},
+    contract_interfaces::{anchor_viewer, wrapped_appchain_asset_manager},
+};
+use appchain_anchor::appchain_messages::{LockAssetPayload, PayloadType, RawMessage};
+use near_contract_standards::fungible_token::metadata::{FungibleTokenMetadata, FT_METADATA_SPEC};
+use near_primitives::borsh::BorshSerialize;
+use near_sdk::{json_types::U128, serde_json::json};
+use near_units::parse_near;
+use workspaces::{network::Sandbox, Account, AccountId, Worker};
+
+const ASSET_ID: &str = "usdc";
+const SENDER_ID_IN_APPCHAIN: &str =
+    "0xd43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d";
+
+#[tokio::test]
+async fn test_wrapped_appchain_assets() -> anyhow::Result<()> {
+    let worker = workspaces::sandbox().await?;
+    let (root, _, _, _, anchor, _wat_faucet, users, appchain_message_nonce) =
+        common::test_normal_actions(&worker, false, false, vec!["0x00".to_string()]).await?;
+    let result = root
+        .call(
+            &worker,
+            anchor.id(),
+            "store_wasm_of_wrapped_appchain_asset_contract",
+        )
+        .args(std::fs::read(format!("res/wrapped_appchain_token.wasm"))?)
+        .gas(300_000_000_000_000)
+        .deposit(parse_near!("30 N"))
+        .transact()
+        .await?;
+    assert!(result.is_success());
+    let metadata = FungibleTokenMetadata {
+        spec: FT_METADATA_SPEC.to_string(),
+        name: "Wrapped USDC".to_string(),
+        symbol: "USDC".to_string(),
+        icon: None,
+        reference: None,
+        reference_hash: None,
+        decimals: 6,
+    };
+    //
+    // Only the owner can register a wrapped appchain asset,
+    // and its contract is deployed in a sub-account of anchor
+    //
+    let result = wrapped_appchain_asset_manager::register_wrapped_appchain_asset(
+        &worker,
+        &users[0],
+        &anchor,
+        ASSET_ID.to_string(),
+        metadata.clone(),
+        U128::from(0),
+    )
+    .await?;
+    assert!(!result.is_success());
+    let result = wrapped_appchain_asset_manager::register_wrapped_appchain_asset(
+        &worker,
+        &root,
+        &anchor,
+        ASSET_ID.to_string(),
+        metadata.clone(),
+        U128::from(0),
+    )
+    .await?;
+    assert!(result.is_success());
+    let contract_account: AccountId = format!("{}.{}", ASSET_ID, anchor.id()).parse().unwrap();
+    let wrapped_appchain_assets =
+        anchor_viewer::get_wrapped_appchain_assets(&worker, &anchor).await?;
+    assert_eq!(wrapped_appchain_assets.len(), 1);
+    assert_eq!(
+        wrapped_appchain_assets[0].contract_account.to_string(),
+        contract_account.to_string()
+    );
+    let deployed_metadata = worker
+        .view(&contract_account, "ft_metadata", Vec::new())
+        .await?
+        .json::<FungibleTokenMetadata>()?;
+    assert_eq!(deployed_metadata.symbol, metadata.symbol);
+    assert_eq!(deployed_metadata.decimals, metadata.decimals);
+    let result = wrapped_appchain_asset_manager::open_bridging_of_wrapped_appchain_asset(
+        &worker,
+        &root,
+        &anchor,
+        ASSET_ID.to_string(),
+    )
+    .await?;
+    assert!(result.is_success());
+    //
+    // The wrapped asset is minted to the receiver when the asset is locked in the appchain
+    //
+    complex_actions::stage_appchain_messages_by_witnesses(
+        &worker,
+        &[&users[5]],
+        &anchor,
+        vec![RawMessage {
+            nonce: (appchain_message_nonce + 1) as u64,
+            payload_type: PayloadType::LockAsset,
+            payload: LockAssetPayload {
+                asset_id: ASSET_ID.to_string(),
+                sender: SENDER_ID_IN_APPCHAIN.to_string(),
+                receiver_id: users[0].id().to_string().parse().unwrap(),
+                amount: 1000,
+            }
+            .try_to_vec()
+            .unwrap(),
+        }],
+    )
+    .await?;
+    complex_actions::process_appchain_messages(&worker, &users[5], &anchor).await?;
+    assert_eq!(
+        get_balance_of_wrapped_asset(&worker, &contract_account, &users[0]).await?,
+        1000
+    );
+    let wrapped_appchain_asset = anchor_viewer::get_wrapped_appchain_assets(&worker, &anchor)
+        .await?
+        .remove(0);
+    assert_eq!(wrapped_appchain_asset.total_supply.0, 1000);
+    assert_eq!(wrapped_appchain_asset.pending_mint_amount.0, 0);
+    //
+    // The wrapped asset is burnt for transferring back to the appchain
+    //
+    let result = wrapped_appchain_asset_manager::burn_wrapped_appchain_asset(
+        &worker,
+        &users[0],
+        &anchor,
+        ASSET_ID.to_string(),
+        SENDER_ID_IN_APPCHAIN.to_string(),
+        U128::from(400),
+    )
+    .await?;
+    assert!(result.is_success());
+    assert_eq!(
+        common::get_anchor_events(&result, "wrapped_appchain_asset_burnt").len(),
+        1
+    );
+    assert_eq!(
+        get_balance_of_wrapped_asset(&worker, &contract_account, &users[0]).await?,
+        600
+    );
+    assert_eq!(
+        anchor_viewer::get_wrapped_appchain_assets(&worker, &anchor).await?[0]
+            .total_supply
+            .0,
+        600
+    );
+    Ok(())
+}
+
+//
+async fn get_balance_of_wrapped_asset(
+    worker: &Worker<Sandbox>,
+    contract_account: &AccountId,
+    user: &Account,
+) -> anyhow::Result<u128> {
+    Ok(worker
+        .view(
+            contract_account,
+            "ft_balance_of",
+            json!({ "account_id": user.id() }).to_string().into_bytes(),
+        )
+        .await?
+        .json::<U128>()?
+        .0)
+}